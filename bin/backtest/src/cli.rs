@@ -0,0 +1,32 @@
+use clap::Parser;
+
+#[derive(Parser, Debug)]
+pub struct Cli {
+    /// First replayed block
+    #[arg(long)]
+    pub start_block: u64,
+
+    /// Last block swaps are composed on, the block after it is replayed to check them
+    #[arg(long)]
+    pub end_block: u64,
+
+    /// Pipeline toml with an `actors` list, or a topology config when `--pipeline` is set
+    #[arg(short, long)]
+    pub config: Option<String>,
+
+    /// Name of the pipeline in the topology config
+    #[arg(short, long)]
+    pub pipeline: Option<String>,
+
+    /// Folder for cached RPC responses
+    #[arg(long, default_value = "./.cache")]
+    pub cache_dir: String,
+
+    /// Report path without extension, `.json` and `.csv` files are written
+    #[arg(short, long, default_value = "backtest")]
+    pub output: String,
+
+    /// Time to wait for swaps still in the pipeline after the last block
+    #[arg(long, default_value_t = 5000)]
+    pub settle_ms: u64,
+}
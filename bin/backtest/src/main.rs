@@ -0,0 +1,271 @@
+use std::collections::BTreeMap;
+use std::env;
+use std::time::Duration;
+
+use alloy::primitives::U256;
+use alloy::{providers::ProviderBuilder, rpc::client::ClientBuilder};
+use clap::Parser;
+use eyre::{eyre, Result};
+use revm::primitives::Env;
+use tokio::sync::broadcast::error::RecvError;
+use tracing::{error, info, warn};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{fmt, EnvFilter, Layer};
+use url::Url;
+
+use loom_core_blockchain::{Blockchain, BlockchainState, Strategy};
+use loom_core_blockchain_actors::{BlockchainActors, PipelineActorConfig, PipelineConfig};
+use loom_core_topology::TopologyConfig;
+use loom_evm_db::LoomDB;
+use loom_evm_utils::evm_env::env_for_block;
+use loom_evm_utils::NWETH;
+use loom_execution_multicaller::MulticallerSwapEncoder;
+use loom_node_debug_provider::HttpCachedTransport;
+use loom_types_blockchain::GethStateUpdateVec;
+use loom_types_entities::{MarketState, Swap, SwapAmountType};
+use loom_types_events::{SwapComposeData, SwapComposeMessage};
+
+use crate::cli::Cli;
+use crate::report::{BacktestReport, Opportunity, OpportunityStatus};
+
+mod cli;
+mod report;
+
+/// State updates kept for swaps arriving after their target block was replayed
+const STATE_UPDATES_KEPT: u64 = 16;
+
+/// Block backrun on pools found in the replayed blocks
+fn default_pipeline() -> PipelineConfig {
+    PipelineConfig::new(vec![
+        PipelineActorConfig::BlockHistory,
+        PipelineActorConfig::PoolLoaders { history: false, new: true, protocol: false },
+        PipelineActorConfig::BackrunBlock { config: Default::default() },
+        PipelineActorConfig::SwapRouter { inventory: None },
+        PipelineActorConfig::EvmEstimator { provider: false },
+    ])
+}
+
+/// Loads the pipeline spec and replaces its live block source with the block player.
+/// Actors talking to a live node or relays are dropped, signers and market state are set up by the harness.
+fn load_pipeline(cli: &Cli) -> Result<PipelineConfig> {
+    let pipeline = match (&cli.config, &cli.pipeline) {
+        (Some(config), Some(name)) => {
+            let topology = TopologyConfig::load_from_file(config.clone())?;
+            topology.pipelines.as_ref().and_then(|pipelines| pipelines.get(name)).ok_or_else(|| eyre!("PIPELINE_NOT_FOUND"))?.pipeline()
+        }
+        (Some(config), None) => toml::from_str(&std::fs::read_to_string(config)?)?,
+        (None, Some(_)) => return Err(eyre!("PIPELINE_WITHOUT_CONFIG")),
+        (None, None) => default_pipeline(),
+    };
+
+    // swaps composed on the last block target the next one, it is replayed only to check them
+    let mut actors = vec![PipelineActorConfig::BlockPlayer { start_block: cli.start_block, end_block: cli.end_block + 1 }];
+    for actor in pipeline.actors {
+        match actor {
+            PipelineActorConfig::WaitForNodeSync
+            | PipelineActorConfig::HeadWatchdog
+            | PipelineActorConfig::BlockEvents
+            | PipelineActorConfig::ExexEvents
+            | PipelineActorConfig::BlockPlayer { .. }
+            | PipelineActorConfig::LocalMempoolEvents
+            | PipelineActorConfig::Signers { .. }
+            | PipelineActorConfig::MarketStatePreloader
+            | PipelineActorConfig::FlashbotsBroadcaster { .. } => info!(kind = actor.kind(), "Actor is not used in backtest"),
+            actor => actors.push(actor),
+        }
+    }
+    Ok(PipelineConfig::new(actors))
+}
+
+/// Profit of the swap recalculated with the same amounts on `db`, None for swaps that cannot be recalculated
+fn recalculate_profit_eth(swap: &Swap, db: &LoomDB, env: &Env) -> Option<U256> {
+    match swap {
+        Swap::BackrunSwapLine(swap_line) => {
+            let SwapAmountType::Set(amount_in) = swap_line.amount_in else {
+                return None;
+            };
+            let first_token = swap_line.get_first_token()?;
+            let profit = match swap_line.calculate_with_in_amount(db, env.clone(), amount_in) {
+                Ok((amount_out, _, _)) => amount_out.saturating_sub(amount_in),
+                Err(_) => U256::ZERO,
+            };
+            Some(first_token.calc_eth_value(profit).unwrap_or_default())
+        }
+        Swap::Multiple(swap_vec) => swap_vec.iter().map(|swap| recalculate_profit_eth(swap, db, env)).sum(),
+        _ => None,
+    }
+}
+
+/// Swap waiting for the state update of its target block
+struct PendingCheck {
+    opportunity: Opportunity,
+    swap: Swap,
+    poststate: Option<LoomDB>,
+    block_timestamp: u64,
+}
+
+impl PendingCheck {
+    fn new(data: SwapComposeData<LoomDB>) -> Self {
+        let mut pool_classes: Vec<String> = data.swap.get_pools_vec().iter().map(|pool| pool.get_class().to_string()).collect();
+        pool_classes.sort();
+        pool_classes.dedup();
+
+        let opportunity = Opportunity {
+            block_number: data.tx_compose.next_block_number,
+            strategy: data.origin.clone().unwrap_or_else(|| "unknown".to_string()),
+            pool_classes,
+            swap: data.swap.to_string(),
+            profit_eth: NWETH::to_float(data.swap.abs_profit_eth()),
+            tips_and_gas_eth: NWETH::to_float(data.tips.unwrap_or_default()),
+            status: OpportunityStatus::Unchecked,
+            next_block_profit_eth: None,
+        };
+        Self { opportunity, swap: data.swap, poststate: data.poststate, block_timestamp: data.tx_compose.next_block_timestamp }
+    }
+
+    /// The swap survived when it still covers its costs on the composed state with the whole target block applied.
+    /// This is the state at the end of the block, not at the position a backrun would have been included.
+    fn check(self, state_update: &GethStateUpdateVec) -> Opportunity {
+        let Self { mut opportunity, swap, poststate, block_timestamp } = self;
+        let Some(mut db) = poststate else {
+            return opportunity;
+        };
+        db.apply_geth_update_vec(state_update.clone());
+
+        if let Some(profit) = recalculate_profit_eth(&swap, &db, &env_for_block(opportunity.block_number, block_timestamp)) {
+            let profit_eth = NWETH::to_float(profit);
+            opportunity.next_block_profit_eth = Some(profit_eth);
+            opportunity.status =
+                if profit_eth > opportunity.tips_and_gas_eth { OpportunityStatus::Survived } else { OpportunityStatus::Gone };
+        }
+        opportunity
+    }
+}
+
+#[derive(Default)]
+struct Backtest {
+    end_block: u64,
+    pending: BTreeMap<u64, Vec<PendingCheck>>,
+    state_updates: BTreeMap<u64, GethStateUpdateVec>,
+    opportunities: Vec<Opportunity>,
+}
+
+impl Backtest {
+    fn on_ready(&mut self, data: SwapComposeData<LoomDB>) {
+        let pending_check = PendingCheck::new(data);
+        let block_number = pending_check.opportunity.block_number;
+        match self.state_updates.get(&block_number) {
+            Some(state_update) => self.opportunities.push(pending_check.check(state_update)),
+            // composed on the block replayed past the range only to check the swaps of the last block
+            None if block_number > self.end_block + 1 => {}
+            None => self.pending.entry(block_number).or_default().push(pending_check),
+        }
+    }
+
+    fn on_state_update(&mut self, block_number: u64, state_update: GethStateUpdateVec) {
+        for pending_check in self.pending.remove(&block_number).unwrap_or_default() {
+            self.opportunities.push(pending_check.check(&state_update));
+        }
+        self.state_updates.insert(block_number, state_update);
+        self.state_updates.retain(|stored, _| stored + STATE_UPDATES_KEPT > block_number);
+    }
+
+    fn report(mut self, start_block: u64) -> BacktestReport {
+        let unchecked = std::mem::take(&mut self.pending).into_values().flatten().map(|pending_check| pending_check.opportunity);
+        self.opportunities.extend(unchecked);
+        self.opportunities.sort_by_key(|opportunity| opportunity.block_number);
+        BacktestReport::new(start_block, self.end_block, self.opportunities)
+    }
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let env_filter = EnvFilter::try_from_default_env()
+        .unwrap_or_else(|_| "info,alloy_rpc_client=off,loom_node_debug_provider=info,alloy_transport_http=off,hyper_util=off".into());
+    let fmt_layer = fmt::Layer::default().with_thread_ids(true).with_file(false).with_line_number(true).with_filter(env_filter);
+    tracing_subscriber::registry().with(fmt_layer).init();
+
+    let cli = Cli::parse();
+    if cli.end_block < cli.start_block {
+        return Err(eyre!("END_BLOCK_BEFORE_START_BLOCK"));
+    }
+    let pipeline = load_pipeline(&cli)?;
+
+    let node_url = Url::parse(env::var("MAINNET_HTTP")?.as_str())?;
+    let transport = HttpCachedTransport::new(node_url, Some(cli.cache_dir.as_str())).await;
+    transport.set_block_number(cli.start_block);
+
+    let client = ClientBuilder::default().transport(transport.clone(), true).with_poll_interval(Duration::from_millis(50));
+    let provider = ProviderBuilder::new().disable_recommended_fillers().on_client(client);
+
+    let bc = Blockchain::new(1);
+    let bc_state = BlockchainState::new_with_market_state(MarketState::new(LoomDB::empty()));
+    let strategy = Strategy::<LoomDB>::new();
+
+    // subscribe before the player starts to not miss the first blocks
+    let mut swap_compose_rx = strategy.swap_compose_channel().subscribe();
+    let mut state_update_rx = bc.new_block_state_update_channel().subscribe();
+
+    let mut bc_actors = BlockchainActors::new(provider, MulticallerSwapEncoder::default(), bc.clone(), bc_state, strategy.clone(), vec![]);
+    bc_actors.with_nonce_and_balance_monitor_only_events()?.initialize_signers_with_anvil().await?;
+    bc_actors.with_market_state_preloader_virtual(vec![]).await?.with_pipeline(&pipeline).await?;
+    tokio::task::spawn(bc_actors.wait());
+
+    info!(start_block = cli.start_block, end_block = cli.end_block, actors = pipeline.actors.len(), "Backtest started");
+
+    let mut backtest = Backtest { end_block: cli.end_block, ..Backtest::default() };
+
+    loop {
+        tokio::select! {
+            msg = swap_compose_rx.recv() => match msg {
+                Ok(msg) => if let SwapComposeMessage::Ready(data) = msg.inner {
+                    backtest.on_ready(data);
+                }
+                Err(RecvError::Lagged(lag)) => warn!(lag, "Swap compose messages are missing in the report"),
+                Err(RecvError::Closed) => break,
+            },
+            msg = state_update_rx.recv() => match msg {
+                Ok(msg) => {
+                    let block_number = msg.inner.block_header.number;
+                    info!(block_number, opportunities = backtest.opportunities.len(), "Block replayed");
+                    backtest.on_state_update(block_number, msg.inner.state_update);
+                    if block_number > cli.end_block {
+                        break;
+                    }
+                }
+                Err(RecvError::Lagged(lag)) => error!(lag, "Block state updates are missing, swaps of these blocks are unchecked"),
+                Err(RecvError::Closed) => break,
+            },
+            _ = tokio::signal::ctrl_c() => {
+                warn!("Interrupted, writing report for replayed blocks");
+                break;
+            }
+        }
+    }
+
+    // swaps composed for the last blocks may still be estimated
+    while let Ok(msg) = tokio::time::timeout(Duration::from_millis(cli.settle_ms), swap_compose_rx.recv()).await {
+        match msg {
+            Ok(msg) => {
+                if let SwapComposeMessage::Ready(data) = msg.inner {
+                    backtest.on_ready(data);
+                }
+            }
+            Err(RecvError::Lagged(_)) => continue,
+            Err(RecvError::Closed) => break,
+        }
+    }
+
+    let report = backtest.report(cli.start_block);
+    report.write(&cli.output).await?;
+    info!(
+        opportunities = report.total.opportunities,
+        survived = report.total.survived,
+        hit_rate = report.total.hit_rate,
+        realized_pnl_eth = report.total.realized_pnl_eth,
+        output = %cli.output,
+        "Backtest finished"
+    );
+    Ok(())
+}
@@ -0,0 +1,173 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use eyre::Result;
+use serde::Serialize;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OpportunityStatus {
+    /// Still profitable at the end of the target block, none of its txes captured it
+    Survived,
+    /// Not profitable anymore at the end of the target block
+    Gone,
+    /// Target block was not replayed or the swap kind cannot be recalculated
+    Unchecked,
+}
+
+/// `Ready` swap with the result of the check against the target block
+#[derive(Clone, Debug, Serialize)]
+pub struct Opportunity {
+    /// Block the swap was composed for
+    pub block_number: u64,
+    pub strategy: String,
+    pub pool_classes: Vec<String>,
+    pub swap: String,
+    pub profit_eth: f64,
+    /// Tips and gas cost of the estimated tx
+    pub tips_and_gas_eth: f64,
+    pub status: OpportunityStatus,
+    pub next_block_profit_eth: Option<f64>,
+}
+
+impl Opportunity {
+    pub fn expected_pnl_eth(&self) -> f64 {
+        self.profit_eth - self.tips_and_gas_eth
+    }
+
+    /// PnL with the profit left after the target block, only surviving opportunities count
+    pub fn realized_pnl_eth(&self) -> f64 {
+        match (self.status, self.next_block_profit_eth) {
+            (OpportunityStatus::Survived, Some(profit_eth)) => profit_eth - self.tips_and_gas_eth,
+            _ => 0.0,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct ReportRow {
+    pub opportunities: usize,
+    pub checked: usize,
+    pub survived: usize,
+    /// Surviving share of checked opportunities
+    pub hit_rate: f64,
+    pub expected_pnl_eth: f64,
+    pub realized_pnl_eth: f64,
+}
+
+impl ReportRow {
+    fn add(&mut self, opportunity: &Opportunity) {
+        self.opportunities += 1;
+        if opportunity.status != OpportunityStatus::Unchecked {
+            self.checked += 1;
+        }
+        if opportunity.status == OpportunityStatus::Survived {
+            self.survived += 1;
+        }
+        self.hit_rate = if self.checked > 0 { self.survived as f64 / self.checked as f64 } else { 0.0 };
+        self.expected_pnl_eth += opportunity.expected_pnl_eth();
+        self.realized_pnl_eth += opportunity.realized_pnl_eth();
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct BacktestReport {
+    pub start_block: u64,
+    pub end_block: u64,
+    pub total: ReportRow,
+    pub by_strategy: BTreeMap<String, ReportRow>,
+    /// Swaps over several pool classes are counted for each of them
+    pub by_pool_class: BTreeMap<String, ReportRow>,
+    pub opportunities: Vec<Opportunity>,
+}
+
+impl BacktestReport {
+    pub fn new(start_block: u64, end_block: u64, opportunities: Vec<Opportunity>) -> Self {
+        let mut total = ReportRow::default();
+        let mut by_strategy: BTreeMap<String, ReportRow> = BTreeMap::new();
+        let mut by_pool_class: BTreeMap<String, ReportRow> = BTreeMap::new();
+
+        for opportunity in opportunities.iter() {
+            total.add(opportunity);
+            by_strategy.entry(opportunity.strategy.clone()).or_default().add(opportunity);
+            for pool_class in opportunity.pool_classes.iter() {
+                by_pool_class.entry(pool_class.clone()).or_default().add(opportunity);
+            }
+        }
+
+        Self { start_block, end_block, total, by_strategy, by_pool_class, opportunities }
+    }
+
+    /// Summary rows grouped by strategy and pool class
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("group,key,opportunities,checked,survived,hit_rate,expected_pnl_eth,realized_pnl_eth\n");
+        let rows = std::iter::once(("total", "all", &self.total))
+            .chain(self.by_strategy.iter().map(|(key, row)| ("strategy", key.as_str(), row)))
+            .chain(self.by_pool_class.iter().map(|(key, row)| ("pool_class", key.as_str(), row)));
+        for (group, key, row) in rows {
+            let _ = writeln!(
+                csv,
+                "{group},{key},{},{},{},{:.4},{:.6},{:.6}",
+                row.opportunities, row.checked, row.survived, row.hit_rate, row.expected_pnl_eth, row.realized_pnl_eth
+            );
+        }
+        csv
+    }
+
+    /// Writes `<output>.json` with all opportunities and `<output>.csv` with the summary
+    pub async fn write(&self, output: &str) -> Result<()> {
+        tokio::fs::write(format!("{output}.json"), serde_json::to_vec_pretty(self)?).await?;
+        tokio::fs::write(format!("{output}.csv"), self.to_csv()).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn opportunity(strategy: &str, pool_classes: &[&str], status: OpportunityStatus, next_block_profit_eth: Option<f64>) -> Opportunity {
+        Opportunity {
+            block_number: 100,
+            strategy: strategy.to_string(),
+            pool_classes: pool_classes.iter().map(|pool_class| pool_class.to_string()).collect(),
+            swap: String::new(),
+            profit_eth: 0.1,
+            tips_and_gas_eth: 0.02,
+            status,
+            next_block_profit_eth,
+        }
+    }
+
+    #[test]
+    fn test_report_rows() {
+        let report = BacktestReport::new(
+            100,
+            101,
+            vec![
+                opportunity("block", &["UniswapV2"], OpportunityStatus::Survived, Some(0.08)),
+                opportunity("block", &["UniswapV2", "UniswapV3"], OpportunityStatus::Gone, Some(0.01)),
+                opportunity("mempool", &["UniswapV3"], OpportunityStatus::Unchecked, None),
+            ],
+        );
+
+        assert_eq!(report.total.opportunities, 3);
+        assert_eq!(report.total.checked, 2);
+        assert_eq!(report.total.survived, 1);
+        assert_eq!(report.total.hit_rate, 0.5);
+        assert!((report.total.expected_pnl_eth - 0.24).abs() < 1e-9);
+        // only the surviving opportunity is realized
+        assert!((report.total.realized_pnl_eth - 0.06).abs() < 1e-9);
+
+        let mempool = &report.by_strategy["mempool"];
+        assert_eq!((mempool.checked, mempool.hit_rate, mempool.realized_pnl_eth), (0, 0.0, 0.0));
+        // the swap over both pool classes counts for each of them
+        assert_eq!(report.by_pool_class["UniswapV2"].opportunities, 2);
+        assert_eq!(report.by_pool_class["UniswapV3"].opportunities, 2);
+        assert_eq!(report.by_pool_class["UniswapV3"].checked, 1);
+
+        let csv = report.to_csv();
+        assert_eq!(csv.lines().count(), 6);
+        assert_eq!(csv.lines().nth(1), Some("total,all,3,2,1,0.5000,0.240000,0.060000"));
+    }
+}
@@ -8,6 +8,7 @@ use loom_defi_address_book::{
 use loom_defi_market::fetch_and_add_pool_by_pool_id;
 use loom_defi_pools::PoolLoadersBuilder;
 use loom_node_debug_provider::DebugProviderExt;
+use loom_types_blockchain::ChainParameters;
 use loom_types_entities::pool_config::PoolsLoadingConfig;
 use loom_types_entities::{Market, MarketState, PoolClass, Token};
 use revm::{Database, DatabaseCommit, DatabaseRef};
@@ -31,7 +32,9 @@ where
 
     drop(market_instance);
 
-    let pool_loaders = Arc::new(PoolLoadersBuilder::default_pool_loaders(client.clone(), PoolsLoadingConfig::default()));
+    let chain_parameters = ChainParameters::from_chain_id(client.get_chain_id().await?);
+    let pools_config = PoolsLoadingConfig::default().with_chain_parameters(chain_parameters);
+    let pool_loaders = Arc::new(PoolLoadersBuilder::default_pool_loaders(client.clone(), pools_config));
 
     fetch_and_add_pool_by_pool_id(
         client.clone(),
@@ -24,7 +24,7 @@ use loom::broadcast::accounts::{InitializeSignersOneShotBlockingActor, NonceAndB
 use loom::broadcast::broadcaster::{AnvilBroadcastActor, FlashbotsBroadcastActor};
 use loom::broadcast::flashbots::client::RelayConfig;
 use loom::broadcast::flashbots::Flashbots;
-use loom::core::actors::{Accessor, Actor, Broadcaster, Consumer, Producer, SharedState};
+use loom::core::actors::{Accessor, Actor, Broadcaster, Consumer, Mpsc, MpscConsumer, MpscProducer, Producer, SharedState};
 use loom::core::block_history::BlockHistoryActor;
 use loom::core::router::SwapRouterActor;
 use loom::defi::address_book::TokenAddressEth;
@@ -181,16 +181,16 @@ async fn main() -> Result<()> {
     let mempool_instance = Mempool::<LoomDataTypesEthereum>::new();
 
     info!("Creating channels");
-    let new_block_headers_channel: Broadcaster<MessageBlockHeader> = Broadcaster::new(10);
-    let new_block_with_tx_channel: Broadcaster<MessageBlock> = Broadcaster::new(10);
-    let new_block_state_update_channel: Broadcaster<MessageBlockStateUpdate> = Broadcaster::new(10);
-    let new_block_logs_channel: Broadcaster<MessageBlockLogs> = Broadcaster::new(10);
+    let new_block_headers_channel: Broadcaster<MessageBlockHeader> = Broadcaster::named("new_block_headers", 10);
+    let new_block_with_tx_channel: Broadcaster<MessageBlock> = Broadcaster::named("new_block_with_tx", 10);
+    let new_block_state_update_channel: Broadcaster<MessageBlockStateUpdate> = Broadcaster::named("new_block_state_update", 10);
+    let new_block_logs_channel: Broadcaster<MessageBlockLogs> = Broadcaster::named("new_block_logs", 10);
 
-    let market_events_channel: Broadcaster<MarketEvents> = Broadcaster::new(100);
-    let mempool_events_channel: Broadcaster<MempoolEvents> = Broadcaster::new(500);
-    let pool_health_monitor_channel: Broadcaster<MessageHealthEvent> = Broadcaster::new(100);
+    let market_events_channel: Broadcaster<MarketEvents> = Broadcaster::named("market_events", 100);
+    let mempool_events_channel: Broadcaster<MempoolEvents> = Broadcaster::named("mempool_events", 500);
+    let pool_health_monitor_channel: Broadcaster<MessageHealthEvent> = Broadcaster::named("pool_health_monitor", 100);
 
-    let influx_channel: Broadcaster<WriteQuery> = Broadcaster::new(100);
+    let influx_channel: Broadcaster<WriteQuery> = Broadcaster::named("influx_write", 100);
 
     let market_instance = SharedState::new(market_instance);
     let market_state = SharedState::new(market_state_instance);
@@ -218,7 +218,7 @@ async fn main() -> Result<()> {
     info!("Starting initialize signers actor");
 
     let mut initialize_signers_actor = InitializeSignersOneShotBlockingActor::new(Some(priv_key));
-    match initialize_signers_actor.access(tx_signers.clone()).access(accounts_state.clone()).start_and_wait() {
+    match initialize_signers_actor.access(tx_signers.clone()).access(accounts_state.clone()).start_and_wait().await {
         Err(e) => {
             error!("{}", e);
             panic!("Cannot initialize signers");
@@ -251,7 +251,7 @@ async fn main() -> Result<()> {
     let mut market_state_preload_actor = MarketStatePreloadedOneShotActor::new(client.clone())
         .with_copied_account(multicaller_encoder.get_contract_address())
         .with_signers(tx_signers.clone());
-    match market_state_preload_actor.access(market_state.clone()).start_and_wait() {
+    match market_state_preload_actor.access(market_state.clone()).start_and_wait().await {
         Err(e) => {
             error!("{}", e)
         }
@@ -292,17 +292,9 @@ async fn main() -> Result<()> {
         _ => info!("Nonce monitor has been initialized"),
     }
 
-    info!("Starting price actor");
-    let mut price_actor = PriceActor::new(client.clone()).only_once();
-    match price_actor.access(market_instance.clone()).start_and_wait() {
-        Err(e) => {
-            error!("{}", e);
-            panic!("Cannot initialize price actor");
-        }
-        _ => info!("Price actor has been initialized"),
-    }
-
-    let pool_loaders = Arc::new(PoolLoadersBuilder::default_pool_loaders(client.clone(), PoolsLoadingConfig::default()));
+    let chain_parameters = ChainParameters::from_chain_id(client.get_chain_id().await?);
+    let pools_config = PoolsLoadingConfig::default().with_chain_parameters(chain_parameters.clone());
+    let pool_loaders = Arc::new(PoolLoadersBuilder::default_pool_loaders(client.clone(), pools_config));
 
     for (pool_name, pool_config) in test_config.pools {
         match pool_config.class {
@@ -340,6 +332,16 @@ async fn main() -> Result<()> {
         );
     }
 
+    info!("Starting price actor");
+    let mut price_actor = PriceActor::new().only_once();
+    match price_actor.access(market_instance.clone()).access(market_state.clone()).start_and_wait().await {
+        Err(e) => {
+            error!("{}", e);
+            panic!("Cannot initialize price actor");
+        }
+        _ => info!("Price actor has been initialized"),
+    }
+
     info!("Starting block history actor");
     let mut block_history_actor = BlockHistoryActor::new(client.clone());
     match block_history_actor
@@ -361,8 +363,9 @@ async fn main() -> Result<()> {
         }
     }
 
-    let swap_compose_channel: Broadcaster<MessageSwapCompose<LoomDBType>> = Broadcaster::new(100);
-    let tx_compose_channel: Broadcaster<MessageTxCompose> = Broadcaster::new(100);
+    let swap_compose_channel: Broadcaster<MessageSwapCompose<LoomDBType>> = Broadcaster::named("strategy_compose", 100);
+    let estimate_channel: Mpsc<MessageSwapCompose<LoomDBType>> = Mpsc::new(100);
+    let tx_compose_channel: Broadcaster<MessageTxCompose> = Broadcaster::named("tx_compose", 100);
 
     let mut broadcast_actor = AnvilBroadcastActor::new(client.clone());
     match broadcast_actor.consume(tx_compose_channel.clone()).start() {
@@ -374,7 +377,7 @@ async fn main() -> Result<()> {
 
     // Start estimator actor
     let mut estimator_actor = EvmEstimatorActor::new_with_provider(multicaller_encoder.clone(), Some(client.clone()));
-    match estimator_actor.consume(swap_compose_channel.clone()).produce(swap_compose_channel.clone()).start() {
+    match estimator_actor.consume_mpsc(estimate_channel.clone()).produce(swap_compose_channel.clone()).start() {
         Err(e) => error!("{e}"),
         _ => {
             info!("Estimate actor started successfully")
@@ -408,7 +411,7 @@ async fn main() -> Result<()> {
             .access(tx_signers.clone())
             .access(accounts_state.clone())
             .consume(swap_compose_channel.clone())
-            .produce(swap_compose_channel.clone())
+            .produce_mpsc(estimate_channel.clone())
             .produce(tx_compose_channel.clone())
             .start()
         {
@@ -539,7 +542,7 @@ async fn main() -> Result<()> {
     // we need to wait for all actors to start. For the CI it can be a bit longer
     tokio::time::sleep(Duration::from_secs(args.wait_init)).await;
 
-    let next_block_base_fee = ChainParameters::ethereum().calc_next_block_base_fee(
+    let next_block_base_fee = chain_parameters.calc_next_block_base_fee(
         block_header.gas_used,
         block_header.gas_limit,
         block_header.base_fee_per_gas.unwrap_or_default(),
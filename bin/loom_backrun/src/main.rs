@@ -1,8 +1,9 @@
 use alloy::providers::Provider;
 use eyre::Result;
+use std::time::Duration;
 use tracing::{error, info};
 
-use loom::core::actors::{Accessor, Actor, Consumer, Producer};
+use loom::core::actors::{shutdown_signal, Accessor, Actor, Consumer, MpscProducer, Producer};
 use loom::core::router::SwapRouterActor;
 use loom::core::topology::{Topology, TopologyConfig};
 use loom::defi::health_monitor::{MetricsRecorderActor, StateHealthMonitorActor, StuffingTxMonitorActor};
@@ -14,6 +15,8 @@ use loom::strategy::merger::{ArbSwapPathMergerActor, DiffPathMergerActor, SamePa
 use loom::types::entities::strategy_config::load_from_file;
 use loom::types::events::MarketEvents;
 
+const SHUTDOWN_GRACE: Duration = Duration::from_secs(5);
+
 #[tokio::main]
 async fn main() -> Result<()> {
     env_logger::Builder::from_env(
@@ -23,13 +26,24 @@ async fn main() -> Result<()> {
     .init();
 
     let topology_config = TopologyConfig::load_from_file("config.toml".to_string())?;
+
+    // dry run: config is valid at this point, print what would be started
+    if std::env::args().any(|arg| arg == "--check-config") {
+        print!("{}", topology_config.actor_graph()?);
+        return Ok(());
+    }
+
     let influxdb_config = topology_config.influxdb.clone();
 
-    let encoder = MulticallerSwapEncoder::default();
+    let backrun_config: BackrunConfigSection = load_from_file("./config.toml".to_string().into()).await?;
+    let backrun_config: BackrunConfig = backrun_config.backrun_strategy;
+
+    let encoder = MulticallerSwapEncoder::default().with_tips_bidder(backrun_config.bidding().bidder()?);
 
     let topology =
         Topology::<LoomDBType>::from_config(topology_config).with_swap_encoder(encoder).build_blockchains().start_clients().await?;
 
+    let shutdown = topology.shutdown();
     let mut worker_task_vec = topology.start_actors().await?;
 
     //mut worker_task_vec = topology.start_actors().await;
@@ -43,9 +57,6 @@ async fn main() -> Result<()> {
 
     let tx_signers = topology.get_signers(Some("env_signer".to_string()).as_ref())?;
 
-    let backrun_config: BackrunConfigSection = load_from_file("./config.toml".to_string().into()).await?;
-    let backrun_config: BackrunConfig = backrun_config.backrun_strategy;
-
     let block_nr = client.get_block_number().await?;
     info!("Block : {}", block_nr);
 
@@ -64,7 +75,7 @@ async fn main() -> Result<()> {
         .produce(strategy.swap_compose_channel())
         .produce(blockchain.health_monitor_channel())
         .produce(blockchain.influxdb_write_channel())
-        .start()
+        .start_with_shutdown(&shutdown)
     {
         Err(e) => {
             error!("{}", e)
@@ -83,10 +94,11 @@ async fn main() -> Result<()> {
     match swap_path_encoder_actor
         .access(tx_signers.clone())
         .access(blockchain.nonce_and_balance())
+        .access(blockchain.head_health())
         .consume(strategy.swap_compose_channel())
-        .produce(strategy.swap_compose_channel())
+        .produce_mpsc(strategy.estimate_channel())
         .produce(blockchain.tx_compose_channel())
-        .start()
+        .start_with_shutdown(&shutdown)
     {
         Ok(r) => {
             worker_task_vec.extend(r);
@@ -106,7 +118,7 @@ async fn main() -> Result<()> {
         .consume(blockchain.market_events_channel())
         .consume(strategy.swap_compose_channel())
         .produce(strategy.swap_compose_channel())
-        .start()
+        .start_with_shutdown(&shutdown)
     {
         Ok(r) => {
             worker_task_vec.extend(r);
@@ -125,7 +137,7 @@ async fn main() -> Result<()> {
         .consume(blockchain.market_events_channel())
         .consume(strategy.swap_compose_channel())
         .produce(strategy.swap_compose_channel())
-        .start()
+        .start_with_shutdown(&shutdown)
     {
         Ok(r) => {
             worker_task_vec.extend(r);
@@ -143,7 +155,7 @@ async fn main() -> Result<()> {
         .consume(blockchain.market_events_channel())
         .consume(strategy.swap_compose_channel())
         .produce(strategy.swap_compose_channel())
-        .start()
+        .start_with_shutdown(&shutdown)
     {
         Ok(r) => {
             worker_task_vec.extend(r);
@@ -161,7 +173,7 @@ async fn main() -> Result<()> {
         .access(blockchain_state.market_state())
         .consume(blockchain.tx_compose_channel())
         .consume(blockchain.market_events_channel())
-        .start()
+        .start_with_shutdown(&shutdown)
     {
         Err(e) => {
             panic!("State health monitor actor failed : {}", e)
@@ -179,7 +191,7 @@ async fn main() -> Result<()> {
         .consume(blockchain.tx_compose_channel())
         .consume(blockchain.market_events_channel())
         .produce(blockchain.influxdb_write_channel())
-        .start()
+        .start_with_shutdown(&shutdown)
     {
         Err(e) => {
             panic!("Stuffing txs monitor actor failed : {}", e)
@@ -193,7 +205,7 @@ async fn main() -> Result<()> {
     // Recording InfluxDB metrics
     if let Some(influxdb_config) = influxdb_config {
         let mut influxdb_writer_actor = InfluxDbWriterActor::new(influxdb_config.url, influxdb_config.database, influxdb_config.tags);
-        match influxdb_writer_actor.consume(blockchain.influxdb_write_channel()).start() {
+        match influxdb_writer_actor.consume(blockchain.influxdb_write_channel()).start_with_shutdown(&shutdown) {
             Err(e) => {
                 panic!("InfluxDB writer actor failed : {}", e)
             }
@@ -209,7 +221,7 @@ async fn main() -> Result<()> {
             .access(blockchain_state.market_state())
             .consume(blockchain.new_block_headers_channel())
             .produce(blockchain.influxdb_write_channel())
-            .start()
+            .start_with_shutdown(&shutdown)
         {
             Err(e) => {
                 panic!("Block latency recorder actor failed : {}", e)
@@ -242,20 +254,36 @@ async fn main() -> Result<()> {
         }
     });
 
-    // listening to MarketEvents in an infinite loop
+    // listening to MarketEvents until SIGINT or SIGTERM
     let mut s = blockchain.market_events_channel().subscribe();
+    let shutdown_signal = shutdown_signal();
+    tokio::pin!(shutdown_signal);
     loop {
-        let msg = s.recv().await;
-        if let Ok(msg) = msg {
-            match msg {
-                MarketEvents::BlockTxUpdate { block_number, block_hash } => {
-                    info!("New block received {} {}", block_number, block_hash);
+        tokio::select! {
+            msg = s.recv() => {
+                if let Ok(msg) = msg {
+                    match msg {
+                        MarketEvents::BlockTxUpdate { block_number, block_hash } => {
+                            info!("New block received {} {}", block_number, block_hash);
+                        }
+                        MarketEvents::BlockStateUpdate { block_hash } => {
+                            info!("New block state received {}", block_hash);
+                        }
+                        _ => {}
+                    }
                 }
-                MarketEvents::BlockStateUpdate { block_hash } => {
-                    info!("New block state received {}", block_hash);
+            }
+            result = &mut shutdown_signal => {
+                if let Err(error) = result {
+                    error!(%error, "Failed to listen for shutdown signal");
                 }
-                _ => {}
+                break;
             }
         }
     }
+
+    // producers stop first, then processors drain and writers flush
+    shutdown.cancel_stages(SHUTDOWN_GRACE).await;
+    info!("Shutdown completed");
+    Ok(())
 }
@@ -27,6 +27,10 @@ pub struct LoomArgs {
     #[arg(long, default_value = "config.toml")]
     pub loom_config: String,
 
+    /// Validate the loom config, print the resolved actor graph and exit without connecting anywhere
+    #[arg(long, default_value_t = false)]
+    pub check_config: bool,
+
     // Original RETH CLI arguments
     /// Configure persistence threshold for engine experimental.
     #[arg(long = "engine.persistence-threshold", default_value_t = DEFAULT_PERSISTENCE_THRESHOLD)]
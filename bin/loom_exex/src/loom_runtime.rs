@@ -3,6 +3,7 @@ use alloy::primitives::Address;
 use alloy::providers::Provider;
 use axum::Router;
 use eyre::{ErrReport, OptionExt};
+use loom::core::actors::{shutdown_signal, SupervisorPolicy};
 use loom::core::blockchain::{Blockchain, BlockchainState, Strategy};
 use loom::core::blockchain_actors::BlockchainActors;
 use loom::core::topology::{BroadcasterConfig, EncoderConfig, TopologyConfig};
@@ -23,7 +24,8 @@ use reth_node_api::FullNodeComponents;
 use reth_primitives::EthPrimitives;
 use std::env;
 use std::future::Future;
-use tracing::info;
+use std::time::Duration;
+use tracing::{error, info};
 
 pub async fn init<Node>(
     ctx: ExExContext<Node>,
@@ -71,7 +73,7 @@ where
     let private_key_encrypted = hex::decode(env::var("DATA")?)?;
     info!(address=?multicaller_address, "Multicaller");
 
-    let webserver_host = topology_config.webserver.unwrap_or_default().host;
+    let webserver_config = topology_config.webserver.unwrap_or_default();
     let db_url = topology_config.database.unwrap().url;
     let db_pool = init_db_pool(db_url).await?;
 
@@ -91,13 +93,24 @@ where
     let backrun_config: BackrunConfigSection = load_from_file::<BackrunConfigSection>(loom_config_filepath.into()).await?;
     let backrun_config: BackrunConfig = backrun_config.backrun_strategy;
 
-    let swap_encoder = MulticallerSwapEncoder::default_with_address(multicaller_address);
+    let swap_encoder =
+        MulticallerSwapEncoder::default_with_address(multicaller_address).with_tips_bidder(backrun_config.bidding().bidder()?);
+
+    let restart_policy = SupervisorPolicy::exponential_backoff(Duration::from_secs(1), Duration::from_secs(60))
+        .with_max_restarts(10, Duration::from_secs(600));
 
     let mut bc_actors = BlockchainActors::new(provider.clone(), swap_encoder.clone(), bc.clone(), bc_state, strategy, relays);
+    bc_actors
+        .with_default_supervisor_policy(restart_policy)
+        .with_supervisor_policy("BlockHistoryActor", restart_policy.critical())
+        .with_supervisor_policy("MempoolActor", restart_policy.critical());
     bc_actors
         .mempool()?
-        .with_wait_for_node_sync()? // wait for node to sync before
-        .initialize_signers_with_encrypted_key(private_key_encrypted)? // initialize signer with encrypted key
+        .with_wait_for_node_sync() // wait for node to sync before
+        .await?
+        .with_head_watchdog()? // pause trading while the head is stale
+        .initialize_signers_with_encrypted_key(private_key_encrypted) // initialize signer with encrypted key
+        .await?
         .with_block_history()? // collect blocks
         .with_price_station()? // calculate price fo tokens
         .with_health_monitor_pools()? // monitor pools health to disable empty
@@ -107,7 +120,8 @@ where
         .with_evm_estimator()? // estimate gas, add tips
         .with_signers()? // start signer actor that signs transactions before broadcasting
         .with_flashbots_broadcaster( true)? // broadcast signed txes to flashbots
-        .with_market_state_preloader()? // preload contracts to market state
+        .with_market_state_preloader() // preload contracts to market state
+        .await?
         .with_nonce_and_balance_monitor()? // start monitoring balances of
         .with_pool_history_loader(pools_config.clone())? // load pools used in latest 10000 blocks
         //.with_curve_pool_protocol_loader()? // load curve + steth + wsteth
@@ -118,7 +132,7 @@ where
         .with_same_path_merger()? // load merger for same swap paths with different stuffing txes
         .with_backrun_block(backrun_config.clone())? // load backrun searcher for incoming block
         .with_backrun_mempool(backrun_config)? // load backrun searcher for mempool txes
-        .with_web_server(webserver_host, Router::new(), db_pool)? // start web server
+        .with_web_server(webserver_config.host, Router::new(), db_pool, webserver_config.control_token)? // start web server
     ;
 
     if !is_exex {
@@ -131,7 +145,14 @@ where
             .with_block_latency_recorder()?;
     }
 
-    bc_actors.wait().await;
+    // actors are stopped in order on SIGINT or SIGTERM
+    let shutdown = bc_actors.shutdown();
+    tokio::task::spawn(async move {
+        match shutdown_signal().await {
+            Ok(_) => shutdown.request(),
+            Err(error) => error!(%error, "Failed to listen for shutdown signal"),
+        }
+    });
 
-    Ok(())
+    bc_actors.wait().await
 }
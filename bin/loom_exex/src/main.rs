@@ -3,11 +3,13 @@ use alloy::eips::BlockId;
 use alloy::providers::{ProviderBuilder, WsConnect};
 use alloy::rpc::client::ClientBuilder;
 use clap::{CommandFactory, FromArgMatches, Parser};
+use loom::core::actors::Actor;
 use loom::core::blockchain::{Blockchain, BlockchainState, Strategy};
 use loom::core::topology::TopologyConfig;
 use loom::evm::db::{AlloyDB, LoomDB};
 use loom::node::actor_config::NodeBlockActorConfig;
-use loom::node::exex::mempool_worker;
+use loom::node::exex::ExExMempoolSource;
+use loom::node::json_rpc::MempoolSourceActor;
 use loom::types::entities::MarketState;
 use reth::builder::engine_tree_config::TreeConfig;
 use reth::builder::EngineNodeLauncher;
@@ -16,8 +18,6 @@ use reth::cli::Cli;
 use reth_node_ethereum::node::EthereumAddOns;
 use reth_node_ethereum::EthereumNode;
 use reth_provider::providers::BlockchainProvider;
-use std::time::Duration;
-use tokio::{signal, task};
 use tracing::{error, info};
 use tracing_subscriber::layer::SubscriberExt;
 use tracing_subscriber::util::SubscriberInitExt;
@@ -36,6 +36,10 @@ fn main() -> eyre::Result<()> {
     match app_args.command {
         Command::Node(_) => Cli::<EthereumChainSpecParser, LoomArgs>::parse().run(|builder, loom_args: LoomArgs| async move {
             let topology_config = TopologyConfig::load_from_file(loom_args.loom_config.clone())?;
+            if loom_args.check_config {
+                print!("{}", topology_config.actor_graph()?);
+                return Ok(());
+            }
 
             let bc = Blockchain::new(builder.config().chain.chain.id());
             let bc_clone = bc.clone();
@@ -81,7 +85,7 @@ fn main() -> eyre::Result<()> {
                     error!("Error starting loom: {:?}", e);
                 }
             });
-            tokio::task::spawn(mempool_worker(mempool, bc));
+            MempoolSourceActor::new(ExExMempoolSource::new(mempool)).with_name("exex".to_string()).on_bc(&bc).start()?;
 
             handle.node_exit_future.await
         }),
@@ -91,6 +95,10 @@ fn main() -> eyre::Result<()> {
             rt.block_on(async {
                 info!("Loading config from {}", loom_args.loom_config);
                 let topology_config = TopologyConfig::load_from_file(loom_args.loom_config.clone())?;
+                if loom_args.check_config {
+                    print!("{}", topology_config.actor_graph()?);
+                    return Ok(());
+                }
 
                 let client_config = topology_config.clients.get("remote").unwrap();
                 let transport = WsConnect { url: client_config.url(), auth: None, config: None };
@@ -111,18 +119,8 @@ fn main() -> eyre::Result<()> {
                     panic!("{}", e)
                 }
 
-                // keep loom running
-                tokio::select! {
-                    _ = signal::ctrl_c() => {
-                    info!("CTRL+C received... exiting");
-                }
-                _ = async {
-                        loop {
-                        tokio::time::sleep(Duration::from_secs(60)).await;
-                        task::yield_now().await;
-                        }
-                    } => {}
-                }
+                // start_loom returns after all actors stopped on shutdown signal
+                info!("Loom stopped");
                 Ok::<(), eyre::Error>(())
             })?;
             Ok(())
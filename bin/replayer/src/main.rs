@@ -86,11 +86,11 @@ async fn main() -> Result<()> {
     // instead fo code above
     let mut bc_actors =
         BlockchainActors::new(provider.clone(), swap_encoder.clone(), bc.clone(), bc_state.clone(), strategy.clone(), vec![]);
+    bc_actors.with_nonce_and_balance_monitor_only_events()?.initialize_signers_with_anvil().await?;
+    bc_actors.with_market_state_preloader_virtual(vec![]).await?;
     bc_actors
-        .with_nonce_and_balance_monitor_only_events()?
-        .initialize_signers_with_anvil()?
-        .with_market_state_preloader_virtual(vec![])?
-        .with_preloaded_state(vec![(UniswapV3PoolAddress::USDC_WETH_500, PoolClass::UniswapV3)], Some(required_state))?
+        .with_preloaded_state(vec![(UniswapV3PoolAddress::USDC_WETH_500, PoolClass::UniswapV3)], Some(required_state))
+        .await?
         .with_block_history()?
         .with_swap_encoder(swap_encoder)?
         .with_evm_estimator()?;
@@ -0,0 +1,162 @@
+use std::collections::HashMap;
+use std::marker::PhantomData;
+
+use alloy_network::Network;
+use alloy_primitives::{Address, U256};
+use alloy_provider::Provider;
+use eyre::eyre;
+use loom_core_actors::{subscribe, Accessor, Actor, ActorResult, Broadcaster, Consumer, Producer, SharedState, WorkerResult};
+use loom_core_actors_macros::{Accessor, Consumer, Producer};
+use loom_core_blockchain::Blockchain;
+use loom_defi_abi::IERC20;
+use loom_types_entities::{AccountNonceAndBalanceState, InventoryConfig, InventoryLevel, Market};
+use loom_types_events::{HealthEvent, MarketEvents, MessageHealthEvent};
+use tokio::sync::broadcast::error::RecvError;
+use tracing::{error, info, warn};
+
+/// Loads the multicaller balances of the inventory tokens and reports balances leaving their rebalancing bounds.
+/// Balances are kept up to date by the nonce and balance monitor, they are checked on every new block header.
+pub async fn inventory_monitor_worker<P, N>(
+    client: P,
+    multicaller_address: Address,
+    inventory_config: InventoryConfig,
+    market: SharedState<Market>,
+    accounts_state: SharedState<AccountNonceAndBalanceState>,
+    market_events_rx: Broadcaster<MarketEvents>,
+    health_monitor_channel_tx: Broadcaster<MessageHealthEvent>,
+) -> WorkerResult
+where
+    N: Network,
+    P: Provider<N> + Send + Sync + Clone + 'static,
+{
+    subscribe!(market_events_rx);
+
+    for token in inventory_config.tokens.keys() {
+        // balances set by the preloader are kept, a virtual multicaller has no balance on chain
+        let balance =
+            accounts_state.read().await.get_account(&multicaller_address).map(|account| account.get_balance(token)).unwrap_or_default();
+        if balance != U256::ZERO {
+            continue;
+        }
+        let balance = IERC20::IERC20Instance::new(*token, client.clone()).balanceOf(multicaller_address).call().await?._0;
+        accounts_state.write().await.get_entry_or_default(multicaller_address).set_balance(*token, balance);
+        info!(%token, %balance, "Inventory balance loaded");
+    }
+
+    let mut levels: HashMap<Address, InventoryLevel> = HashMap::new();
+
+    loop {
+        match market_events_rx.recv().await {
+            Ok(MarketEvents::BlockHeaderUpdate { .. }) => {}
+            Ok(_) => continue,
+            Err(RecvError::Lagged(lag)) => {
+                warn!(lag, "Market events channel lagged");
+                continue;
+            }
+            Err(RecvError::Closed) => {
+                error!("Market events channel closed");
+                break Err(eyre!("MARKET_EVENTS_RX_CLOSED"));
+            }
+        }
+
+        let accounts_guard = accounts_state.read().await;
+        let market_guard = market.read().await;
+
+        for (token, token_config) in inventory_config.tokens.iter() {
+            let balance = accounts_guard.get_account(&multicaller_address).map(|account| account.get_balance(token)).unwrap_or_default();
+            let token_info = market_guard.get_token_or_default(token);
+            let level = token_config.level(balance, token_info.get_decimals());
+
+            let previous_level = levels.insert(*token, level);
+            if previous_level == Some(level) || (previous_level.is_none() && level == InventoryLevel::Normal) {
+                continue;
+            }
+
+            let balance_float = token_info.to_float(balance);
+            match level {
+                InventoryLevel::Low | InventoryLevel::High => {
+                    warn!(%token, symbol = token_info.get_symbol(), balance = balance_float, ?level, "Inventory needs rebalancing")
+                }
+                InventoryLevel::Normal => {
+                    info!(%token, symbol = token_info.get_symbol(), balance = balance_float, "Inventory balance is back to normal")
+                }
+            }
+
+            if let Err(error) = health_monitor_channel_tx.send(MessageHealthEvent::new(HealthEvent::InventoryLevelChanged {
+                token: *token,
+                balance,
+                level,
+            })) {
+                error!(%error, "Failed to send inventory level to health monitor channel");
+            }
+        }
+    }
+}
+
+#[derive(Accessor, Consumer, Producer)]
+pub struct InventoryMonitorActor<P, N> {
+    client: P,
+    multicaller_address: Address,
+    inventory_config: InventoryConfig,
+    #[accessor]
+    market: Option<SharedState<Market>>,
+    #[accessor]
+    accounts_nonce_and_balance: Option<SharedState<AccountNonceAndBalanceState>>,
+    #[consumer]
+    market_events: Option<Broadcaster<MarketEvents>>,
+    #[producer]
+    health_monitor_channel_tx: Option<Broadcaster<MessageHealthEvent>>,
+    _n: PhantomData<N>,
+}
+
+impl<P, N> InventoryMonitorActor<P, N>
+where
+    N: Network,
+    P: Provider<N> + Send + Sync + Clone + 'static,
+{
+    pub fn new(client: P, multicaller_address: Address, inventory_config: InventoryConfig) -> Self {
+        Self {
+            client,
+            multicaller_address,
+            inventory_config,
+            market: None,
+            accounts_nonce_and_balance: None,
+            market_events: None,
+            health_monitor_channel_tx: None,
+            _n: PhantomData,
+        }
+    }
+
+    pub fn on_bc(self, bc: &Blockchain) -> Self {
+        Self {
+            market: Some(bc.market()),
+            accounts_nonce_and_balance: Some(bc.nonce_and_balance()),
+            market_events: Some(bc.market_events_channel()),
+            health_monitor_channel_tx: Some(bc.health_monitor_channel()),
+            ..self
+        }
+    }
+}
+
+impl<P, N> Actor for InventoryMonitorActor<P, N>
+where
+    N: Network,
+    P: Provider<N> + Send + Sync + Clone + 'static,
+{
+    fn start(&self) -> ActorResult {
+        let task = tokio::task::spawn(inventory_monitor_worker(
+            self.client.clone(),
+            self.multicaller_address,
+            self.inventory_config.clone(),
+            self.market.clone().unwrap(),
+            self.accounts_nonce_and_balance.clone().unwrap(),
+            self.market_events.clone().unwrap(),
+            self.health_monitor_channel_tx.clone().unwrap(),
+        ));
+        Ok(vec![task])
+    }
+
+    fn name(&self) -> &'static str {
+        "InventoryMonitorActor"
+    }
+}
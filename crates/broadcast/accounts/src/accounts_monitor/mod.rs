@@ -1,3 +1,5 @@
 mod accounts_actor;
+mod inventory_actor;
 
 pub use accounts_actor::NonceAndBalanceMonitorActor;
+pub use inventory_actor::InventoryMonitorActor;
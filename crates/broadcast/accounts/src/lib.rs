@@ -1,4 +1,4 @@
-pub use crate::accounts_monitor::NonceAndBalanceMonitorActor;
+pub use crate::accounts_monitor::{InventoryMonitorActor, NonceAndBalanceMonitorActor};
 pub use crate::signers::{InitializeSignersOneShotBlockingActor, TxSignersActor};
 
 mod accounts_monitor;
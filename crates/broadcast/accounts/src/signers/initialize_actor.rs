@@ -65,7 +65,7 @@ impl InitializeSignersOneShotBlockingActor {
 }
 
 impl Actor for InitializeSignersOneShotBlockingActor {
-    fn start_and_wait(&self) -> eyre::Result<()> {
+    fn start(&self) -> ActorResult {
         let key = match self.key.clone() {
             Some(key) => key,
             _ => {
@@ -81,16 +81,8 @@ impl Actor for InitializeSignersOneShotBlockingActor {
             }
         };
 
-        let rt = tokio::runtime::Runtime::new()?; // we need a different runtime to wait for the result
-        let handle = rt.spawn(async { initialize_signers_one_shot_worker(key, signers, monitor).await });
-
-        self.wait(Ok(vec![handle]))?;
-        rt.shutdown_background();
-
-        Ok(())
-    }
-    fn start(&self) -> ActorResult {
-        Err(eyre!("NEED_TO_BE_WAITED"))
+        let handle = tokio::task::spawn(initialize_signers_one_shot_worker(key, signers, monitor));
+        Ok(vec![handle])
     }
 
     fn name(&self) -> &'static str {
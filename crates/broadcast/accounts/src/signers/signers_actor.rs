@@ -1,10 +1,9 @@
 use alloy_primitives::Bytes;
 use eyre::{eyre, Result};
 use tokio::sync::broadcast::error::RecvError;
-use tokio::sync::broadcast::Receiver;
 use tracing::{error, info};
 
-use loom_core_actors::{Actor, ActorResult, Broadcaster, Consumer, Producer, WorkerResult};
+use loom_core_actors::{Actor, ActorResult, Broadcaster, BroadcasterReceiver, Consumer, Producer, WorkerResult};
 use loom_core_actors_macros::{Accessor, Consumer, Producer};
 use loom_core_blockchain::Blockchain;
 use loom_types_blockchain::{LoomDataTypes, LoomDataTypesEthereum, LoomTx};
@@ -62,7 +61,7 @@ async fn request_listener_worker<LDT: LoomDataTypes>(
     compose_channel_rx: Broadcaster<MessageTxCompose<LDT>>,
     compose_channel_tx: Broadcaster<MessageTxCompose<LDT>>,
 ) -> WorkerResult {
-    let mut compose_channel_rx: Receiver<MessageTxCompose<LDT>> = compose_channel_rx.subscribe();
+    let mut compose_channel_rx: BroadcasterReceiver<MessageTxCompose<LDT>> = compose_channel_rx.subscribe();
 
     loop {
         tokio::select! {
@@ -4,13 +4,16 @@ use alloy_network::Ethereum;
 use alloy_primitives::Bytes;
 use alloy_provider::Provider;
 use eyre::{eyre, Result};
-use tokio::sync::broadcast::error::RecvError;
-use tracing::error;
+use tokio::sync::broadcast::error::{RecvError, TryRecvError};
+use tokio::task::JoinSet;
+use tokio_util::sync::CancellationToken;
+use tracing::{error, warn};
 
 use loom_broadcast_flashbots::Flashbots;
-use loom_core_actors::{subscribe, Actor, ActorResult, Broadcaster, Consumer, WorkerResult};
+use loom_core_actors::{subscribe, Accessor, Actor, ActorResult, Broadcaster, Consumer, SharedState, ShutdownStage, WorkerResult};
 use loom_core_actors_macros::{Accessor, Consumer};
 use loom_core_blockchain::Blockchain;
+use loom_types_entities::{ControlFlags, HeadHealth};
 use loom_types_events::{MessageTxCompose, RlpState, TxComposeData, TxComposeMessageType};
 
 async fn broadcast_task<P>(broadcast_request: TxComposeData, client: Arc<Flashbots<P>>) -> Result<()>
@@ -38,17 +41,40 @@ where
     }
 }
 
+async fn is_broadcast_allowed(
+    head_health: &Option<SharedState<HeadHealth>>,
+    control_flags: &Option<SharedState<ControlFlags>>,
+    allow_broadcast: bool,
+    block: u64,
+) -> bool {
+    let is_head_fresh = match head_health {
+        Some(head_health) => head_health.read().await.is_fresh(),
+        None => true,
+    };
+    if !is_head_fresh {
+        warn!(block, "Head is not fresh, broadcast skipped");
+        return false;
+    }
+    match control_flags {
+        Some(control_flags) => control_flags.read().await.is_broadcast_allowed(allow_broadcast),
+        None => allow_broadcast,
+    }
+}
+
 async fn flashbots_broadcaster_worker<P>(
     client: Arc<Flashbots<P>>,
     bundle_rx: Broadcaster<MessageTxCompose>,
+    head_health: Option<SharedState<HeadHealth>>,
+    control_flags: Option<SharedState<ControlFlags>>,
     allow_broadcast: bool,
+    shutdown_token: CancellationToken,
 ) -> WorkerResult
 where
     P: Provider<Ethereum> + Send + Sync + Clone + 'static,
 {
     subscribe!(bundle_rx);
 
-    //let mut current_block: u64 = 0;
+    let mut broadcasts: JoinSet<Result<()>> = JoinSet::new();
 
     loop {
         tokio::select! {
@@ -56,15 +82,10 @@ where
                 let broadcast_msg : Result<MessageTxCompose, RecvError> = msg;
                 match broadcast_msg {
                     Ok(compose_request) => {
-                        if let TxComposeMessageType::Broadcast(broadcast_request)  = compose_request.inner {
-                            if allow_broadcast {
-                                      tokio::task::spawn(
-                                        broadcast_task(
-                                            broadcast_request,
-                                            client.clone(),
-                                        )
-                                    );
-                                }
+                        if let TxComposeMessageType::Broadcast(broadcast_request) = compose_request.inner {
+                            if is_broadcast_allowed(&head_health, &control_flags, allow_broadcast, broadcast_request.next_block_number).await {
+                                broadcasts.spawn(broadcast_task(broadcast_request, client.clone()));
+                            }
 
                             //TODO : Move smart mode to Strategy router
                             /*
@@ -100,13 +121,48 @@ where
                              */
                         }
                     }
-                    Err(e)=>{
-                        error!("flashbots_broadcaster_worker {}", e)
+                    Err(RecvError::Lagged(lag)) => {
+                        warn!(lag, "Flashbots broadcaster lagged");
+                    }
+                    Err(RecvError::Closed) => {
+                        error!("Tx compose channel closed");
+                        return Err(eyre!("TX_COMPOSE_CHANNEL_CLOSED"));
+                    }
+                }
+            }
+            Some(result) = broadcasts.join_next() => {
+                if let Ok(Err(error)) = result {
+                    error!(%error, "Broadcast failed");
+                }
+            }
+            _ = shutdown_token.cancelled() => {
+                break;
+            }
+        }
+    }
+
+    // send bundles composed before shutdown and wait for in-flight broadcasts
+    let mut drained = 0;
+    loop {
+        match bundle_rx.try_recv() {
+            Ok(compose_request) => {
+                if let TxComposeMessageType::Broadcast(broadcast_request) = compose_request.inner {
+                    if is_broadcast_allowed(&head_health, &control_flags, allow_broadcast, broadcast_request.next_block_number).await {
+                        broadcasts.spawn(broadcast_task(broadcast_request, client.clone()));
+                        drained += 1;
                     }
                 }
             }
+            Err(TryRecvError::Lagged(lag)) => warn!(lag, "Flashbots broadcaster lagged on shutdown"),
+            Err(_) => break,
         }
     }
+    while let Some(result) = broadcasts.join_next().await {
+        if let Ok(Err(error)) = result {
+            error!(%error, "Broadcast failed");
+        }
+    }
+    Ok(format!("Flashbots broadcaster stopped, {drained} buffered bundles sent"))
 }
 
 #[derive(Accessor, Consumer)]
@@ -114,6 +170,10 @@ pub struct FlashbotsBroadcastActor<P> {
     client: Arc<Flashbots<P>>,
     #[consumer]
     tx_compose_channel_rx: Option<Broadcaster<MessageTxCompose>>,
+    #[accessor]
+    head_health: Option<SharedState<HeadHealth>>,
+    #[accessor]
+    control_flags: Option<SharedState<ControlFlags>>,
     allow_broadcast: bool,
 }
 
@@ -122,11 +182,22 @@ where
     P: Provider<Ethereum> + Send + Sync + Clone + 'static,
 {
     pub fn new(client: Flashbots<P>, allow_broadcast: bool) -> FlashbotsBroadcastActor<P> {
-        FlashbotsBroadcastActor { client: Arc::new(client), tx_compose_channel_rx: None, allow_broadcast }
+        FlashbotsBroadcastActor {
+            client: Arc::new(client),
+            tx_compose_channel_rx: None,
+            head_health: None,
+            control_flags: None,
+            allow_broadcast,
+        }
     }
 
     pub fn on_bc(self, bc: &Blockchain) -> Self {
-        Self { tx_compose_channel_rx: Some(bc.tx_compose_channel()), ..self }
+        Self {
+            tx_compose_channel_rx: Some(bc.tx_compose_channel()),
+            head_health: Some(bc.head_health()),
+            control_flags: Some(bc.control_flags()),
+            ..self
+        }
     }
 }
 
@@ -135,14 +206,25 @@ where
     P: Provider<Ethereum> + Send + Sync + Clone + 'static,
 {
     fn start(&self) -> ActorResult {
+        self.start_until(CancellationToken::new())
+    }
+
+    fn start_until(&self, shutdown_token: CancellationToken) -> ActorResult {
         let task = tokio::task::spawn(flashbots_broadcaster_worker(
             self.client.clone(),
             self.tx_compose_channel_rx.clone().unwrap(),
+            self.head_health.clone(),
+            self.control_flags.clone(),
             self.allow_broadcast,
+            shutdown_token,
         ));
         Ok(vec![task])
     }
 
+    fn shutdown_stage(&self) -> ShutdownStage {
+        ShutdownStage::Writer
+    }
+
     fn name(&self) -> &'static str {
         "FlashbotsBroadcastActor"
     }
@@ -1,7 +1,7 @@
 use proc_macro::TokenStream;
 
 use quote::quote;
-use syn::{parse_macro_input, Data, DeriveInput, Field, Fields, PathArguments, Type};
+use syn::{parse_macro_input, Data, DeriveInput, Field, Fields, Ident, PathArguments, Type};
 
 #[proc_macro_derive(Accessor, attributes(accessor))]
 pub fn derive_access(input: TokenStream) -> TokenStream {
@@ -72,6 +72,29 @@ pub fn derive_access(input: TokenStream) -> TokenStream {
     expanded.into()
 }
 
+/// Returns the channel type name and the message type of an `Option<Channel<T>>` field
+fn channel_type(ty: &Type) -> (Ident, Type) {
+    match ty {
+        Type::Path(type_path) => match &type_path.path.segments[0].arguments {
+            PathArguments::AngleBracketed(params) => match &params.args[0] {
+                syn::GenericArgument::Type(Type::Path(channel_path)) => {
+                    let channel = channel_path.path.segments.last().expect("Expected channel type");
+                    match &channel.arguments {
+                        PathArguments::AngleBracketed(params) => match &params.args[0] {
+                            syn::GenericArgument::Type(ty) => (channel.ident.clone(), ty.clone()),
+                            _ => panic!("Expected type parameter"),
+                        },
+                        _ => panic!("Expected Angle Brackets"),
+                    }
+                }
+                _ => panic!("Expected DataType parameter"),
+            },
+            _ => panic!("Expected Angle Brackets"),
+        },
+        _ => panic!("Expected Option"),
+    }
+}
+
 #[proc_macro_derive(Consumer, attributes(consumer))]
 pub fn derive_consumer(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
@@ -104,31 +127,33 @@ pub fn derive_consumer(input: TokenStream) -> TokenStream {
         .into_iter()
         .map(|field| {
             let field_name = field.ident.expect("Field needs name");
-            let field_type = match field.ty.clone() {
-                Type::Path(type_path) => match &type_path.path.segments[0].arguments.clone() {
-                    PathArguments::AngleBracketed(params) => match &params.args[0] {
-                        syn::GenericArgument::Type(ty) => match &ty {
-                            Type::Path(type_path) => match &type_path.path.segments[0].arguments.clone() {
-                                PathArguments::AngleBracketed(params) => match &params.args[0] {
-                                    syn::GenericArgument::Type(ty) => ty.clone(),
-                                    _ => panic!("Excpected type parameter"),
-                                },
-                                _ => panic!("Expected Angle Brackets"),
-                            },
-                            _ => panic!("Expected DataType parameter"),
-                        },
-                        _ => panic!("Expected type parameter"),
-                    },
-                    _ => panic!("Expected Angle Brackets"),
-                },
-                _ => panic!("Expected Option"),
-            };
-
-            quote! {
-                impl #impl_generics Consumer<#field_type> for #name #ty_generics #where_clause {
-                    fn consume(&mut self, value : Broadcaster<#field_type>) -> &mut Self {
-                        self.#field_name = Some(value);
-                        self
+            let (channel, field_type) = channel_type(&field.ty);
+
+            if channel == "WorkQueue" {
+                quote! {
+                    impl #impl_generics QueueConsumer<#field_type> for #name #ty_generics #where_clause {
+                        fn consume_queue(&mut self, value : WorkQueue<#field_type>) -> &mut Self {
+                            self.#field_name = Some(value);
+                            self
+                        }
+                    }
+                }
+            } else if channel == "Mpsc" {
+                quote! {
+                    impl #impl_generics MpscConsumer<#field_type> for #name #ty_generics #where_clause {
+                        fn consume_mpsc(&mut self, value : Mpsc<#field_type>) -> &mut Self {
+                            self.#field_name = Some(value);
+                            self
+                        }
+                    }
+                }
+            } else {
+                quote! {
+                    impl #impl_generics Consumer<#field_type> for #name #ty_generics #where_clause {
+                        fn consume(&mut self, value : Broadcaster<#field_type>) -> &mut Self {
+                            self.#field_name = Some(value);
+                            self
+                        }
                     }
                 }
             }
@@ -173,31 +198,33 @@ pub fn derive_producer(input: TokenStream) -> TokenStream {
         .into_iter()
         .map(|field| {
             let field_name = field.ident.expect("Field needs name");
-            let field_type = match field.ty.clone() {
-                Type::Path(type_path) => match &type_path.path.segments[0].arguments.clone() {
-                    PathArguments::AngleBracketed(params) => match &params.args[0] {
-                        syn::GenericArgument::Type(ty) => match &ty {
-                            Type::Path(type_path) => match &type_path.path.segments[0].arguments.clone() {
-                                PathArguments::AngleBracketed(params) => match &params.args[0] {
-                                    syn::GenericArgument::Type(ty) => ty.clone(),
-                                    _ => panic!("Expected type parameter"),
-                                },
-                                _ => panic!("Expected Angle Brackets"),
-                            },
-                            _ => panic!("Expected DataType parameter"),
-                        },
-                        _ => panic!("Excected type parameter"),
-                    },
-                    _ => panic!("Expected Angle Brackets"),
-                },
-                _ => panic!("Expected Option"),
-            };
-
-            quote! {
-                impl #impl_generics Producer<#field_type> for #name #ty_generics #where_clause {
-                    fn produce(&mut self, value : Broadcaster<#field_type>) -> &mut Self {
-                        self.#field_name = Some(value);
-                        self
+            let (channel, field_type) = channel_type(&field.ty);
+
+            if channel == "WorkQueue" {
+                quote! {
+                    impl #impl_generics QueueProducer<#field_type> for #name #ty_generics #where_clause {
+                        fn produce_queue(&mut self, value : WorkQueue<#field_type>) -> &mut Self {
+                            self.#field_name = Some(value);
+                            self
+                        }
+                    }
+                }
+            } else if channel == "Mpsc" {
+                quote! {
+                    impl #impl_generics MpscProducer<#field_type> for #name #ty_generics #where_clause {
+                        fn produce_mpsc(&mut self, value : Mpsc<#field_type>) -> &mut Self {
+                            self.#field_name = Some(value);
+                            self
+                        }
+                    }
+                }
+            } else {
+                quote! {
+                    impl #impl_generics Producer<#field_type> for #name #ty_generics #where_clause {
+                        fn produce(&mut self, value : Broadcaster<#field_type>) -> &mut Self {
+                            self.#field_name = Some(value);
+                            self
+                        }
                     }
                 }
             }
@@ -1,7 +1,10 @@
-use crate::channels::Broadcaster;
+use crate::channels::{Broadcaster, Mpsc, WorkQueue};
 use crate::shared_state::SharedState;
+use crate::shutdown::{Shutdown, ShutdownStage};
 use eyre::{eyre, Result};
+use std::future::Future;
 use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
 use tracing::info;
 
 pub type WorkerResult = Result<String>;
@@ -9,11 +12,14 @@ pub type WorkerResult = Result<String>;
 pub type ActorResult = Result<Vec<JoinHandle<WorkerResult>>>;
 
 pub trait Actor {
-    fn wait(&self, handles: ActorResult) -> Result<()> {
-        let handles = handles?;
+    /// Waits for the workers of a one-shot actor
+    fn wait(&self, handles: ActorResult) -> impl Future<Output = Result<()>> + Send
+    where
+        Self: Sized,
+    {
         let actor_name = self.name();
-        futures::executor::block_on(async {
-            for handle in handles {
+        async move {
+            for handle in handles? {
                 match handle.await {
                     Ok(result) => match result {
                         Ok(msg) => info!("One-shot actor '{}' completed with message: {}", actor_name, msg),
@@ -23,16 +29,35 @@ pub trait Actor {
                 }
             }
             Ok(())
-        })
+        }
     }
 
-    fn start_and_wait(&self) -> Result<()> {
-        let handles = self.start();
-        self.wait(handles)
+    fn start_and_wait(&self) -> impl Future<Output = Result<()>> + Send
+    where
+        Self: Sized,
+    {
+        self.wait(self.start())
     }
 
     fn start(&self) -> ActorResult;
 
+    /// Start workers that stop gracefully when `shutdown_token` is cancelled.
+    /// Actors that do not override it ignore the token and their workers are aborted on shutdown.
+    fn start_until(&self, _shutdown_token: CancellationToken) -> ActorResult {
+        self.start()
+    }
+
+    /// Start workers with the token of the actor's shutdown stage, the workers are tracked by the stage of `shutdown`
+    fn start_with_shutdown(&self, shutdown: &Shutdown) -> ActorResult {
+        let stage = self.shutdown_stage();
+        let workers = self.start_until(shutdown.stage_token(stage))?;
+        Ok(workers.into_iter().map(|worker| shutdown.track(stage, worker)).collect())
+    }
+
+    fn shutdown_stage(&self) -> ShutdownStage {
+        ShutdownStage::Processor
+    }
+
     fn name(&self) -> &'static str;
 }
 
@@ -54,6 +79,46 @@ where
     }
 }
 
+/// Sends to a [`WorkQueue`], implemented by `#[producer]` fields of type `Option<WorkQueue<T>>`
+pub trait QueueProducer<T>
+where
+    T: Send,
+{
+    fn produce_queue(&mut self, _queue: WorkQueue<T>) -> &mut Self {
+        panic!("Not implemented");
+    }
+}
+
+/// Receives from a [`WorkQueue`], implemented by `#[consumer]` fields of type `Option<WorkQueue<T>>`
+pub trait QueueConsumer<T>
+where
+    T: Send,
+{
+    fn consume_queue(&mut self, _queue: WorkQueue<T>) -> &mut Self {
+        panic!("Not implemented");
+    }
+}
+
+/// Sends to an [`Mpsc`] channel, implemented by `#[producer]` fields of type `Option<Mpsc<T>>`
+pub trait MpscProducer<T>
+where
+    T: Send,
+{
+    fn produce_mpsc(&mut self, _channel: Mpsc<T>) -> &mut Self {
+        panic!("Not implemented");
+    }
+}
+
+/// Receives from an [`Mpsc`] channel, implemented by `#[consumer]` fields of type `Option<Mpsc<T>>`
+pub trait MpscConsumer<T>
+where
+    T: Send,
+{
+    fn consume_mpsc(&mut self, _channel: Mpsc<T>) -> &mut Self {
+        panic!("Not implemented");
+    }
+}
+
 pub trait Accessor<T> {
     fn access(&mut self, _data: SharedState<T>) -> &mut Self {
         panic!("Not implemented");
@@ -1,12 +1,73 @@
-use eyre::Result;
-use tokio::task::JoinHandle;
-use tracing::{error, info};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
 
-use crate::{Actor, WorkerResult};
+use eyre::{eyre, Result};
+use futures::future::BoxFuture;
+use futures::stream::{FuturesUnordered, StreamExt};
+use tokio::task::{AbortHandle, JoinError, JoinHandle};
+use tracing::{error, info, warn};
 
-#[derive(Default)]
+use crate::shutdown::{Shutdown, ShutdownStage};
+use crate::supervisor::{ActorExit, ActorExitReason, RestartTracker, SupervisorPolicy};
+use crate::{Actor, Broadcaster, WorkerResult};
+
+/// actor index, actor generation, worker index, result
+type WorkerExit = (usize, usize, usize, Result<WorkerResult, JoinError>);
+
+struct SupervisedActor {
+    actor: Box<dyn Actor + Send + Sync>,
+    stage: ShutdownStage,
+    tracker: RestartTracker,
+    // workers of previous generations were aborted on restart and their exits are ignored
+    generation: usize,
+    abort_handles: Vec<AbortHandle>,
+    alive: usize,
+}
+
+impl SupervisedActor {
+    fn abort(&mut self) {
+        for abort_handle in self.abort_handles.drain(..) {
+            abort_handle.abort();
+        }
+        self.generation += 1;
+        self.alive = 0;
+    }
+}
+
+async fn join_worker(actor_idx: usize, generation: usize, worker_idx: usize, handle: JoinHandle<WorkerResult>) -> WorkerExit {
+    (actor_idx, generation, worker_idx, handle.await)
+}
+
+async fn restart_after(actor_idx: usize, delay: Duration) -> usize {
+    tokio::time::sleep(delay).await;
+    actor_idx
+}
+
+/// Starts actors and supervises their workers according to per-actor [`SupervisorPolicy`].
 pub struct ActorsManager {
-    tasks: Vec<JoinHandle<WorkerResult>>,
+    actors: Vec<SupervisedActor>,
+    workers: FuturesUnordered<BoxFuture<'static, WorkerExit>>,
+    restarts: FuturesUnordered<BoxFuture<'static, usize>>,
+    policies: HashMap<&'static str, SupervisorPolicy>,
+    default_policy: SupervisorPolicy,
+    exit_channel: Broadcaster<ActorExit>,
+    shutdown: Shutdown,
+    shutdown_grace: Duration,
+}
+
+impl Default for ActorsManager {
+    fn default() -> Self {
+        Self {
+            actors: Vec::new(),
+            workers: FuturesUnordered::new(),
+            restarts: FuturesUnordered::new(),
+            policies: HashMap::new(),
+            default_policy: SupervisorPolicy::default(),
+            exit_channel: Broadcaster::new(100),
+            shutdown: Shutdown::new(),
+            shutdown_grace: Duration::from_secs(5),
+        }
+    }
 }
 
 impl ActorsManager {
@@ -14,11 +75,56 @@ impl ActorsManager {
         Self::default()
     }
 
-    pub fn start(&mut self, actor: impl Actor + 'static) -> Result<()> {
-        match actor.start() {
+    /// Policy for actors started without an explicit one
+    pub fn set_default_policy(&mut self, policy: SupervisorPolicy) {
+        self.default_policy = policy;
+    }
+
+    /// Policy for actors with the name, must be set before the actor is started
+    pub fn set_policy(&mut self, actor_name: &'static str, policy: SupervisorPolicy) {
+        self.policies.insert(actor_name, policy);
+    }
+
+    /// Time given to actors of every shutdown stage to stop before they are aborted
+    pub fn set_shutdown_grace(&mut self, shutdown_grace: Duration) {
+        self.shutdown_grace = shutdown_grace;
+    }
+
+    /// Share the shutdown of an outer owner, must be set before actors are started
+    pub fn set_shutdown(&mut self, shutdown: Shutdown) {
+        self.shutdown = shutdown;
+    }
+
+    /// Channel with [`ActorExit`] events of all supervised workers
+    pub fn exit_channel(&self) -> Broadcaster<ActorExit> {
+        self.exit_channel.clone()
+    }
+
+    /// Request shutdown with [`Shutdown::request`] to stop all actors in order
+    pub fn shutdown(&self) -> Shutdown {
+        self.shutdown.clone()
+    }
+
+    pub fn start(&mut self, actor: impl Actor + Send + Sync + 'static) -> Result<()> {
+        let policy = self.policies.get(actor.name()).copied().unwrap_or(self.default_policy);
+        self.start_with_policy(actor, policy)
+    }
+
+    pub fn start_with_policy(&mut self, actor: impl Actor + Send + Sync + 'static, policy: SupervisorPolicy) -> Result<()> {
+        match actor.start_with_shutdown(&self.shutdown) {
             Ok(workers) => {
                 info!("{} started successfully", actor.name());
-                self.tasks.extend(workers);
+                let actor_idx = self.actors.len();
+                let alive = workers.len();
+                let abort_handles = self.push_workers(actor_idx, 0, workers);
+                self.actors.push(SupervisedActor {
+                    stage: actor.shutdown_stage(),
+                    actor: Box::new(actor),
+                    tracker: RestartTracker::new(policy, Instant::now()),
+                    generation: 0,
+                    abort_handles,
+                    alive,
+                });
                 Ok(())
             }
             Err(e) => {
@@ -28,8 +134,8 @@ impl ActorsManager {
         }
     }
 
-    pub fn start_and_wait(&mut self, actor: impl Actor + Send + Sync + 'static) -> Result<()> {
-        match actor.start_and_wait() {
+    pub async fn start_and_wait(&mut self, actor: impl Actor + Send + Sync + 'static) -> Result<()> {
+        match actor.start_and_wait().await {
             Ok(_) => {
                 info!("{} started successfully", actor.name());
                 Ok(())
@@ -41,27 +147,209 @@ impl ActorsManager {
         }
     }
 
-    pub async fn wait(self) {
-        let mut f_remaining_futures = self.tasks;
-        let mut futures_counter = f_remaining_futures.len();
+    /// Waits for all workers, restarting actors according to their policies.
+    /// On shutdown request actors are stopped in the order of their [`ShutdownStage`].
+    /// Returns an error when a critical actor exits and is not restarted, all other actors are stopped in that case.
+    pub async fn wait(mut self) -> Result<()> {
+        let shutdown = self.shutdown.clone();
+        // the shutdown branch never completes by itself, return once all workers are gone like before supervision
+        while !self.workers.is_empty() || !self.restarts.is_empty() {
+            let is_fatal = tokio::select! {
+                Some(worker_exit) = self.workers.next() => self.on_worker_exit(worker_exit),
+                Some(actor_idx) = self.restarts.next() => self.restart(actor_idx),
+                _ = shutdown.requested() => {
+                    self.stop_in_order().await;
+                    return Ok(());
+                }
+                else => break,
+            };
+            if is_fatal {
+                self.stop_in_order().await;
+                return Err(eyre!("CRITICAL_ACTOR_EXITED"));
+            }
+        }
+        Ok(())
+    }
 
-        while futures_counter > 0 {
-            let (result, _index, remaining_futures) = futures::future::select_all(f_remaining_futures).await;
-            match result {
-                Ok(work_result) => match work_result {
-                    Ok(s) => {
-                        info!("ActorWorker {_index} finished : {s}")
-                    }
-                    Err(e) => {
-                        error!("ActorWorker {_index} finished with error : {e}")
+    fn push_workers(&mut self, actor_idx: usize, generation: usize, workers: Vec<JoinHandle<WorkerResult>>) -> Vec<AbortHandle> {
+        let abort_handles = workers.iter().map(|handle| handle.abort_handle()).collect();
+        for (worker_idx, handle) in workers.into_iter().enumerate() {
+            self.workers.push(Box::pin(join_worker(actor_idx, generation, worker_idx, handle)));
+        }
+        abort_handles
+    }
+
+    /// Returns true if a critical actor stopped
+    fn on_worker_exit(&mut self, (actor_idx, generation, worker_idx, result): WorkerExit) -> bool {
+        if self.actors[actor_idx].generation != generation {
+            return false;
+        }
+        let reason = match result {
+            Ok(Ok(msg)) => ActorExitReason::Finished(msg),
+            Ok(Err(e)) => ActorExitReason::Failed(e.to_string()),
+            Err(e) if e.is_panic() => ActorExitReason::Panicked(e.to_string()),
+            Err(_) => ActorExitReason::Cancelled,
+        };
+        self.on_exit(actor_idx, worker_idx, reason)
+    }
+
+    fn on_exit(&mut self, actor_idx: usize, worker_idx: usize, reason: ActorExitReason) -> bool {
+        let is_shutdown = self.shutdown.is_requested();
+        let supervised = &mut self.actors[actor_idx];
+        supervised.alive = supervised.alive.saturating_sub(1);
+
+        // finished workers did their job, one-shot actors must not run again
+        let restart_in = match reason {
+            ActorExitReason::Failed(_) | ActorExitReason::Panicked(_) if !is_shutdown => supervised.tracker.next_restart(Instant::now()),
+            _ => None,
+        };
+
+        if let Some(delay) = restart_in {
+            // remaining workers are restarted together with the exited one
+            supervised.abort();
+            self.restarts.push(Box::pin(restart_after(actor_idx, delay)));
+        }
+
+        let exit = ActorExit {
+            actor_name: supervised.actor.name(),
+            worker_index: worker_idx,
+            reason,
+            restarts: supervised.tracker.restarts(),
+            restart_in,
+            critical: supervised.tracker.policy().critical,
+        };
+
+        match &exit.reason {
+            ActorExitReason::Finished(msg) => {
+                info!(actor = exit.actor_name, worker = worker_idx, restart_in = ?exit.restart_in, "ActorWorker finished : {msg}")
+            }
+            ActorExitReason::Cancelled => {
+                warn!(actor = exit.actor_name, worker = worker_idx, restart_in = ?exit.restart_in, "ActorWorker cancelled")
+            }
+            ActorExitReason::Failed(e) | ActorExitReason::Panicked(e) => {
+                error!(actor = exit.actor_name, worker = worker_idx, restart_in = ?exit.restart_in, "ActorWorker finished with error : {e}")
+            }
+        }
+
+        let is_fatal = !is_shutdown && exit.is_fatal();
+        let actor_name = exit.actor_name;
+        // nobody may listen to exits
+        let _ = self.exit_channel.send(exit);
+
+        if is_fatal {
+            error!(actor = actor_name, "Critical actor stopped, stopping all actors");
+        }
+        is_fatal
+    }
+
+    /// Returns true if a critical actor failed to restart and stopped
+    fn restart(&mut self, actor_idx: usize) -> bool {
+        if self.shutdown.is_requested() {
+            return false;
+        }
+        let supervised = &mut self.actors[actor_idx];
+        let generation = supervised.generation;
+        match supervised.actor.start_with_shutdown(&self.shutdown) {
+            Ok(workers) => {
+                info!(actor = supervised.actor.name(), restarts = supervised.tracker.restarts(), "Actor restarted");
+                supervised.tracker.on_started(Instant::now());
+                supervised.alive = workers.len();
+                let abort_handles = self.push_workers(actor_idx, generation, workers);
+                self.actors[actor_idx].abort_handles = abort_handles;
+                false
+            }
+            Err(e) => {
+                // on_exit accounts for one worker
+                supervised.alive = 1;
+                self.on_exit(actor_idx, 0, ActorExitReason::Failed(format!("restart failed : {e}")))
+            }
+        }
+    }
+
+    /// Stop producers first, then processors and writers. Actors that do not stop within the grace period are aborted.
+    async fn stop_in_order(&mut self) {
+        self.shutdown.request();
+        self.restarts.clear();
+
+        for stage in ShutdownStage::ORDERED {
+            info!(?stage, "Stopping actors");
+            self.shutdown.cancel_stage(stage);
+
+            let deadline = tokio::time::sleep(self.shutdown_grace);
+            tokio::pin!(deadline);
+
+            while self.actors.iter().any(|a| a.stage == stage && a.alive > 0) {
+                tokio::select! {
+                    Some(worker_exit) = self.workers.next() => {
+                        self.on_worker_exit(worker_exit);
                     }
-                },
-                Err(e) => {
-                    error!("ActorWorker join error {_index} : {e}")
+                    _ = &mut deadline => break,
                 }
             }
-            f_remaining_futures = remaining_futures;
-            futures_counter = f_remaining_futures.len();
+
+            for supervised in self.actors.iter_mut().filter(|a| a.stage == stage && a.alive > 0) {
+                warn!(actor = supervised.actor.name(), ?stage, "Actor did not stop in time, aborting");
+                supervised.abort();
+            }
+        }
+        info!("All actors stopped");
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ActorResult;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    struct CountingActor {
+        starts: Arc<AtomicUsize>,
+        fail: bool,
+    }
+
+    impl Actor for CountingActor {
+        fn start(&self) -> ActorResult {
+            self.starts.fetch_add(1, Ordering::SeqCst);
+            let fail = self.fail;
+            let task = tokio::task::spawn(async move {
+                if fail {
+                    Err(eyre!("WORKER_FAILED"))
+                } else {
+                    Ok("done".to_string())
+                }
+            });
+            Ok(vec![task])
+        }
+
+        fn name(&self) -> &'static str {
+            "CountingActor"
+        }
+    }
+
+    #[tokio::test]
+    async fn test_finished_worker_is_not_restarted() {
+        let policy = SupervisorPolicy::always(Duration::ZERO).with_max_restarts(2, Duration::from_secs(60));
+
+        let finished_starts = Arc::new(AtomicUsize::new(0));
+        let failed_starts = Arc::new(AtomicUsize::new(0));
+        let mut actor_manager = ActorsManager::new();
+        actor_manager.start_with_policy(CountingActor { starts: finished_starts.clone(), fail: false }, policy).unwrap();
+        actor_manager.start_with_policy(CountingActor { starts: failed_starts.clone(), fail: true }, policy).unwrap();
+
+        let mut exit_rx = actor_manager.exit_channel().subscribe();
+        tokio::time::timeout(Duration::from_secs(1), actor_manager.wait()).await.unwrap().unwrap();
+
+        assert_eq!(finished_starts.load(Ordering::SeqCst), 1);
+        // first start and two restarts
+        assert_eq!(failed_starts.load(Ordering::SeqCst), 3);
+
+        let mut exits = Vec::new();
+        while let Ok(exit) = exit_rx.try_recv() {
+            exits.push(exit);
         }
+        let finished: Vec<&ActorExit> = exits.iter().filter(|exit| matches!(exit.reason, ActorExitReason::Finished(_))).collect();
+        assert_eq!(finished.len(), 1);
+        assert!(!finished[0].is_restarted());
     }
 }
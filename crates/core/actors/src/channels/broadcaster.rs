@@ -1,43 +1,174 @@
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+
 use eyre::Result;
 use tokio::sync::broadcast;
-use tokio::sync::broadcast::error::SendError;
+use tokio::sync::broadcast::error::{RecvError, SendError, TryRecvError};
 use tokio::sync::broadcast::Receiver;
 
+use crate::channels::registry::{register_channel, short_type_name, ChannelInfo, ChannelSnapshot, ChannelStats, SubscriberStats};
+
+struct BroadcasterInner<T> {
+    name: String,
+    capacity: usize,
+    sender: broadcast::Sender<T>,
+    stats: Arc<ChannelStats>,
+}
+
+impl<T: Clone + Send + Sync + 'static> ChannelInfo for BroadcasterInner<T> {
+    fn snapshot(&self) -> ChannelSnapshot {
+        let subscribers = self.stats.subscribers();
+
+        ChannelSnapshot {
+            name: self.name.clone(),
+            capacity: self.capacity,
+            depth: self.sender.len(),
+            receivers: self.sender.receiver_count(),
+            sent: self.stats.sent.load(Ordering::Relaxed),
+            send_errors: self.stats.send_errors.load(Ordering::Relaxed),
+            lagged: subscribers.iter().map(|s| s.lagged).sum(),
+            subscribers,
+        }
+    }
+}
+
+/// Broadcast channel with a name and send, receive and lag counters readable from the channel registry.
 #[derive(Clone)]
 pub struct Broadcaster<T>
 where
     T: Clone + Send + Sync + 'static,
 {
-    sender: broadcast::Sender<T>,
+    inner: Arc<BroadcasterInner<T>>,
 }
 
 impl<T: Clone + Send + Sync + 'static> Broadcaster<T> {
+    /// Channel named after the message type
     pub fn new(capacity: usize) -> Self {
+        Self::named(short_type_name::<T>(), capacity)
+    }
+
+    pub fn named(name: &str, capacity: usize) -> Self {
         let (sender, _) = broadcast::channel(capacity);
-        Self { sender }
+        let inner = Arc::new(BroadcasterInner { name: name.to_string(), capacity, sender, stats: Arc::new(ChannelStats::default()) });
+        let info: Arc<dyn ChannelInfo> = inner.clone();
+        register_channel(Arc::downgrade(&info));
+        Self { inner }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.inner.name
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.inner.capacity
+    }
+
+    /// Messages in the channel not received by all subscribers yet
+    pub fn depth(&self) -> usize {
+        self.inner.sender.len()
     }
 
     pub fn send(&self, value: T) -> Result<usize, SendError<T>> {
-        self.sender.send(value)
-    }
-
-    /*
-    pub fn try_send(&self, value: T) -> Result<usize> {
-        //let sender = self.sender.write().await;
-        match self.sender.try_write() {
-            Ok(guard) => match guard.send(value) {
-                Ok(size) => Ok(size),
-                Err(_) => Err(eyre!("ERROR_SEND")),
-            },
-            Err(e) => {
-                error!("self.sender.try_write {}", e);
-                Err(eyre!("ERROR_WRITE_LOCK"))
+        let result = self.inner.sender.send(value);
+        match result {
+            Ok(_) => self.inner.stats.sent.fetch_add(1, Ordering::Relaxed),
+            Err(_) => self.inner.stats.send_errors.fetch_add(1, Ordering::Relaxed),
+        };
+        result
+    }
+
+    pub fn subscribe(&self) -> BroadcasterReceiver<T> {
+        let (id, stats) = self.inner.stats.add_subscriber();
+        BroadcasterReceiver { id, receiver: self.inner.sender.subscribe(), stats, channel_stats: self.inner.stats.clone() }
+    }
+
+    pub fn snapshot(&self) -> ChannelSnapshot {
+        self.inner.snapshot()
+    }
+}
+
+/// Receiver of a [`Broadcaster`] counting received and lagged messages
+pub struct BroadcasterReceiver<T: Clone> {
+    id: u64,
+    receiver: Receiver<T>,
+    stats: Arc<SubscriberStats>,
+    channel_stats: Arc<ChannelStats>,
+}
+
+impl<T: Clone> BroadcasterReceiver<T> {
+    pub async fn recv(&mut self) -> Result<T, RecvError> {
+        let result = self.receiver.recv().await;
+        match &result {
+            Ok(_) => {
+                self.stats.received.fetch_add(1, Ordering::Relaxed);
+            }
+            Err(RecvError::Lagged(lagged)) => self.on_lagged(*lagged),
+            Err(RecvError::Closed) => {}
+        }
+        result
+    }
+
+    pub fn try_recv(&mut self) -> Result<T, TryRecvError> {
+        let result = self.receiver.try_recv();
+        match &result {
+            Ok(_) => {
+                self.stats.received.fetch_add(1, Ordering::Relaxed);
             }
+            Err(TryRecvError::Lagged(lagged)) => self.on_lagged(*lagged),
+            Err(_) => {}
         }
+        result
+    }
+
+    /// Number of messages not received yet
+    pub fn len(&self) -> usize {
+        self.receiver.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.receiver.is_empty()
     }
-     */
 
-    pub fn subscribe(&self) -> Receiver<T> {
-        self.sender.subscribe()
+    fn on_lagged(&self, lagged: u64) {
+        self.stats.lagged.fetch_add(lagged, Ordering::Relaxed);
+        self.stats.lag_events.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+impl<T: Clone> Drop for BroadcasterReceiver<T> {
+    fn drop(&mut self) {
+        self.channel_stats.remove_subscriber(self.id);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_broadcaster_counts_lag() {
+        let channel: Broadcaster<u64> = Broadcaster::new(2);
+        assert_eq!(channel.name(), "u64");
+
+        let mut fast = channel.subscribe();
+        let mut slow = channel.subscribe();
+
+        channel.send(1).unwrap();
+        assert_eq!(fast.recv().await.unwrap(), 1);
+        channel.send(2).unwrap();
+        channel.send(3).unwrap();
+        channel.send(4).unwrap();
+
+        assert!(matches!(slow.recv().await, Err(RecvError::Lagged(2))));
+        assert_eq!(slow.recv().await.unwrap(), 3);
+
+        let snapshot = channel.snapshot();
+        assert_eq!(snapshot.sent, 4);
+        assert_eq!(snapshot.receivers, 2);
+        assert_eq!(snapshot.lagged, 2);
+        assert_eq!(snapshot.subscribers.len(), 2);
+
+        drop(fast);
+        assert_eq!(channel.snapshot().subscribers.len(), 1);
     }
 }
@@ -1,5 +1,11 @@
 mod broadcaster;
+mod mpsc;
 mod multiproducer;
+mod registry;
+mod work_queue;
 
 pub use broadcaster::*;
+pub use mpsc::{Mpsc, MpscReceiver};
 pub use multiproducer::*;
+pub use registry::{channel_snapshots, ChannelSnapshot, SubscriberSnapshot};
+pub use work_queue::{DropPolicy, WorkQueue, WorkQueueReceiver};
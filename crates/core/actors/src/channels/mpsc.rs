@@ -0,0 +1,191 @@
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+
+use tokio::sync::mpsc::error::{SendError, TryRecvError, TrySendError};
+use tokio::sync::{mpsc, Mutex};
+
+use crate::channels::registry::{register_channel, short_type_name, ChannelInfo, ChannelSnapshot, ChannelStats, SubscriberStats};
+
+struct MpscInner<T> {
+    name: String,
+    capacity: usize,
+    sender: mpsc::Sender<T>,
+    receiver: Mutex<mpsc::Receiver<T>>,
+    stats: Arc<ChannelStats>,
+}
+
+impl<T: Send + 'static> ChannelInfo for MpscInner<T> {
+    fn snapshot(&self) -> ChannelSnapshot {
+        let subscribers = self.stats.subscribers();
+
+        ChannelSnapshot {
+            name: self.name.clone(),
+            capacity: self.capacity,
+            depth: self.capacity - self.sender.capacity(),
+            receivers: subscribers.len(),
+            sent: self.stats.sent.load(Ordering::Relaxed),
+            send_errors: self.stats.send_errors.load(Ordering::Relaxed),
+            lagged: 0,
+            subscribers,
+        }
+    }
+}
+
+/// Bounded multi producer, single consumer channel. Messages are never dropped, senders wait while the channel is full.
+///
+/// Receivers share the receiving end, a second receiver takes over when the first one is dropped, e.g. by an actor restart.
+/// Use a [`crate::WorkQueue`] when several consumers should compete for messages or when messages may be dropped.
+pub struct Mpsc<T> {
+    inner: Arc<MpscInner<T>>,
+}
+
+impl<T> Clone for Mpsc<T> {
+    fn clone(&self) -> Self {
+        Self { inner: self.inner.clone() }
+    }
+}
+
+impl<T: Send + 'static> Mpsc<T> {
+    /// Channel named after the message type
+    pub fn new(capacity: usize) -> Self {
+        Self::named(short_type_name::<T>(), capacity)
+    }
+
+    pub fn named(name: &str, capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+        let (sender, receiver) = mpsc::channel(capacity);
+        let inner = Arc::new(MpscInner {
+            name: name.to_string(),
+            capacity,
+            sender,
+            receiver: Mutex::new(receiver),
+            stats: Arc::new(ChannelStats::default()),
+        });
+        let info: Arc<dyn ChannelInfo> = inner.clone();
+        register_channel(Arc::downgrade(&info));
+        Self { inner }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.inner.name
+    }
+
+    /// Sends a message, waiting for space if the channel is full
+    pub async fn send(&self, value: T) -> Result<(), SendError<T>> {
+        let result = self.inner.sender.send(value).await;
+        self.count_send(result.is_ok());
+        result
+    }
+
+    /// Sends a message without waiting, a full channel returns `Full`
+    pub fn try_send(&self, value: T) -> Result<(), TrySendError<T>> {
+        let result = self.inner.sender.try_send(value);
+        self.count_send(result.is_ok());
+        result
+    }
+
+    pub fn subscribe(&self) -> MpscReceiver<T> {
+        let (id, stats) = self.inner.stats.add_subscriber();
+        MpscReceiver { id, channel: self.clone(), stats }
+    }
+
+    pub fn len(&self) -> usize {
+        self.inner.capacity - self.inner.sender.capacity()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn snapshot(&self) -> ChannelSnapshot {
+        self.inner.snapshot()
+    }
+
+    fn count_send(&self, ok: bool) {
+        match ok {
+            true => self.inner.stats.sent.fetch_add(1, Ordering::Relaxed),
+            false => self.inner.stats.send_errors.fetch_add(1, Ordering::Relaxed),
+        };
+    }
+}
+
+/// Consumer of an [`Mpsc`] channel counting received messages
+pub struct MpscReceiver<T: Send + 'static> {
+    id: u64,
+    channel: Mpsc<T>,
+    stats: Arc<SubscriberStats>,
+}
+
+impl<T: Send + 'static> MpscReceiver<T> {
+    /// Waits for the next message. The channel holds a sender itself, so it is never closed.
+    pub async fn recv(&mut self) -> Option<T> {
+        let value = self.channel.inner.receiver.lock().await.recv().await;
+        if value.is_some() {
+            self.stats.received.fetch_add(1, Ordering::Relaxed);
+        }
+        value
+    }
+
+    /// Returns `Empty` while another receiver is waiting for a message
+    pub fn try_recv(&mut self) -> Result<T, TryRecvError> {
+        let value = self.channel.inner.receiver.try_lock().map_err(|_| TryRecvError::Empty)?.try_recv()?;
+        self.stats.received.fetch_add(1, Ordering::Relaxed);
+        Ok(value)
+    }
+
+    pub fn len(&self) -> usize {
+        self.channel.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.channel.is_empty()
+    }
+}
+
+impl<T: Send + 'static> Drop for MpscReceiver<T> {
+    fn drop(&mut self) {
+        self.channel.inner.stats.remove_subscriber(self.id);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_backpressure() {
+        let channel: Mpsc<u64> = Mpsc::named("mpsc_test", 1);
+        let mut receiver = channel.subscribe();
+        channel.send(0).await.unwrap();
+        assert!(matches!(channel.try_send(1), Err(TrySendError::Full(1))));
+
+        let sender = channel.clone();
+        let send_task = tokio::spawn(async move { sender.send(1).await });
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        assert!(!send_task.is_finished());
+
+        assert_eq!(receiver.recv().await, Some(0));
+        send_task.await.unwrap().unwrap();
+        assert_eq!(receiver.try_recv(), Ok(1));
+        assert_eq!(receiver.try_recv(), Err(TryRecvError::Empty));
+
+        let snapshot = channel.snapshot();
+        assert_eq!(snapshot.sent, 2);
+        assert_eq!(snapshot.send_errors, 1);
+        assert_eq!(snapshot.depth, 0);
+        assert_eq!(snapshot.subscribers[0].received, 2);
+    }
+
+    #[tokio::test]
+    async fn test_receiver_takeover() {
+        let channel: Mpsc<u64> = Mpsc::new(10);
+        let receiver = channel.subscribe();
+        channel.send(0).await.unwrap();
+        drop(receiver);
+
+        let mut receiver = channel.subscribe();
+        assert_eq!(receiver.recv().await, Some(0));
+        assert_eq!(channel.snapshot().receivers, 1);
+    }
+}
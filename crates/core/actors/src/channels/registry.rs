@@ -0,0 +1,111 @@
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, Weak};
+
+use lazy_static::lazy_static;
+
+pub(crate) trait ChannelInfo: Send + Sync {
+    fn snapshot(&self) -> ChannelSnapshot;
+}
+
+lazy_static! {
+    static ref CHANNELS: Mutex<Vec<Weak<dyn ChannelInfo>>> = Mutex::new(Vec::new());
+}
+
+#[derive(Clone, Debug)]
+pub struct SubscriberSnapshot {
+    pub id: u64,
+    pub received: u64,
+    /// Messages overwritten before the subscriber received them
+    pub lagged: u64,
+    pub lag_events: u64,
+}
+
+/// Counters of a channel at the moment of the snapshot
+#[derive(Clone, Debug)]
+pub struct ChannelSnapshot {
+    pub name: String,
+    pub capacity: usize,
+    /// Messages in the channel not received by all subscribers yet
+    pub depth: usize,
+    pub receivers: usize,
+    pub sent: u64,
+    /// Messages sent when the channel had no receivers or was closed
+    pub send_errors: u64,
+    /// Messages lost: overwritten before a subscriber received them or dropped by the drop policy of a queue
+    pub lagged: u64,
+    pub subscribers: Vec<SubscriberSnapshot>,
+}
+
+#[derive(Default)]
+pub(crate) struct SubscriberStats {
+    pub(crate) received: AtomicU64,
+    pub(crate) lagged: AtomicU64,
+    pub(crate) lag_events: AtomicU64,
+}
+
+/// Counters shared by all handles and receivers of a channel
+#[derive(Default)]
+pub(crate) struct ChannelStats {
+    pub(crate) sent: AtomicU64,
+    pub(crate) send_errors: AtomicU64,
+    pub(crate) dropped: AtomicU64,
+    next_subscriber_id: AtomicU64,
+    subscribers: Mutex<BTreeMap<u64, Arc<SubscriberStats>>>,
+}
+
+impl ChannelStats {
+    pub(crate) fn add_subscriber(&self) -> (u64, Arc<SubscriberStats>) {
+        let id = self.next_subscriber_id.fetch_add(1, Ordering::Relaxed);
+        let subscriber_stats = Arc::new(SubscriberStats::default());
+        if let Ok(mut subscribers) = self.subscribers.lock() {
+            subscribers.insert(id, subscriber_stats.clone());
+        }
+        (id, subscriber_stats)
+    }
+
+    pub(crate) fn remove_subscriber(&self, id: u64) {
+        if let Ok(mut subscribers) = self.subscribers.lock() {
+            subscribers.remove(&id);
+        }
+    }
+
+    pub(crate) fn subscribers(&self) -> Vec<SubscriberSnapshot> {
+        match self.subscribers.lock() {
+            Ok(subscribers) => subscribers
+                .iter()
+                .map(|(id, stats)| SubscriberSnapshot {
+                    id: *id,
+                    received: stats.received.load(Ordering::Relaxed),
+                    lagged: stats.lagged.load(Ordering::Relaxed),
+                    lag_events: stats.lag_events.load(Ordering::Relaxed),
+                })
+                .collect(),
+            Err(_) => Vec::new(),
+        }
+    }
+}
+
+/// Type name without module path and generic arguments
+pub(crate) fn short_type_name<T>() -> &'static str {
+    let type_name = std::any::type_name::<T>();
+    type_name.split('<').next().unwrap_or(type_name).rsplit("::").next().unwrap_or(type_name)
+}
+
+pub(crate) fn register_channel(channel: Weak<dyn ChannelInfo>) {
+    if let Ok(mut channels) = CHANNELS.lock() {
+        channels.retain(|c| c.strong_count() > 0);
+        channels.push(channel);
+    }
+}
+
+/// Snapshots of all channels alive in the process
+pub fn channel_snapshots() -> Vec<ChannelSnapshot> {
+    match CHANNELS.lock() {
+        Ok(mut channels) => {
+            channels.retain(|c| c.strong_count() > 0);
+            channels.iter().filter_map(|c| c.upgrade()).map(|c| c.snapshot()).collect()
+        }
+        Err(_) => Vec::new(),
+    }
+}
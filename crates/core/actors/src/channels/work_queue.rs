@@ -0,0 +1,308 @@
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use tokio::sync::mpsc::error::TrySendError;
+use tokio::sync::Notify;
+
+use crate::channels::registry::{register_channel, short_type_name, ChannelInfo, ChannelSnapshot, ChannelStats, SubscriberStats};
+
+/// What a [`WorkQueue`] does with a message sent while the queue is full
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DropPolicy {
+    /// Sender waits until a consumer takes a message
+    #[default]
+    Block,
+    /// New message is rejected and returned to the sender
+    DropNewest,
+    /// Oldest queued message is dropped to make room
+    DropOldest,
+}
+
+struct WorkQueueInner<T> {
+    name: String,
+    capacity: usize,
+    drop_policy: DropPolicy,
+    queue: Mutex<VecDeque<T>>,
+    closed: AtomicBool,
+    items: Notify,
+    space: Notify,
+    stats: Arc<ChannelStats>,
+}
+
+impl<T: Send + 'static> ChannelInfo for WorkQueueInner<T> {
+    fn snapshot(&self) -> ChannelSnapshot {
+        let subscribers = self.stats.subscribers();
+
+        ChannelSnapshot {
+            name: self.name.clone(),
+            capacity: self.capacity,
+            depth: self.queue.lock().map(|queue| queue.len()).unwrap_or_default(),
+            receivers: subscribers.len(),
+            sent: self.stats.sent.load(Ordering::Relaxed),
+            send_errors: self.stats.send_errors.load(Ordering::Relaxed),
+            lagged: self.stats.dropped.load(Ordering::Relaxed),
+            subscribers,
+        }
+    }
+}
+
+/// Bounded point-to-point channel, every message is received by exactly one consumer.
+///
+/// With one consumer it is an mpsc channel. With several consumers they compete for messages, so an expensive
+/// pipeline stage can be scaled by starting more actors on the same queue instead of cloning messages to every subscriber.
+/// A full queue applies the [`DropPolicy`] to senders.
+pub struct WorkQueue<T> {
+    inner: Arc<WorkQueueInner<T>>,
+}
+
+impl<T> Clone for WorkQueue<T> {
+    fn clone(&self) -> Self {
+        Self { inner: self.inner.clone() }
+    }
+}
+
+impl<T: Send + 'static> WorkQueue<T> {
+    /// Queue named after the message type
+    pub fn new(capacity: usize) -> Self {
+        Self::named(short_type_name::<T>(), capacity)
+    }
+
+    pub fn named(name: &str, capacity: usize) -> Self {
+        Self::with_drop_policy(name, capacity, DropPolicy::default())
+    }
+
+    pub fn with_drop_policy(name: &str, capacity: usize, drop_policy: DropPolicy) -> Self {
+        let inner = Arc::new(WorkQueueInner {
+            name: name.to_string(),
+            capacity: capacity.max(1),
+            drop_policy,
+            queue: Mutex::new(VecDeque::with_capacity(capacity)),
+            closed: AtomicBool::new(false),
+            items: Notify::new(),
+            space: Notify::new(),
+            stats: Arc::new(ChannelStats::default()),
+        });
+        let info: Arc<dyn ChannelInfo> = inner.clone();
+        register_channel(Arc::downgrade(&info));
+        Self { inner }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.inner.name
+    }
+
+    pub fn drop_policy(&self) -> DropPolicy {
+        self.inner.drop_policy
+    }
+
+    /// Sends a message, waiting for space if the queue is full and the policy is [`DropPolicy::Block`].
+    /// Returns `Full` if the message was rejected by [`DropPolicy::DropNewest`].
+    pub async fn send(&self, value: T) -> Result<(), TrySendError<T>> {
+        let mut value = value;
+        loop {
+            let space = self.inner.space.notified();
+            tokio::pin!(space);
+            space.as_mut().enable();
+
+            match self.push(value) {
+                Err(TrySendError::Full(returned)) if self.inner.drop_policy == DropPolicy::Block => value = returned,
+                result => return result,
+            }
+            space.await;
+        }
+    }
+
+    /// Sends a message without waiting. A full queue with [`DropPolicy::Block`] returns `Full`.
+    pub fn try_send(&self, value: T) -> Result<(), TrySendError<T>> {
+        self.push(value)
+    }
+
+    pub fn subscribe(&self) -> WorkQueueReceiver<T> {
+        let (id, stats) = self.inner.stats.add_subscriber();
+        WorkQueueReceiver { id, queue: self.clone(), stats }
+    }
+
+    /// Closes the queue, consumers receive the remaining messages and then `None`
+    pub fn close(&self) {
+        self.inner.closed.store(true, Ordering::Release);
+        self.inner.items.notify_waiters();
+        self.inner.space.notify_waiters();
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.inner.closed.load(Ordering::Acquire)
+    }
+
+    pub fn len(&self) -> usize {
+        self.inner.queue.lock().map(|queue| queue.len()).unwrap_or_default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn snapshot(&self) -> ChannelSnapshot {
+        self.inner.snapshot()
+    }
+
+    fn push(&self, value: T) -> Result<(), TrySendError<T>> {
+        if self.is_closed() {
+            self.inner.stats.send_errors.fetch_add(1, Ordering::Relaxed);
+            return Err(TrySendError::Closed(value));
+        }
+        let Ok(mut queue) = self.inner.queue.lock() else {
+            self.inner.stats.send_errors.fetch_add(1, Ordering::Relaxed);
+            return Err(TrySendError::Closed(value));
+        };
+
+        if queue.len() >= self.inner.capacity {
+            match self.inner.drop_policy {
+                DropPolicy::DropOldest => {
+                    queue.pop_front();
+                    self.inner.stats.dropped.fetch_add(1, Ordering::Relaxed);
+                }
+                DropPolicy::Block => return Err(TrySendError::Full(value)),
+                DropPolicy::DropNewest => {
+                    self.inner.stats.dropped.fetch_add(1, Ordering::Relaxed);
+                    return Err(TrySendError::Full(value));
+                }
+            }
+        }
+
+        queue.push_back(value);
+        drop(queue);
+        self.inner.stats.sent.fetch_add(1, Ordering::Relaxed);
+        self.inner.items.notify_one();
+        Ok(())
+    }
+
+    fn pop(&self) -> Option<T> {
+        let value = self.inner.queue.lock().ok()?.pop_front();
+        if value.is_some() {
+            self.inner.space.notify_one();
+        }
+        value
+    }
+}
+
+/// Consumer of a [`WorkQueue`] counting received messages
+pub struct WorkQueueReceiver<T: Send + 'static> {
+    id: u64,
+    queue: WorkQueue<T>,
+    stats: Arc<SubscriberStats>,
+}
+
+impl<T: Send + 'static> WorkQueueReceiver<T> {
+    /// Waits for the next message, returns `None` when the queue is closed and empty
+    pub async fn recv(&mut self) -> Option<T> {
+        // the notification borrows the queue, not the receiver polled below
+        let inner = self.queue.inner.clone();
+        loop {
+            let items = inner.items.notified();
+            tokio::pin!(items);
+            items.as_mut().enable();
+
+            if let Some(value) = self.try_recv() {
+                return Some(value);
+            }
+            if self.queue.is_closed() {
+                return None;
+            }
+            items.await;
+        }
+    }
+
+    pub fn try_recv(&mut self) -> Option<T> {
+        let value = self.queue.pop();
+        if value.is_some() {
+            self.stats.received.fetch_add(1, Ordering::Relaxed);
+            // another consumer may wait for the messages left in the queue
+            if !self.queue.is_empty() {
+                self.queue.inner.items.notify_one();
+            }
+        }
+        value
+    }
+
+    /// Number of messages waiting in the queue for all consumers
+    pub fn len(&self) -> usize {
+        self.queue.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+}
+
+impl<T: Send + 'static> Drop for WorkQueueReceiver<T> {
+    fn drop(&mut self) {
+        self.queue.inner.stats.remove_subscriber(self.id);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_competing_consumers() {
+        let queue: WorkQueue<u64> = WorkQueue::new(10);
+        let mut first = queue.subscribe();
+        let mut second = queue.subscribe();
+
+        for i in 0..4 {
+            queue.send(i).await.unwrap();
+        }
+
+        assert_eq!(first.recv().await, Some(0));
+        assert_eq!(second.recv().await, Some(1));
+        assert_eq!(first.recv().await, Some(2));
+        assert_eq!(second.try_recv(), Some(3));
+        assert_eq!(first.try_recv(), None);
+
+        queue.close();
+        assert_eq!(first.recv().await, None);
+        assert!(queue.send(5).await.is_err());
+
+        let snapshot = queue.snapshot();
+        assert_eq!(snapshot.sent, 4);
+        assert_eq!(snapshot.receivers, 2);
+        assert_eq!(snapshot.send_errors, 1);
+    }
+
+    #[tokio::test]
+    async fn test_drop_policies() {
+        let queue: WorkQueue<u64> = WorkQueue::with_drop_policy("drop_oldest", 2, DropPolicy::DropOldest);
+        let mut receiver = queue.subscribe();
+        for i in 0..3 {
+            queue.send(i).await.unwrap();
+        }
+        assert_eq!(receiver.recv().await, Some(1));
+        assert_eq!(queue.snapshot().lagged, 1);
+
+        let queue: WorkQueue<u64> = WorkQueue::with_drop_policy("drop_newest", 1, DropPolicy::DropNewest);
+        queue.send(0).await.unwrap();
+        assert!(matches!(queue.send(1).await, Err(TrySendError::Full(1))));
+        assert_eq!(queue.subscribe().recv().await, Some(0));
+    }
+
+    #[tokio::test]
+    async fn test_block_waits_for_consumer() {
+        let queue: WorkQueue<u64> = WorkQueue::new(1);
+        let mut receiver = queue.subscribe();
+        queue.send(0).await.unwrap();
+        assert!(matches!(queue.try_send(1), Err(TrySendError::Full(1))));
+
+        let sender = queue.clone();
+        let send_task = tokio::spawn(async move { sender.send(1).await });
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        assert!(!send_task.is_finished());
+
+        assert_eq!(receiver.recv().await, Some(0));
+        send_task.await.unwrap().unwrap();
+        assert_eq!(receiver.recv().await, Some(1));
+        assert_eq!(queue.snapshot().lagged, 0);
+    }
+}
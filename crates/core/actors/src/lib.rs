@@ -1,12 +1,19 @@
-pub use actor::{Accessor, Actor, ActorResult, Consumer, Producer, WorkerResult};
+pub use actor::{Accessor, Actor, ActorResult, Consumer, MpscConsumer, MpscProducer, Producer, QueueConsumer, QueueProducer, WorkerResult};
 pub use actor_manager::ActorsManager;
-pub use channels::{Broadcaster, MultiProducer};
+pub use channels::{
+    channel_snapshots, Broadcaster, BroadcasterReceiver, ChannelSnapshot, DropPolicy, Mpsc, MpscReceiver, MultiProducer,
+    SubscriberSnapshot, WorkQueue, WorkQueueReceiver,
+};
 pub use shared_state::SharedState;
+pub use shutdown::{shutdown_signal, Shutdown, ShutdownStage};
+pub use supervisor::{ActorExit, ActorExitReason, RestartPolicy, SupervisorPolicy};
 
 mod actor;
 mod actor_manager;
 mod channels;
 mod shared_state;
+mod shutdown;
+mod supervisor;
 
 #[macro_export]
 macro_rules! run_async {
@@ -28,9 +35,40 @@ macro_rules! run_sync {
     };
 }
 
+/// Channel that hands out receivers, used by the `subscribe!` macro
+pub trait Subscribe {
+    type Receiver;
+
+    fn subscribe_receiver(&self) -> Self::Receiver;
+}
+
+impl<A: Clone + Send + Sync + 'static> Subscribe for Broadcaster<A> {
+    type Receiver = BroadcasterReceiver<A>;
+
+    fn subscribe_receiver(&self) -> Self::Receiver {
+        self.subscribe()
+    }
+}
+
+impl<A: Send + 'static> Subscribe for WorkQueue<A> {
+    type Receiver = WorkQueueReceiver<A>;
+
+    fn subscribe_receiver(&self) -> Self::Receiver {
+        self.subscribe()
+    }
+}
+
+impl<A: Send + 'static> Subscribe for Mpsc<A> {
+    type Receiver = MpscReceiver<A>;
+
+    fn subscribe_receiver(&self) -> Self::Receiver {
+        self.subscribe()
+    }
+}
+
 #[inline]
-pub fn subscribe_helper<A: Clone + Send + Sync>(broadcaster: &Broadcaster<A>) -> tokio::sync::broadcast::Receiver<A> {
-    broadcaster.subscribe()
+pub fn subscribe_helper<C: Subscribe>(channel: &C) -> C::Receiver {
+    channel.subscribe_receiver()
 }
 
 #[macro_export]
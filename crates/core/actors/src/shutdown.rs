@@ -0,0 +1,222 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use eyre::{eyre, Result};
+use tokio::sync::watch;
+use tokio::task::{AbortHandle, JoinHandle};
+use tokio_util::sync::CancellationToken;
+use tracing::{info, warn};
+
+use crate::WorkerResult;
+
+/// Order in which actors are stopped on shutdown.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum ShutdownStage {
+    /// Sources of new data like node block and mempool subscriptions, stopped first
+    Producer,
+    /// Actors consuming and producing events, they get time to drain in-flight messages
+    #[default]
+    Processor,
+    /// Actors writing to databases and metrics storages, stopped last after flushing their buffers
+    Writer,
+}
+
+impl ShutdownStage {
+    pub const ORDERED: [ShutdownStage; 3] = [ShutdownStage::Producer, ShutdownStage::Processor, ShutdownStage::Writer];
+}
+
+/// Running workers of a stage
+struct StageWorkers {
+    alive: watch::Sender<usize>,
+    abort_handles: Mutex<Vec<AbortHandle>>,
+}
+
+impl Default for StageWorkers {
+    fn default() -> Self {
+        Self { alive: watch::channel(0).0, abort_handles: Mutex::new(Vec::new()) }
+    }
+}
+
+/// Aborts the tracked worker and counts it as stopped when the tracking task ends or is aborted
+struct TrackedWorker {
+    stage_workers: Arc<StageWorkers>,
+    worker: AbortHandle,
+}
+
+impl Drop for TrackedWorker {
+    fn drop(&mut self) {
+        self.worker.abort();
+        self.stage_workers.alive.send_modify(|alive| *alive = alive.saturating_sub(1));
+    }
+}
+
+/// Shutdown request, cancellation tokens and running workers of every [`ShutdownStage`].
+#[derive(Clone, Default)]
+pub struct Shutdown {
+    requested: CancellationToken,
+    producers: CancellationToken,
+    processors: CancellationToken,
+    writers: CancellationToken,
+    workers: [Arc<StageWorkers>; 3],
+}
+
+impl Shutdown {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Request shutdown, stages are cancelled by the owner of the actors
+    pub fn request(&self) {
+        self.requested.cancel();
+    }
+
+    pub fn is_requested(&self) -> bool {
+        self.requested.is_cancelled()
+    }
+
+    pub async fn requested(&self) {
+        self.requested.cancelled().await
+    }
+
+    /// Token that is cancelled when actors of the stage should stop
+    pub fn stage_token(&self, stage: ShutdownStage) -> CancellationToken {
+        self.stage(stage).child_token()
+    }
+
+    pub fn cancel_stage(&self, stage: ShutdownStage) {
+        self.stage(stage).cancel();
+    }
+
+    /// Counts the worker as running in the stage until it ends. Producer workers are stopped as soon as their stage is
+    /// cancelled, they have nothing in flight to drain. Called by [`crate::Actor::start_with_shutdown`].
+    pub fn track(&self, stage: ShutdownStage, mut worker: JoinHandle<WorkerResult>) -> JoinHandle<WorkerResult> {
+        let stage_workers = self.stage_workers(stage).clone();
+        stage_workers.alive.send_modify(|alive| *alive += 1);
+        let tracked = TrackedWorker { stage_workers: stage_workers.clone(), worker: worker.abort_handle() };
+        let stop_token = (stage == ShutdownStage::Producer).then(|| self.stage_token(stage));
+
+        let handle = tokio::task::spawn(async move {
+            let _tracked = tracked;
+            let result = match stop_token {
+                Some(stop_token) => tokio::select! {
+                    result = &mut worker => result,
+                    _ = stop_token.cancelled() => return Ok("Stopped on shutdown".to_string()),
+                },
+                None => worker.await,
+            };
+            match result {
+                Ok(result) => result,
+                Err(e) if e.is_panic() => std::panic::resume_unwind(e.into_panic()),
+                Err(e) => Err(eyre!("WORKER_CANCELLED : {e}")),
+            }
+        });
+
+        if let Ok(mut abort_handles) = stage_workers.abort_handles.lock() {
+            abort_handles.retain(|abort_handle| !abort_handle.is_finished());
+            abort_handles.push(handle.abort_handle());
+        }
+        handle
+    }
+
+    /// Cancel stages in order and wait up to `grace` for the workers of each stage, workers still running after that are aborted.
+    /// Used when workers are not supervised by `ActorsManager`.
+    pub async fn cancel_stages(&self, grace: Duration) {
+        self.request();
+        for stage in ShutdownStage::ORDERED {
+            info!(?stage, "Stopping actors");
+            self.cancel_stage(stage);
+
+            let stage_workers = self.stage_workers(stage);
+            let mut alive = stage_workers.alive.subscribe();
+            if tokio::time::timeout(grace, alive.wait_for(|alive| *alive == 0)).await.is_err() {
+                warn!(?stage, alive = *alive.borrow(), "Workers did not stop in time, aborting");
+                if let Ok(mut abort_handles) = stage_workers.abort_handles.lock() {
+                    abort_handles.drain(..).for_each(|abort_handle| abort_handle.abort());
+                }
+            }
+        }
+    }
+
+    fn stage(&self, stage: ShutdownStage) -> &CancellationToken {
+        match stage {
+            ShutdownStage::Producer => &self.producers,
+            ShutdownStage::Processor => &self.processors,
+            ShutdownStage::Writer => &self.writers,
+        }
+    }
+
+    fn stage_workers(&self, stage: ShutdownStage) -> &Arc<StageWorkers> {
+        match stage {
+            ShutdownStage::Producer => &self.workers[0],
+            ShutdownStage::Processor => &self.workers[1],
+            ShutdownStage::Writer => &self.workers[2],
+        }
+    }
+}
+
+/// Completes on SIGINT or SIGTERM
+pub async fn shutdown_signal() -> Result<()> {
+    #[cfg(unix)]
+    {
+        let mut sigterm = tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())?;
+        tokio::select! {
+            result = tokio::signal::ctrl_c() => result?,
+            _ = sigterm.recv() => {}
+        }
+    }
+    #[cfg(not(unix))]
+    tokio::signal::ctrl_c().await?;
+
+    info!("Shutdown signal received");
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_stages_are_cancelled_in_order() {
+        let shutdown = Shutdown::new();
+        let producer = shutdown.stage_token(ShutdownStage::Producer);
+        let writer = shutdown.stage_token(ShutdownStage::Writer);
+
+        shutdown.request();
+        assert!(shutdown.is_requested());
+        assert!(!producer.is_cancelled());
+
+        shutdown.cancel_stage(ShutdownStage::Producer);
+        assert!(producer.is_cancelled());
+        assert!(!writer.is_cancelled());
+
+        shutdown.cancel_stages(Duration::ZERO).await;
+        assert!(writer.is_cancelled());
+    }
+
+    #[tokio::test]
+    async fn test_cancel_stages_waits_for_workers() {
+        let shutdown = Shutdown::new();
+
+        // producer stops on its token without looking at it
+        let producer = shutdown.track(ShutdownStage::Producer, tokio::task::spawn(std::future::pending()));
+
+        let writer_token = shutdown.stage_token(ShutdownStage::Writer);
+        let writer = shutdown.track(
+            ShutdownStage::Writer,
+            tokio::task::spawn(async move {
+                writer_token.cancelled().await;
+                tokio::time::sleep(Duration::from_millis(20)).await;
+                Ok("flushed".to_string())
+            }),
+        );
+
+        // ignores its token and is aborted after the grace period
+        let processor = shutdown.track(ShutdownStage::Processor, tokio::task::spawn(std::future::pending()));
+
+        shutdown.cancel_stages(Duration::from_millis(100)).await;
+
+        assert_eq!(producer.await.unwrap().unwrap(), "Stopped on shutdown");
+        assert!(processor.await.unwrap_err().is_cancelled());
+        assert_eq!(writer.await.unwrap().unwrap(), "flushed");
+    }
+}
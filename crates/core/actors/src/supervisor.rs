@@ -0,0 +1,176 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// When an actor is started again after one of its workers exited.
+///
+/// A failed or panicked worker restarts all workers of the actor. Workers that finish with `Ok` are not restarted.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RestartPolicy {
+    #[default]
+    Never,
+    Always {
+        delay: Duration,
+    },
+    /// Delay doubles with every restart up to `max_delay`. It is reset when the actor ran longer than `max_delay`.
+    ExponentialBackoff {
+        initial_delay: Duration,
+        max_delay: Duration,
+    },
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SupervisorPolicy {
+    pub restart: RestartPolicy,
+    /// Maximum number of restarts within a time window, the actor stays stopped after that
+    pub max_restarts: Option<(usize, Duration)>,
+    /// Stop all actors when this actor exits and is not restarted
+    pub critical: bool,
+}
+
+impl SupervisorPolicy {
+    pub fn never() -> Self {
+        Self::default()
+    }
+
+    pub fn always(delay: Duration) -> Self {
+        Self { restart: RestartPolicy::Always { delay }, ..Self::default() }
+    }
+
+    pub fn exponential_backoff(initial_delay: Duration, max_delay: Duration) -> Self {
+        Self { restart: RestartPolicy::ExponentialBackoff { initial_delay, max_delay }, ..Self::default() }
+    }
+
+    pub fn with_max_restarts(self, max_restarts: usize, window: Duration) -> Self {
+        Self { max_restarts: Some((max_restarts, window)), ..self }
+    }
+
+    pub fn critical(self) -> Self {
+        Self { critical: true, ..self }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ActorExitReason {
+    /// Worker returned Ok
+    Finished(String),
+    /// Worker returned an error or the actor failed to restart
+    Failed(String),
+    Panicked(String),
+    Cancelled,
+}
+
+/// Emitted by [`crate::ActorsManager`] for every exited worker
+#[derive(Clone, Debug)]
+pub struct ActorExit {
+    pub actor_name: &'static str,
+    pub worker_index: usize,
+    pub reason: ActorExitReason,
+    /// Number of restarts of the actor so far
+    pub restarts: usize,
+    /// Delay before the actor is restarted, None if it stays stopped
+    pub restart_in: Option<Duration>,
+    pub critical: bool,
+}
+
+impl ActorExit {
+    pub fn is_restarted(&self) -> bool {
+        self.restart_in.is_some()
+    }
+
+    /// Critical actor stopped for good, the process should shut down
+    pub fn is_fatal(&self) -> bool {
+        self.critical && self.restart_in.is_none()
+    }
+}
+
+/// Restart bookkeeping of one actor
+pub(crate) struct RestartTracker {
+    policy: SupervisorPolicy,
+    restarts: usize,
+    backoff_step: u32,
+    restart_times: VecDeque<Instant>,
+    started_at: Instant,
+}
+
+impl RestartTracker {
+    pub(crate) fn new(policy: SupervisorPolicy, started_at: Instant) -> Self {
+        Self { policy, restarts: 0, backoff_step: 0, restart_times: VecDeque::new(), started_at }
+    }
+
+    pub(crate) fn policy(&self) -> &SupervisorPolicy {
+        &self.policy
+    }
+
+    pub(crate) fn restarts(&self) -> usize {
+        self.restarts
+    }
+
+    pub(crate) fn on_started(&mut self, now: Instant) {
+        self.started_at = now;
+    }
+
+    /// Returns the delay before the next restart or None if the actor stays stopped.
+    pub(crate) fn next_restart(&mut self, now: Instant) -> Option<Duration> {
+        let delay = match self.policy.restart {
+            RestartPolicy::Never => return None,
+            RestartPolicy::Always { delay } => delay,
+            RestartPolicy::ExponentialBackoff { initial_delay, max_delay } => {
+                if now.duration_since(self.started_at) > max_delay {
+                    self.backoff_step = 0;
+                }
+                let delay = initial_delay.saturating_mul(2u32.saturating_pow(self.backoff_step)).min(max_delay);
+                self.backoff_step = self.backoff_step.saturating_add(1);
+                delay
+            }
+        };
+
+        if let Some((max_restarts, window)) = self.policy.max_restarts {
+            while self.restart_times.front().is_some_and(|t| now.duration_since(*t) > window) {
+                self.restart_times.pop_front();
+            }
+            if self.restart_times.len() >= max_restarts {
+                return None;
+            }
+            self.restart_times.push_back(now);
+        }
+
+        self.restarts += 1;
+        Some(delay)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_exponential_backoff() {
+        let now = Instant::now();
+        let mut tracker = RestartTracker::new(SupervisorPolicy::exponential_backoff(Duration::from_secs(1), Duration::from_secs(5)), now);
+
+        assert_eq!(tracker.next_restart(now), Some(Duration::from_secs(1)));
+        assert_eq!(tracker.next_restart(now), Some(Duration::from_secs(2)));
+        assert_eq!(tracker.next_restart(now), Some(Duration::from_secs(4)));
+        assert_eq!(tracker.next_restart(now), Some(Duration::from_secs(5)));
+
+        // healthy run resets the backoff
+        tracker.on_started(now);
+        assert_eq!(tracker.next_restart(now + Duration::from_secs(10)), Some(Duration::from_secs(1)));
+        assert_eq!(tracker.restarts(), 5);
+    }
+
+    #[test]
+    fn test_max_restarts_in_window() {
+        let now = Instant::now();
+        let policy = SupervisorPolicy::always(Duration::ZERO).with_max_restarts(2, Duration::from_secs(60));
+        let mut tracker = RestartTracker::new(policy, now);
+
+        assert!(tracker.next_restart(now).is_some());
+        assert!(tracker.next_restart(now + Duration::from_secs(1)).is_some());
+        assert!(tracker.next_restart(now + Duration::from_secs(2)).is_none());
+        assert!(tracker.next_restart(now + Duration::from_secs(62)).is_some());
+
+        let mut tracker = RestartTracker::new(SupervisorPolicy::never(), now);
+        assert!(tracker.next_restart(now).is_none());
+    }
+}
@@ -3,11 +3,11 @@ use alloy_primitives::{Address, B256, U256};
 use alloy_provider::{Provider, RootProvider};
 use axum::Router;
 use eyre::{eyre, ErrReport, Result};
-use loom_broadcast_accounts::{InitializeSignersOneShotBlockingActor, NonceAndBalanceMonitorActor, TxSignersActor};
+use loom_broadcast_accounts::{InitializeSignersOneShotBlockingActor, InventoryMonitorActor, NonceAndBalanceMonitorActor, TxSignersActor};
 use loom_broadcast_broadcaster::FlashbotsBroadcastActor;
 use loom_broadcast_flashbots::client::RelayConfig;
 use loom_broadcast_flashbots::Flashbots;
-use loom_core_actors::{Actor, ActorsManager, SharedState};
+use loom_core_actors::{Actor, ActorExit, ActorsManager, Broadcaster, SharedState, Shutdown, SupervisorPolicy};
 use loom_core_block_history::BlockHistoryActor;
 use loom_core_blockchain::{Blockchain, BlockchainState, Strategy};
 use loom_core_mempool::MempoolActor;
@@ -16,10 +16,11 @@ use loom_defi_address_book::TokenAddressEth;
 use loom_defi_health_monitor::{MetricsRecorderActor, PoolHealthMonitorActor, StuffingTxMonitorActor};
 use loom_defi_market::{
     HistoryPoolLoaderOneShotActor, NewPoolLoaderActor, PoolLoaderActor, ProtocolPoolLoaderOneShotActor, RequiredPoolLoaderActor,
+    TokenLoaderActor,
 };
 use loom_defi_pools::{PoolLoadersBuilder, PoolsLoadingConfig};
 use loom_defi_preloader::MarketStatePreloadedOneShotActor;
-use loom_defi_price::PriceActor;
+use loom_defi_price::{PriceActor, ReferencePriceConfig};
 use loom_evm_db::DatabaseLoomExt;
 use loom_evm_utils::NWETH;
 use loom_execution_estimator::{EvmEstimatorActor, GethEstimatorActor};
@@ -30,20 +31,28 @@ use loom_node_actor_config::NodeBlockActorConfig;
 use loom_node_db_access::RethDbAccessBlockActor;
 use loom_node_debug_provider::DebugProviderExt;
 use loom_node_grpc::NodeExExGrpcActor;
-use loom_node_json_rpc::{NodeBlockActor, NodeMempoolActor, WaitForNodeSyncOneShotBlockingActor};
+use loom_node_json_rpc::{
+    HeadWatchdogActor, HeadWatchdogConfig, MempoolSourceActor, NodeBlockActor, NodeMempoolSource, WaitForNodeSyncOneShotBlockingActor,
+};
+use loom_node_player::{MempoolLogPlayerActor, MempoolRecorderActor, NodeBlockPlayerActor};
 use loom_rpc_handler::WebServerActor;
 use loom_storage_db::DbPool;
 use loom_strategy_backrun::{
-    BackrunConfig, BlockStateChangeProcessorActor, PendingTxStateChangeProcessorActor, StateChangeArbSearcherActor,
+    BackrunConfig, BidOutcomeRecorderActor, BlockStateChangeProcessorActor, BlockTxAnalyzerActor, PendingTxStateChangeProcessorActor,
+    StateChangeArbSearcherActor,
 };
 use loom_strategy_merger::{ArbSwapPathMergerActor, DiffPathMergerActor, SamePathMergerActor};
 use loom_types_entities::required_state::RequiredState;
-use loom_types_entities::{BlockHistoryState, PoolClass, SwapEncoder, TxSigners};
+use loom_types_entities::{BlockHistoryState, InventoryConfig, PoolClass, SwapEncoder, TxSigners};
 use revm::{Database, DatabaseCommit, DatabaseRef};
 use std::collections::HashMap;
+use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio_util::sync::CancellationToken;
 
+use crate::pipeline::{PipelineActorConfig, PipelineConfig, SignersInit};
+
 pub struct BlockchainActors<P, DB: Clone + Send + Sync + 'static, E: Clone = MulticallerSwapEncoder> {
     provider: P,
     bc: Blockchain,
@@ -98,65 +107,108 @@ where
         }
     }
 
-    pub async fn wait(self) {
+    /// Wait for all actors, returns an error when a critical actor stopped
+    pub async fn wait(self) -> Result<()> {
         self.actor_manager.wait().await
     }
 
+    /// Restart policy for actors without their own policy, must be set before actors are started
+    pub fn with_default_supervisor_policy(&mut self, policy: SupervisorPolicy) -> &mut Self {
+        self.actor_manager.set_default_policy(policy);
+        self
+    }
+
+    /// Restart policy for the actor with the name, must be set before the actor is started
+    pub fn with_supervisor_policy(&mut self, actor_name: &'static str, policy: SupervisorPolicy) -> &mut Self {
+        self.actor_manager.set_policy(actor_name, policy);
+        self
+    }
+
+    /// Request shutdown with [`Shutdown::request`], `wait` then stops actors in order
+    pub fn shutdown(&self) -> Shutdown {
+        self.actor_manager.shutdown()
+    }
+
+    /// Time given to actors of every shutdown stage to stop before they are aborted
+    pub fn with_shutdown_grace(&mut self, shutdown_grace: Duration) -> &mut Self {
+        self.actor_manager.set_shutdown_grace(shutdown_grace);
+        self
+    }
+
+    /// Stop actors together with an outer owner like `Topology`, must be set before actors are started
+    pub fn with_shutdown(&mut self, shutdown: Shutdown) -> &mut Self {
+        self.actor_manager.set_shutdown(shutdown);
+        self
+    }
+
+    /// Exit events of actor workers
+    pub fn actor_exit_channel(&self) -> Broadcaster<ActorExit> {
+        self.actor_manager.exit_channel()
+    }
+
     /// Start a custom actor
-    pub fn start(&mut self, actor: impl Actor + 'static) -> Result<&mut Self> {
+    pub fn start(&mut self, actor: impl Actor + Send + Sync + 'static) -> Result<&mut Self> {
         self.actor_manager.start(actor)?;
         Ok(self)
     }
 
     /// Start a custom actor and wait for it to finish
-    pub fn start_and_wait(&mut self, actor: impl Actor + Send + Sync + 'static) -> Result<&mut Self> {
-        self.actor_manager.start_and_wait(actor)?;
+    pub async fn start_and_wait(&mut self, actor: impl Actor + Send + Sync + 'static) -> Result<&mut Self> {
+        self.actor_manager.start_and_wait(actor).await?;
         Ok(self)
     }
 
     /// Initialize signers with the default anvil Private Key
-    pub fn initialize_signers_with_anvil(&mut self) -> Result<&mut Self> {
+    pub async fn initialize_signers_with_anvil(&mut self) -> Result<&mut Self> {
         let key: B256 = "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80".parse()?;
 
-        self.actor_manager.start_and_wait(
-            InitializeSignersOneShotBlockingActor::new(Some(key.to_vec())).with_signers(self.signers.clone()).on_bc(&self.bc),
-        )?;
+        self.actor_manager
+            .start_and_wait(
+                InitializeSignersOneShotBlockingActor::new(Some(key.to_vec())).with_signers(self.signers.clone()).on_bc(&self.bc),
+            )
+            .await?;
         self.with_signers()?;
         Ok(self)
     }
 
     /// Initialize signers with the private key. Random key generated if param in None
-    pub fn initialize_signers_with_key(&mut self, key: Option<Vec<u8>>) -> Result<&mut Self> {
+    pub async fn initialize_signers_with_key(&mut self, key: Option<Vec<u8>>) -> Result<&mut Self> {
         self.actor_manager
-            .start_and_wait(InitializeSignersOneShotBlockingActor::new(key).with_signers(self.signers.clone()).on_bc(&self.bc))?;
+            .start_and_wait(InitializeSignersOneShotBlockingActor::new(key).with_signers(self.signers.clone()).on_bc(&self.bc))
+            .await?;
         self.with_signers()?;
         Ok(self)
     }
 
     /// Initialize signers with multiple private keys
-    pub fn initialize_signers_with_keys(&mut self, keys: Vec<Vec<u8>>) -> Result<&mut Self> {
+    pub async fn initialize_signers_with_keys(&mut self, keys: Vec<Vec<u8>>) -> Result<&mut Self> {
         for key in keys {
             self.actor_manager
-                .start_and_wait(InitializeSignersOneShotBlockingActor::new(Some(key)).with_signers(self.signers.clone()).on_bc(&self.bc))?;
+                .start_and_wait(InitializeSignersOneShotBlockingActor::new(Some(key)).with_signers(self.signers.clone()).on_bc(&self.bc))
+                .await?;
         }
         self.with_signers()?;
         Ok(self)
     }
 
     /// Initialize signers with encrypted private key
-    pub fn initialize_signers_with_encrypted_key(&mut self, key: Vec<u8>) -> Result<&mut Self> {
-        self.actor_manager.start_and_wait(
-            InitializeSignersOneShotBlockingActor::new_from_encrypted_key(key).with_signers(self.signers.clone()).on_bc(&self.bc),
-        )?;
+    pub async fn initialize_signers_with_encrypted_key(&mut self, key: Vec<u8>) -> Result<&mut Self> {
+        self.actor_manager
+            .start_and_wait(
+                InitializeSignersOneShotBlockingActor::new_from_encrypted_key(key).with_signers(self.signers.clone()).on_bc(&self.bc),
+            )
+            .await?;
         self.with_signers()?;
         Ok(self)
     }
 
     /// Initializes signers with encrypted key form DATA env var
-    pub fn initialize_signers_with_env(&mut self) -> Result<&mut Self> {
-        self.actor_manager.start_and_wait(
-            InitializeSignersOneShotBlockingActor::new_from_encrypted_env().with_signers(self.signers.clone()).on_bc(&self.bc),
-        )?;
+    pub async fn initialize_signers_with_env(&mut self) -> Result<&mut Self> {
+        self.actor_manager
+            .start_and_wait(
+                InitializeSignersOneShotBlockingActor::new_from_encrypted_env().with_signers(self.signers.clone()).on_bc(&self.bc),
+            )
+            .await?;
         self.with_signers()?;
         Ok(self)
     }
@@ -178,22 +230,47 @@ where
         Ok(self)
     }
 
+    /// Starts router with the encoder passed to `new`
+    pub fn with_swap_router(&mut self) -> Result<&mut Self> {
+        let swap_encoder = self.encoder.clone().ok_or(eyre!("NO_ENCODER"))?;
+        self.with_swap_encoder(swap_encoder)
+    }
+
+    /// Starts router funding swaps from the multicaller inventory and the inventory balances monitor.
+    /// Balances are updated by the nonce and balance monitor, the estimator needs the provider for the multicaller token balances.
+    pub fn with_swap_router_and_inventory(&mut self, inventory_config: InventoryConfig) -> Result<&mut Self> {
+        let swap_encoder = self.encoder.clone().ok_or(eyre!("NO_ENCODER"))?;
+        let multicaller_address = swap_encoder.address();
+        self.mutlicaller_address = Some(multicaller_address);
+        self.actor_manager.start(
+            SwapRouterActor::<DB>::new()
+                .with_signers(self.signers.clone())
+                .with_inventory(multicaller_address, inventory_config.clone())
+                .on_bc(&self.bc, &self.strategy),
+        )?;
+        self.actor_manager
+            .start(InventoryMonitorActor::new(self.provider.clone(), multicaller_address, inventory_config).on_bc(&self.bc))?;
+        Ok(self)
+    }
+
     /// Starts market state preloader
-    pub fn with_market_state_preloader(&mut self) -> Result<&mut Self> {
+    pub async fn with_market_state_preloader(&mut self) -> Result<&mut Self> {
         let mut address_vec = self.signers.inner().try_read()?.get_address_vec();
 
         if let Some(loom_multicaller) = self.mutlicaller_address {
             address_vec.push(loom_multicaller);
         }
 
-        self.actor_manager.start_and_wait(
-            MarketStatePreloadedOneShotActor::new(self.provider.clone()).with_copied_accounts(address_vec).on_bc(&self.bc, &self.state),
-        )?;
+        self.actor_manager
+            .start_and_wait(
+                MarketStatePreloadedOneShotActor::new(self.provider.clone()).with_copied_accounts(address_vec).on_bc(&self.bc, &self.state),
+            )
+            .await?;
         Ok(self)
     }
 
     /// Starts preloaded virtual artefacts
-    pub fn with_market_state_preloader_virtual(&mut self, address_to_copy: Vec<Address>) -> Result<&mut Self> {
+    pub async fn with_market_state_preloader_virtual(&mut self, address_to_copy: Vec<Address>) -> Result<&mut Self> {
         let address_vec = self.signers.inner().try_read()?.get_address_vec();
 
         let mut market_state_preloader = MarketStatePreloadedOneShotActor::new(self.provider.clone());
@@ -217,14 +294,14 @@ where
         );
 
         market_state_preloader = market_state_preloader.with_token_balance(
-            TokenAddressEth::WETH,
+            self.bc.chain_parameters().weth,
             loom_execution_multicaller::DEFAULT_VIRTUAL_ADDRESS,
             NWETH::from_float(10.0),
         );
 
         self.mutlicaller_address = Some(loom_execution_multicaller::DEFAULT_VIRTUAL_ADDRESS);
 
-        self.actor_manager.start_and_wait(market_state_preloader.on_bc(&self.bc, &self.state))?;
+        self.actor_manager.start_and_wait(market_state_preloader.on_bc(&self.bc, &self.state)).await?;
         Ok(self)
     }
 
@@ -247,7 +324,13 @@ where
 
     /// Starts token price calculator
     pub fn with_price_station(&mut self) -> Result<&mut Self> {
-        self.actor_manager.start(PriceActor::new(self.provider.clone()).on_bc(&self.bc))?;
+        self.actor_manager.start(PriceActor::new().on_bc(&self.bc, &self.state))?;
+        Ok(self)
+    }
+
+    /// Starts token price calculator with spot prices checked against reference prices
+    pub fn with_price_station_and_reference_prices(&mut self, reference_config: ReferencePriceConfig) -> Result<&mut Self> {
+        self.actor_manager.start(PriceActor::new().with_reference_prices(reference_config).on_bc(&self.bc, &self.state))?;
         Ok(self)
     }
 
@@ -257,6 +340,14 @@ where
         Ok(self)
     }
 
+    /// Starts replaying blocks from `start_block` to `end_block` with their state updates fetched from the provider
+    pub fn with_block_player(&mut self, start_block: u64, end_block: u64) -> Result<&mut Self> {
+        self.actor_manager.start(
+            NodeBlockPlayerActor::<P, Ethereum, DB>::new(self.provider.clone(), start_block, end_block).on_bc(&self.bc, &self.state),
+        )?;
+        Ok(self)
+    }
+
     /// Starts receiving blocks events through direct Reth DB access
     #[cfg(feature = "db-access")]
     pub fn reth_node_with_blocks(&mut self, db_path: String, config: NodeBlockActorConfig) -> Result<&mut Self> {
@@ -283,7 +374,7 @@ where
     /// Starts local node pending tx provider
     pub fn with_local_mempool_events(&mut self) -> Result<&mut Self> {
         self.mempool()?;
-        self.actor_manager.start(NodeMempoolActor::new(self.provider.clone()).on_bc(&self.bc))?;
+        self.actor_manager.start(MempoolSourceActor::new(NodeMempoolSource::new(self.provider.clone())).on_bc(&self.bc))?;
         Ok(self)
     }
 
@@ -293,7 +384,20 @@ where
         PM: Provider<Ethereum> + Send + Sync + Clone + 'static,
     {
         self.mempool()?;
-        self.actor_manager.start(NodeMempoolActor::new(provider).on_bc(&self.bc))?;
+        self.actor_manager.start(MempoolSourceActor::new(NodeMempoolSource::new(provider)).on_bc(&self.bc))?;
+        Ok(self)
+    }
+
+    /// Starts recording mempool and block events to a file for replay
+    pub fn with_mempool_recorder(&mut self, path: PathBuf) -> Result<&mut Self> {
+        self.actor_manager.start(MempoolRecorderActor::new(path).on_bc(&self.bc))?;
+        Ok(self)
+    }
+
+    /// Starts replaying recorded mempool and block events, speed 1.0 keeps the original timing
+    pub fn with_mempool_log_player(&mut self, path: PathBuf, speed: f64) -> Result<&mut Self> {
+        self.mempool()?;
+        self.actor_manager.start(MempoolLogPlayerActor::new(path).with_speed(speed).on_bc(&self.bc))?;
         Ok(self)
     }
 
@@ -337,47 +441,76 @@ where
 
     /// Start pool loader from new block events
     pub fn with_new_pool_loader(&mut self, pools_config: PoolsLoadingConfig) -> Result<&mut Self> {
-        let pool_loader = Arc::new(PoolLoadersBuilder::default_pool_loaders(self.provider.clone(), pools_config));
+        let pool_loader = Arc::new(PoolLoadersBuilder::default_pool_loaders(
+            self.provider.clone(),
+            pools_config.with_chain_parameters(self.bc.chain_parameters()),
+        ));
         self.actor_manager.start(NewPoolLoaderActor::new(pool_loader).on_bc(&self.bc))?;
         Ok(self)
     }
 
     /// Start pool loader for last 10000 blocks
     pub fn with_pool_history_loader(&mut self, pools_config: PoolsLoadingConfig) -> Result<&mut Self> {
-        let pool_loaders = Arc::new(PoolLoadersBuilder::default_pool_loaders(self.provider.clone(), pools_config));
+        let pool_loaders = Arc::new(PoolLoadersBuilder::default_pool_loaders(
+            self.provider.clone(),
+            pools_config.with_chain_parameters(self.bc.chain_parameters()),
+        ));
         self.actor_manager.start(HistoryPoolLoaderOneShotActor::new(self.provider.clone(), pool_loaders).on_bc(&self.bc))?;
         Ok(self)
     }
 
     /// Start pool loader from new block events
     pub fn with_pool_loader(&mut self, pools_config: PoolsLoadingConfig) -> Result<&mut Self> {
-        let pool_loaders = Arc::new(PoolLoadersBuilder::default_pool_loaders(self.provider.clone(), pools_config.clone()));
+        let pool_loaders = Arc::new(PoolLoadersBuilder::default_pool_loaders(
+            self.provider.clone(),
+            pools_config.clone().with_chain_parameters(self.bc.chain_parameters()),
+        ));
         self.actor_manager.start(PoolLoaderActor::new(self.provider.clone(), pool_loaders, pools_config).on_bc(&self.bc, &self.state))?;
         Ok(self)
     }
 
     /// Start pool loader for curve + steth + wsteth
     pub fn with_curve_pool_protocol_loader(&mut self, pools_config: PoolsLoadingConfig) -> Result<&mut Self> {
-        let pool_loaders = Arc::new(PoolLoadersBuilder::default_pool_loaders(self.provider.clone(), pools_config));
+        let pool_loaders = Arc::new(PoolLoadersBuilder::default_pool_loaders(
+            self.provider.clone(),
+            pools_config.with_chain_parameters(self.bc.chain_parameters()),
+        ));
         self.actor_manager.start(ProtocolPoolLoaderOneShotActor::new(self.provider.clone(), pool_loaders).on_bc(&self.bc))?;
         Ok(self)
     }
 
+    /// Start token metadata loader for tokens of new pools
+    pub fn with_token_loader(&mut self) -> Result<&mut Self> {
+        self.actor_manager.start(TokenLoaderActor::new(self.provider.clone()).on_bc(&self.bc))?;
+        Ok(self)
+    }
+
     /// Start all pool loaders
     pub fn with_pool_loaders(&mut self, pools_config: PoolsLoadingConfig) -> Result<&mut Self> {
         if pools_config.is_enabled(PoolClass::Curve) {
             self.with_new_pool_loader(pools_config.clone())?
                 .with_pool_history_loader(pools_config.clone())?
                 .with_curve_pool_protocol_loader(pools_config.clone())?
-                .with_pool_loader(pools_config)
+                .with_pool_loader(pools_config)?
+                .with_token_loader()
         } else {
-            self.with_new_pool_loader(pools_config.clone())?.with_pool_history_loader(pools_config.clone())?.with_pool_loader(pools_config)
+            self.with_new_pool_loader(pools_config.clone())?
+                .with_pool_history_loader(pools_config.clone())?
+                .with_pool_loader(pools_config)?
+                .with_token_loader()
         }
     }
 
     //
-    pub fn with_preloaded_state(&mut self, pools: Vec<(Address, PoolClass)>, state_required: Option<RequiredState>) -> Result<&mut Self> {
-        let pool_loaders = Arc::new(PoolLoadersBuilder::default_pool_loaders(self.provider.clone(), PoolsLoadingConfig::default()));
+    pub async fn with_preloaded_state(
+        &mut self,
+        pools: Vec<(Address, PoolClass)>,
+        state_required: Option<RequiredState>,
+    ) -> Result<&mut Self> {
+        let pool_loaders = Arc::new(PoolLoadersBuilder::default_pool_loaders(
+            self.provider.clone(),
+            PoolsLoadingConfig::default().with_chain_parameters(self.bc.chain_parameters()),
+        ));
         let mut actor = RequiredPoolLoaderActor::new(self.provider.clone(), pool_loaders);
 
         for (pool_address, pool_class) in pools {
@@ -388,7 +521,7 @@ where
             actor = actor.with_required_state(state_required);
         }
 
-        self.actor_manager.start_and_wait(actor.on_bc(&self.bc, &self.state))?;
+        self.actor_manager.start_and_wait(actor.on_bc(&self.bc, &self.state)).await?;
         Ok(self)
     }
 
@@ -454,14 +587,27 @@ where
     /// Start backrun for pending txs
     pub fn with_backrun_mempool(&mut self, backrun_config: BackrunConfig) -> Result<&mut Self> {
         if !self.has_state_update {
-            self.actor_manager.start(StateChangeArbSearcherActor::new(backrun_config).on_bc(&self.bc, &self.strategy))?;
+            self.actor_manager.start(StateChangeArbSearcherActor::new(backrun_config.clone()).on_bc(&self.bc, &self.strategy))?;
             self.has_state_update = true
         }
-        self.actor_manager.start(PendingTxStateChangeProcessorActor::new(self.provider.clone()).on_bc(
-            &self.bc,
-            &self.state,
-            &self.strategy,
-        ))?;
+        self.actor_manager.start(
+            PendingTxStateChangeProcessorActor::new(self.provider.clone())
+                .with_simulation(backrun_config.pending_tx_simulation())
+                .with_bundling(backrun_config.bundle_pending_txs())
+                .on_bc(&self.bc, &self.state, &self.strategy),
+        )?;
+        Ok(self)
+    }
+
+    /// Start competitor analysis of new blocks, opportunities are appended to `path`
+    pub fn with_block_tx_analyzer(&mut self, path: PathBuf) -> Result<&mut Self> {
+        self.actor_manager.start(BlockTxAnalyzerActor::new(self.provider.clone(), path).on_bc(&self.bc, &self.state))?;
+        Ok(self)
+    }
+
+    /// Start recording outcomes of broadcast bundles to `path`, they are read by the historical tips bidder
+    pub fn with_bid_outcome_recorder(&mut self, path: PathBuf) -> Result<&mut Self> {
+        self.actor_manager.start(BidOutcomeRecorderActor::new(path).on_bc(&self.bc))?;
         Ok(self)
     }
 
@@ -482,19 +628,114 @@ where
         Ok(self)
     }
 
-    /// Start web server
-    pub fn with_web_server<S>(&mut self, host: String, router: Router<S>, db_pool: DbPool) -> Result<&mut Self>
+    /// Start web server, control endpoints are only served with a `control_token`
+    pub fn with_web_server<S>(
+        &mut self,
+        host: String,
+        router: Router<S>,
+        db_pool: DbPool,
+        control_token: Option<String>,
+    ) -> Result<&mut Self>
     where
         S: Clone + Send + Sync + 'static,
         Router: From<Router<S>>,
     {
-        self.actor_manager.start(WebServerActor::new(host, router, db_pool, CancellationToken::new()).on_bc(&self.bc, &self.state))?;
+        self.actor_manager.start(
+            WebServerActor::new(host, router, db_pool, CancellationToken::new())
+                .with_control_token(control_token)
+                .on_bc(&self.bc, &self.state),
+        )?;
         Ok(self)
     }
 
     /// Wait for node sync
-    pub fn with_wait_for_node_sync(&mut self) -> Result<&mut Self> {
-        self.actor_manager.start_and_wait(WaitForNodeSyncOneShotBlockingActor::new(self.provider.clone()))?;
+    pub async fn with_wait_for_node_sync(&mut self) -> Result<&mut Self> {
+        self.actor_manager.start_and_wait(WaitForNodeSyncOneShotBlockingActor::new(self.provider.clone())).await?;
+        Ok(self)
+    }
+
+    /// Watches the head freshness and pauses routing and broadcasting while the state is stale
+    pub fn with_head_watchdog(&mut self) -> Result<&mut Self> {
+        self.actor_manager.start(HeadWatchdogActor::new(self.provider.clone()).on_bc(&self.bc))?;
+        Ok(self)
+    }
+
+    /// Watches the head freshness with custom thresholds
+    pub fn with_head_watchdog_config(&mut self, config: HeadWatchdogConfig) -> Result<&mut Self> {
+        self.actor_manager.start(HeadWatchdogActor::new(self.provider.clone()).with_config(config).on_bc(&self.bc))?;
+        Ok(self)
+    }
+
+    /// Validates the channel wiring of the pipeline and starts its actors in order
+    pub async fn with_pipeline(&mut self, pipeline: &PipelineConfig) -> Result<&mut Self> {
+        pipeline.validate()?;
+        self.with_pipeline_unchecked(pipeline).await
+    }
+
+    /// Starts the actors of the pipeline in order, the wiring must be checked together with the actors started outside the pipeline
+    pub async fn with_pipeline_unchecked(&mut self, pipeline: &PipelineConfig) -> Result<&mut Self> {
+        for actor in pipeline.actors.iter() {
+            match actor {
+                PipelineActorConfig::WaitForNodeSync => self.with_wait_for_node_sync().await?,
+                PipelineActorConfig::HeadWatchdog => self.with_head_watchdog()?,
+                PipelineActorConfig::BlockEvents => self.with_block_events(NodeBlockActorConfig::all_enabled())?,
+                PipelineActorConfig::BlockPlayer { start_block, end_block } => self.with_block_player(*start_block, *end_block)?,
+                PipelineActorConfig::ExexEvents => self.with_exex_events()?,
+                PipelineActorConfig::Mempool => self.mempool()?,
+                PipelineActorConfig::LocalMempoolEvents => self.with_local_mempool_events()?,
+                PipelineActorConfig::MempoolRecorder { path } => self.with_mempool_recorder(path.clone())?,
+                PipelineActorConfig::MempoolLogPlayer { path, speed } => {
+                    self.with_mempool_log_player(path.clone(), speed.unwrap_or(1.0))?
+                }
+                PipelineActorConfig::Signers { init } => match init {
+                    Some(SignersInit::Env) => self.initialize_signers_with_env().await?,
+                    Some(SignersInit::Anvil) => self.initialize_signers_with_anvil().await?,
+                    Some(SignersInit::Random) => self.initialize_signers_with_key(None).await?,
+                    None => self.with_signers()?,
+                },
+                PipelineActorConfig::MarketStatePreloader => self.with_market_state_preloader().await?,
+                PipelineActorConfig::NonceAndBalanceMonitor => self.with_nonce_and_balance_monitor()?,
+                PipelineActorConfig::BlockHistory => self.with_block_history()?,
+                PipelineActorConfig::Price { reference: Some(reference) } => {
+                    self.with_price_station_and_reference_prices(reference.clone())?
+                }
+                PipelineActorConfig::Price { reference: None } => self.with_price_station()?,
+                PipelineActorConfig::PoolLoaders { history, new, protocol } => {
+                    let pools_config = PoolsLoadingConfig::default().with_chain_parameters(self.bc.chain_parameters());
+                    if *new {
+                        self.with_new_pool_loader(pools_config.clone())?;
+                    }
+                    if *history {
+                        self.with_pool_history_loader(pools_config.clone())?;
+                    }
+                    if *protocol {
+                        self.with_curve_pool_protocol_loader(pools_config.clone())?;
+                    }
+                    self.with_pool_loader(pools_config)?.with_token_loader()?
+                }
+                PipelineActorConfig::SwapRouter { inventory: None } => self.with_swap_router()?,
+                PipelineActorConfig::SwapRouter { inventory: Some(inventory_config) } => {
+                    self.with_swap_router_and_inventory(inventory_config.clone())?
+                }
+                PipelineActorConfig::EvmEstimator { provider: true } => self.with_evm_estimator_and_provider()?,
+                PipelineActorConfig::EvmEstimator { provider: false } => self.with_evm_estimator()?,
+                PipelineActorConfig::GethEstimator => self.with_geth_estimator()?,
+                PipelineActorConfig::FlashbotsBroadcaster { allow_broadcast } => self.with_flashbots_broadcaster(*allow_broadcast)?,
+                PipelineActorConfig::HealthMonitorPools => self.with_health_monitor_pools()?,
+                PipelineActorConfig::HealthMonitorStuffingTx => self.with_health_monitor_stuffing_tx()?,
+                PipelineActorConfig::SwapPathMerger => self.with_swap_path_merger()?,
+                PipelineActorConfig::SamePathMerger => self.with_same_path_merger()?,
+                PipelineActorConfig::DiffPathMerger => self.with_diff_path_merger()?,
+                PipelineActorConfig::BackrunBlock { config } => self.with_backrun_block(config.clone())?,
+                PipelineActorConfig::BackrunMempool { config } => self.with_backrun_mempool(config.clone())?,
+                PipelineActorConfig::BlockTxAnalyzer { path } => self.with_block_tx_analyzer(path.clone())?,
+                PipelineActorConfig::BidOutcomeRecorder { path } => self.with_bid_outcome_recorder(path.clone())?,
+                PipelineActorConfig::InfluxdbWriter { url, database, tags } => {
+                    self.with_influxdb_writer(url.clone(), database.clone(), tags.clone())?
+                }
+                PipelineActorConfig::BlockLatencyRecorder => self.with_block_latency_recorder()?,
+            };
+        }
         Ok(self)
     }
 }
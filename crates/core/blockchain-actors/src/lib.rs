@@ -1,3 +1,5 @@
 pub use actor::BlockchainActors;
+pub use pipeline::{ChannelWiring, PipelineActorConfig, PipelineConfig, SignersInit, WiringIssue, TELEMETRY_CHANNELS};
 
 mod actor;
+mod pipeline;
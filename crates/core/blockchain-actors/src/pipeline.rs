@@ -0,0 +1,468 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::path::PathBuf;
+
+use eyre::{eyre, Result};
+use loom_defi_price::ReferencePriceConfig;
+use loom_strategy_backrun::BackrunConfig;
+use loom_types_entities::InventoryConfig;
+use serde::Deserialize;
+use tracing::error;
+
+const BLOCK_CHANNELS: [&str; 4] = ["new_block_headers", "new_block_with_tx", "new_block_logs", "new_block_state_update"];
+
+/// Channels only used for metrics and health events, producing them without a consumer is fine
+pub const TELEMETRY_CHANNELS: [&str; 2] = ["influx_write", "pool_health_monitor"];
+
+/// Shared states kept up to date by an actor, wired like channels so their readers have an updater
+pub const STATE_UPDATES: [&str; 1] = ["nonce_and_balance"];
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SignersInit {
+    /// Encrypted private key from the DATA env var
+    Env,
+    /// Default anvil private key
+    Anvil,
+    Random,
+}
+
+/// Actor kind with its parameters, each kind maps to a `with_*` method of [`crate::BlockchainActors`]
+#[derive(Clone, Debug, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum PipelineActorConfig {
+    WaitForNodeSync,
+    HeadWatchdog,
+    BlockEvents,
+    /// Replays historical blocks from the provider, used for backtesting
+    BlockPlayer {
+        start_block: u64,
+        end_block: u64,
+    },
+    ExexEvents,
+    Mempool,
+    LocalMempoolEvents,
+    /// Records mempool and block events to a json lines file
+    MempoolRecorder {
+        path: PathBuf,
+    },
+    /// Replays a recording of the mempool recorder instead of node events, speed 1.0 keeps the original timing
+    MempoolLogPlayer {
+        path: PathBuf,
+        speed: Option<f64>,
+    },
+    Signers {
+        init: Option<SignersInit>,
+    },
+    MarketStatePreloader,
+    NonceAndBalanceMonitor,
+    BlockHistory,
+    /// Spot prices are checked against `reference` prices if it is set, divergences are reported as health events
+    Price {
+        reference: Option<ReferencePriceConfig>,
+    },
+    PoolLoaders {
+        #[serde(default)]
+        history: bool,
+        #[serde(default)]
+        new: bool,
+        #[serde(default)]
+        protocol: bool,
+    },
+    SwapRouter {
+        /// Funds swaps from the multicaller token balances and monitors them
+        #[serde(default)]
+        inventory: Option<InventoryConfig>,
+    },
+    EvmEstimator {
+        /// Use the provider for gas estimation
+        #[serde(default)]
+        provider: bool,
+    },
+    GethEstimator,
+    FlashbotsBroadcaster {
+        #[serde(default)]
+        allow_broadcast: bool,
+    },
+    HealthMonitorPools,
+    HealthMonitorStuffingTx,
+    SwapPathMerger,
+    SamePathMerger,
+    DiffPathMerger,
+    /// `config.bidding` applies to the swap lines of the searcher, merged swaps use the encoder bidder
+    BackrunBlock {
+        #[serde(default)]
+        config: BackrunConfig,
+    },
+    /// `config.bidding` applies to the swap lines of the searcher, merged swaps use the encoder bidder
+    BackrunMempool {
+        #[serde(default)]
+        config: BackrunConfig,
+    },
+    /// Writes backrun opportunities after each tx of new blocks and the txs that captured them to a json lines file
+    BlockTxAnalyzer {
+        path: PathBuf,
+    },
+    /// Writes the outcomes of broadcast bundles to a json lines file read by the historical tips bidder
+    BidOutcomeRecorder {
+        path: PathBuf,
+    },
+    InfluxdbWriter {
+        url: String,
+        database: String,
+        #[serde(default)]
+        tags: HashMap<String, String>,
+    },
+    BlockLatencyRecorder,
+}
+
+impl PipelineActorConfig {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::WaitForNodeSync => "wait_for_node_sync",
+            Self::HeadWatchdog => "head_watchdog",
+            Self::BlockEvents => "block_events",
+            Self::BlockPlayer { .. } => "block_player",
+            Self::ExexEvents => "exex_events",
+            Self::Mempool => "mempool",
+            Self::LocalMempoolEvents => "local_mempool_events",
+            Self::MempoolRecorder { .. } => "mempool_recorder",
+            Self::MempoolLogPlayer { .. } => "mempool_log_player",
+            Self::Signers { .. } => "signers",
+            Self::MarketStatePreloader => "market_state_preloader",
+            Self::NonceAndBalanceMonitor => "nonce_and_balance_monitor",
+            Self::BlockHistory => "block_history",
+            Self::Price { .. } => "price",
+            Self::PoolLoaders { .. } => "pool_loaders",
+            Self::SwapRouter { .. } => "swap_router",
+            Self::EvmEstimator { .. } => "evm_estimator",
+            Self::GethEstimator => "geth_estimator",
+            Self::FlashbotsBroadcaster { .. } => "flashbots_broadcaster",
+            Self::HealthMonitorPools => "health_monitor_pools",
+            Self::HealthMonitorStuffingTx => "health_monitor_stuffing_tx",
+            Self::SwapPathMerger => "swap_path_merger",
+            Self::SamePathMerger => "same_path_merger",
+            Self::DiffPathMerger => "diff_path_merger",
+            Self::BackrunBlock { .. } => "backrun_block",
+            Self::BackrunMempool { .. } => "backrun_mempool",
+            Self::BlockTxAnalyzer { .. } => "block_tx_analyzer",
+            Self::BidOutcomeRecorder { .. } => "bid_outcome_recorder",
+            Self::InfluxdbWriter { .. } => "influxdb_writer",
+            Self::BlockLatencyRecorder => "block_latency_recorder",
+        }
+    }
+
+    /// Channels the started actors subscribe to
+    pub fn consumes(&self) -> Vec<&'static str> {
+        match self {
+            // mempool actor started with the pending tx sources is fed internally
+            Self::Mempool | Self::LocalMempoolEvents => vec!["new_block_headers", "new_block_with_tx"],
+            Self::Signers { .. } => vec!["tx_compose"],
+            Self::NonceAndBalanceMonitor | Self::Price { .. } => vec!["market_events"],
+            Self::BlockHistory | Self::MempoolRecorder { .. } => BLOCK_CHANNELS.to_vec(),
+            Self::PoolLoaders { new: true, .. } => vec!["new_block_logs"],
+            // inventory balances of the multicaller are updated by the nonce and balance monitor
+            Self::SwapRouter { inventory: Some(_) } => vec!["strategy_compose", "market_events", "nonce_and_balance"],
+            Self::SwapRouter { inventory: None } => vec!["strategy_compose"],
+            Self::EvmEstimator { .. } | Self::GethEstimator => vec!["strategy_estimate"],
+            // the block player puts signed txes into the replayed mempool
+            Self::FlashbotsBroadcaster { .. } | Self::BlockPlayer { .. } => vec!["tx_compose"],
+            Self::BidOutcomeRecorder { .. } => vec!["tx_compose", "new_block_with_tx"],
+            Self::HealthMonitorPools => vec!["pool_health_monitor"],
+            Self::HealthMonitorStuffingTx => vec!["tx_compose", "market_events"],
+            Self::SwapPathMerger | Self::SamePathMerger | Self::DiffPathMerger => vec!["market_events", "strategy_compose"],
+            Self::BackrunBlock { .. } | Self::BlockTxAnalyzer { .. } => vec!["market_events"],
+            Self::BackrunMempool { .. } => vec!["market_events", "mempool_events"],
+            Self::InfluxdbWriter { .. } => vec!["influx_write"],
+            Self::BlockLatencyRecorder => vec!["new_block_headers"],
+            Self::WaitForNodeSync
+            | Self::HeadWatchdog
+            | Self::BlockEvents
+            | Self::ExexEvents
+            | Self::MempoolLogPlayer { .. }
+            | Self::MarketStatePreloader
+            | Self::PoolLoaders { .. } => vec![],
+        }
+    }
+
+    /// Channels the started actors publish to
+    pub fn produces(&self) -> Vec<&'static str> {
+        match self {
+            Self::HeadWatchdog | Self::Price { reference: Some(_) } => vec!["pool_health_monitor"],
+            Self::BlockEvents | Self::BlockPlayer { .. } => BLOCK_CHANNELS.to_vec(),
+            Self::ExexEvents | Self::MempoolLogPlayer { .. } => [BLOCK_CHANNELS.as_slice(), &["mempool_events", "influx_write"]].concat(),
+            Self::Mempool | Self::LocalMempoolEvents => vec!["mempool_events", "influx_write"],
+            Self::Signers { .. } => vec!["tx_compose"],
+            Self::BlockHistory => vec!["market_events"],
+            Self::NonceAndBalanceMonitor => vec!["nonce_and_balance"],
+            Self::PoolLoaders { .. } => vec!["market_events"],
+            Self::SwapRouter { inventory: Some(_) } => vec!["strategy_estimate", "tx_compose", "pool_health_monitor"],
+            Self::SwapRouter { inventory: None } => vec!["strategy_estimate", "tx_compose"],
+            Self::EvmEstimator { .. } => vec!["strategy_compose", "pool_health_monitor", "influx_write"],
+            Self::GethEstimator | Self::SwapPathMerger | Self::SamePathMerger | Self::DiffPathMerger => vec!["strategy_compose"],
+            Self::HealthMonitorPools | Self::HealthMonitorStuffingTx | Self::BlockLatencyRecorder => vec!["influx_write"],
+            Self::BackrunBlock { .. } | Self::BackrunMempool { .. } => vec!["strategy_compose", "pool_health_monitor", "influx_write"],
+            Self::WaitForNodeSync
+            | Self::MarketStatePreloader
+            | Self::Price { reference: None }
+            | Self::MempoolRecorder { .. }
+            | Self::FlashbotsBroadcaster { .. }
+            | Self::BlockTxAnalyzer { .. }
+            | Self::BidOutcomeRecorder { .. }
+            | Self::InfluxdbWriter { .. } => vec![],
+        }
+    }
+}
+
+/// Problem in the channel wiring with the path of the offending actor
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WiringIssue {
+    pub path: String,
+    pub message: String,
+}
+
+impl fmt::Display for WiringIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} : {}", self.path, self.message)
+    }
+}
+
+/// Producer and consumer counts of channels, used to find channels nobody writes to or reads from
+#[derive(Clone, Debug, Default)]
+pub struct ChannelWiring {
+    producers: HashMap<&'static str, usize>,
+    consumers: HashMap<&'static str, usize>,
+}
+
+impl ChannelWiring {
+    pub fn add(&mut self, consumes: &[&'static str], produces: &[&'static str]) {
+        for channel in consumes {
+            *self.consumers.entry(channel).or_default() += 1;
+        }
+        for channel in produces {
+            *self.producers.entry(channel).or_default() += 1;
+        }
+    }
+
+    /// Checks an actor that was added before. An actor feeding its own input, like the router re-sending swaps, does not count.
+    pub fn issues(&self, path: &str, consumes: &[&'static str], produces: &[&'static str]) -> Vec<WiringIssue> {
+        let others = |counts: &HashMap<&'static str, usize>, channel: &str, own: &[&'static str]| {
+            counts.get(channel).copied().unwrap_or_default().saturating_sub(own.contains(&channel) as usize)
+        };
+
+        let mut issues = Vec::new();
+        for channel in consumes {
+            if others(&self.producers, channel, produces) == 0 {
+                issues.push(WiringIssue { path: path.to_string(), message: format!("no producer for channel \"{channel}\"") });
+            }
+        }
+        for channel in produces {
+            let optional = TELEMETRY_CHANNELS.contains(channel) || STATE_UPDATES.contains(channel);
+            if !optional && others(&self.consumers, channel, consumes) == 0 {
+                issues.push(WiringIssue { path: path.to_string(), message: format!("no consumer for channel \"{channel}\"") });
+            }
+        }
+        issues
+    }
+}
+
+/// Actors started in order by [`crate::BlockchainActors::with_pipeline`]
+///
+/// ```toml
+/// actors = [
+///     { kind = "block_events" },
+///     { kind = "block_history" },
+///     { kind = "flashbots_broadcaster", allow_broadcast = true },
+/// ]
+/// ```
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct PipelineConfig {
+    pub actors: Vec<PipelineActorConfig>,
+}
+
+impl PipelineConfig {
+    pub fn new(actors: Vec<PipelineActorConfig>) -> Self {
+        Self { actors }
+    }
+
+    pub fn with_actor(self, actor: PipelineActorConfig) -> Self {
+        let mut actors = self.actors;
+        actors.push(actor);
+        Self { actors }
+    }
+
+    /// Every consumed channel needs a producer and every produced channel a consumer within the pipeline
+    pub fn wiring_issues(&self) -> Vec<WiringIssue> {
+        let mut wiring = ChannelWiring::default();
+        for actor in self.actors.iter() {
+            wiring.add(&actor.consumes(), &actor.produces());
+        }
+        let mut issues: Vec<WiringIssue> = self
+            .actors
+            .iter()
+            .enumerate()
+            .flat_map(|(idx, actor)| wiring.issues(&format!("actors[{idx}] {}", actor.kind()), &actor.consumes(), &actor.produces()))
+            .collect();
+        issues.extend(self.inventory_issues(|idx, actor| format!("actors[{idx}] {}", actor.kind())));
+        issues
+    }
+
+    /// The simulated state has no token balances of the multicaller, inventory funded swaps are estimated with the provider
+    pub fn inventory_issues(&self, path: impl Fn(usize, &PipelineActorConfig) -> String) -> Vec<WiringIssue> {
+        if !self.actors.iter().any(|actor| matches!(actor, PipelineActorConfig::SwapRouter { inventory: Some(_) })) {
+            return vec![];
+        }
+        self.actors
+            .iter()
+            .enumerate()
+            .filter(|(_, actor)| matches!(actor, PipelineActorConfig::EvmEstimator { provider: false }))
+            .map(|(idx, actor)| WiringIssue {
+                path: path(idx, actor),
+                message: "inventory funding needs the provider, set provider = true".to_string(),
+            })
+            .collect()
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        let issues = self.wiring_issues();
+        if issues.is_empty() {
+            Ok(())
+        } else {
+            for issue in issues.iter() {
+                error!("Pipeline wiring : {issue}");
+            }
+            Err(eyre!("PIPELINE_WIRING"))
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use loom_defi_price::DivergenceAction;
+
+    #[test]
+    fn test_wiring() {
+        let pipeline: PipelineConfig = toml::from_str(
+            r#"
+            actors = [
+                { kind = "block_events" },
+                { kind = "block_history" },
+                { kind = "price" },
+                { kind = "backrun_block" },
+                { kind = "swap_router" },
+                { kind = "evm_estimator" },
+                { kind = "signers", init = "env" },
+                { kind = "flashbots_broadcaster", allow_broadcast = false },
+            ]
+            "#,
+        )
+        .unwrap();
+        assert!(pipeline.wiring_issues().is_empty());
+
+        let pipeline: PipelineConfig = toml::from_str(
+            r#"
+            actors = [
+                { kind = "block_player", start_block = 20179184, end_block = 20179284 },
+                { kind = "block_history" },
+                { kind = "backrun_block" },
+                { kind = "swap_router" },
+                { kind = "evm_estimator" },
+            ]
+            "#,
+        )
+        .unwrap();
+        assert!(pipeline.wiring_issues().is_empty());
+
+        let pipeline: PipelineConfig = toml::from_str(
+            r#"
+            actors = [
+                { kind = "mempool_log_player", path = "mempool.jsonl", speed = 0.0 },
+                { kind = "block_history" },
+                { kind = "backrun_mempool" },
+                { kind = "swap_router" },
+                { kind = "evm_estimator" },
+                { kind = "signers" },
+                { kind = "mempool_recorder", path = "replayed.jsonl" },
+            ]
+            "#,
+        )
+        .unwrap();
+        assert!(pipeline.wiring_issues().is_empty());
+
+        let pipeline = PipelineConfig::new(vec![PipelineActorConfig::BlockHistory, PipelineActorConfig::SwapRouter { inventory: None }]);
+        let messages: Vec<String> = pipeline.wiring_issues().iter().map(|issue| issue.to_string()).collect();
+        assert_eq!(
+            messages,
+            vec![
+                "actors[0] block_history : no producer for channel \"new_block_headers\"",
+                "actors[0] block_history : no producer for channel \"new_block_with_tx\"",
+                "actors[0] block_history : no producer for channel \"new_block_logs\"",
+                "actors[0] block_history : no producer for channel \"new_block_state_update\"",
+                "actors[0] block_history : no consumer for channel \"market_events\"",
+                "actors[1] swap_router : no producer for channel \"strategy_compose\"",
+                "actors[1] swap_router : no consumer for channel \"strategy_estimate\"",
+                "actors[1] swap_router : no consumer for channel \"tx_compose\"",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_inventory_wiring() {
+        let actors = r#"
+            { kind = "block_events" },
+            { kind = "block_history" },
+            { kind = "backrun_block" },
+            { kind = "swap_router", inventory = { mode = "inventory" } },
+            { kind = "signers" },
+            { kind = "flashbots_broadcaster" },
+        "#;
+
+        let pipeline: PipelineConfig = toml::from_str(&format!("actors = [{actors} {{ kind = \"evm_estimator\" }}]")).unwrap();
+        let messages: Vec<String> = pipeline.wiring_issues().iter().map(|issue| issue.to_string()).collect();
+        assert_eq!(
+            messages,
+            vec![
+                "actors[3] swap_router : no producer for channel \"nonce_and_balance\"",
+                "actors[6] evm_estimator : inventory funding needs the provider, set provider = true",
+            ]
+        );
+
+        let pipeline: PipelineConfig = toml::from_str(&format!(
+            "actors = [{actors} {{ kind = \"evm_estimator\", provider = true }}, {{ kind = \"nonce_and_balance_monitor\" }}]"
+        ))
+        .unwrap();
+        assert!(pipeline.wiring_issues().is_empty());
+    }
+
+    #[test]
+    fn test_price_reference() {
+        let pipeline: PipelineConfig = toml::from_str(
+            r#"
+            [[actors]]
+            kind = "block_events"
+            [[actors]]
+            kind = "block_history"
+            [[actors]]
+            kind = "price"
+            [actors.reference]
+            max_divergence_bps = 300
+            action = "refuse"
+            [actors.reference.sources."0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48"]
+            type = "chainlink_eth"
+            aggregator = "0x986b5e1e1755e3c2440e960477f25201b0a8bbd4"
+            [[actors]]
+            kind = "health_monitor_pools"
+            "#,
+        )
+        .unwrap();
+        assert!(pipeline.wiring_issues().is_empty());
+
+        let PipelineActorConfig::Price { reference: Some(reference) } = &pipeline.actors[2] else {
+            panic!("price actor without reference prices");
+        };
+        assert_eq!(reference.max_divergence_bps, 300);
+        assert_eq!(reference.action, DivergenceAction::Refuse);
+        assert_eq!(reference.sources.len(), 1);
+    }
+}
@@ -5,12 +5,12 @@ use influxdb::WriteQuery;
 use loom_core_actors::{Broadcaster, SharedState};
 use loom_types_blockchain::{ChainParameters, Mempool};
 use loom_types_blockchain::{LoomDataTypes, LoomDataTypesEthereum};
-use loom_types_entities::{AccountNonceAndBalanceState, LatestBlock, Market};
+use loom_types_entities::{AccountNonceAndBalanceState, ControlFlags, HeadHealth, LatestBlock, Market};
 use loom_types_events::{
     LoomTask, MarketEvents, MempoolEvents, MessageBlock, MessageBlockHeader, MessageBlockLogs, MessageBlockStateUpdate, MessageHealthEvent,
     MessageMempoolDataUpdate, MessageTxCompose,
 };
-use tracing::error;
+use tracing::{error, warn};
 
 #[derive(Clone)]
 pub struct Blockchain<LDT: LoomDataTypes + 'static = LoomDataTypesEthereum> {
@@ -18,6 +18,8 @@ pub struct Blockchain<LDT: LoomDataTypes + 'static = LoomDataTypesEthereum> {
     chain_parameters: ChainParameters,
     market: SharedState<Market<LDT>>,
     latest_block: SharedState<LatestBlock<LDT>>,
+    head_health: SharedState<HeadHealth>,
+    control_flags: SharedState<ControlFlags>,
     mempool: SharedState<Mempool<LDT>>,
     account_nonce_and_balance: SharedState<AccountNonceAndBalanceState<LDT>>,
 
@@ -33,37 +35,49 @@ pub struct Blockchain<LDT: LoomDataTypes + 'static = LoomDataTypesEthereum> {
     pool_health_monitor_channel: Broadcaster<MessageHealthEvent<LDT>>,
     influxdb_write_channel: Broadcaster<WriteQuery>,
     tasks_channel: Broadcaster<LoomTask>,
+    control_flags_channel: Broadcaster<ControlFlags>,
 }
 
 impl Blockchain<LoomDataTypesEthereum> {
+    /// Creates a blockchain with preset parameters for the chain. Unknown chains fall back to Ethereum parameters.
     pub fn new(chain_id: ChainId) -> Blockchain<LoomDataTypesEthereum> {
-        let new_block_headers_channel: Broadcaster<MessageBlockHeader> = Broadcaster::new(10);
-        let new_block_with_tx_channel: Broadcaster<MessageBlock> = Broadcaster::new(10);
-        let new_block_state_update_channel: Broadcaster<MessageBlockStateUpdate> = Broadcaster::new(10);
-        let new_block_logs_channel: Broadcaster<MessageBlockLogs> = Broadcaster::new(10);
+        if let Err(error) = ChainParameters::try_from(chain_id) {
+            warn!(%error, chain_id, "No chain parameters preset, using Ethereum parameters");
+        }
+        Self::new_with_chain_parameters(ChainParameters::from_chain_id(chain_id))
+    }
+
+    pub fn new_with_chain_parameters(chain_parameters: ChainParameters) -> Blockchain<LoomDataTypesEthereum> {
+        let new_block_headers_channel: Broadcaster<MessageBlockHeader> = Broadcaster::named("new_block_headers", 10);
+        let new_block_with_tx_channel: Broadcaster<MessageBlock> = Broadcaster::named("new_block_with_tx", 10);
+        let new_block_state_update_channel: Broadcaster<MessageBlockStateUpdate> = Broadcaster::named("new_block_state_update", 10);
+        let new_block_logs_channel: Broadcaster<MessageBlockLogs> = Broadcaster::named("new_block_logs", 10);
 
-        let new_mempool_tx_channel: Broadcaster<MessageMempoolDataUpdate> = Broadcaster::new(5000);
+        let new_mempool_tx_channel: Broadcaster<MessageMempoolDataUpdate> = Broadcaster::named("new_mempool_tx", 5000);
 
-        let market_events_channel: Broadcaster<MarketEvents> = Broadcaster::new(100);
-        let mempool_events_channel: Broadcaster<MempoolEvents> = Broadcaster::new(2000);
-        let tx_compose_channel: Broadcaster<MessageTxCompose> = Broadcaster::new(2000);
+        let market_events_channel: Broadcaster<MarketEvents> = Broadcaster::named("market_events", 100);
+        let mempool_events_channel: Broadcaster<MempoolEvents> = Broadcaster::named("mempool_events", 2000);
+        let tx_compose_channel: Broadcaster<MessageTxCompose> = Broadcaster::named("tx_compose", 2000);
 
-        let pool_health_monitor_channel: Broadcaster<MessageHealthEvent> = Broadcaster::new(1000);
-        let influx_write_channel: Broadcaster<WriteQuery> = Broadcaster::new(1000);
-        let tasks_channel: Broadcaster<LoomTask> = Broadcaster::new(1000);
+        let pool_health_monitor_channel: Broadcaster<MessageHealthEvent> = Broadcaster::named("pool_health_monitor", 1000);
+        let influx_write_channel: Broadcaster<WriteQuery> = Broadcaster::named("influx_write", 1000);
+        let tasks_channel: Broadcaster<LoomTask> = Broadcaster::named("tasks", 1000);
+        let control_flags_channel: Broadcaster<ControlFlags> = Broadcaster::named("control_flags", 10);
 
         let mut market_instance = Market::default();
 
-        if let Err(error) = add_default_tokens_to_market(&mut market_instance, chain_id) {
+        if let Err(error) = add_default_tokens_to_market(&mut market_instance, &chain_parameters) {
             error!(%error, "Failed to add default tokens to market");
         }
 
         Blockchain {
-            chain_id,
-            chain_parameters: ChainParameters::ethereum(),
+            chain_id: chain_parameters.chain_id,
+            chain_parameters,
             market: SharedState::new(market_instance),
             mempool: SharedState::new(Mempool::<LoomDataTypesEthereum>::new()),
             latest_block: SharedState::new(LatestBlock::new(0, BlockHash::ZERO)),
+            head_health: SharedState::new(HeadHealth::default()),
+            control_flags: SharedState::new(ControlFlags::default()),
             account_nonce_and_balance: SharedState::new(AccountNonceAndBalanceState::new()),
             new_block_headers_channel,
             new_block_with_tx_channel,
@@ -76,6 +90,7 @@ impl Blockchain<LoomDataTypesEthereum> {
             tx_compose_channel,
             influxdb_write_channel: influx_write_channel,
             tasks_channel,
+            control_flags_channel,
         }
     }
 }
@@ -97,6 +112,16 @@ impl<LDT: LoomDataTypes> Blockchain<LDT> {
         self.latest_block.clone()
     }
 
+    /// Written by the head watchdog, stays fresh if no watchdog is running
+    pub fn head_health(&self) -> SharedState<HeadHealth> {
+        self.head_health.clone()
+    }
+
+    /// Runtime switches changed through the control API
+    pub fn control_flags(&self) -> SharedState<ControlFlags> {
+        self.control_flags.clone()
+    }
+
     pub fn mempool(&self) -> SharedState<Mempool<LDT>> {
         self.mempool.clone()
     }
@@ -148,4 +173,9 @@ impl<LDT: LoomDataTypes> Blockchain<LDT> {
     pub fn tasks_channel(&self) -> Broadcaster<LoomTask> {
         self.tasks_channel.clone()
     }
+
+    /// Control flags after every change
+    pub fn control_flags_channel(&self) -> Broadcaster<ControlFlags> {
+        self.control_flags_channel.clone()
+    }
 }
@@ -1,4 +1,4 @@
-use loom_core_actors::Broadcaster;
+use loom_core_actors::{Broadcaster, Mpsc};
 use loom_evm_db::DatabaseLoomExt;
 use loom_types_blockchain::{LoomDataTypes, LoomDataTypesEthereum};
 use loom_types_entities::BlockHistoryState;
@@ -8,7 +8,10 @@ use revm::{Database, DatabaseCommit, DatabaseRef};
 #[derive(Clone)]
 pub struct Strategy<DB: Clone + Send + Sync + 'static, LDT: LoomDataTypes + 'static = LoomDataTypesEthereum> {
     swap_compose_channel: Broadcaster<MessageSwapCompose<DB, LDT>>,
-    state_update_channel: Broadcaster<StateUpdateEvent<DB, LDT>>,
+    /// Heavy state updates are sent to the searcher only, processors wait for it instead of cloning or dropping updates
+    state_update_channel: Mpsc<StateUpdateEvent<DB, LDT>>,
+    /// Routed swaps waiting for gas estimation, the router waits for the estimator instead of dropping them
+    estimate_channel: Mpsc<MessageSwapCompose<DB, LDT>>,
 }
 
 impl<DB: DatabaseRef + Database + DatabaseCommit + BlockHistoryState + DatabaseLoomExt + Send + Sync + Clone + Default + 'static> Default
@@ -23,9 +26,10 @@ impl<DB: DatabaseRef + Database + DatabaseCommit + BlockHistoryState + DatabaseL
     Strategy<DB, LoomDataTypesEthereum>
 {
     pub fn new() -> Self {
-        let compose_channel: Broadcaster<MessageSwapCompose<DB, LoomDataTypesEthereum>> = Broadcaster::new(100);
-        let state_update_channel: Broadcaster<StateUpdateEvent<DB, LoomDataTypesEthereum>> = Broadcaster::new(100);
-        Strategy { swap_compose_channel: compose_channel, state_update_channel }
+        let compose_channel: Broadcaster<MessageSwapCompose<DB, LoomDataTypesEthereum>> = Broadcaster::named("strategy_compose", 100);
+        let state_update_channel: Mpsc<StateUpdateEvent<DB, LoomDataTypesEthereum>> = Mpsc::named("strategy_state_update", 100);
+        let estimate_channel: Mpsc<MessageSwapCompose<DB, LoomDataTypesEthereum>> = Mpsc::named("strategy_estimate", 100);
+        Strategy { swap_compose_channel: compose_channel, state_update_channel, estimate_channel }
     }
 }
 
@@ -34,7 +38,11 @@ impl<DB: Send + Sync + Clone + 'static> Strategy<DB, LoomDataTypesEthereum> {
         self.swap_compose_channel.clone()
     }
 
-    pub fn state_update_channel(&self) -> Broadcaster<StateUpdateEvent<DB, LoomDataTypesEthereum>> {
+    pub fn state_update_channel(&self) -> Mpsc<StateUpdateEvent<DB, LoomDataTypesEthereum>> {
         self.state_update_channel.clone()
     }
+
+    pub fn estimate_channel(&self) -> Mpsc<MessageSwapCompose<DB, LoomDataTypesEthereum>> {
+        self.estimate_channel.clone()
+    }
 }
@@ -1,3 +1,3 @@
-pub use mempool_actor::MempoolActor;
+pub use mempool_actor::{MempoolActor, DEFAULT_MAX_MEMPOOL_TXS};
 
 mod mempool_actor;
@@ -12,8 +12,12 @@ use loom_types_blockchain::{ChainParameters, Mempool, MempoolTx};
 use loom_types_blockchain::{LoomBlock, LoomDataTypes, LoomDataTypesEthereum, LoomHeader, LoomTx};
 use loom_types_events::{MempoolEvents, MessageBlock, MessageBlockHeader, MessageMempoolDataUpdate};
 
+/// Default upper limit of txes kept in the mempool
+pub const DEFAULT_MAX_MEMPOOL_TXS: usize = 100_000;
+
 pub async fn new_mempool_worker<LDT: LoomDataTypes>(
     chain_parameters: ChainParameters,
+    max_txs: usize,
     mempool: SharedState<Mempool<LDT>>,
     mempool_update_rx: Broadcaster<MessageMempoolDataUpdate<LDT>>,
     block_header_rx: Broadcaster<MessageBlockHeader<LDT>>,
@@ -49,6 +53,7 @@ pub async fn new_mempool_worker<LDT: LoomDataTypes>(
 
                 let mut mempool_guard = mempool.write().await;
                 let tx_hash = mempool_update_msg.tx_hash;
+                let source = mempool_update_msg.source();
                 let mempool_entry = mempool_guard.txs.entry(tx_hash).or_insert( MempoolTx::<LDT>{ tx_hash,  source : mempool_update_msg.source(), ..MempoolTx::default()});
                 if let Some(logs) = &mempool_update_msg.mempool_tx.logs {
                     if mempool_entry.logs.is_none() {
@@ -71,8 +76,21 @@ pub async fn new_mempool_worker<LDT: LoomDataTypes>(
                             }
                         }
                         run_sync!(broadcaster.send(MempoolEvents::MempoolTxUpdate {tx_hash }));
+                        if let Some(replaced_tx_hash) = mempool_guard.track_replacement(tx) {
+                            debug!(%replaced_tx_hash, %tx_hash, "Mempool tx replaced");
+                            run_sync!(broadcaster.send(MempoolEvents::TxReplaced { tx_hash: replaced_tx_hash, replaced_by: tx_hash }));
+                        }
+                    } else if tx.has_blob_sidecar() && !mempool_entry.tx.as_ref().is_some_and(|entry_tx| entry_tx.has_blob_sidecar()) {
+                        // a later source may deliver the sidecar of a blob tx
+                        mempool_entry.tx = Some(tx.clone());
                     }
                 }
+                mempool_guard.record_source(tx_hash, &source, Utc::now());
+
+                let evicted = mempool_guard.evict(max_txs);
+                if !evicted.is_empty() {
+                    debug!("Mempool evicted {} txs, len : {}", evicted.len(), mempool_guard.len());
+                }
                 drop(mempool_guard);
             },
             msg = block_header_rx.recv() => {
@@ -179,6 +197,19 @@ pub async fn new_mempool_worker<LDT: LoomDataTypes>(
                        error!("Failed to send mempool stat to influxdb: {:?}", e);
                 }
 
+                for (source, stats) in mempool_write_guard.source_stats() {
+                    let write_query = WriteQuery::new(Timestamp::from(start_time_utc), "mempool_source")
+                        .add_tag("source", source.clone())
+                        .add_field("first_seen", stats.first_seen)
+                        .add_field("late", stats.late)
+                        .add_field("avg_late_delay_ms", stats.avg_late_delay_ms())
+                        .add_field("ahead", stats.ahead)
+                        .add_field("avg_ahead_ms", stats.avg_ahead_ms());
+                    if let Err(e) = influxdb_write_channel_tx.send(write_query) {
+                        error!("Failed to send mempool source stat to influxdb: {:?}", e);
+                    }
+                }
+
                 drop(mempool_write_guard);
             }
         }
@@ -188,6 +219,7 @@ pub async fn new_mempool_worker<LDT: LoomDataTypes>(
 #[derive(Accessor, Consumer, Producer)]
 pub struct MempoolActor<LDT: LoomDataTypes + 'static = LoomDataTypesEthereum> {
     chain_parameters: ChainParameters,
+    max_txs: usize,
     #[accessor]
     mempool: Option<SharedState<Mempool<LDT>>>,
     #[consumer]
@@ -206,6 +238,7 @@ impl<LDT: LoomDataTypes> Default for MempoolActor<LDT> {
     fn default() -> Self {
         Self {
             chain_parameters: ChainParameters::ethereum(),
+            max_txs: DEFAULT_MAX_MEMPOOL_TXS,
             mempool: None,
             mempool_update_rx: None,
             mempool_events_tx: None,
@@ -221,9 +254,14 @@ impl<LDT: LoomDataTypes> MempoolActor<LDT> {
         MempoolActor::default()
     }
 
+    pub fn with_max_txs(self, max_txs: usize) -> Self {
+        Self { max_txs, ..self }
+    }
+
     pub fn on_bc(self, bc: &Blockchain<LDT>) -> MempoolActor<LDT> {
         Self {
             chain_parameters: bc.chain_parameters(),
+            max_txs: self.max_txs,
             mempool: Some(bc.mempool()),
             mempool_update_rx: Some(bc.new_mempool_tx_channel()),
             block_header_rx: Some(bc.new_block_headers_channel()),
@@ -238,6 +276,7 @@ impl<LDT: LoomDataTypes> Actor for MempoolActor<LDT> {
     fn start(&self) -> ActorResult {
         let task = tokio::task::spawn(new_mempool_worker(
             self.chain_parameters.clone(),
+            self.max_txs,
             self.mempool.clone().unwrap(),
             self.mempool_update_rx.clone().unwrap(),
             self.block_header_rx.clone().unwrap(),
@@ -1,20 +1,30 @@
+use std::sync::Arc;
+
+use alloy_primitives::{Address, U256};
 use eyre::{eyre, Result};
-use loom_core_actors::{Accessor, Actor, ActorResult, Broadcaster, Consumer, Producer, SharedState, WorkerResult};
+use loom_core_actors::{
+    Accessor, Actor, ActorResult, Broadcaster, BroadcasterReceiver, Consumer, Mpsc, MpscProducer, Producer, SharedState, WorkerResult,
+};
 use loom_core_actors_macros::{Accessor, Consumer, Producer};
 use loom_core_blockchain::{Blockchain, Strategy};
-use loom_types_entities::{AccountNonceAndBalanceState, TxSigners};
+use loom_types_blockchain::Mempool;
+use loom_types_entities::{
+    AccountNonceAndBalanceState, ControlFlags, HeadHealth, InventoryConfig, InventoryReservations, SwapFunding, TxSigners,
+};
 use loom_types_events::{MessageSwapCompose, MessageTxCompose, SwapComposeData, SwapComposeMessage, TxComposeData};
 use revm::DatabaseRef;
 use tokio::sync::broadcast::error::RecvError;
-use tokio::sync::broadcast::Receiver;
+use tokio_util::sync::CancellationToken;
 use tracing::{debug, error, info};
 
 /// encoder task performs initial routing for swap request
 async fn router_task_prepare<DB: DatabaseRef + Send + Sync + Clone + 'static>(
     route_request: SwapComposeData<DB>,
-    compose_channel_tx: Broadcaster<MessageSwapCompose<DB>>,
+    estimate_channel_tx: Mpsc<MessageSwapCompose<DB>>,
     signers: SharedState<TxSigners>,
     account_monitor: SharedState<AccountNonceAndBalanceState>,
+    inventory: Option<(Address, Arc<InventoryConfig>)>,
+    inventory_reservations: SharedState<InventoryReservations>,
 ) -> Result<()> {
     debug!("router_task_prepare started {}", route_request.swap);
 
@@ -33,15 +43,43 @@ async fn router_task_prepare<DB: DatabaseRef + Send + Sync + Clone + 'static>(
 
     let gas = (route_request.swap.pre_estimate_gas()) * 2;
 
+    let funding = match &inventory {
+        Some((multicaller_address, inventory_config)) => {
+            let balance = match route_request.swap.get_first_token() {
+                Some(token) => account_monitor
+                    .read()
+                    .await
+                    .get_account(multicaller_address)
+                    .map(|account| account.get_balance(&token.get_address()))
+                    .unwrap_or_default(),
+                None => U256::ZERO,
+            };
+            // checked and reserved under the lock, so concurrent requests can't fund from the same tokens
+            let mut reservations = inventory_reservations.write().await;
+            inventory_config.reserve_funding(
+                &route_request.swap,
+                *multicaller_address,
+                balance,
+                route_request.tx_compose.next_block_number,
+                &mut reservations,
+            )
+        }
+        None => SwapFunding::Flash,
+    };
+    if let SwapFunding::Inventory { balance } = funding {
+        debug!(%balance, swap = %route_request.swap, "Swap funded from inventory");
+    }
+
     let estimate_request = SwapComposeData {
         tx_compose: TxComposeData { signer: Some(signer), nonce, eth_balance, gas, ..route_request.tx_compose },
+        funding,
         ..route_request
     };
     let estimate_request = MessageSwapCompose::estimate(estimate_request);
 
-    match compose_channel_tx.send(estimate_request) {
+    match estimate_channel_tx.send(estimate_request).await {
         Err(_) => {
-            error!("compose_channel_tx.send(estimate_request)");
+            error!("estimate_channel_tx.send(estimate_request)");
             Err(eyre!("ERROR_SENDING_REQUEST"))
         }
         Ok(_) => Ok(()),
@@ -65,36 +103,102 @@ async fn router_task_broadcast<DB: DatabaseRef + Send + Sync + Clone + 'static>(
     }
 }
 
+/// Without a head health state swaps are always routed
+async fn is_head_fresh(head_health: Option<&SharedState<HeadHealth>>) -> bool {
+    match head_health {
+        Some(head_health) => head_health.read().await.is_fresh(),
+        None => true,
+    }
+}
+
+/// Returns false if the control flags drop the request, sets the tips limit applied by the encoder otherwise
+async fn apply_control_flags<DB>(control_flags: Option<&SharedState<ControlFlags>>, request: &mut SwapComposeData<DB>) -> bool {
+    let Some(control_flags) = control_flags else {
+        return true;
+    };
+    let control_flags = control_flags.read().await;
+
+    if !control_flags.is_strategy_enabled(request.origin.as_deref()) {
+        debug!(origin = ?request.origin, "Strategy is disabled, swap compose request skipped");
+        return false;
+    }
+    if let Some(reason) = control_flags.swap_rejection(&request.swap) {
+        debug!(reason, swap = %request.swap, "Swap compose request skipped");
+        return false;
+    }
+    // requests without a tips percentage are left to the tips bidder, the limit is applied after bidding
+    request.max_tips_pct = control_flags.max_tips_pct;
+    true
+}
+
+/// Backruns of replaced txes can't be included anymore
+async fn is_stuffing_replaced<DB>(mempool: Option<&SharedState<Mempool>>, request: &SwapComposeData<DB>) -> bool {
+    match mempool {
+        Some(mempool) => mempool.read().await.is_any_replaced(&request.tx_compose.stuffing_txs_hashes),
+        None => false,
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
 async fn swap_router_worker<DB: DatabaseRef + Clone + Send + Sync + 'static>(
     signers: SharedState<TxSigners>,
     account_monitor: SharedState<AccountNonceAndBalanceState>,
+    mempool: Option<SharedState<Mempool>>,
+    inventory: Option<(Address, Arc<InventoryConfig>)>,
+    head_health: Option<SharedState<HeadHealth>>,
+    control_flags: Option<SharedState<ControlFlags>>,
     swap_compose_channel_rx: Broadcaster<MessageSwapCompose<DB>>,
-    swap_compose_channel_tx: Broadcaster<MessageSwapCompose<DB>>,
+    estimate_channel_tx: Mpsc<MessageSwapCompose<DB>>,
     tx_compose_channel_tx: Broadcaster<MessageTxCompose>,
+    shutdown_token: CancellationToken,
 ) -> WorkerResult {
-    let mut compose_channel_rx: Receiver<MessageSwapCompose<DB>> = swap_compose_channel_rx.subscribe();
+    let mut compose_channel_rx: BroadcasterReceiver<MessageSwapCompose<DB>> = swap_compose_channel_rx.subscribe();
+    let inventory_reservations = SharedState::new(InventoryReservations::default());
 
     info!("swap router worker started");
 
     loop {
         tokio::select! {
+            _ = shutdown_token.cancelled() => {
+                break Ok("Swap router stopped".to_string());
+            }
             msg = compose_channel_rx.recv() => {
                 let msg : Result<MessageSwapCompose<DB>, RecvError> = msg;
                 match msg {
                     Ok(compose_request) => {
+                        if !is_head_fresh(head_health.as_ref()).await {
+                            debug!("Head is not fresh, swap compose request skipped");
+                            continue;
+                        }
                         match compose_request.inner {
-                            SwapComposeMessage::Prepare(swap_compose_request)=>{
+                            SwapComposeMessage::Prepare(mut swap_compose_request)=>{
+                                if !apply_control_flags(control_flags.as_ref(), &mut swap_compose_request).await {
+                                    continue;
+                                }
+                                if is_stuffing_replaced(mempool.as_ref(), &swap_compose_request).await {
+                                    debug!(stuffing = ?swap_compose_request.tx_compose.stuffing_txs_hashes, "Stuffing tx replaced, swap compose request dropped");
+                                    continue;
+                                }
                                 debug!("MessageSwapComposeRequest::Prepare received. stuffing: {:?} swap: {}", swap_compose_request.tx_compose.stuffing_txs_hashes, swap_compose_request.swap);
                                 tokio::task::spawn(
                                     router_task_prepare(
                                         swap_compose_request,
-                                        swap_compose_channel_tx.clone(),
+                                        estimate_channel_tx.clone(),
                                         signers.clone(),
                                         account_monitor.clone(),
+                                        inventory.clone(),
+                                        inventory_reservations.clone(),
                                     )
                                 );
                             }
-                            SwapComposeMessage::Ready(swap_compose_request)=>{
+                            SwapComposeMessage::Ready(mut swap_compose_request)=>{
+                                if !apply_control_flags(control_flags.as_ref(), &mut swap_compose_request).await {
+                                    continue;
+                                }
+                                if is_stuffing_replaced(mempool.as_ref(), &swap_compose_request).await {
+                                    debug!(stuffing = ?swap_compose_request.tx_compose.stuffing_txs_hashes, "Stuffing tx replaced, swap compose request dropped");
+                                    continue;
+                                }
                                 debug!("MessageSwapComposeRequest::Ready received. stuffing: {:?} swap: {}", swap_compose_request.tx_compose.stuffing_txs_hashes, swap_compose_request.swap);
                                 tokio::task::spawn(
                                     router_task_broadcast(
@@ -120,10 +224,18 @@ pub struct SwapRouterActor<DB: Send + Sync + Clone + 'static> {
     signers: Option<SharedState<TxSigners>>,
     #[accessor]
     account_nonce_balance: Option<SharedState<AccountNonceAndBalanceState>>,
+    #[accessor]
+    mempool: Option<SharedState<Mempool>>,
+    /// Multicaller address and its inventory tokens
+    inventory: Option<(Address, Arc<InventoryConfig>)>,
+    #[accessor]
+    head_health: Option<SharedState<HeadHealth>>,
+    #[accessor]
+    control_flags: Option<SharedState<ControlFlags>>,
     #[consumer]
     swap_compose_channel_rx: Option<Broadcaster<MessageSwapCompose<DB>>>,
     #[producer]
-    swap_compose_channel_tx: Option<Broadcaster<MessageSwapCompose<DB>>>,
+    estimate_channel_tx: Option<Mpsc<MessageSwapCompose<DB>>>,
     #[producer]
     tx_compose_channel_tx: Option<Broadcaster<MessageTxCompose>>,
 }
@@ -136,8 +248,12 @@ where
         SwapRouterActor {
             signers: None,
             account_nonce_balance: None,
+            mempool: None,
+            inventory: None,
+            head_health: None,
+            control_flags: None,
             swap_compose_channel_rx: None,
-            swap_compose_channel_tx: None,
+            estimate_channel_tx: None,
             tx_compose_channel_tx: None,
         }
     }
@@ -146,11 +262,19 @@ where
         Self { signers: Some(signers), ..self }
     }
 
+    /// Funds swaps from the tokens held by the multicaller when the inventory config allows it
+    pub fn with_inventory(self, multicaller_address: Address, inventory_config: InventoryConfig) -> Self {
+        Self { inventory: Some((multicaller_address, Arc::new(inventory_config))), ..self }
+    }
+
     pub fn on_bc(self, bc: &Blockchain, strategy: &Strategy<DB>) -> Self {
         Self {
             swap_compose_channel_rx: Some(strategy.swap_compose_channel()),
-            swap_compose_channel_tx: Some(strategy.swap_compose_channel()),
+            estimate_channel_tx: Some(strategy.estimate_channel()),
             account_nonce_balance: Some(bc.nonce_and_balance()),
+            mempool: Some(bc.mempool()),
+            head_health: Some(bc.head_health()),
+            control_flags: Some(bc.control_flags()),
             tx_compose_channel_tx: Some(bc.tx_compose_channel()),
             ..self
         }
@@ -162,12 +286,21 @@ where
     DB: DatabaseRef + Send + Sync + Clone + Default + 'static,
 {
     fn start(&self) -> ActorResult {
+        self.start_until(CancellationToken::new())
+    }
+
+    fn start_until(&self, shutdown_token: CancellationToken) -> ActorResult {
         let task = tokio::task::spawn(swap_router_worker(
             self.signers.clone().unwrap(),
             self.account_nonce_balance.clone().unwrap(),
+            self.mempool.clone(),
+            self.inventory.clone(),
+            self.head_health.clone(),
+            self.control_flags.clone(),
             self.swap_compose_channel_rx.clone().unwrap(),
-            self.swap_compose_channel_tx.clone().unwrap(),
+            self.estimate_channel_tx.clone().unwrap(),
             self.tx_compose_channel_tx.clone().unwrap(),
+            shutdown_token,
         ));
         Ok(vec![task])
     }
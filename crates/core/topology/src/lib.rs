@@ -1,5 +1,7 @@
 pub use topology::Topology;
 pub use topology_config::*;
+pub use topology_config_check::{ActorGraph, ConfigErrors, ConfigIssue, ResolvedActor, DEFAULT_CLIENT_NAME};
 
 mod topology;
 mod topology_config;
+mod topology_config_check;
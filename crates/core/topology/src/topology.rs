@@ -1,8 +1,12 @@
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Duration;
 
 use crate::topology_config::TransportType;
-use crate::topology_config::{BroadcasterConfig, ClientConfig, EncoderConfig, EstimatorConfig, SignersConfig, TopologyConfig};
+use crate::topology_config::{
+    BroadcasterConfig, ClientConfig, EncoderConfig, EstimatorConfig, MempoolSourceType, SignersConfig, TopologyConfig,
+};
+use crate::topology_config_check::DEFAULT_CLIENT_NAME;
 use alloy_primitives::Address;
 use alloy_provider::network::Ethereum;
 use alloy_provider::{Network, Provider, ProviderBuilder, RootProvider};
@@ -13,10 +17,11 @@ use eyre::{eyre, ErrReport, Result};
 use loom_broadcast_accounts::{InitializeSignersOneShotBlockingActor, NonceAndBalanceMonitorActor, TxSignersActor};
 use loom_broadcast_broadcaster::FlashbotsBroadcastActor;
 use loom_broadcast_flashbots::Flashbots;
-use loom_core_actors::{Accessor, Actor, Consumer, Producer, SharedState, WorkerResult};
+use loom_core_actors::{Accessor, Actor, Consumer, MpscConsumer, Producer, SharedState, Shutdown, WorkerResult};
 use loom_core_block_history::BlockHistoryActor;
 use loom_core_blockchain::{Blockchain, BlockchainState, Strategy};
-use loom_core_mempool::MempoolActor;
+use loom_core_blockchain_actors::BlockchainActors;
+use loom_core_mempool::{MempoolActor, DEFAULT_MAX_MEMPOOL_TXS};
 use loom_defi_health_monitor::PoolHealthMonitorActor;
 use loom_defi_market::{HistoryPoolLoaderOneShotActor, NewPoolLoaderActor, PoolLoaderActor, ProtocolPoolLoaderOneShotActor};
 use loom_defi_pools::PoolLoadersBuilder;
@@ -29,7 +34,11 @@ use loom_node_actor_config::NodeBlockActorConfig;
 #[cfg(feature = "db-access")]
 use loom_node_db_access::RethDbAccessBlockActor;
 use loom_node_grpc::NodeExExGrpcActor;
-use loom_node_json_rpc::{NodeBlockActor, NodeMempoolActor};
+use loom_node_json_rpc::{
+    BloxrouteMempoolSource, ClientPool, ClientPoolConfig, FileMempoolSource, HeadWatchdogActor, HeadWatchdogConfig, MempoolSourceActor,
+    NodeBlockActor, NodeMempoolSource, WsMempoolSource,
+};
+use loom_node_player::MempoolRecorderActor;
 use loom_types_blockchain::{LoomDataTypes, LoomDataTypesEthereum};
 use loom_types_entities::pool_config::PoolsLoadingConfig;
 use loom_types_entities::{BlockHistoryState, MarketState, PoolLoaders, SwapEncoder, TxSigners};
@@ -46,6 +55,7 @@ pub struct Topology<
 > {
     config: TopologyConfig,
     clients: HashMap<String, RootProvider<N>>,
+    client_pools: HashMap<String, ClientPool>,
     blockchains: HashMap<String, Blockchain>,
     blockchain_states: HashMap<String, BlockchainState<DB>>,
     strategies: HashMap<String, Strategy<DB>>,
@@ -56,6 +66,7 @@ pub struct Topology<
     default_signer_name: Option<String>,
     swap_encoder: E,
     pool_loaders: Arc<PoolLoaders<P, N, LDT>>,
+    shutdown: Shutdown,
 }
 
 impl<
@@ -80,6 +91,7 @@ impl<
         Topology::<DB, MulticallerSwapEncoder> {
             config,
             clients: HashMap::new(),
+            client_pools: HashMap::new(),
             blockchains: HashMap::new(),
             blockchain_states: HashMap::new(),
             strategies: HashMap::new(),
@@ -90,6 +102,7 @@ impl<
             default_signer_name: None,
             swap_encoder: encoder,
             pool_loaders,
+            shutdown: Shutdown::new(),
         }
     }
 
@@ -101,6 +114,7 @@ impl<
         Topology {
             config: self.config,
             clients: self.clients,
+            client_pools: self.client_pools,
             blockchains: self.blockchains,
             blockchain_states: self.blockchain_states,
             strategies: self.strategies,
@@ -111,6 +125,7 @@ impl<
             default_signer_name: self.default_signer_name,
             pool_loaders: self.pool_loaders,
             swap_encoder,
+            shutdown: self.shutdown,
         }
     }
 
@@ -121,6 +136,7 @@ impl<
         Topology {
             config: self.config,
             clients: self.clients,
+            client_pools: self.client_pools,
             blockchains: self.blockchains,
             blockchain_states: self.blockchain_states,
             strategies: self.strategies,
@@ -131,11 +147,13 @@ impl<
             default_signer_name: self.default_signer_name,
             swap_encoder: self.swap_encoder,
             pool_loaders: Arc::new(pool_loaders),
+            shutdown: self.shutdown,
         }
     }
 
     pub async fn start_clients(self) -> Result<Self> {
         let mut clients = HashMap::new();
+        let mut client_pools = HashMap::new();
         for (name, v) in self.config.clients.iter() {
             let config_params = v.clone();
 
@@ -167,7 +185,39 @@ impl<
 
             clients.insert(name.clone(), provider);
         }
-        Ok(Topology { clients, ..self })
+
+        for (name, group) in self.config.client_groups.iter().flatten() {
+            let members: Vec<(String, RootProvider)> =
+                group.clients.iter().filter_map(|client| clients.get(client).map(|provider| (client.clone(), provider.clone()))).collect();
+            if members.is_empty() {
+                error!("No clients of group {name} connected");
+                continue;
+            }
+
+            let mut pool_config = ClientPoolConfig::default();
+            if let Some(max_head_lag) = group.max_head_lag {
+                pool_config.max_head_lag = max_head_lag;
+            }
+            if let Some(probe_interval_ms) = group.probe_interval_ms {
+                pool_config.probe_interval = Duration::from_millis(probe_interval_ms);
+            }
+            if let Some(request_timeout_ms) = group.request_timeout_ms {
+                pool_config.request_timeout = Duration::from_millis(request_timeout_ms);
+            }
+
+            info!("Starting client group {name} with {} of {} clients", members.len(), group.clients.len());
+            let pool = ClientPool::new(name, members, pool_config);
+            match pool.connect_provider().await {
+                Ok(provider) => {
+                    clients.insert(name.clone(), provider);
+                    client_pools.insert(name.clone(), pool);
+                }
+                Err(e) => {
+                    error!("Error starting client group {name} error : {}", e);
+                }
+            }
+        }
+        Ok(Topology { clients, client_pools, ..self })
     }
 
     pub fn build_blockchains(self) -> Self {
@@ -236,7 +286,7 @@ impl<
             return Err(eyre!("NO_CLIENTS_CONNECTED"));
         }
 
-        for (k, _params) in self.config.blockchains.iter() {
+        for (k, params) in self.config.blockchains.iter() {
             let blockchain = self.get_blockchain(Some(k))?;
             let blockchain_state = self.get_blockchain_state(Some(k))?;
             let client = self.get_client(None)?;
@@ -252,7 +302,7 @@ impl<
                 .consume(blockchain.new_block_logs_channel())
                 .consume(blockchain.new_block_state_update_channel())
                 .produce(blockchain.market_events_channel())
-                .start()
+                .start_with_shutdown(&self.shutdown)
             {
                 Ok(r) => {
                     tasks.extend(r);
@@ -264,7 +314,7 @@ impl<
             }
 
             info!("Starting mempool actor {k}");
-            let mut mempool_actor = MempoolActor::new();
+            let mut mempool_actor = MempoolActor::new().with_max_txs(params.max_mempool_txs.unwrap_or(DEFAULT_MAX_MEMPOOL_TXS));
             match mempool_actor
                 .access(blockchain.mempool())
                 .consume(blockchain.new_mempool_tx_channel())
@@ -272,7 +322,7 @@ impl<
                 .consume(blockchain.new_block_with_tx_channel())
                 .produce(blockchain.mempool_events_channel())
                 .produce(blockchain.influxdb_write_channel())
-                .start()
+                .start_with_shutdown(&self.shutdown)
             {
                 Ok(r) => {
                     tasks.extend(r);
@@ -289,7 +339,7 @@ impl<
                 .access(blockchain.market())
                 .consume(blockchain.health_monitor_channel())
                 .produce(blockchain.influxdb_write_channel())
-                .start()
+                .start_with_shutdown(&self.shutdown)
             {
                 Ok(r) => {
                     tasks.extend(r);
@@ -309,7 +359,7 @@ impl<
                     let blockchain = self.get_blockchain(params.blockchain.as_ref())?;
 
                     let mut initialize_signers_actor = InitializeSignersOneShotBlockingActor::new_from_encrypted_env();
-                    match initialize_signers_actor.access(signers.clone()).access(blockchain.nonce_and_balance()).start_and_wait() {
+                    match initialize_signers_actor.access(signers.clone()).access(blockchain.nonce_and_balance()).start_and_wait().await {
                         Ok(_) => {
                             info!("Signers have been initialized")
                         }
@@ -319,7 +369,11 @@ impl<
                     }
 
                     let mut signers_actor = TxSignersActor::new();
-                    match signers_actor.consume(blockchain.tx_compose_channel()).produce(blockchain.tx_compose_channel()).start() {
+                    match signers_actor
+                        .consume(blockchain.tx_compose_channel())
+                        .produce(blockchain.tx_compose_channel())
+                        .start_with_shutdown(&self.shutdown)
+                    {
                         Ok(r) => {
                             tasks.extend(r);
                             info!("Signers actor has been started")
@@ -342,8 +396,8 @@ impl<
 
                 let mut market_state_preload_actor = MarketStatePreloadedOneShotActor::new(client)
                     .with_signers(signers.clone())
-                    .with_copied_account(self.get_multicaller_address(None)?);
-                match market_state_preload_actor.access(blockchain_state.market_state()).start_and_wait() {
+                    .with_copied_account(self.get_multicaller_address(params.encoder.as_ref())?);
+                match market_state_preload_actor.access(blockchain_state.market_state()).start_and_wait().await {
                     Ok(_) => {
                         info!("Market state preload actor executed successfully")
                     }
@@ -369,7 +423,7 @@ impl<
                     .produce(blockchain.new_block_logs_channel())
                     .produce(blockchain.new_block_state_update_channel())
                     .produce(blockchain.new_mempool_tx_channel())
-                    .start()
+                    .start_with_shutdown(&self.shutdown)
                 {
                     Ok(r) => {
                         tasks.extend(r);
@@ -379,6 +433,24 @@ impl<
                         panic!("{}", e)
                     }
                 }
+
+                let client = params.client.as_ref().map(|client| self.get_client(Some(client))).transpose()?;
+                let mut head_watchdog_actor = HeadWatchdogActor::new_with_provider(client)
+                    .with_config(HeadWatchdogConfig::from_chain_parameters(&blockchain.chain_parameters()));
+                match head_watchdog_actor
+                    .access(blockchain.latest_block())
+                    .access(blockchain.head_health())
+                    .produce(blockchain.health_monitor_channel())
+                    .start_with_shutdown(&self.shutdown)
+                {
+                    Ok(r) => {
+                        tasks.extend(r);
+                        info!("Head watchdog actor started successfully for : {} @ {}", name, blockchain.chain_id())
+                    }
+                    Err(e) => {
+                        panic!("{}", e)
+                    }
+                }
             }
         }
 
@@ -402,7 +474,7 @@ impl<
                         .produce(blockchain.new_block_with_tx_channel())
                         .produce(blockchain.new_block_logs_channel())
                         .produce(blockchain.new_block_state_update_channel())
-                        .start()
+                        .start_with_shutdown(&self.shutdown)
                     {
                         Ok(r) => {
                             tasks.extend(r);
@@ -414,14 +486,32 @@ impl<
                     }
                 }
 
-                if client_config.db_path.is_none() {
+                let mut head_watchdog_actor = HeadWatchdogActor::new(client.clone())
+                    .with_config(HeadWatchdogConfig::from_chain_parameters(&blockchain.chain_parameters()));
+                match head_watchdog_actor
+                    .access(blockchain.latest_block())
+                    .access(blockchain.head_health())
+                    .produce(blockchain.health_monitor_channel())
+                    .start_with_shutdown(&self.shutdown)
+                {
+                    Ok(r) => {
+                        tasks.extend(r);
+                        info!("Head watchdog actor started successfully for : {} @ {}", name, blockchain.chain_id())
+                    }
+                    Err(e) => {
+                        panic!("{}", e)
+                    }
+                }
+
+                // db_path is ignored without the db-access feature
+                if client_config.db_path.is_none() || cfg!(not(feature = "db-access")) {
                     let mut node_block_actor = NodeBlockActor::new(client, NodeBlockActorConfig::all_enabled());
                     match node_block_actor
                         .produce(blockchain.new_block_headers_channel())
                         .produce(blockchain.new_block_with_tx_channel())
                         .produce(blockchain.new_block_logs_channel())
                         .produce(blockchain.new_block_state_update_channel())
-                        .start()
+                        .start_with_shutdown(&self.shutdown)
                     {
                         Ok(r) => {
                             tasks.extend(r);
@@ -438,22 +528,44 @@ impl<
         if let Some(node_mempool_actors) = &self.config.actors.mempool {
             for (name, params) in node_mempool_actors {
                 let blockchain = self.get_blockchain(params.blockchain.as_ref())?;
-                match self.get_client(params.client.as_ref()) {
-                    Ok(client) => {
-                        println!("Starting node mempool actor {name}");
-                        let mut node_mempool_actor = NodeMempoolActor::new(client).with_name(name.clone());
-                        match node_mempool_actor.produce(blockchain.new_mempool_tx_channel()).start() {
-                            Ok(r) => {
-                                tasks.extend(r);
-                                info!("Node mempool actor started successfully {name}")
-                            }
-                            Err(e) => {
-                                panic!("{}", e)
-                            }
+                let started = match params.source_type {
+                    MempoolSourceType::Node => match self.get_client(params.client.as_ref()) {
+                        Ok(client) => {
+                            info!("Starting node mempool actor {name}");
+                            MempoolSourceActor::new(NodeMempoolSource::new(client))
+                                .with_name(name.clone())
+                                .on_bc(blockchain)
+                                .start_with_shutdown(&self.shutdown)
+                        }
+                        Err(e) => {
+                            error!("Skipping mempool actor for {} @ {} : {}", name, blockchain.chain_id(), e);
+                            continue;
                         }
+                    },
+                    MempoolSourceType::Ws => {
+                        let source =
+                            WsMempoolSource::new(params.url.clone().unwrap_or_default()).with_auth_header(params.auth_header.clone());
+                        MempoolSourceActor::new(source).with_name(name.clone()).on_bc(blockchain).start_with_shutdown(&self.shutdown)
+                    }
+                    MempoolSourceType::Bloxroute => {
+                        let source = BloxrouteMempoolSource::new(
+                            params.url.clone().unwrap_or_default(),
+                            params.auth_header.clone().unwrap_or_default(),
+                        );
+                        MempoolSourceActor::new(source).with_name(name.clone()).on_bc(blockchain).start_with_shutdown(&self.shutdown)
+                    }
+                    MempoolSourceType::File => {
+                        let source = FileMempoolSource::new(params.path.clone().unwrap_or_default().into());
+                        MempoolSourceActor::new(source).with_name(name.clone()).on_bc(blockchain).start_with_shutdown(&self.shutdown)
+                    }
+                };
+                match started {
+                    Ok(r) => {
+                        tasks.extend(r);
+                        info!("Mempool source {} actor started successfully {name}", params.source_type)
                     }
                     Err(e) => {
-                        error!("Skipping mempool actor for {} @ {} : {}", name, blockchain.chain_id(), e)
+                        panic!("{}", e)
                     }
                 }
             }
@@ -461,11 +573,20 @@ impl<
 
         if let Some(price_actors) = &self.config.actors.price {
             for (name, c) in price_actors {
-                let client = self.get_client(c.client.as_ref())?;
                 let blockchain = self.get_blockchain(c.blockchain.as_ref())?;
+                let blockchain_state = self.get_blockchain_state(c.blockchain.as_ref())?;
                 info!("Starting price actor");
-                let mut price_actor = PriceActor::new(client);
-                match price_actor.access(blockchain.market()).start() {
+                let mut price_actor = PriceActor::new();
+                if let Some(reference) = &c.reference {
+                    price_actor = price_actor.with_reference_prices(reference.clone());
+                }
+                match price_actor
+                    .access(blockchain.market())
+                    .access(blockchain_state.market_state())
+                    .consume(blockchain.market_events_channel())
+                    .produce(blockchain.health_monitor_channel())
+                    .start_with_shutdown(&self.shutdown)
+                {
                     Ok(r) => {
                         tasks.extend(r);
                         info!("Price actor has been initialized : {}", name)
@@ -479,6 +600,22 @@ impl<
             warn!("No price actor in config")
         }
 
+        if let Some(recorder_actors) = &self.config.actors.recorder {
+            for (name, params) in recorder_actors {
+                let blockchain = self.get_blockchain(params.blockchain.as_ref())?;
+                info!("Starting mempool recorder actor {name} to {}", params.path);
+                match MempoolRecorderActor::new(params.path.clone().into()).on_bc(blockchain).start_with_shutdown(&self.shutdown) {
+                    Ok(r) => {
+                        tasks.extend(r);
+                        info!("Mempool recorder actor started successfully {name}")
+                    }
+                    Err(e) => {
+                        panic!("Cannot start mempool recorder {} : {}", name, e);
+                    }
+                }
+            }
+        }
+
         if let Some(node_balance_actors) = &self.config.actors.noncebalance {
             for (name, c) in node_balance_actors {
                 let client = self.get_client(c.client.as_ref())?;
@@ -490,7 +627,7 @@ impl<
                     .access(blockchain.nonce_and_balance())
                     .access(blockchain.latest_block())
                     .consume(blockchain.market_events_channel())
-                    .start()
+                    .start_with_shutdown(&self.shutdown)
                 {
                     Ok(r) => {
                         tasks.extend(r);
@@ -514,7 +651,11 @@ impl<
 
                         let flashbots_client = Flashbots::new(client, "https://relay.flashbots.net", None).with_default_relays();
                         let mut flashbots_actor = FlashbotsBroadcastActor::new(flashbots_client, true);
-                        match flashbots_actor.consume(blockchain.tx_compose_channel()).start() {
+                        match flashbots_actor
+                            .consume(blockchain.tx_compose_channel())
+                            .access(blockchain.head_health())
+                            .start_with_shutdown(&self.shutdown)
+                        {
                             Ok(r) => {
                                 tasks.extend(r);
                                 info!("Flashbots broadcaster actor {name} started successfully for {}", blockchain.chain_id())
@@ -545,7 +686,7 @@ impl<
                     info!("Starting history pools loader {name}");
 
                     let mut history_pools_loader_actor = HistoryPoolLoaderOneShotActor::new(client.clone(), pool_loaders.clone());
-                    match history_pools_loader_actor.produce(blockchain.tasks_channel()).start() {
+                    match history_pools_loader_actor.produce(blockchain.tasks_channel()).start_with_shutdown(&self.shutdown) {
                         Ok(r) => {
                             tasks.extend(r);
                             info!("History pool loader actor started successfully {name}")
@@ -559,7 +700,7 @@ impl<
                     info!("Starting curve pools loader {name}");
 
                     let mut curve_pools_loader_actor = ProtocolPoolLoaderOneShotActor::new(client.clone(), pool_loaders.clone());
-                    match curve_pools_loader_actor.produce(blockchain.tasks_channel()).start() {
+                    match curve_pools_loader_actor.produce(blockchain.tasks_channel()).start_with_shutdown(&self.shutdown) {
                         Err(e) => {
                             panic!("CurvePoolLoaderOneShotActor : {}", e)
                         }
@@ -573,7 +714,11 @@ impl<
                 if params.new {
                     info!("Starting new pool loader actor {name}");
                     let mut new_pool_actor = NewPoolLoaderActor::new(pool_loaders.clone());
-                    match new_pool_actor.consume(blockchain.new_block_logs_channel()).produce(blockchain.tasks_channel()).start() {
+                    match new_pool_actor
+                        .consume(blockchain.new_block_logs_channel())
+                        .produce(blockchain.tasks_channel())
+                        .start_with_shutdown(&self.shutdown)
+                    {
                         Ok(r) => {
                             tasks.extend(r);
                             info!("New pool actor started")
@@ -585,13 +730,17 @@ impl<
                 }
 
                 info!("Starting pool loader actor {name}");
-                let mut pool_loader_actor = PoolLoaderActor::new(client.clone(), pool_loaders.clone(), PoolsLoadingConfig::new());
+                let mut pool_loader_actor = PoolLoaderActor::new(
+                    client.clone(),
+                    pool_loaders.clone(),
+                    PoolsLoadingConfig::new().with_chain_parameters(blockchain.chain_parameters()),
+                );
                 match pool_loader_actor
                     .access(blockchain.market())
                     .access(blockchain_state.market_state())
                     .consume(blockchain.tasks_channel())
                     .produce(blockchain.market_events_channel())
-                    .start()
+                    .start_with_shutdown(&self.shutdown)
                 {
                     Ok(r) => {
                         tasks.extend(r);
@@ -621,11 +770,11 @@ impl<
 
                         let mut evm_estimator_actor = EvmEstimatorActor::new_with_provider(encoder, client);
                         match evm_estimator_actor
-                            .consume(strategy.swap_compose_channel())
+                            .consume_mpsc(strategy.estimate_channel())
                             .produce(strategy.swap_compose_channel())
                             .produce(blockchain.health_monitor_channel())
                             .produce(blockchain.influxdb_write_channel())
-                            .start()
+                            .start_with_shutdown(&self.shutdown)
                         {
                             Ok(r) => {
                                 tasks.extend(r);
@@ -648,7 +797,10 @@ impl<
                         let flashbots_client = Arc::new(Flashbots::new(client, "https://relay.flashbots.net", None).with_default_relays());
 
                         let mut geth_estimator_actor = GethEstimatorActor::new(flashbots_client, encoder);
-                        match geth_estimator_actor.consume(strategy.swap_compose_channel()).produce(strategy.swap_compose_channel()).start()
+                        match geth_estimator_actor
+                            .consume_mpsc(strategy.estimate_channel())
+                            .produce(strategy.swap_compose_channel())
+                            .start_with_shutdown(&self.shutdown)
                         {
                             Ok(r) => {
                                 tasks.extend(r);
@@ -665,18 +817,69 @@ impl<
             warn!("No estimator actors in config")
         }
 
+        // pipelines may rely on channels produced by the topology actors, the wiring is checked against all actors of the blockchain
+        if self.config.pipelines.as_ref().is_some_and(|pipelines| !pipelines.is_empty()) {
+            self.config.validate()?;
+        }
+
+        for (name, params) in self.config.pipelines.iter().flatten() {
+            let client = self.get_client(params.client.as_ref())?;
+            let blockchain = self.get_blockchain(params.blockchain.as_ref())?;
+            let blockchain_state = self.get_blockchain_state(params.blockchain.as_ref())?;
+            let strategy = self.get_strategy(params.blockchain.as_ref())?;
+
+            let mut encoder = self.swap_encoder.clone();
+            if let Some(encoder_name) = &params.encoder {
+                encoder.set_address(self.get_multicaller_address(Some(encoder_name))?);
+            }
+
+            let mut pipeline_actors =
+                BlockchainActors::new(client, encoder, blockchain.clone(), blockchain_state.clone(), strategy.clone(), vec![]);
+            pipeline_actors.with_shutdown(self.shutdown.clone());
+            if let Some(signers_name) = &params.signers {
+                pipeline_actors.signers = self.get_signers(Some(signers_name))?;
+            }
+
+            info!("Starting pipeline {name} with {} actors", params.actors.len());
+            pipeline_actors.with_pipeline_unchecked(&params.pipeline()).await?;
+
+            let name = name.clone();
+            tasks.push(tokio::task::spawn(async move {
+                pipeline_actors.wait().await?;
+                Ok(format!("Pipeline {name} finished"))
+            }));
+        }
+
         Ok(tasks)
     }
 
+    /// Actors started by the topology stop in order after [`Shutdown::cancel_stages`]
+    pub fn shutdown(&self) -> Shutdown {
+        self.shutdown.clone()
+    }
+
     pub fn get_client(&self, name: Option<&String>) -> Result<RootProvider> {
-        match self.clients.get(name.unwrap_or(&"local".to_string())) {
+        match self.clients.get(name.unwrap_or(&DEFAULT_CLIENT_NAME.to_string())) {
             Some(a) => Ok(a.clone()),
             None => Err(eyre!("CLIENT_NOT_FOUND")),
         }
     }
 
+    /// Client group by name, [`ClientPool::health`] reports the state of its clients
+    pub fn get_client_pool(&self, name: &str) -> Result<ClientPool> {
+        self.client_pools.get(name).cloned().ok_or_else(|| eyre!("CLIENT_POOL_NOT_FOUND"))
+    }
+
     pub fn get_client_config(&self, name: Option<&String>) -> Result<ClientConfig> {
-        match self.config.clients.get(name.unwrap_or(&"local".to_string())) {
+        let name = name.cloned().unwrap_or(DEFAULT_CLIENT_NAME.to_string());
+        if let Some(group) = self.config.client_groups.as_ref().and_then(|groups| groups.get(&name)) {
+            // a group is used through its merged subscriptions only, without direct db or exex access
+            return match group.clients.first().and_then(|client| self.config.clients.get(client)) {
+                Some(a) => Ok(ClientConfig { db_path: None, exex: None, ..a.clone() }),
+                None => Err(eyre!("CLIENT_NOT_FOUND")),
+            };
+        }
+        match self.config.clients.get(&name) {
             Some(a) => Ok(a.clone()),
             None => Err(eyre!("CLIENT_NOT_FOUND")),
         }
@@ -711,7 +914,7 @@ impl<
     }
 
     pub fn get_signers(&self, name: Option<&String>) -> Result<SharedState<TxSigners>> {
-        match self.signers.get(name.unwrap_or(&self.default_multicaller_encoder_name.clone().unwrap())) {
+        match self.signers.get(name.unwrap_or(&self.default_signer_name.clone().unwrap())) {
             Some(a) => Ok(a.clone()),
             None => Err(eyre!("SIGNERS_NOT_FOUND")),
         }
@@ -1,13 +1,29 @@
 use eyre::Result;
 use loom_broadcast_flashbots::client::RelayConfig;
+use loom_core_blockchain_actors::{PipelineActorConfig, PipelineConfig};
+use loom_defi_price::ReferencePriceConfig;
+use loom_types_entities::config_source::{config_layers, load_toml_layers};
 use serde::Deserialize;
 use std::collections::HashMap;
-use std::fs;
+use std::path::{Path, PathBuf};
 use strum_macros::Display;
 
+/// Several clients behind one name with failover for requests and merged subscriptions
+#[derive(Clone, Debug, Deserialize)]
+pub struct ClientGroupConfig {
+    pub clients: Vec<String>,
+    /// Blocks a client may fall behind the best head of the group before requests avoid it
+    pub max_head_lag: Option<u64>,
+    pub probe_interval_ms: Option<u64>,
+    /// Time a client has to answer a request before the next client of the group is tried
+    pub request_timeout_ms: Option<u64>,
+}
+
 #[derive(Clone, Debug, Deserialize)]
 pub struct BlockchainConfig {
     pub chain_id: Option<i64>,
+    /// Pending txes kept in the mempool, the oldest ones are evicted above it
+    pub max_mempool_txs: Option<usize>,
 }
 
 #[derive(Clone, Debug, Default, Deserialize, Display)]
@@ -26,6 +42,7 @@ pub enum TransportType {
     #[default]
     #[serde(rename = "ws")]
     Ws,
+    /// Rejected by the config check, clients need subscriptions
     #[serde(rename = "http")]
     Http,
     #[serde(rename = "ipc")]
@@ -129,11 +146,62 @@ pub struct BlockchainClientConfig {
     pub blockchain: Option<String>,
     pub client: Option<String>,
 }
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct PriceConfig {
+    #[serde(rename = "bc")]
+    pub blockchain: Option<String>,
+    pub client: Option<String>,
+    /// Spot prices are checked against reference prices, divergences are sent to the pool health monitor
+    pub reference: Option<ReferencePriceConfig>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Display, PartialEq)]
+#[strum(ascii_case_insensitive, serialize_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum MempoolSourceType {
+    /// `eth_subscribe` of a configured client
+    #[default]
+    Node,
+    /// `eth_subscribe` on a raw WebSocket feed
+    Ws,
+    /// bloXroute style `newTxs` stream
+    Bloxroute,
+    /// Txes replayed from a file
+    File,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct MempoolSourceConfig {
+    #[serde(rename = "bc")]
+    pub blockchain: Option<String>,
+    /// Client of `node` sources
+    pub client: Option<String>,
+    #[serde(default, rename = "type")]
+    pub source_type: MempoolSourceType,
+    /// Feed url of `ws` and `bloxroute` sources
+    pub url: Option<String>,
+    /// Authorization header sent to `ws` and `bloxroute` feeds
+    pub auth_header: Option<String>,
+    /// Tx file of `file` sources
+    pub path: Option<String>,
+}
+
+/// Records mempool and block events of a blockchain to `path` for replay
+#[derive(Clone, Debug, Deserialize)]
+pub struct RecorderConfig {
+    #[serde(rename = "bc")]
+    pub blockchain: Option<String>,
+    pub path: String,
+}
+
 #[derive(Clone, Debug, Deserialize)]
 pub struct ExExClientConfig {
     #[serde(rename = "bc")]
     pub blockchain: Option<String>,
     pub url: Option<String>,
+    /// Client the head watchdog compares the local head with, without it only the block age is checked
+    pub client: Option<String>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
@@ -207,14 +275,33 @@ pub struct PoolsConfig {
     pub protocol: bool,
 }
 
+/// Actors started from a declarative list instead of a binary wiring them by hand, see [`PipelineActorConfig`] for the kinds
+#[derive(Clone, Debug, Deserialize)]
+pub struct PipelineSectionConfig {
+    #[serde(rename = "bc")]
+    pub blockchain: Option<String>,
+    pub client: Option<String>,
+    pub encoder: Option<String>,
+    pub signers: Option<String>,
+    pub actors: Vec<PipelineActorConfig>,
+}
+
+impl PipelineSectionConfig {
+    pub fn pipeline(&self) -> PipelineConfig {
+        PipelineConfig::new(self.actors.clone())
+    }
+}
+
 #[derive(Clone, Debug, Deserialize)]
 pub struct WebserverConfig {
     pub host: String,
+    /// Bearer token of the `/api/v1/control` endpoints, they are disabled without it
+    pub control_token: Option<String>,
 }
 
 impl Default for WebserverConfig {
     fn default() -> Self {
-        WebserverConfig { host: "127.0.0.1:3333".to_string() }
+        WebserverConfig { host: "127.0.0.1:3333".to_string(), control_token: None }
     }
 }
 
@@ -228,30 +315,41 @@ pub struct ActorConfig {
     pub broadcaster: Option<HashMap<String, BroadcasterConfig>>,
     pub node: Option<HashMap<String, BlockchainClientConfig>>,
     pub node_exex: Option<HashMap<String, ExExClientConfig>>,
-    pub mempool: Option<HashMap<String, BlockchainClientConfig>>,
-    pub price: Option<HashMap<String, BlockchainClientConfig>>,
+    pub mempool: Option<HashMap<String, MempoolSourceConfig>>,
+    pub price: Option<HashMap<String, PriceConfig>>,
     pub pools: Option<HashMap<String, PoolsConfig>>,
     pub noncebalance: Option<HashMap<String, BlockchainClientConfig>>,
     pub estimator: Option<HashMap<String, EstimatorConfig>>,
+    pub recorder: Option<HashMap<String, RecorderConfig>>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct TopologyConfig {
     pub influxdb: Option<InfluxDbConfig>,
     pub clients: HashMap<String, ClientConfig>,
+    pub client_groups: Option<HashMap<String, ClientGroupConfig>>,
     pub blockchains: HashMap<String, BlockchainConfig>,
     pub actors: ActorConfig,
     pub signers: HashMap<String, SignersConfig>,
     pub encoders: HashMap<String, EncoderConfig>,
     pub preloaders: Option<HashMap<String, PreloaderConfig>>,
+    pub pipelines: Option<HashMap<String, PipelineSectionConfig>>,
     pub webserver: Option<WebserverConfig>,
     pub database: Option<DatabaseConfig>,
 }
 
 impl TopologyConfig {
+    /// Loads and validates the config with the override for `LOOM_ENV`, all problems are reported at once
     pub fn load_from_file(file_name: String) -> Result<TopologyConfig> {
-        let contents = fs::read_to_string(file_name)?;
-        let config: TopologyConfig = toml::from_str(&contents)?;
+        Self::load_from_files(&config_layers(Path::new(&file_name)))
+    }
+
+    /// Merges config files in order, later files override values of earlier ones.
+    /// `${VAR}`, `${VAR:-default}` and `file:<path>` values are resolved before validation.
+    pub fn load_from_files(files: &[PathBuf]) -> Result<TopologyConfig> {
+        let value = load_toml_layers(files)?;
+        let config: TopologyConfig = value.try_into()?;
+        config.validate()?;
         Ok(config)
     }
 }
@@ -0,0 +1,848 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use alloy_primitives::Address;
+use loom_core_blockchain_actors::{ChannelWiring, WiringIssue};
+
+use crate::topology_config::{
+    BroadcasterConfig, EncoderConfig, EstimatorConfig, MempoolSourceType, NodeType, SignersConfig, TopologyConfig, TransportType,
+};
+
+/// Client used by actors without a `client` reference
+pub const DEFAULT_CLIENT_NAME: &str = "local";
+
+/// Problem found in the config with the TOML path of the offending value
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConfigIssue {
+    pub path: String,
+    pub message: String,
+}
+
+impl fmt::Display for ConfigIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} : {}", self.path, self.message)
+    }
+}
+
+/// All problems found in the config
+#[derive(Clone, Debug, Default)]
+pub struct ConfigErrors {
+    pub issues: Vec<ConfigIssue>,
+}
+
+impl fmt::Display for ConfigErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} problem(s) in topology config", self.issues.len())?;
+        for issue in self.issues.iter() {
+            write!(f, "\n  {issue}")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ConfigErrors {}
+
+/// Actor the topology would start, with all references resolved to names from the config
+#[derive(Clone, Debug)]
+pub struct ResolvedActor {
+    pub path: String,
+    pub actor: &'static str,
+    pub blockchain: Option<String>,
+    pub client: Option<String>,
+    pub encoder: Option<String>,
+    pub signers: Option<String>,
+    pub consumes: Vec<&'static str>,
+    pub produces: Vec<&'static str>,
+}
+
+impl ResolvedActor {
+    fn new(path: String, actor: &'static str, blockchain: Option<String>) -> Self {
+        Self { path, actor, blockchain, client: None, encoder: None, signers: None, consumes: vec![], produces: vec![] }
+    }
+
+    fn with_client(self, client: Option<String>) -> Self {
+        Self { client, ..self }
+    }
+
+    fn with_encoder(self, encoder: Option<String>) -> Self {
+        Self { encoder, ..self }
+    }
+
+    fn with_signers(self, signers: Option<String>) -> Self {
+        Self { signers, ..self }
+    }
+
+    fn consumes(self, consumes: &[&'static str]) -> Self {
+        Self { consumes: consumes.to_vec(), ..self }
+    }
+
+    fn produces(self, produces: &[&'static str]) -> Self {
+        Self { produces: produces.to_vec(), ..self }
+    }
+}
+
+impl fmt::Display for ResolvedActor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} [{}]", self.actor, self.path)?;
+        for (key, value) in [("bc", &self.blockchain), ("client", &self.client), ("encoder", &self.encoder), ("signers", &self.signers)] {
+            if let Some(value) = value {
+                write!(f, " {key}={value}")?;
+            }
+        }
+        if !self.consumes.is_empty() {
+            write!(f, "\n    consumes : {}", self.consumes.join(", "))?;
+        }
+        if !self.produces.is_empty() {
+            write!(f, "\n    produces : {}", self.produces.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+/// Actors and channels the topology would start from the config
+#[derive(Clone, Debug, Default)]
+pub struct ActorGraph {
+    pub actors: Vec<ResolvedActor>,
+}
+
+impl fmt::Display for ActorGraph {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for actor in self.actors.iter() {
+            writeln!(f, "{actor}")?;
+        }
+        Ok(())
+    }
+}
+
+const BLOCK_CHANNELS: [&str; 4] = ["new_block_headers", "new_block_with_tx", "new_block_logs", "new_block_state_update"];
+/// Pipeline kinds replaying blocks, they need a blockchain without a live block feed
+const REPLAY_KINDS: [&str; 2] = ["block_player", "mempool_log_player"];
+
+fn sorted<V>(map: &HashMap<String, V>) -> Vec<(&String, &V)> {
+    let mut entries: Vec<_> = map.iter().collect();
+    entries.sort_by(|a, b| a.0.cmp(b.0));
+    entries
+}
+
+fn is_http_url(url: &str) -> bool {
+    url.starts_with("http://") || url.starts_with("https://")
+}
+
+fn is_ws_url(url: &str) -> bool {
+    url.starts_with("ws://") || url.starts_with("wss://")
+}
+
+struct ConfigChecker<'a> {
+    config: &'a TopologyConfig,
+    issues: Vec<ConfigIssue>,
+    actors: Vec<ResolvedActor>,
+}
+
+impl<'a> ConfigChecker<'a> {
+    fn new(config: &'a TopologyConfig) -> Self {
+        Self { config, issues: Vec::new(), actors: Vec::new() }
+    }
+
+    fn issue(&mut self, path: impl Into<String>, message: impl Into<String>) {
+        self.issues.push(ConfigIssue { path: path.into(), message: message.into() });
+    }
+
+    /// Resolves a reference to a section entry. Without a name the only entry of the section is used.
+    fn reference<V>(&mut self, path: String, section: &str, entries: &HashMap<String, V>, name: Option<&String>) -> Option<String> {
+        match name {
+            Some(name) if entries.contains_key(name) => Some(name.clone()),
+            Some(name) => {
+                self.issue(path, format!("unknown {section} \"{name}\""));
+                None
+            }
+            None if entries.len() == 1 => entries.keys().next().cloned(),
+            None if entries.is_empty() => {
+                self.issue(path, format!("no {section} configured"));
+                None
+            }
+            None => {
+                self.issue(path, format!("must be set, there are several {section}"));
+                None
+            }
+        }
+    }
+
+    fn client(&mut self, path: String, name: Option<&String>) -> Option<String> {
+        let name = name.cloned().unwrap_or(DEFAULT_CLIENT_NAME.to_string());
+        if self.config.clients.contains_key(&name) || self.config.client_groups.as_ref().is_some_and(|groups| groups.contains_key(&name)) {
+            Some(name)
+        } else {
+            self.issue(path, format!("unknown client \"{name}\""));
+            None
+        }
+    }
+
+    fn blockchain(&mut self, path: String, name: Option<&String>) -> Option<String> {
+        let config = self.config;
+        self.reference(path, "blockchains", &config.blockchains, name)
+    }
+
+    fn encoder(&mut self, path: String, name: Option<&String>) -> Option<String> {
+        let config = self.config;
+        self.reference(path, "encoders", &config.encoders, name)
+    }
+
+    fn signers(&mut self, path: String, name: Option<&String>) -> Option<String> {
+        let config = self.config;
+        self.reference(path, "signers", &config.signers, name)
+    }
+
+    fn check_sections(&mut self) {
+        let config = self.config;
+
+        if config.clients.is_empty() {
+            self.issue("clients", "at least one client is required");
+        }
+        for (name, client) in sorted(&config.clients) {
+            let path = format!("clients.{name}");
+            if client.url.is_empty() {
+                self.issue(format!("{path}.url"), "must not be empty");
+            }
+            match client.transport {
+                TransportType::Http => self.issue(
+                    format!("{path}.transport"),
+                    "\"http\" is not supported, clients need subscriptions, use transport = \"ws\" with a ws:// or wss:// url",
+                ),
+                TransportType::Ws if !is_ws_url(&client.url) => {
+                    self.issue(format!("{path}.url"), format!("\"{}\" is not a ws:// or wss:// url", client.url))
+                }
+                TransportType::Ipc if is_ws_url(&client.url) || is_http_url(&client.url) => {
+                    self.issue(format!("{path}.url"), format!("\"{}\" is not an ipc path", client.url))
+                }
+                _ => {}
+            }
+            if client.db_path.is_some() && !matches!(client.node, NodeType::Reth) {
+                self.issue(format!("{path}.db_path"), "db_path can only be used with node = \"reth\"");
+            }
+            if let Some(exex) = &client.exex {
+                if !is_http_url(exex) {
+                    self.issue(format!("{path}.exex"), format!("\"{exex}\" is not an http:// or https:// url"));
+                }
+            }
+        }
+
+        for (name, group) in sorted(config.client_groups.as_ref().unwrap_or(&HashMap::new())) {
+            let path = format!("client_groups.{name}");
+            if config.clients.contains_key(name) {
+                self.issue(path.clone(), format!("name is already used by client \"{name}\""));
+            }
+            if group.clients.is_empty() {
+                self.issue(format!("{path}.clients"), "at least one client is required");
+            }
+            for (idx, client) in group.clients.iter().enumerate() {
+                if !config.clients.contains_key(client) {
+                    self.issue(format!("{path}.clients[{idx}]"), format!("unknown client \"{client}\""));
+                }
+            }
+            if group.probe_interval_ms == Some(0) {
+                self.issue(format!("{path}.probe_interval_ms"), "must be greater than 0");
+            }
+            if group.request_timeout_ms == Some(0) {
+                self.issue(format!("{path}.request_timeout_ms"), "must be greater than 0");
+            }
+        }
+
+        if config.blockchains.is_empty() {
+            self.issue("blockchains", "at least one blockchain is required");
+        }
+        for (name, blockchain) in sorted(&config.blockchains) {
+            if let Some(chain_id) = blockchain.chain_id {
+                if chain_id <= 0 {
+                    self.issue(format!("blockchains.{name}.chain_id"), format!("{chain_id} is not a valid chain id"));
+                }
+            }
+        }
+
+        for (name, encoder) in sorted(&config.encoders) {
+            match encoder {
+                EncoderConfig::SwapStep(encoder) => {
+                    if encoder.address.parse::<Address>().is_err() {
+                        self.issue(format!("encoders.{name}.address"), format!("\"{}\" is not a valid address", encoder.address));
+                    }
+                }
+            }
+        }
+
+        if let Some(influxdb) = &config.influxdb {
+            if !is_http_url(&influxdb.url) {
+                self.issue("influxdb.url", format!("\"{}\" is not an http:// or https:// url", influxdb.url));
+            }
+            if influxdb.database.is_empty() {
+                self.issue("influxdb.database", "must not be empty");
+            }
+        }
+
+        if let Some(webserver) = &config.webserver {
+            if webserver.host.rsplit_once(':').and_then(|(_, port)| port.parse::<u16>().ok()).is_none() {
+                self.issue("webserver.host", format!("\"{}\" is not in host:port format", webserver.host));
+            }
+        }
+
+        if let Some(database) = &config.database {
+            if !database.url.starts_with("postgres://") && !database.url.starts_with("postgresql://") {
+                self.issue("database.url", "must be a postgres:// url");
+            }
+        }
+    }
+
+    fn check_actors(&mut self) {
+        let config = self.config;
+
+        for (name, _) in sorted(&config.blockchains) {
+            let path = format!("blockchains.{name}");
+            let bc = Some(name.clone());
+            let client = self.client(path.clone(), None);
+            self.actors.push(
+                ResolvedActor::new(path.clone(), "BlockHistoryActor", bc.clone())
+                    .with_client(client)
+                    .consumes(&BLOCK_CHANNELS)
+                    .produces(&["market_events"]),
+            );
+            self.actors.push(
+                ResolvedActor::new(path.clone(), "MempoolActor", bc.clone())
+                    .consumes(&["new_mempool_tx", "new_block_headers", "new_block_with_tx"])
+                    .produces(&["mempool_events", "influx_write"]),
+            );
+            self.actors.push(
+                ResolvedActor::new(path, "PoolHealthMonitorActor", bc).consumes(&["pool_health_monitor"]).produces(&["influx_write"]),
+            );
+        }
+
+        for (name, signers) in sorted(&config.signers) {
+            let path = format!("signers.{name}");
+            match signers {
+                SignersConfig::Env(params) => {
+                    let bc = self.blockchain(format!("{path}.bc"), params.blockchain.as_ref());
+                    self.actors.push(
+                        ResolvedActor::new(path.clone(), "InitializeSignersOneShotBlockingActor", bc.clone())
+                            .with_signers(Some(name.clone())),
+                    );
+                    self.actors.push(
+                        ResolvedActor::new(path, "TxSignersActor", bc)
+                            .with_signers(Some(name.clone()))
+                            .consumes(&["tx_compose"])
+                            .produces(&["tx_compose"]),
+                    );
+                }
+            }
+        }
+
+        for (name, params) in sorted(config.preloaders.as_ref().unwrap_or(&HashMap::new())) {
+            let path = format!("preloaders.{name}");
+            let bc = self.blockchain(format!("{path}.bc"), params.blockchain.as_ref());
+            let client = self.client(format!("{path}.client"), params.client.as_ref());
+            let encoder = self.encoder(format!("{path}.encoder"), params.encoder.as_ref());
+            let signers = self.signers(format!("{path}.signers"), params.signers.as_ref());
+            self.actors.push(
+                ResolvedActor::new(path, "MarketStatePreloadedOneShotActor", bc)
+                    .with_client(client)
+                    .with_encoder(encoder)
+                    .with_signers(signers),
+            );
+        }
+
+        let mut node_blockchains: HashMap<String, String> = HashMap::new();
+
+        for (name, params) in sorted(config.actors.node_exex.as_ref().unwrap_or(&HashMap::new())) {
+            let path = format!("actors.node_exex.{name}");
+            let bc = self.blockchain(format!("{path}.bc"), params.blockchain.as_ref());
+            if let Some(url) = &params.url {
+                if !is_http_url(url) {
+                    self.issue(format!("{path}.url"), format!("\"{url}\" is not an http:// or https:// url"));
+                }
+            }
+            let client = params.client.as_ref().and_then(|client| self.client(format!("{path}.client"), Some(client)));
+            if let Some(bc) = &bc {
+                node_blockchains.insert(bc.clone(), path.clone());
+            }
+            let mut produces = BLOCK_CHANNELS.to_vec();
+            produces.push("new_mempool_tx");
+            self.actors.push(ResolvedActor::new(path, "NodeExExGrpcActor", bc).with_client(client).produces(&produces));
+        }
+
+        for (name, params) in sorted(config.actors.node.as_ref().unwrap_or(&HashMap::new())) {
+            let path = format!("actors.node.{name}");
+            let bc = self.blockchain(format!("{path}.bc"), params.blockchain.as_ref());
+            let client = self.client(format!("{path}.client"), params.client.as_ref());
+            if let Some(exex_path) = bc.as_ref().and_then(|bc| node_blockchains.get(bc)) {
+                self.issue(
+                    path.clone(),
+                    format!("node and node_exex actors are mutually exclusive, {exex_path} feeds the same blockchain"),
+                );
+            }
+            // db_path is ignored without the db-access feature
+            let db_access = cfg!(feature = "db-access")
+                && client.as_ref().and_then(|client| config.clients.get(client)).is_some_and(|client| client.db_path.is_some());
+            let actor = if db_access { "RethDbAccessBlockActor" } else { "NodeBlockActor" };
+            self.actors.push(ResolvedActor::new(path, actor, bc).with_client(client).produces(&BLOCK_CHANNELS));
+        }
+
+        for (name, params) in sorted(config.actors.mempool.as_ref().unwrap_or(&HashMap::new())) {
+            let path = format!("actors.mempool.{name}");
+            let bc = self.blockchain(format!("{path}.bc"), params.blockchain.as_ref());
+            let actor = match params.source_type {
+                MempoolSourceType::Node => {
+                    let client = self.client(format!("{path}.client"), params.client.as_ref());
+                    ResolvedActor::new(path, "MempoolSourceActor", bc).with_client(client)
+                }
+                MempoolSourceType::Ws | MempoolSourceType::Bloxroute => {
+                    match &params.url {
+                        Some(url) if !is_ws_url(url) => {
+                            self.issue(format!("{path}.url"), format!("\"{url}\" is not a ws:// or wss:// url"))
+                        }
+                        Some(_) => {}
+                        None => self.issue(format!("{path}.url"), format!("must be set for {} sources", params.source_type)),
+                    }
+                    if params.source_type == MempoolSourceType::Bloxroute && params.auth_header.is_none() {
+                        self.issue(format!("{path}.auth_header"), "must be set for bloxroute sources");
+                    }
+                    ResolvedActor::new(path, "MempoolSourceActor", bc)
+                }
+                MempoolSourceType::File => {
+                    if params.path.is_none() {
+                        self.issue(format!("{path}.path"), "must be set for file sources");
+                    }
+                    ResolvedActor::new(path, "MempoolSourceActor", bc)
+                }
+            };
+            self.actors.push(actor.produces(&["new_mempool_tx"]));
+        }
+
+        for (name, params) in sorted(config.actors.recorder.as_ref().unwrap_or(&HashMap::new())) {
+            let path = format!("actors.recorder.{name}");
+            let bc = self.blockchain(format!("{path}.bc"), params.blockchain.as_ref());
+            if params.path.is_empty() {
+                self.issue(format!("{path}.path"), "must not be empty");
+            }
+            let mut consumes = BLOCK_CHANNELS.to_vec();
+            consumes.push("new_mempool_tx");
+            self.actors.push(ResolvedActor::new(path, "MempoolRecorderActor", bc).consumes(&consumes));
+        }
+
+        for (name, params) in sorted(config.actors.price.as_ref().unwrap_or(&HashMap::new())) {
+            let path = format!("actors.price.{name}");
+            let bc = self.blockchain(format!("{path}.bc"), params.blockchain.as_ref());
+            let produces = if params.reference.is_some() { vec!["pool_health_monitor"] } else { vec![] };
+            self.actors.push(ResolvedActor::new(path, "PriceActor", bc).consumes(&["market_events"]).produces(&produces));
+        }
+
+        for (name, params) in sorted(config.actors.noncebalance.as_ref().unwrap_or(&HashMap::new())) {
+            let path = format!("actors.noncebalance.{name}");
+            let bc = self.blockchain(format!("{path}.bc"), params.blockchain.as_ref());
+            let client = self.client(format!("{path}.client"), params.client.as_ref());
+            self.actors.push(
+                ResolvedActor::new(path, "NonceAndBalanceMonitorActor", bc)
+                    .with_client(client)
+                    .consumes(&["market_events"])
+                    .produces(&["nonce_and_balance"]),
+            );
+        }
+
+        for (name, params) in sorted(config.actors.broadcaster.as_ref().unwrap_or(&HashMap::new())) {
+            let path = format!("actors.broadcaster.{name}");
+            match params {
+                BroadcasterConfig::Flashbots(params) => {
+                    let bc = self.blockchain(format!("{path}.bc"), params.blockchain.as_ref());
+                    let client = self.client(format!("{path}.client"), params.client.as_ref());
+                    let mut relay_ids = HashMap::new();
+                    for (idx, relay) in params.relays().iter().enumerate() {
+                        if !is_http_url(&relay.url) {
+                            self.issue(format!("{path}.relays[{idx}].url"), format!("\"{}\" is not an http:// or https:// url", relay.url));
+                        }
+                        if let Some(first_idx) = relay_ids.insert(relay.id, idx) {
+                            self.issue(
+                                format!("{path}.relays[{idx}].id"),
+                                format!("id {} is already used by relays[{first_idx}]", relay.id),
+                            );
+                        }
+                    }
+                    self.actors.push(ResolvedActor::new(path, "FlashbotsBroadcastActor", bc).with_client(client).consumes(&["tx_compose"]));
+                }
+            }
+        }
+
+        for (name, params) in sorted(config.actors.pools.as_ref().unwrap_or(&HashMap::new())) {
+            let path = format!("actors.pools.{name}");
+            let bc = self.blockchain(format!("{path}.bc"), params.blockchain.as_ref());
+            let client = self.client(format!("{path}.client"), params.client.as_ref());
+            if params.history {
+                self.actors.push(
+                    ResolvedActor::new(path.clone(), "HistoryPoolLoaderOneShotActor", bc.clone())
+                        .with_client(client.clone())
+                        .produces(&["tasks"]),
+                );
+            }
+            if params.protocol {
+                self.actors.push(
+                    ResolvedActor::new(path.clone(), "ProtocolPoolLoaderOneShotActor", bc.clone())
+                        .with_client(client.clone())
+                        .produces(&["tasks"]),
+                );
+            }
+            if params.new {
+                self.actors.push(
+                    ResolvedActor::new(path.clone(), "NewPoolLoaderActor", bc.clone()).consumes(&["new_block_logs"]).produces(&["tasks"]),
+                );
+            }
+            self.actors.push(
+                ResolvedActor::new(path, "PoolLoaderActor", bc).with_client(client).consumes(&["tasks"]).produces(&["market_events"]),
+            );
+        }
+
+        for (name, params) in sorted(config.actors.estimator.as_ref().unwrap_or(&HashMap::new())) {
+            let path = format!("actors.estimator.{name}");
+            match params {
+                EstimatorConfig::Evm(params) => {
+                    let bc = self.blockchain(format!("{path}.bc"), params.blockchain.as_ref());
+                    // EVM estimator runs without a client if none is set
+                    let client = params.client.as_ref().and_then(|client| self.client(format!("{path}.client"), Some(client)));
+                    let encoder = self.encoder(format!("{path}.encoder"), params.encoder.as_ref());
+                    self.actors.push(
+                        ResolvedActor::new(path, "EvmEstimatorActor", bc)
+                            .with_client(client)
+                            .with_encoder(encoder)
+                            .consumes(&["strategy_estimate"])
+                            .produces(&["strategy_compose", "pool_health_monitor", "influx_write"]),
+                    );
+                }
+                EstimatorConfig::Geth(params) => {
+                    let bc = self.blockchain(format!("{path}.bc"), params.blockchain.as_ref());
+                    let client = self.client(format!("{path}.client"), params.client.as_ref());
+                    let encoder = self.encoder(format!("{path}.encoder"), params.encoder.as_ref());
+                    self.actors.push(
+                        ResolvedActor::new(path, "GethEstimatorActor", bc)
+                            .with_client(client)
+                            .with_encoder(encoder)
+                            .consumes(&["strategy_estimate"])
+                            .produces(&["strategy_compose"]),
+                    );
+                }
+            }
+        }
+
+        for (name, params) in sorted(config.pipelines.as_ref().unwrap_or(&HashMap::new())) {
+            let path = format!("pipelines.{name}");
+            let bc = self.blockchain(format!("{path}.bc"), params.blockchain.as_ref());
+            let client = self.client(format!("{path}.client"), params.client.as_ref());
+            let encoder = params.encoder.as_ref().and_then(|encoder| self.encoder(format!("{path}.encoder"), Some(encoder)));
+            let signers = params.signers.as_ref().and_then(|signers| self.signers(format!("{path}.signers"), Some(signers)));
+            if params.actors.is_empty() {
+                self.issue(format!("{path}.actors"), "at least one actor is required");
+            }
+            for issue in params.pipeline().inventory_issues(|idx, _| format!("{path}.actors[{idx}]")) {
+                self.issue(issue.path, issue.message);
+            }
+            for (idx, actor) in params.actors.iter().enumerate() {
+                self.actors.push(
+                    ResolvedActor::new(format!("{path}.actors[{idx}]"), actor.kind(), bc.clone())
+                        .with_client(client.clone())
+                        .with_encoder(encoder.clone())
+                        .with_signers(signers.clone())
+                        .consumes(&actor.consumes())
+                        .produces(&actor.produces()),
+                );
+            }
+        }
+    }
+
+    /// Pipeline actors are checked against all actors of their blockchain, channels are not shared between blockchains
+    fn check_pipeline_wiring(&mut self) {
+        let mut wiring: HashMap<Option<String>, ChannelWiring> = HashMap::new();
+        for actor in self.actors.iter() {
+            wiring.entry(actor.blockchain.clone()).or_default().add(&actor.consumes, &actor.produces);
+        }
+
+        let mut issues = Vec::new();
+        for actor in self.actors.iter().filter(|actor| actor.path.starts_with("pipelines.")) {
+            if let Some(wiring) = wiring.get(&actor.blockchain) {
+                issues.extend(wiring.issues(&actor.path, &actor.consumes, &actor.produces));
+            }
+        }
+
+        // replayed blocks mixed with a live feed of the same blockchain corrupt the block history
+        for actor in self.actors.iter().filter(|actor| REPLAY_KINDS.contains(&actor.actor)) {
+            let live = self.actors.iter().find(|other| {
+                other.path != actor.path
+                    && other.blockchain == actor.blockchain
+                    && other.produces.iter().any(|channel| BLOCK_CHANNELS.contains(channel))
+            });
+            if let Some(live) = live {
+                issues.push(WiringIssue {
+                    path: actor.path.clone(),
+                    message: format!("{} also feeds blocks to this blockchain, give the replay its own blockchain", live.path),
+                });
+            }
+        }
+
+        // inventory funded swaps of a pipeline router can't be estimated without the provider
+        let inventory_blockchains: Vec<&Option<String>> = self
+            .actors
+            .iter()
+            .filter(|actor| actor.path.starts_with("pipelines.") && actor.consumes.contains(&"nonce_and_balance"))
+            .map(|actor| &actor.blockchain)
+            .collect();
+        for actor in self.actors.iter().filter(|actor| actor.actor == "EvmEstimatorActor" && actor.client.is_none()) {
+            if inventory_blockchains.contains(&&actor.blockchain) {
+                issues.push(WiringIssue {
+                    path: actor.path.clone(),
+                    message: "inventory funding needs the provider, set a client".to_string(),
+                });
+            }
+        }
+        for issue in issues {
+            self.issue(issue.path, issue.message);
+        }
+    }
+}
+
+impl TopologyConfig {
+    /// Checks references between sections, addresses, urls and mutually exclusive options
+    pub fn validate(&self) -> Result<(), ConfigErrors> {
+        self.actor_graph().map(|_| ())
+    }
+
+    /// Resolves the actors the topology would start without connecting anywhere
+    pub fn actor_graph(&self) -> Result<ActorGraph, ConfigErrors> {
+        let mut checker = ConfigChecker::new(self);
+        checker.check_sections();
+        checker.check_actors();
+        checker.check_pipeline_wiring();
+
+        if checker.issues.is_empty() {
+            Ok(ActorGraph { actors: checker.actors })
+        } else {
+            Err(ConfigErrors { issues: checker.issues })
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use loom_types_entities::config_source::load_toml_layers;
+
+    #[test]
+    fn test_example_config_is_valid() {
+        let config: TopologyConfig = load_toml_layers(&["../../config-example.toml".into()]).unwrap().try_into().unwrap();
+        config.validate().unwrap();
+        let graph = config.actor_graph().unwrap();
+        assert!(graph.actors.iter().any(|actor| actor.actor == "EvmEstimatorActor" && actor.encoder.as_deref() == Some("mainnet")));
+    }
+
+    #[test]
+    fn test_reports_all_issues() {
+        let config: TopologyConfig = toml::from_str(
+            r#"
+            [clients]
+            local = { url = "http://localhost:8545", transport = "ws", node = "geth" }
+            rpc = { url = "http://localhost:8545", transport = "http", node = "geth" }
+
+            [blockchains]
+            mainnet = {}
+            optimism = { chain_id = 10 }
+
+            [signers]
+            env_signer = { type = "env", bc = "mainnet" }
+
+            [encoders]
+            mainnet = { type = "swapstep", address = "0x123" }
+
+            [actors.node]
+            mainnet_node = { client = "remote", bc = "mainnet" }
+
+            [actors.node_exex]
+            mainnet_exex = { bc = "mainnet" }
+
+            [actors.price]
+            mainnet = {}
+            "#,
+        )
+        .unwrap();
+
+        let issues = config.validate().unwrap_err().issues;
+        let paths: Vec<&str> = issues.iter().map(|issue| issue.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            vec![
+                "clients.local.url",
+                "clients.rpc.transport",
+                "encoders.mainnet.address",
+                "actors.node.mainnet_node.client",
+                "actors.node.mainnet_node",
+                "actors.price.mainnet.bc"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_client_groups() {
+        let config: TopologyConfig = toml::from_str(
+            r#"
+            [clients]
+            local = { url = "ws://localhost:8546", transport = "ws", node = "geth" }
+            remote = { url = "ws://10.0.0.1:8546", transport = "ws", node = "geth" }
+
+            [client_groups]
+            pool = { clients = ["local", "remote"] }
+            remote = { clients = ["backup"], probe_interval_ms = 0 }
+
+            [blockchains]
+            mainnet = {}
+
+            [signers]
+            [encoders]
+
+            [actors.node]
+            mainnet_node = { client = "pool", bc = "mainnet" }
+            "#,
+        )
+        .unwrap();
+
+        let issues = config.validate().unwrap_err().issues;
+        let paths: Vec<&str> = issues.iter().map(|issue| issue.path.as_str()).collect();
+        assert_eq!(paths, vec!["client_groups.remote", "client_groups.remote.clients[0]", "client_groups.remote.probe_interval_ms"]);
+    }
+
+    #[test]
+    fn test_pipeline_wiring() {
+        let config: TopologyConfig = toml::from_str(
+            r#"
+            [clients]
+            local = { url = "ws://localhost:8546", transport = "ws", node = "geth" }
+
+            [blockchains]
+            mainnet = {}
+
+            [signers]
+            env_signer = { type = "env", bc = "mainnet" }
+
+            [encoders]
+            mainnet = { type = "swapstep", address = "0x0000000000000000000000000000000000000000" }
+
+            [actors.node]
+            mainnet_node = { client = "local", bc = "mainnet" }
+
+            [pipelines.backrun]
+            bc = "mainnet"
+            signers = "env_signer"
+            actors = [
+                { kind = "backrun_block" },
+                { kind = "swap_router" },
+                { kind = "evm_estimator" },
+                { kind = "flashbots_broadcaster" },
+            ]
+
+            [pipelines.mergers]
+            bc = "mainnet"
+            actors = [{ kind = "same_path_merger" }]
+            "#,
+        )
+        .unwrap();
+
+        let graph = config.actor_graph().unwrap();
+        assert!(graph.actors.iter().any(|actor| actor.path == "pipelines.backrun.actors[1]" && actor.actor == "swap_router"));
+
+        let config: TopologyConfig = toml::from_str(
+            r#"
+            [clients]
+            local = { url = "ws://localhost:8546", transport = "ws", node = "geth" }
+
+            [blockchains]
+            mainnet = {}
+
+            [signers]
+            [encoders]
+
+            [actors.node]
+            mainnet_node = { client = "local", bc = "mainnet" }
+
+            [pipelines.backrun]
+            bc = "mainnet"
+            actors = [{ kind = "backrun_mempool" }, { kind = "evm_estimator" }]
+            "#,
+        )
+        .unwrap();
+
+        let issues = config.validate().unwrap_err().issues;
+        let messages: Vec<String> = issues.iter().map(|issue| issue.to_string()).collect();
+        assert_eq!(
+            messages,
+            vec![
+                "pipelines.backrun.actors[0] : no consumer for channel \"strategy_compose\"",
+                "pipelines.backrun.actors[1] : no producer for channel \"strategy_estimate\"",
+                "pipelines.backrun.actors[1] : no consumer for channel \"strategy_compose\"",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_replay_needs_own_blockchain() {
+        let config: TopologyConfig = toml::from_str(
+            r#"
+            [clients]
+            local = { url = "ws://localhost:8546", transport = "ws", node = "geth" }
+
+            [blockchains]
+            mainnet = {}
+            replay = {}
+
+            [signers]
+            [encoders]
+
+            [actors.node]
+            mainnet_node = { client = "local", bc = "mainnet" }
+
+            [pipelines.replay]
+            bc = "mainnet"
+            client = "local"
+            actors = [{ kind = "mempool_log_player", path = "mempool.jsonl" }, { kind = "block_history" }]
+            "#,
+        )
+        .unwrap();
+
+        let overlaps = |config: &TopologyConfig| -> Vec<String> {
+            let issues = config.validate().err().map(|errors| errors.issues).unwrap_or_default();
+            issues.iter().filter(|issue| issue.message.contains("own blockchain")).map(|issue| issue.to_string()).collect()
+        };
+        assert_eq!(
+            overlaps(&config),
+            vec!["pipelines.replay.actors[0] : actors.node.mainnet_node also feeds blocks to this blockchain, give the replay its own blockchain"]
+        );
+
+        let mut config = config;
+        config.pipelines.as_mut().unwrap().get_mut("replay").unwrap().blockchain = Some("replay".to_string());
+        assert!(overlaps(&config).is_empty());
+    }
+
+    #[test]
+    fn test_mempool_sources() {
+        let config: TopologyConfig = toml::from_str(
+            r#"
+            [clients]
+            local = { url = "ws://localhost:8546", transport = "ws", node = "geth" }
+
+            [blockchains]
+            mainnet = {}
+
+            [signers]
+            [encoders]
+
+            [actors.mempool]
+            node = { client = "local", bc = "mainnet" }
+            feed = { type = "ws", url = "wss://feed.example.com", bc = "mainnet" }
+            bloxroute = { type = "bloxroute", url = "https://api.blxrbdn.com", bc = "mainnet" }
+            replay = { type = "file", bc = "mainnet" }
+            "#,
+        )
+        .unwrap();
+
+        let issues = config.validate().unwrap_err().issues;
+        let paths: Vec<&str> = issues.iter().map(|issue| issue.path.as_str()).collect();
+        assert_eq!(paths, vec!["actors.mempool.bloxroute.url", "actors.mempool.bloxroute.auth_header", "actors.mempool.replay.path"]);
+    }
+}
@@ -1,4 +1,5 @@
 pub use abi_helpers::AbiEncoderHelper;
+pub use chainlink::IChainlinkAggregator;
 pub use erc20::IERC20;
 pub use multicaller::IMultiCaller;
 pub use weth::IWETH;
@@ -6,6 +7,7 @@ pub use weth::IWETH;
 mod abi_helpers;
 
 pub mod balancer;
+mod chainlink;
 pub mod curve;
 mod erc20;
 pub mod lido;
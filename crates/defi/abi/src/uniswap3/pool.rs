@@ -166,6 +166,11 @@ sol! {
                 bool initialized
         );
 
+        function observe(uint32[] calldata secondsAgos) external view returns (
+                int56[] memory tickCumulatives,
+                uint160[] memory secondsPerLiquidityCumulativeX128s
+        );
+
     }
 
 }
@@ -50,6 +50,21 @@ impl TokenAddressBase {
     pub const ETH_NATIVE: Address = Address::ZERO;
     pub const WETH: Address = address!("4200000000000000000000000000000000000006");
     pub const USDC: Address = address!("833589fCD6eDb6E08f4c7C32D4f71b54bdA02913");
+    pub const USDBC: Address = address!("d9aAEc86B65D86f6A7B5B1b0c42FFA531710b6CA");
+    pub const DAI: Address = address!("50c5725949A6F0c72E6C4a641F24049A917DB0Cb");
+    pub const CBETH: Address = address!("2Ae3F1Ec7F1F5012CFEab0185bfc7aa3cf0DEc22");
+}
+
+#[non_exhaustive]
+pub struct TokenAddressOptimism;
+impl TokenAddressOptimism {
+    pub const ETH_NATIVE: Address = Address::ZERO;
+    pub const WETH: Address = address!("4200000000000000000000000000000000000006");
+    pub const USDC: Address = address!("0b2C639c533813f4Aa9D7837CAf62653d097Ff85");
+    pub const USDT: Address = address!("94b008aA00579c1307B0EF2c499aD98a8ce58e58");
+    pub const DAI: Address = address!("DA10009cBd5D07dd0CeCc66161FC93D7c9000da1");
+    pub const WBTC: Address = address!("68f180fcCe6836688e9084f035309E29Bf0A2095");
+    pub const OP: Address = address!("4200000000000000000000000000000000000042");
 }
 
 #[non_exhaustive]
@@ -81,6 +96,37 @@ impl FactoryAddress {
     pub const UNISWAP_V4_POOL_MANAGER_ADDRESS: Address = address!("000000000004444c5dc75cB358380D2e3dE08A90");
 }
 
+#[non_exhaustive]
+pub struct FactoryAddressArbitrum;
+
+impl FactoryAddressArbitrum {
+    pub const UNISWAP_V2: Address = address!("f1D7CC64Fb4452F05c498126312eBE29f30Fbcf9");
+    pub const UNISWAP_V3: Address = address!("1F98431c8aD98523631AE4a59f267346ea31F984");
+    pub const SUSHISWAP_V2: Address = address!("c35DADB65012eC5796536bD9864eD8773aBc74C4");
+    pub const SUSHISWAP_V3: Address = address!("1af415a1EbA07a4986a52B6f2e7dE7003D82231e");
+    pub const PANCAKE_V3: Address = address!("0BFbCF9fa4f9C56B0F40a671Ad40E0805A091865");
+}
+
+#[non_exhaustive]
+pub struct FactoryAddressBase;
+
+impl FactoryAddressBase {
+    pub const UNISWAP_V2: Address = address!("8909Dc15e40173Ff4699343b6eB8132c65e18eC6");
+    pub const UNISWAP_V3: Address = address!("33128a8fC17869897dcE68Ed026d694621f6FDfD");
+    pub const SUSHISWAP_V2: Address = address!("71524B4f93c58fcbF659783284E38825f0622859");
+    pub const SUSHISWAP_V3: Address = address!("c35DADB65012eC5796536bD9864eD8773aBc74C4");
+    pub const PANCAKE_V3: Address = address!("0BFbCF9fa4f9C56B0F40a671Ad40E0805A091865");
+}
+
+#[non_exhaustive]
+pub struct FactoryAddressOptimism;
+
+impl FactoryAddressOptimism {
+    pub const UNISWAP_V2: Address = address!("0c3c1c532F1e39EdF36BE9Fe0bE1410313E074Bf");
+    pub const UNISWAP_V3: Address = address!("1F98431c8aD98523631AE4a59f267346ea31F984");
+    pub const SUSHISWAP_V3: Address = address!("9c6522117e2ed1fE5bdb72bb0eD5E3f2bdE7DBe0");
+}
+
 #[non_exhaustive]
 pub struct PeripheryAddress;
 
@@ -1,7 +1,7 @@
 use eyre::eyre;
 use influxdb::{Timestamp, WriteQuery};
 use loom_core_actors::Producer;
-use loom_core_actors::{subscribe, Actor, ActorResult, Broadcaster, WorkerResult};
+use loom_core_actors::{channel_snapshots, subscribe, Actor, ActorResult, Broadcaster, WorkerResult};
 use loom_core_actors::{Accessor, Consumer, SharedState};
 use loom_core_actors_macros::{Accessor, Consumer, Producer};
 use loom_core_blockchain::{Blockchain, BlockchainState};
@@ -113,6 +113,21 @@ async fn metrics_recorder_worker<DB: DatabaseLoomExt + DatabaseRef + Send + Sync
             if let Err(e) = influx_channel_clone.send(write_query) {
                 error!("Failed to send block latency to influxdb: {:?}", e);
             }
+
+            for channel in channel_snapshots() {
+                let write_query = WriteQuery::new(Timestamp::from(current_timestamp), "channels")
+                    .add_tag("channel", channel.name)
+                    .add_field("sent", channel.sent)
+                    .add_field("send_errors", channel.send_errors)
+                    .add_field("depth", channel.depth as u64)
+                    .add_field("capacity", channel.capacity as u64)
+                    .add_field("receivers", channel.receivers as u64)
+                    .add_field("lagged", channel.lagged)
+                    .add_field("block_number", block_header.inner.header.number);
+                if let Err(e) = influx_channel_clone.send(write_query) {
+                    error!("Failed to send channel stats to influxdb: {:?}", e);
+                }
+            }
         })
         .await
         {
@@ -7,10 +7,9 @@ use alloy_provider::Provider;
 use chrono::{DateTime, Duration, Local};
 use eyre::Result;
 use tokio::sync::broadcast::error::RecvError;
-use tokio::sync::broadcast::Receiver;
 use tracing::{error, info, warn};
 
-use loom_core_actors::{Accessor, Actor, ActorResult, Broadcaster, Consumer, SharedState, WorkerResult};
+use loom_core_actors::{Accessor, Actor, ActorResult, Broadcaster, BroadcasterReceiver, Consumer, SharedState, WorkerResult};
 use loom_core_actors_macros::{Accessor, Consumer};
 use loom_core_blockchain::{Blockchain, BlockchainState};
 use loom_evm_db::DatabaseLoomExt;
@@ -61,8 +60,8 @@ pub async fn state_health_monitor_worker<
     tx_compose_channel_rx: Broadcaster<MessageTxCompose>,
     market_events_rx: Broadcaster<MarketEvents>,
 ) -> WorkerResult {
-    let mut tx_compose_channel_rx: Receiver<MessageTxCompose> = tx_compose_channel_rx.subscribe();
-    let mut market_events_rx: Receiver<MarketEvents> = market_events_rx.subscribe();
+    let mut tx_compose_channel_rx: BroadcasterReceiver<MessageTxCompose> = tx_compose_channel_rx.subscribe();
+    let mut market_events_rx: BroadcasterReceiver<MarketEvents> = market_events_rx.subscribe();
 
     let mut check_time_map: HashMap<Address, DateTime<Local>> = HashMap::new();
     let mut pool_address_to_verify_vec: Vec<Address> = Vec::new();
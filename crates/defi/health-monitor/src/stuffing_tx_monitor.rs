@@ -2,21 +2,20 @@ use alloy_consensus::transaction::Transaction;
 use alloy_network::{Ethereum, TransactionResponse};
 use alloy_primitives::{Address, TxHash, U256};
 use alloy_provider::Provider;
-use eyre::{eyre, Result};
+use eyre::Result;
 use influxdb::{Timestamp, WriteQuery};
 use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::broadcast::error::RecvError;
-use tokio::sync::broadcast::Receiver;
 use tracing::{error, info};
 
 use loom_core_blockchain::Blockchain;
 use loom_evm_utils::NWETH;
 use loom_types_entities::{LatestBlock, Swap, Token};
 
-use loom_core_actors::{Accessor, Actor, ActorResult, Broadcaster, Consumer, Producer, SharedState, WorkerResult};
+use loom_core_actors::{Accessor, Actor, ActorResult, Broadcaster, BroadcasterReceiver, Consumer, Producer, SharedState, WorkerResult};
 use loom_core_actors_macros::{Accessor, Consumer, Producer};
-use loom_types_blockchain::debug_trace_transaction;
+use loom_types_blockchain::{coinbase_balance_diff, debug_trace_transaction};
 use loom_types_events::{MarketEvents, MessageTxCompose, TxComposeMessageType};
 
 #[derive(Clone, Debug)]
@@ -32,10 +31,7 @@ struct TxToCheck {
 async fn calc_coinbase_diff<P: Provider<Ethereum> + 'static>(client: P, tx_hash: TxHash, coinbase: Address) -> Result<U256> {
     let (pre, post) = debug_trace_transaction(client, tx_hash, true).await?;
 
-    let coinbase_pre = pre.get(&coinbase).ok_or(eyre!("COINBASE_NOT_FOUND_IN_PRE"))?;
-    let coinbase_post = post.get(&coinbase).ok_or(eyre!("COINBASE_NOT_FOUND_IN_POST"))?;
-
-    let balance_diff = coinbase_post.balance.unwrap_or_default().checked_sub(coinbase_pre.balance.unwrap_or_default()).unwrap_or_default();
+    let balance_diff = coinbase_balance_diff(&pre, &post, &coinbase)?;
     info!("Stuffing tx mined MF tx: {:?} sent to coinbase: {}", tx_hash, NWETH::to_float(balance_diff));
 
     Ok(balance_diff)
@@ -48,8 +44,8 @@ pub async fn stuffing_tx_monitor_worker<P: Provider<Ethereum> + Clone + 'static>
     market_events_rx: Broadcaster<MarketEvents>,
     influxdb_write_channel_tx: Broadcaster<WriteQuery>,
 ) -> WorkerResult {
-    let mut tx_compose_channel_rx: Receiver<MessageTxCompose> = tx_compose_channel_rx.subscribe();
-    let mut market_events_rx: Receiver<MarketEvents> = market_events_rx.subscribe();
+    let mut tx_compose_channel_rx: BroadcasterReceiver<MessageTxCompose> = tx_compose_channel_rx.subscribe();
+    let mut market_events_rx: BroadcasterReceiver<MarketEvents> = market_events_rx.subscribe();
 
     let mut txs_to_check: HashMap<TxHash, TxToCheck> = HashMap::new();
 
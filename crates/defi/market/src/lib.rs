@@ -3,6 +3,7 @@ pub use new_pool_actor::NewPoolLoaderActor;
 pub use pool_loader_actor::{fetch_and_add_pool_by_pool_id, fetch_state_and_add_pool, PoolLoaderActor};
 pub use protocol_pool_loader_actor::ProtocolPoolLoaderOneShotActor;
 pub use required_pools_actor::RequiredPoolLoaderActor;
+pub use token_loader_actor::{fetch_token_metadata, TokenLoaderActor, TokenMetadata};
 
 mod history_pool_loader_actor;
 mod logs_parser;
@@ -10,3 +11,4 @@ mod new_pool_actor;
 mod pool_loader_actor;
 mod protocol_pool_loader_actor;
 mod required_pools_actor;
+mod token_loader_actor;
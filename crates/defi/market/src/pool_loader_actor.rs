@@ -2,8 +2,12 @@ use std::collections::{BTreeMap, HashMap};
 use std::marker::PhantomData;
 use std::sync::Arc;
 
-use alloy_network::Network;
+use alloy_consensus::BlockHeader;
+use alloy_eips::BlockNumberOrTag;
+use alloy_network::{BlockResponse, Network};
+use alloy_primitives::{Address, U256};
 use alloy_provider::Provider;
+use alloy_rpc_types::BlockTransactionsKind;
 use eyre::Result;
 use tracing::{debug, error, info};
 
@@ -11,13 +15,17 @@ use loom_core_actors::{run_sync, subscribe, Actor, ActorResult, Broadcaster, Pro
 use loom_core_actors::{Accessor, Consumer};
 use loom_core_actors_macros::{Accessor, Consumer, Producer};
 use loom_core_blockchain::{Blockchain, BlockchainState};
+use loom_defi_pools::TokenBehaviourDetector;
 use loom_node_debug_provider::DebugProviderExt;
 use loom_types_entities::required_state::RequiredStateReader;
-use loom_types_entities::{Market, MarketState, PoolClass, PoolId, PoolLoaders, PoolWrapper, SwapDirection};
+use loom_types_entities::{
+    Market, MarketState, PoolClass, PoolId, PoolLoaders, PoolWrapper, PreswapRequirement, SwapDirection, Token, TokenBehaviour,
+};
 use loom_types_events::{LoomTask, MarketEvents};
 
 use loom_types_blockchain::get_touched_addresses;
 use loom_types_entities::pool_config::PoolsLoadingConfig;
+use revm::primitives::Env;
 use revm::{Database, DatabaseCommit, DatabaseRef};
 use tokio::sync::Semaphore;
 
@@ -128,7 +136,7 @@ where
     DB: Database + DatabaseRef + DatabaseCommit + Send + Sync + Clone + 'static,
 {
     match pool_wrapped.get_state_required() {
-        Ok(required_state) => match RequiredStateReader::fetch_calls_and_slots(client, required_state, None).await {
+        Ok(required_state) => match RequiredStateReader::fetch_calls_and_slots(client.clone(), required_state, None).await {
             Ok(state) => {
                 let pool_address = pool_wrapped.get_address();
                 {
@@ -149,6 +157,9 @@ where
                     drop(market_state_write_guard);
                 }
 
+                let env = latest_block_env(&client).await;
+                let token_behaviours = detect_pool_token_behaviours(market.clone(), market_state.clone(), &pool_wrapped, env).await;
+
                 let directions_vec = pool_wrapped.get_swap_directions();
                 let pool_manager_cells = pool_wrapped.get_pool_manager_cells();
                 let pool_id = pool_wrapped.get_pool_id();
@@ -162,6 +173,15 @@ where
                 // Ignore error if pool already exists because it was maybe already added by e.g. db pool loader
                 let _ = market_write_guard.add_pool(pool_wrapped);
 
+                for (token_address, token_behaviour) in token_behaviours {
+                    if market_write_guard.get_token(&token_address).is_none() {
+                        market_write_guard.add_token(Token::new(token_address));
+                    }
+                    if let Some(token) = market_write_guard.get_token(&token_address) {
+                        token.set_behaviour(Some(token_behaviour));
+                    }
+                }
+
                 let swap_paths = market_write_guard.build_swap_path_vec(&directions_tree)?;
                 let swap_paths_added = market_write_guard.add_paths(swap_paths);
 
@@ -190,6 +210,70 @@ where
     }
 }
 
+/// Env of the latest block the pool state was fetched at, taxes of some tokens depend on the block number or timestamp.
+async fn latest_block_env<P, N>(client: &P) -> Env
+where
+    N: Network,
+    P: Provider<N> + Send + Sync + Clone + 'static,
+{
+    let mut env = Env::default();
+    match client.get_block_by_number(BlockNumberOrTag::Latest, BlockTransactionsKind::Hashes).await {
+        Ok(Some(block)) => {
+            let header = block.header();
+            env.block.number = U256::from(header.number());
+            env.block.timestamp = U256::from(header.timestamp());
+            env.block.basefee = U256::from(header.base_fee_per_gas().unwrap_or_default());
+            env.block.coinbase = header.beneficiary();
+        }
+        Ok(None) => debug!("Latest block not found, default env is used for token behaviour detection"),
+        Err(error) => debug!(%error, "Failed to fetch latest block, default env is used for token behaviour detection"),
+    }
+    env
+}
+
+/// Detect transfer fees and rebasing for tokens of pools that receive tokens by transfer before the swap.
+async fn detect_pool_token_behaviours<DB>(
+    market: SharedState<Market>,
+    market_state: SharedState<MarketState<DB>>,
+    pool_wrapped: &PoolWrapper,
+    env: Env,
+) -> Vec<(Address, TokenBehaviour)>
+where
+    DB: DatabaseRef + Send + Sync + Clone + 'static,
+{
+    let PreswapRequirement::Transfer(_) = pool_wrapped.preswap_requirement() else {
+        return vec![];
+    };
+
+    let pool_address = pool_wrapped.get_address();
+    let tokens_to_check: Vec<Address> = {
+        let market_guard = market.read().await;
+        pool_wrapped
+            .get_tokens()
+            .into_iter()
+            .filter(|token_address| !market_guard.is_weth(token_address))
+            .filter(|token_address| market_guard.get_token(token_address).is_none_or(|token| !token.is_behaviour_known()))
+            .collect()
+    };
+
+    let market_state_guard = market_state.read().await;
+    let mut ret = Vec::new();
+    for token_address in tokens_to_check {
+        match TokenBehaviourDetector::detect(&market_state_guard.state_db, env.clone(), token_address, pool_address) {
+            Ok(token_behaviour) => {
+                if !token_behaviour.is_standard() {
+                    info!(%token_address, %pool_address, ?token_behaviour, "Non standard token detected");
+                }
+                ret.push((token_address, token_behaviour));
+            }
+            Err(error) => {
+                debug!(%error, %token_address, %pool_address, "Token behaviour detection failed");
+            }
+        }
+    }
+    ret
+}
+
 #[derive(Accessor, Consumer, Producer)]
 pub struct PoolLoaderActor<P, PL, N, DB>
 where
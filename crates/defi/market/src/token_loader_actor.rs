@@ -0,0 +1,234 @@
+use std::collections::{HashMap, HashSet};
+use std::marker::PhantomData;
+
+use alloy_network::Network;
+use alloy_primitives::Address;
+use alloy_provider::Provider;
+use eyre::{eyre, Result};
+use futures::stream::{self, StreamExt};
+use tokio::sync::broadcast::error::RecvError;
+use tracing::{debug, error, info, warn};
+
+use loom_core_actors::{Accessor, Consumer};
+use loom_core_actors::{Actor, ActorResult, Broadcaster, SharedState, WorkerResult};
+use loom_core_actors_macros::{Accessor, Consumer};
+use loom_core_blockchain::Blockchain;
+use loom_defi_abi::IERC20::IERC20Instance;
+use loom_defi_pools::state_readers::ERC20StateReader;
+use loom_types_entities::Market;
+use loom_types_events::MarketEvents;
+
+const MAX_CONCURRENT_REQUESTS: usize = 16;
+/// Tokens failing more often are likely not ERC20 contracts and are not requested again
+const MAX_FETCH_ATTEMPTS: usize = 3;
+
+#[derive(Clone, Debug)]
+pub struct TokenMetadata {
+    pub address: Address,
+    pub symbol: Option<String>,
+    pub name: Option<String>,
+    pub decimals: Option<u8>,
+}
+
+/// Fetch `decimals`, `symbol` and `name` of an ERC20 token. Missing or broken methods are returned as None.
+pub async fn fetch_token_metadata<P, N>(client: P, address: Address) -> Result<TokenMetadata>
+where
+    N: Network,
+    P: Provider<N> + Send + Sync + Clone + 'static,
+{
+    let token_instance = IERC20Instance::new(address, client);
+
+    let decimals_call = token_instance.decimals();
+    let symbol_call = token_instance.symbol();
+    let name_call = token_instance.name();
+
+    let (decimals, symbol, name) = tokio::join!(decimals_call.call_raw(), symbol_call.call_raw(), name_call.call_raw());
+
+    let decimals = decimals.ok().and_then(|data| ERC20StateReader::decode_decimals(&data).ok());
+    let symbol = symbol.ok().and_then(|data| ERC20StateReader::decode_string_or_bytes32(&data).ok());
+    let name = name.ok().and_then(|data| ERC20StateReader::decode_string_or_bytes32(&data).ok());
+
+    if decimals.is_none() && symbol.is_none() {
+        return Err(eyre!("NOT_ERC20_TOKEN"));
+    }
+
+    Ok(TokenMetadata { address, symbol, name, decimals })
+}
+
+/// Fetch metadata for tokens concurrently and update them in the market, returns the tokens that failed
+pub async fn load_tokens_metadata<P, N>(client: P, market: SharedState<Market>, token_addresses: Vec<Address>) -> Result<Vec<Address>>
+where
+    N: Network,
+    P: Provider<N> + Send + Sync + Clone + 'static,
+{
+    if token_addresses.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let results: Vec<Result<TokenMetadata, Address>> = stream::iter(token_addresses)
+        .map(|address| {
+            let client = client.clone();
+            async move {
+                fetch_token_metadata(client, address).await.map_err(|error| {
+                    warn!(%error, %address, "Failed to fetch token metadata");
+                    address
+                })
+            }
+        })
+        .buffer_unordered(MAX_CONCURRENT_REQUESTS)
+        .collect()
+        .await;
+    let (metadata_vec, failed): (Vec<_>, Vec<_>) = results.into_iter().partition(|result| result.is_ok());
+
+    let mut market_guard = market.write().await;
+    for metadata in metadata_vec.into_iter().flatten() {
+        let token = market_guard.get_token_or_default(&metadata.address);
+        if metadata.decimals.is_none() {
+            warn!(address = %metadata.address, symbol = ?metadata.symbol, "Token decimals not found, default is used");
+        }
+        market_guard.update_token(token.with_metadata(metadata.symbol.clone(), metadata.name.clone(), metadata.decimals));
+        debug!(address = %metadata.address, symbol = ?metadata.symbol, decimals = ?metadata.decimals, "Token metadata loaded");
+    }
+
+    Ok(failed.into_iter().filter_map(|result| result.err()).collect())
+}
+
+/// Returns tokens without metadata that were not requested before
+async fn tokens_to_load(market: &SharedState<Market>, token_addresses: Vec<Address>, requested: &mut HashSet<Address>) -> Vec<Address> {
+    let market_guard = market.read().await;
+    token_addresses
+        .into_iter()
+        .filter(|address| market_guard.get_token(address).is_none_or(|token| !token.has_metadata()))
+        .filter(|address| requested.insert(*address))
+        .collect()
+}
+
+/// Loads the tokens and makes failed ones requestable again until they failed `MAX_FETCH_ATTEMPTS` times.
+/// Returns the tokens to retry with the next load.
+async fn load_with_retry<P, N>(
+    client: P,
+    market: SharedState<Market>,
+    token_addresses: Vec<Address>,
+    requested: &mut HashSet<Address>,
+    failures: &mut HashMap<Address, usize>,
+) -> Vec<Address>
+where
+    N: Network,
+    P: Provider<N> + Send + Sync + Clone + 'static,
+{
+    let requested_len = token_addresses.len();
+    let failed = match load_tokens_metadata(client, market, token_addresses.clone()).await {
+        Ok(failed) => failed,
+        Err(error) => {
+            error!(%error, "Failed to load token metadata");
+            token_addresses
+        }
+    };
+    if requested_len > 0 {
+        debug!(loaded = requested_len - failed.len(), failed = failed.len(), "Token metadata requested");
+    }
+
+    failed
+        .into_iter()
+        .filter(|address| {
+            let attempts = failures.entry(*address).or_default();
+            *attempts += 1;
+            *attempts < MAX_FETCH_ATTEMPTS
+        })
+        .inspect(|address| {
+            requested.remove(address);
+        })
+        .collect()
+}
+
+pub async fn token_loader_worker<P, N>(client: P, market: SharedState<Market>, market_events_rx: Broadcaster<MarketEvents>) -> WorkerResult
+where
+    N: Network,
+    P: Provider<N> + Send + Sync + Clone + 'static,
+{
+    let mut market_events_rx = market_events_rx.subscribe();
+    let mut requested: HashSet<Address> = HashSet::new();
+    let mut failures: HashMap<Address, usize> = HashMap::new();
+
+    // refresh tokens of pools that are already in the market
+    let known_tokens: Vec<Address> = {
+        let market_guard = market.read().await;
+        market_guard.pools().values().flat_map(|pool| pool.get_tokens()).collect::<HashSet<_>>().into_iter().collect()
+    };
+    let token_addresses = tokens_to_load(&market, known_tokens, &mut requested).await;
+    info!(tokens = token_addresses.len(), "Loading token metadata for existing pools");
+    // failed tokens are fetched again with the tokens of the next loaded pool
+    let mut retry = load_with_retry(client.clone(), market.clone(), token_addresses, &mut requested, &mut failures).await;
+
+    loop {
+        let pool_id = match market_events_rx.recv().await {
+            Ok(MarketEvents::NewPoolLoaded { pool_id, .. }) => pool_id,
+            Ok(_) => continue,
+            Err(RecvError::Lagged(lag)) => {
+                warn!(lag, "Market events lagged");
+                continue;
+            }
+            Err(RecvError::Closed) => {
+                error!("Market events channel closed");
+                break Err(eyre!("MARKET_EVENTS_RX_CLOSED"));
+            }
+        };
+
+        let pool_tokens = match market.read().await.get_pool(&pool_id) {
+            Some(pool) => pool.get_tokens(),
+            None => continue,
+        };
+
+        let pool_tokens = [std::mem::take(&mut retry), pool_tokens].concat();
+        let token_addresses = tokens_to_load(&market, pool_tokens, &mut requested).await;
+        retry = load_with_retry(client.clone(), market.clone(), token_addresses, &mut requested, &mut failures).await;
+    }
+}
+
+/// Loads ERC20 metadata for every new token found in loaded pools
+#[derive(Accessor, Consumer)]
+pub struct TokenLoaderActor<P, N>
+where
+    N: Network,
+    P: Provider<N> + Send + Sync + Clone + 'static,
+{
+    client: P,
+    #[accessor]
+    market: Option<SharedState<Market>>,
+    #[consumer]
+    market_events_rx: Option<Broadcaster<MarketEvents>>,
+    _n: PhantomData<N>,
+}
+
+impl<P, N> TokenLoaderActor<P, N>
+where
+    N: Network,
+    P: Provider<N> + Send + Sync + Clone + 'static,
+{
+    pub fn new(client: P) -> Self {
+        Self { client, market: None, market_events_rx: None, _n: PhantomData }
+    }
+
+    pub fn on_bc(self, bc: &Blockchain) -> Self {
+        Self { market: Some(bc.market()), market_events_rx: Some(bc.market_events_channel()), ..self }
+    }
+}
+
+impl<P, N> Actor for TokenLoaderActor<P, N>
+where
+    N: Network,
+    P: Provider<N> + Send + Sync + Clone + 'static,
+{
+    fn start(&self) -> ActorResult {
+        let task = tokio::task::spawn(token_loader_worker(
+            self.client.clone(),
+            self.market.clone().unwrap(),
+            self.market_events_rx.clone().unwrap(),
+        ));
+        Ok(vec![task])
+    }
+
+    fn name(&self) -> &'static str {
+        "TokenLoaderActor"
+    }
+}
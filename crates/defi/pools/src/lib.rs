@@ -5,6 +5,7 @@ pub use loaders::*;
 pub use loom_types_entities::pool_config::PoolsLoadingConfig;
 pub use maverickpool::MaverickPool;
 pub use pancakev3pool::PancakeV3Pool;
+pub use token_behaviour::TokenBehaviourDetector;
 pub use uniswapv2pool::UniswapV2Pool;
 pub use uniswapv3pool::{Slot0, UniswapV3Pool};
 
@@ -19,4 +20,5 @@ pub mod protocols;
 
 mod loaders;
 mod pancakev3pool;
+mod token_behaviour;
 mod virtual_impl;
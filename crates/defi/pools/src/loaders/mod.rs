@@ -30,6 +30,7 @@ macro_rules! pool_loader {
             LDT: LoomDataTypes,
         {
             provider: Option<P>,
+            chain_parameters: loom_types_blockchain::ChainParameters,
             phantom_data: PhantomData<(P, N, LDT)>,
         }
 
@@ -45,7 +46,11 @@ macro_rules! pool_loader {
             }
 
             pub fn with_provider(provder: P) -> Self {
-                Self { provider: Some(provder), phantom_data: PhantomData }
+                Self { provider: Some(provder), ..Self::default() }
+            }
+
+            pub fn with_chain_parameters(self, chain_parameters: loom_types_blockchain::ChainParameters) -> Self {
+                Self { chain_parameters, ..self }
             }
         }
 
@@ -56,7 +61,7 @@ macro_rules! pool_loader {
             LDT: LoomDataTypes,
         {
             fn default() -> Self {
-                Self { provider: None, phantom_data: PhantomData }
+                Self { provider: None, chain_parameters: loom_types_blockchain::ChainParameters::ethereum(), phantom_data: PhantomData }
             }
         }
     };
@@ -117,12 +122,16 @@ where
     where
         P: Provider<Ethereum> + Clone,
     {
+        let chain_parameters = config.chain_parameters();
         let pool_loader = PoolLoadersBuilder::<P>::new()
             .with_provider(provider.clone())
             .with_config(config)
             .add_loader(PoolClass::Maverick, MaverickPoolLoader::with_provider(provider.clone()))
-            .add_loader(PoolClass::UniswapV2, UniswapV2PoolLoader::with_provider(provider.clone()))
-            .add_loader(PoolClass::UniswapV3, UniswapV3PoolLoader::with_provider(provider.clone()))
+            .add_loader(
+                PoolClass::UniswapV2,
+                UniswapV2PoolLoader::with_provider(provider.clone()).with_chain_parameters(chain_parameters.clone()),
+            )
+            .add_loader(PoolClass::UniswapV3, UniswapV3PoolLoader::with_provider(provider.clone()).with_chain_parameters(chain_parameters))
             .add_loader(PoolClass::Curve, CurvePoolLoader::with_provider(provider.clone()))
             .build();
 
@@ -62,7 +62,7 @@ where
         Box::pin(async move {
             let pool_address = pool_id.address()?;
             let factory_address = fetch_uni2_factory(provider.clone(), pool_address).await?;
-            match get_protocol_by_factory(factory_address) {
+            match get_protocol_by_factory(&self.chain_parameters, factory_address) {
                 PoolProtocol::NomiswapStable
                 | PoolProtocol::Miniswap
                 | PoolProtocol::Integral
@@ -65,7 +65,7 @@ where
 
             let factory_address_result = fetch_uni3_factory(provider.clone(), pool_address).await;
             match factory_address_result {
-                Ok(factory_address) => match get_protocol_by_factory(factory_address) {
+                Ok(factory_address) => match get_protocol_by_factory(&self.chain_parameters, factory_address) {
                     PoolProtocol::PancakeV3 => {
                         Ok(PoolWrapper::new(Arc::new(PancakeV3Pool::fetch_pool_data(provider.clone(), pool_address).await?)))
                     }
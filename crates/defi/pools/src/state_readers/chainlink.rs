@@ -0,0 +1,40 @@
+use alloy::primitives::{Address, U256};
+use alloy::sol_types::{SolCall, SolInterface};
+use eyre::{eyre, Result};
+use revm::primitives::Env;
+use revm::DatabaseRef;
+
+use loom_defi_abi::IChainlinkAggregator;
+use loom_evm_utils::evm::evm_call;
+
+pub struct ChainlinkStateReader {}
+
+impl ChainlinkStateReader {
+    pub fn decimals<DB: DatabaseRef>(db: &DB, env: Env, aggregator: Address) -> Result<u8> {
+        let call_data_result = evm_call(
+            db,
+            env,
+            aggregator,
+            IChainlinkAggregator::IChainlinkAggregatorCalls::decimals(IChainlinkAggregator::decimalsCall {}).abi_encode(),
+        )?
+        .0;
+        let call_return = IChainlinkAggregator::decimalsCall::abi_decode_returns(&call_data_result, false)?;
+        Ok(call_return._0)
+    }
+
+    /// Returns the latest positive answer and its update timestamp
+    pub fn latest_answer<DB: DatabaseRef>(db: &DB, env: Env, aggregator: Address) -> Result<(U256, u64)> {
+        let call_data_result = evm_call(
+            db,
+            env,
+            aggregator,
+            IChainlinkAggregator::IChainlinkAggregatorCalls::latestRoundData(IChainlinkAggregator::latestRoundDataCall {}).abi_encode(),
+        )?
+        .0;
+        let call_return = IChainlinkAggregator::latestRoundDataCall::abi_decode_returns(&call_data_result, false)?;
+        if call_return.answer.is_negative() || call_return.answer.is_zero() {
+            return Err(eyre!("CHAINLINK_ANSWER_NOT_POSITIVE"));
+        }
+        Ok((call_return.answer.into_raw(), call_return.updatedAt.saturating_to::<u64>()))
+    }
+}
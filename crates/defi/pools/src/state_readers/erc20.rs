@@ -1,6 +1,6 @@
 use alloy::primitives::{Address, U256};
 use alloy::sol_types::{SolCall, SolInterface};
-use eyre::Result;
+use eyre::{eyre, Result};
 use loom_defi_abi::IERC20;
 use loom_evm_utils::evm::evm_call;
 use revm::primitives::Env;
@@ -23,4 +23,54 @@ impl ERC20StateReader {
         let call_return = IERC20::allowanceCall::abi_decode_returns(&call_data_result, false)?;
         Ok(call_return._0)
     }
+
+    /// Decimals above 77 do not fit U256 exponent and are treated as invalid
+    pub fn decode_decimals(data: &[u8]) -> Result<u8> {
+        let decimals = IERC20::decimalsCall::abi_decode_returns(data, false)?._0;
+        if decimals > U256::from(77) {
+            return Err(eyre!("INVALID_DECIMALS"));
+        }
+        Ok(decimals.to::<u8>())
+    }
+
+    /// Decode string return value, some old tokens like MKR return bytes32 instead of string
+    pub fn decode_string_or_bytes32(data: &[u8]) -> Result<String> {
+        let value = match IERC20::symbolCall::abi_decode_returns(data, false) {
+            Ok(ret) => ret._0,
+            Err(_) if data.len() == 32 => String::from_utf8_lossy(data).to_string(),
+            Err(error) => return Err(eyre!(error)),
+        };
+        let value = value.trim_matches(char::from(0)).trim().to_string();
+        if value.is_empty() {
+            Err(eyre!("EMPTY_STRING"))
+        } else {
+            Ok(value)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloy::primitives::B256;
+    use alloy::sol_types::SolValue;
+
+    #[test]
+    fn test_decode_string_or_bytes32() {
+        let string_encoded = "USDC".to_string().abi_encode();
+        assert_eq!(ERC20StateReader::decode_string_or_bytes32(&string_encoded).unwrap(), "USDC");
+
+        let mut bytes32 = [0u8; 32];
+        bytes32[0..3].copy_from_slice(b"MKR");
+        let bytes32_encoded = B256::from(bytes32).abi_encode();
+        assert_eq!(ERC20StateReader::decode_string_or_bytes32(&bytes32_encoded).unwrap(), "MKR");
+
+        assert!(ERC20StateReader::decode_string_or_bytes32(&[0u8; 32]).is_err());
+    }
+
+    #[test]
+    fn test_decode_decimals() {
+        assert_eq!(ERC20StateReader::decode_decimals(&U256::from(6).abi_encode()).unwrap(), 6);
+        assert!(ERC20StateReader::decode_decimals(&U256::from(255).abi_encode()).is_err());
+    }
 }
@@ -58,4 +58,17 @@ impl UniswapV3StateReader {
         let call_return = IUniswapV3Pool::liquidityCall::abi_decode_returns(&call_data_result, false)?;
         Ok(call_return._0)
     }
+
+    /// Returns tick cumulatives for each of `seconds_agos`
+    pub fn observe<DB: DatabaseRef>(db: &DB, env: Env, pool: Address, seconds_agos: Vec<u32>) -> eyre::Result<Vec<i64>> {
+        let call_data_result = evm_call(
+            db,
+            env,
+            pool,
+            IUniswapV3Pool::IUniswapV3PoolCalls::observe(IUniswapV3Pool::observeCall { secondsAgos: seconds_agos }).abi_encode(),
+        )?
+        .0;
+        let call_return = IUniswapV3Pool::observeCall::abi_decode_returns(&call_data_result, false)?;
+        Ok(call_return.tickCumulatives.into_iter().map(|x| x.as_i64()).collect())
+    }
 }
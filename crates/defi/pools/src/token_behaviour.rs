@@ -0,0 +1,156 @@
+use alloy::primitives::{keccak256, Address, Bytes, U256};
+use alloy::sol_types::{SolCall, SolInterface};
+use eyre::{eyre, Result};
+use loom_defi_abi::IERC20;
+use loom_types_entities::{TokenBehaviour, TRANSFER_FEE_DENOMINATOR};
+use revm::db::CacheDB;
+use revm::primitives::{AccountInfo, Bytecode, Env, ExecutionResult, Output, TransactTo, CANCUN, KECCAK_EMPTY};
+use revm::{DatabaseRef, Evm};
+use tracing::debug;
+
+use crate::state_readers::ERC20StateReader;
+
+/// Receiver used for transfer simulations, it should never hold any tokens.
+const PROBE_ADDRESS: Address =
+    Address::new([0x10, 0xad, 0xbe, 0xef, 0x10, 0xad, 0xbe, 0xef, 0x10, 0xad, 0xbe, 0xef, 0x10, 0xad, 0xbe, 0xef, 0x10, 0xad, 0xbe, 0xef]);
+
+/// Part of the pool balance to transfer, 1/1000
+const PROBE_AMOUNT_DIVIDER: u64 = 1000;
+
+const BLACKLIST_SIGNATURES: [&str; 7] = [
+    "isBlacklisted(address)",
+    "isBlackListed(address)",
+    "blacklist(address)",
+    "addBlackList(address)",
+    "isBlocked(address)",
+    "isFrozen(address)",
+    "freeze(address)",
+];
+
+const REBASING_SIGNATURES: [&str; 6] = [
+    "rebase(uint256,int256)",
+    "rebase()",
+    "sharesOf(address)",
+    "scaledBalanceOf(address)",
+    "getSharesByPooledEth(uint256)",
+    "gonsPerFragment()",
+];
+
+pub struct TokenBehaviourDetector {}
+
+impl TokenBehaviourDetector {
+    /// Detect transfer fees by transferring tokens out of the pool and back using revm, rebasing and blacklisting by the token code.
+    pub fn detect<DB: DatabaseRef>(db: &DB, env: Env, token: Address, pool: Address) -> Result<TokenBehaviour> {
+        let code = Self::token_code(db, token)?;
+        let rebasing = Self::code_has_any_selector(&code, &REBASING_SIGNATURES);
+        let blacklistable = Self::code_has_any_selector(&code, &BLACKLIST_SIGNATURES);
+
+        let pool_balance = ERC20StateReader::balance_of(db, env.clone(), token, pool)?;
+        if pool_balance.is_zero() {
+            return Err(eyre!("POOL_HAS_NO_BALANCE"));
+        }
+        let amount = (pool_balance / U256::from(PROBE_AMOUNT_DIVIDER)).max(U256::from(1));
+
+        let mut cache_db = CacheDB::new(db);
+
+        // transactions from accounts with code are rejected, so the pool code is removed for the simulation
+        let pool_info = db.basic_ref(pool).ok().flatten().unwrap_or_default();
+        cache_db.insert_account_info(pool, AccountInfo::new(pool_info.balance, pool_info.nonce, KECCAK_EMPTY, Bytecode::default()));
+
+        // pool -> probe, buy direction
+        let probe_balance_before = ERC20StateReader::balance_of(&cache_db, env.clone(), token, PROBE_ADDRESS)?;
+        Self::transfer(&mut cache_db, env.clone(), token, pool, PROBE_ADDRESS, amount)?;
+        let probe_balance_after = ERC20StateReader::balance_of(&cache_db, env.clone(), token, PROBE_ADDRESS)?;
+        let received = probe_balance_after.saturating_sub(probe_balance_before);
+        if received.is_zero() {
+            return Err(eyre!("NOTHING_RECEIVED"));
+        }
+        let transfer_fee_out = Self::fee_from_amounts(amount, received);
+
+        // probe -> pool, sell direction
+        let pool_balance_before = ERC20StateReader::balance_of(&cache_db, env.clone(), token, pool)?;
+        Self::transfer(&mut cache_db, env.clone(), token, PROBE_ADDRESS, pool, received)?;
+        let pool_balance_after = ERC20StateReader::balance_of(&cache_db, env, token, pool)?;
+        let arrived = pool_balance_after.saturating_sub(pool_balance_before);
+        let transfer_fee_in = Self::fee_from_amounts(received, arrived);
+
+        let behaviour = TokenBehaviour::new(transfer_fee_in, transfer_fee_out, rebasing, blacklistable);
+        debug!(%token, %pool, ?behaviour, "Token behaviour detected");
+
+        Ok(behaviour)
+    }
+
+    fn transfer<DB: DatabaseRef>(db: &mut CacheDB<&DB>, env: Env, token: Address, from: Address, to: Address, amount: U256) -> Result<()> {
+        let mut env = env;
+        env.tx.caller = from;
+        env.tx.transact_to = TransactTo::Call(token);
+        env.tx.value = U256::ZERO;
+        env.tx.data = Bytes::from(IERC20::IERC20Calls::transfer(IERC20::transferCall { to, amount }).abi_encode());
+
+        let mut evm = Evm::builder().with_spec_id(CANCUN).with_db(db).with_env(Box::new(env)).build();
+        let execution_result = evm.transact_commit().map_err(|_| eyre!("TRANSFER_TRANSACT_ERROR"))?;
+
+        match execution_result {
+            ExecutionResult::Success { output: Output::Call(value), .. } => {
+                // some tokens like USDT do not return a value
+                if !value.is_empty() && !IERC20::transferCall::abi_decode_returns(&value, false)?._0 {
+                    return Err(eyre!("TRANSFER_RETURNED_FALSE"));
+                }
+                Ok(())
+            }
+            ExecutionResult::Success { .. } => Ok(()),
+            ExecutionResult::Revert { .. } => Err(eyre!("TRANSFER_REVERTED")),
+            ExecutionResult::Halt { reason, .. } => Err(eyre!("TRANSFER_HALTED {:?}", reason)),
+        }
+    }
+
+    fn token_code<DB: DatabaseRef>(db: &DB, token: Address) -> Result<Bytes> {
+        let account_info =
+            db.basic_ref(token).map_err(|_| eyre!("ERROR_READING_TOKEN_ACCOUNT"))?.ok_or(eyre!("TOKEN_ACCOUNT_NOT_FOUND"))?;
+        let bytecode = match account_info.code {
+            Some(code) => code,
+            None => db.code_by_hash_ref(account_info.code_hash).map_err(|_| eyre!("ERROR_READING_TOKEN_CODE"))?,
+        };
+        Ok(bytecode.original_bytes())
+    }
+
+    /// Selectors are compared with `PUSH4 <selector>` in the bytecode. Proxies are not resolved.
+    fn code_has_any_selector(code: &Bytes, signatures: &[&str]) -> bool {
+        signatures.iter().any(|signature| {
+            let hash = keccak256(signature.as_bytes());
+            let pattern = [0x63, hash[0], hash[1], hash[2], hash[3]];
+            code.windows(pattern.len()).any(|window| window == pattern)
+        })
+    }
+
+    /// Fee in basis points, rounding differences of a few wei are ignored.
+    fn fee_from_amounts(sent: U256, received: U256) -> u32 {
+        if received >= sent || sent.is_zero() {
+            return 0;
+        }
+        let fee = (sent - received) * U256::from(TRANSFER_FEE_DENOMINATOR) / sent;
+        fee.to::<u32>().min(TRANSFER_FEE_DENOMINATOR)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_fee_from_amounts() {
+        assert_eq!(TokenBehaviourDetector::fee_from_amounts(U256::from(1000), U256::from(1000)), 0);
+        assert_eq!(TokenBehaviourDetector::fee_from_amounts(U256::from(1000), U256::from(950)), 500);
+        assert_eq!(TokenBehaviourDetector::fee_from_amounts(U256::from(1_000_000_000u64), U256::from(999_999_999u64)), 0);
+        assert_eq!(TokenBehaviourDetector::fee_from_amounts(U256::from(1000), U256::ZERO), TRANSFER_FEE_DENOMINATOR);
+    }
+
+    #[test]
+    fn test_code_has_any_selector() {
+        let hash = keccak256("isBlacklisted(address)".as_bytes());
+        let code = Bytes::from(vec![0x60, 0x00, 0x63, hash[0], hash[1], hash[2], hash[3], 0x14]);
+
+        assert!(TokenBehaviourDetector::code_has_any_selector(&code, &BLACKLIST_SIGNATURES));
+        assert!(!TokenBehaviourDetector::code_has_any_selector(&code, &REBASING_SIGNATURES));
+    }
+}
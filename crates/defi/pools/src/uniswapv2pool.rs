@@ -78,6 +78,11 @@ impl UniswapV2Pool {
         Self { fee, ..self }
     }
 
+    /// Storage cell with packed reserves, read directly instead of calling `getReserves`
+    pub fn with_reserves_cell(self, reserves_cell: U256) -> Self {
+        Self { reserves_cell: Some(reserves_cell), ..self }
+    }
+
     pub fn get_zero_for_one(token_address_from: Address, token_address_to: Address) -> bool {
         token_address_from < token_address_to
     }
@@ -7,7 +7,7 @@ use alloy_network::Network;
 use alloy_primitives::{Address, Bytes, U256};
 use alloy_provider::Provider;
 use alloy_rpc_types_trace::geth::AccountState;
-use eyre::{eyre, Result};
+use eyre::Result;
 use loom_core_actors::{Accessor, Actor, ActorResult, SharedState, WorkerResult};
 use loom_core_actors_macros::Accessor;
 use loom_core_blockchain::{Blockchain, BlockchainState};
@@ -219,9 +219,8 @@ where
     P: Provider<N> + Send + Sync + Clone + 'static,
     DB: DatabaseRef + Database + DatabaseCommit + Send + Sync + Clone + 'static,
 {
-    fn start_and_wait(&self) -> Result<()> {
-        let rt = tokio::runtime::Runtime::new()?; // we need a different runtime to wait for the result
-        let handler = rt.spawn(preload_market_state(
+    fn start(&self) -> ActorResult {
+        let handler = tokio::task::spawn(preload_market_state(
             self.client.clone(),
             self.copied_accounts.clone(),
             self.new_accounts.clone(),
@@ -229,14 +228,7 @@ where
             self.market_state.clone().unwrap(),
             self.account_nonce_balance_state.clone(),
         ));
-
-        self.wait(Ok(vec![handler]))?;
-        rt.shutdown_background();
-        Ok(())
-    }
-
-    fn start(&self) -> ActorResult {
-        Err(eyre!("NEED_TO_BE_WAITED"))
+        Ok(vec![handler])
     }
 
     fn name(&self) -> &'static str {
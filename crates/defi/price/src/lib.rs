@@ -1,3 +1,7 @@
 mod price_actor;
+mod price_graph;
+mod reference_price;
 
 pub use price_actor::PriceActor;
+pub use price_graph::{calculate_prices, TokenPrice};
+pub use reference_price::{DivergenceAction, ReferencePrice, ReferencePriceConfig, ReferencePriceSource};
@@ -1,133 +1,235 @@
-use std::collections::HashMap;
-use std::marker::PhantomData;
-use std::ops::{Div, Mul};
-use std::time::Duration;
-
-use alloy_network::Network;
-use alloy_primitives::{address, Address, U256};
-use alloy_provider::Provider;
-use loom_core_actors::{Accessor, Actor, ActorResult, SharedState, WorkerResult};
-use loom_core_actors_macros::Accessor;
-use loom_core_blockchain::Blockchain;
-use loom_defi_address_book::TokenAddressEth;
-use loom_defi_pools::protocols::CurveProtocol;
-use loom_defi_pools::CurvePool;
-use loom_types_entities::{Market, Pool};
-use tracing::{debug, error, info};
-
-async fn price_worker<N: Network, P: Provider<N> + Clone + 'static>(client: P, market: SharedState<Market>, once: bool) -> WorkerResult {
-    let curve_tricrypto_usdc = CurveProtocol::new_u256_3_eth_to(client.clone(), address!("7F86Bf177Dd4F3494b841a37e810A34dD56c829B"));
-    let curve_tricrypto_usdt = CurveProtocol::new_u256_3_eth_to(client.clone(), address!("f5f5b97624542d72a9e06f04804bf81baa15e2b4"));
-
-    let mut coins_hash_map: HashMap<Address, CurvePool<P, N>> = HashMap::new();
-
-    let curve_tricrypto_usdc_pool = CurvePool::fetch_pool_data(client.clone(), curve_tricrypto_usdc).await?;
-
-    let curve_tricrypto_usdt_pool = CurvePool::fetch_pool_data(client.clone(), curve_tricrypto_usdt).await?;
-
-    coins_hash_map.insert(TokenAddressEth::USDC, curve_tricrypto_usdc_pool.clone());
-    coins_hash_map.insert(TokenAddressEth::WBTC, curve_tricrypto_usdc_pool.clone());
-    coins_hash_map.insert(TokenAddressEth::USDT, curve_tricrypto_usdt_pool.clone());
-
-    let one_ether = U256::from(10).pow(U256::from(18));
-    let weth_amount = one_ether.mul(U256::from(5));
-
-    match market.read().await.get_token(&TokenAddressEth::WETH) {
-        Some(token) => {
-            token.set_eth_price(Some(one_ether));
-        }
-        _ => {
-            error!("WETH_NOT_FOUND")
+use alloy_primitives::utils::Unit;
+use alloy_primitives::U256;
+use eyre::{eyre, ErrReport};
+use loom_core_actors::{Accessor, Actor, ActorResult, Broadcaster, Consumer, Producer, SharedState, WorkerResult};
+use loom_core_actors_macros::{Accessor, Consumer, Producer};
+use loom_core_blockchain::{Blockchain, BlockchainState};
+use loom_types_entities::{Market, MarketState};
+use loom_types_events::{HealthEvent, MarketEvents, MessageHealthEvent};
+use revm::primitives::Env;
+use revm::DatabaseRef;
+use tokio::sync::broadcast::error::RecvError;
+use tracing::{debug, error, info, warn};
+
+use crate::price_graph::calculate_prices;
+use crate::reference_price::{DivergenceAction, ReferencePrice, ReferencePriceConfig};
+
+const ONE_ETHER: U256 = Unit::ETHER.wei_const();
+
+/// Prices are calculated on a copy of the market, so the market locks are not held during the calculation
+async fn update_prices<DB: DatabaseRef<Error = ErrReport> + Send + Sync + Clone + 'static>(
+    market: &SharedState<Market>,
+    market_state: &SharedState<MarketState<DB>>,
+    env: Env,
+    reference_config: Option<&ReferencePriceConfig>,
+    health_monitor_channel_tx: Option<&Broadcaster<MessageHealthEvent>>,
+) -> usize {
+    let market = market.read().await.clone();
+    let state_db = market_state.read().await.state_db.clone();
+    let reference_config = reference_config.cloned();
+    let health_monitor_channel_tx = health_monitor_channel_tx.cloned();
+
+    match tokio::task::spawn_blocking(move || {
+        calculate_and_set_prices(&market, &state_db, env, reference_config.as_ref(), health_monitor_channel_tx.as_ref())
+    })
+    .await
+    {
+        Ok(updated) => updated,
+        Err(error) => {
+            error!(%error, "Price calculation failed");
+            0
         }
     }
+}
 
-    loop {
-        for (token_address, curve_pool) in coins_hash_map.iter() {
-            debug!("Fetching price of {} at {}", token_address, curve_pool.get_address());
-
-            match curve_pool.fetch_out_amount(TokenAddressEth::WETH, *token_address, weth_amount).await {
-                Ok(out_amount) => {
-                    let price = out_amount.mul(one_ether).div(weth_amount);
-                    info!("Price of ETH in {token_address:#20x} is {price}");
-                    match market.read().await.get_token(token_address) {
-                        Some(tkn) => {
-                            tkn.set_eth_price(Some(price));
-                            debug!("Price is set");
+fn calculate_and_set_prices<DB: DatabaseRef<Error = ErrReport>>(
+    market: &Market,
+    state_db: &DB,
+    env: Env,
+    reference_config: Option<&ReferencePriceConfig>,
+    health_monitor_channel_tx: Option<&Broadcaster<MessageHealthEvent>>,
+) -> usize {
+    let prices = calculate_prices(market, state_db, env.clone());
+
+    // wrapped native token of L2 chains is not LDT::WETH and needs an explicit price
+    if let Some(weth_token) = market.get_token(&market.weth()) {
+        weth_token.set_eth_price(Some(ONE_ETHER));
+    }
+
+    let mut updated = 0;
+    for (token_address, token_price) in prices.iter() {
+        let Some(token) = market.get_token(token_address) else {
+            debug!(address = %token_address, "Priced token not found in market");
+            continue;
+        };
+
+        let mut spot_price = Some(token_price.price);
+
+        if let Some((reference_config, source)) = reference_config.and_then(|c| c.sources.get(token_address).map(|source| (c, source))) {
+            match ReferencePrice::fetch(
+                state_db,
+                env.clone(),
+                source,
+                *token_address,
+                token.get_decimals(),
+                reference_config.max_staleness,
+                market.weth(),
+            ) {
+                Ok(reference_price) => {
+                    let divergence_bps = ReferencePrice::divergence_bps(token_price.price, reference_price);
+                    if divergence_bps > reference_config.max_divergence_bps {
+                        let refused = reference_config.action == DivergenceAction::Refuse;
+                        warn!(token = %token.get_symbol(), spot = %token_price.price, reference = %reference_price, divergence_bps, refused, "Spot price diverges from reference price");
+                        if refused {
+                            spot_price = None;
                         }
-                        _ => {
-                            error!(address=%token_address, "Token not found");
+                        if let Some(health_monitor_channel_tx) = health_monitor_channel_tx {
+                            if let Err(error) = health_monitor_channel_tx.send(MessageHealthEvent::new(HealthEvent::PriceDivergence {
+                                token: *token_address,
+                                spot_price: token_price.price,
+                                reference_price,
+                                divergence_bps,
+                                refused,
+                            })) {
+                                error!(%error, "health_monitor_channel_tx.send")
+                            }
                         }
                     }
                 }
                 Err(error) => {
-                    error!(%error, "fetch_out_amount")
+                    debug!(%error, token = %token.get_symbol(), "Reference price not available");
                 }
             }
         }
 
-        let usdt_price = market.read().await.get_token_or_default(&TokenAddressEth::USDT).get_eth_price();
-        let usdc_price = market.read().await.get_token_or_default(&TokenAddressEth::USDC).get_eth_price();
-
-        let mut usd_price: Option<U256> = None;
-        if let Some(usdc_price) = usdc_price {
-            if let Some(usdt_price) = usdt_price {
-                usd_price = Some((usdc_price + usdt_price) >> 1);
-            }
+        token.set_eth_price(spot_price);
+        if spot_price.is_some() {
+            updated += 1;
         }
+    }
+    updated
+}
 
-        if let Some(usd_price) = usd_price {
-            match market.read().await.get_token(&TokenAddressEth::DAI) {
-                Some(tkn) => {
-                    tkn.set_eth_price(Some(U256::from(10).pow(U256::from(12)).mul(usd_price)));
-                }
-                _ => {
-                    error!("Token {:#20x} not found", TokenAddressEth::DAI);
-                }
+async fn price_worker<DB: DatabaseRef<Error = ErrReport> + Send + Sync + Clone + 'static>(
+    market: SharedState<Market>,
+    market_state: SharedState<MarketState<DB>>,
+    market_events_rx: Option<Broadcaster<MarketEvents>>,
+    health_monitor_channel_tx: Option<Broadcaster<MessageHealthEvent>>,
+    reference_config: Option<ReferencePriceConfig>,
+    once: bool,
+) -> WorkerResult {
+    let mut env = Env::default();
+    env.block.number = U256::from(market_state.read().await.block_number);
+
+    let updated = update_prices(&market, &market_state, env.clone(), reference_config.as_ref(), health_monitor_channel_tx.as_ref()).await;
+    info!(updated, "Token prices calculated");
+
+    if once {
+        return Ok("PriceWorker finished".to_string());
+    }
+
+    let Some(market_events_rx) = market_events_rx else {
+        return Err(eyre!("NO_MARKET_EVENTS_RX"));
+    };
+    let mut market_events_rx = market_events_rx.subscribe();
+
+    loop {
+        match market_events_rx.recv().await {
+            Ok(MarketEvents::BlockHeaderUpdate { block_number, timestamp, base_fee, .. }) => {
+                env.block.number = U256::from(block_number);
+                env.block.timestamp = U256::from(timestamp);
+                env.block.basefee = U256::from(base_fee);
+            }
+            Ok(MarketEvents::BlockStateUpdate { block_hash }) => {
+                let updated =
+                    update_prices(&market, &market_state, env.clone(), reference_config.as_ref(), health_monitor_channel_tx.as_ref()).await;
+                debug!(%block_hash, updated, "Token prices updated");
+            }
+            Ok(_) => {}
+            Err(RecvError::Lagged(lag)) => {
+                warn!(lag, "Market events lagged");
+            }
+            Err(RecvError::Closed) => {
+                error!("Market events channel closed");
+                return Err(eyre!("MARKET_EVENTS_RX_CLOSED"));
             }
         }
-        if once {
-            break;
-        }
-
-        let _ = tokio::time::sleep(Duration::new(60, 0)).await;
     }
-    Ok("PriceWorker finished".to_string())
 }
 
-#[derive(Accessor)]
-pub struct PriceActor<P, N> {
-    client: P,
+/// Calculates ETH prices of market tokens on every block from pools in the market state.
+#[derive(Accessor, Consumer, Producer)]
+pub struct PriceActor<DB: Clone + Send + Sync + 'static> {
     only_once: bool,
+    reference_config: Option<ReferencePriceConfig>,
     #[accessor]
     market: Option<SharedState<Market>>,
-    _n: PhantomData<N>,
+    #[accessor]
+    market_state: Option<SharedState<MarketState<DB>>>,
+    #[consumer]
+    market_events_rx: Option<Broadcaster<MarketEvents>>,
+    #[producer]
+    health_monitor_channel_tx: Option<Broadcaster<MessageHealthEvent>>,
 }
 
-impl<P, N> PriceActor<P, N>
+impl<DB> PriceActor<DB>
 where
-    N: Network,
-    P: Provider<N> + Send + Sync + Clone + 'static,
+    DB: DatabaseRef<Error = ErrReport> + Send + Sync + Clone + 'static,
 {
-    pub fn new(client: P) -> Self {
-        Self { client, only_once: false, market: None, _n: PhantomData }
+    pub fn new() -> Self {
+        Self {
+            only_once: false,
+            reference_config: None,
+            market: None,
+            market_state: None,
+            market_events_rx: None,
+            health_monitor_channel_tx: None,
+        }
     }
 
+    /// Calculate prices once with the current market state and exit.
     pub fn only_once(self) -> Self {
         Self { only_once: true, ..self }
     }
 
-    pub fn on_bc(self, bc: &Blockchain) -> Self {
-        Self { market: Some(bc.market()), ..self }
+    /// Compare spot prices with Chainlink or TWAP reference prices.
+    pub fn with_reference_prices(self, reference_config: ReferencePriceConfig) -> Self {
+        Self { reference_config: Some(reference_config), ..self }
+    }
+
+    pub fn on_bc(self, bc: &Blockchain, state: &BlockchainState<DB>) -> Self {
+        Self {
+            market: Some(bc.market()),
+            market_state: Some(state.market_state()),
+            market_events_rx: Some(bc.market_events_channel()),
+            health_monitor_channel_tx: Some(bc.health_monitor_channel()),
+            ..self
+        }
+    }
+}
+
+impl<DB> Default for PriceActor<DB>
+where
+    DB: DatabaseRef<Error = ErrReport> + Send + Sync + Clone + 'static,
+{
+    fn default() -> Self {
+        Self::new()
     }
 }
 
-impl<P, N> Actor for PriceActor<P, N>
+impl<DB> Actor for PriceActor<DB>
 where
-    N: Network,
-    P: Provider<N> + Send + Sync + Clone + 'static,
+    DB: DatabaseRef<Error = ErrReport> + Send + Sync + Clone + 'static,
 {
     fn start(&self) -> ActorResult {
-        let task = tokio::task::spawn(price_worker(self.client.clone(), self.market.clone().unwrap(), self.only_once));
+        let task = tokio::task::spawn(price_worker(
+            self.market.clone().unwrap(),
+            self.market_state.clone().unwrap(),
+            self.market_events_rx.clone(),
+            self.health_monitor_channel_tx.clone(),
+            self.reference_config.clone(),
+            self.only_once,
+        ));
         Ok(vec![task])
     }
 
@@ -0,0 +1,234 @@
+use std::collections::{HashMap, HashSet};
+
+use alloy_primitives::utils::Unit;
+use alloy_primitives::{Address, U256};
+use eyre::ErrReport;
+use loom_types_entities::{Market, PoolWrapper};
+use revm::primitives::Env;
+use revm::DatabaseRef;
+use tracing::trace;
+
+const ONE_ETHER: U256 = Unit::ETHER.wei_const();
+
+/// Maximum number of hops from WETH to a priced token
+const MAX_HOPS: usize = 3;
+/// Amount in ETH used to calculate the spot price, 0.01 ETH
+const PRICE_PROBE_ETH: U256 = U256::from_limbs([10_000_000_000_000_000, 0, 0, 0]);
+/// Multiplier of the probe used to estimate pool depth from price impact
+const DEPTH_PROBE_MULTIPLIER: u64 = 100;
+/// Pools with less estimated depth in ETH are ignored, 1 ETH
+const MIN_DEPTH_ETH: U256 = ONE_ETHER;
+/// Depth is capped to avoid overflows in the weighted average, 100M ETH
+const MAX_DEPTH_ETH: U256 = U256::from_limbs([0xdcc80cd2e4000000, 0x52b7d2, 0, 0]);
+/// Number of deepest candidates used per token
+const MAX_CANDIDATES: usize = 8;
+/// Candidates deviating more from the weighted median are rejected, in basis points
+const MAX_DEVIATION_BPS: u64 = 300;
+const BPS_DENOMINATOR: u64 = 10000;
+
+/// Price of a token in token units per 1 ETH together with the liquidity that backs it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TokenPrice {
+    pub price: U256,
+    /// Estimated depth of the weakest pool on the path to WETH, in wei
+    pub depth: U256,
+}
+
+impl TokenPrice {
+    pub fn new(price: U256, depth: U256) -> Self {
+        Self { price, depth }
+    }
+}
+
+/// Derives ETH prices of market tokens from the deepest pools connecting them to the wrapped native token of the chain.
+///
+/// Every round quotes the pools of the tokens priced in the previous round with a small amount for the spot price
+/// and a larger one to estimate depth from price impact. A token is repriced when a round finds deeper liquidity.
+/// Prices include pool fees.
+pub fn calculate_prices<DB: DatabaseRef<Error = ErrReport>>(market: &Market, state_db: &DB, env: Env) -> HashMap<Address, TokenPrice> {
+    let weth = market.weth();
+
+    let mut prices: HashMap<Address, TokenPrice> = HashMap::new();
+    prices.insert(weth, TokenPrice::new(ONE_ETHER, U256::MAX));
+
+    // candidates by token and (source token, pool address)
+    let mut candidates: HashMap<Address, HashMap<(Address, Address), TokenPrice>> = HashMap::new();
+    let mut frontier: Vec<Address> = vec![weth];
+
+    for hop in 0..MAX_HOPS {
+        let mut touched: HashSet<Address> = HashSet::new();
+
+        for token_from in frontier.iter() {
+            let Some(price_from) = prices.get(token_from).cloned() else {
+                continue;
+            };
+            let Some(tokens_to) = market.get_token_tokens(token_from) else {
+                continue;
+            };
+
+            for token_to in tokens_to.iter().collect::<HashSet<_>>() {
+                if *token_to == weth {
+                    continue;
+                }
+                let Some(pool_ids) = market.get_token_token_pools(token_from, token_to) else {
+                    continue;
+                };
+                for pool_id in pool_ids.iter() {
+                    if market.is_pool_disabled(pool_id) {
+                        continue;
+                    }
+                    let Some(pool) = market.get_pool(pool_id) else {
+                        continue;
+                    };
+                    if let Some(candidate) = quote_pool(pool, state_db, env.clone(), token_from, token_to, &price_from) {
+                        candidates.entry(*token_to).or_default().insert((*token_from, pool.get_address()), candidate);
+                        touched.insert(*token_to);
+                    }
+                }
+            }
+        }
+
+        frontier = Vec::new();
+        for token in touched {
+            let Some(token_price) = candidates.get(&token).and_then(|c| aggregate_candidates(c.values().cloned().collect())) else {
+                continue;
+            };
+            let is_deeper = prices.get(&token).is_none_or(|current| token_price.depth > current.depth);
+            if is_deeper {
+                trace!(%token, hop, price = %token_price.price, depth = %token_price.depth, "Token price updated");
+                prices.insert(token, token_price);
+                frontier.push(token);
+            }
+        }
+
+        if frontier.is_empty() {
+            break;
+        }
+    }
+
+    prices.remove(&weth);
+    prices
+}
+
+/// Quotes `token_to` price in a pool from the known price of `token_from`.
+fn quote_pool<DB: DatabaseRef<Error = ErrReport>>(
+    pool: &PoolWrapper,
+    state_db: &DB,
+    env: Env,
+    token_from: &Address,
+    token_to: &Address,
+    price_from: &TokenPrice,
+) -> Option<TokenPrice> {
+    let probe_in = price_from.price.checked_mul(PRICE_PROBE_ETH)? / ONE_ETHER;
+    if probe_in.is_zero() {
+        return None;
+    }
+    let (probe_out, _) = pool.calculate_out_amount(state_db, env.clone(), token_from, token_to, probe_in).ok()?;
+    if probe_out.is_zero() {
+        return None;
+    }
+    let price = probe_out.checked_mul(ONE_ETHER)? / PRICE_PROBE_ETH;
+
+    let depth_in = probe_in.checked_mul(U256::from(DEPTH_PROBE_MULTIPLIER))?;
+    let depth_in_eth = PRICE_PROBE_ETH * U256::from(DEPTH_PROBE_MULTIPLIER);
+    let pool_depth = match pool.calculate_out_amount(state_db, env, token_from, token_to, depth_in) {
+        Ok((depth_out, _)) => depth_from_impact(probe_out * U256::from(DEPTH_PROBE_MULTIPLIER), depth_out, depth_in_eth),
+        // the pool cannot handle the larger amount, so it is not deeper than it
+        Err(_) => depth_in_eth,
+    };
+
+    Some(TokenPrice::new(price, pool_depth.min(price_from.depth)))
+}
+
+/// For a constant product pool the price impact is close to amount / reserve, so depth ~ amount / impact.
+fn depth_from_impact(expected_out: U256, actual_out: U256, amount_eth: U256) -> U256 {
+    if expected_out.is_zero() {
+        return U256::ZERO;
+    }
+    let impact_bps = expected_out.saturating_sub(actual_out) * U256::from(BPS_DENOMINATOR) / expected_out;
+    if impact_bps.is_zero() {
+        return MAX_DEPTH_ETH;
+    }
+    (amount_eth * U256::from(BPS_DENOMINATOR) / impact_bps).min(MAX_DEPTH_ETH)
+}
+
+/// Depth weighted average of the deepest candidates after rejecting outliers around the weighted median.
+fn aggregate_candidates(mut candidates: Vec<TokenPrice>) -> Option<TokenPrice> {
+    candidates.retain(|c| c.depth >= MIN_DEPTH_ETH && !c.price.is_zero());
+    if candidates.is_empty() {
+        return None;
+    }
+    for candidate in candidates.iter_mut() {
+        candidate.depth = candidate.depth.min(MAX_DEPTH_ETH);
+    }
+    candidates.sort_by(|a, b| b.depth.cmp(&a.depth));
+    candidates.truncate(MAX_CANDIDATES);
+
+    let median = weighted_median(&candidates)?;
+    let max_deviation = median * U256::from(MAX_DEVIATION_BPS) / U256::from(BPS_DENOMINATOR);
+
+    let mut weighted_sum = U256::ZERO;
+    let mut total_depth = U256::ZERO;
+    for candidate in candidates.iter() {
+        if candidate.price.abs_diff(median) > max_deviation {
+            trace!(price = %candidate.price, %median, "Price candidate rejected");
+            continue;
+        }
+        weighted_sum += candidate.price * candidate.depth;
+        total_depth += candidate.depth;
+    }
+
+    if total_depth.is_zero() {
+        return None;
+    }
+    Some(TokenPrice::new(weighted_sum / total_depth, total_depth))
+}
+
+fn weighted_median(candidates: &[TokenPrice]) -> Option<U256> {
+    let mut sorted: Vec<&TokenPrice> = candidates.iter().collect();
+    sorted.sort_by(|a, b| a.price.cmp(&b.price));
+    let total_depth: U256 = sorted.iter().map(|c| c.depth).sum();
+
+    let mut accumulated = U256::ZERO;
+    for candidate in sorted {
+        accumulated += candidate.depth;
+        if accumulated * U256::from(2) >= total_depth {
+            return Some(candidate.price);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn eth(value: u64) -> U256 {
+        ONE_ETHER * U256::from(value)
+    }
+
+    #[test]
+    fn test_aggregate_rejects_outliers() {
+        let candidates = vec![
+            TokenPrice::new(U256::from(3000), eth(1000)),
+            TokenPrice::new(U256::from(3010), eth(500)),
+            TokenPrice::new(U256::from(4000), eth(400)),
+            TokenPrice::new(U256::from(1000), eth(10)),
+        ];
+        let result = aggregate_candidates(candidates).unwrap();
+
+        assert_eq!(result.depth, eth(1500));
+        assert_eq!(result.price, U256::from(3003));
+    }
+
+    #[test]
+    fn test_aggregate_ignores_shallow_pools() {
+        assert!(aggregate_candidates(vec![TokenPrice::new(U256::from(3000), MIN_DEPTH_ETH - U256::from(1))]).is_none());
+    }
+
+    #[test]
+    fn test_depth_from_impact() {
+        assert_eq!(depth_from_impact(U256::from(1000), U256::from(990), eth(1)), eth(100));
+        assert_eq!(depth_from_impact(U256::from(1000), U256::from(1000), eth(1)), MAX_DEPTH_ETH);
+    }
+}
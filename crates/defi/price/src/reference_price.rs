@@ -0,0 +1,226 @@
+use std::collections::HashMap;
+
+use alloy_primitives::utils::Unit;
+use alloy_primitives::{Address, U256};
+use eyre::{eyre, Result};
+use loom_defi_pools::state_readers::{ChainlinkStateReader, UniswapV3StateReader};
+use loom_defi_uniswap_v3_math::full_math::mul_div;
+use loom_defi_uniswap_v3_math::tick_math::get_sqrt_ratio_at_tick;
+use revm::primitives::Env;
+use revm::DatabaseRef;
+use serde::Deserialize;
+
+const ONE_ETHER: U256 = Unit::ETHER.wei_const();
+const BPS_DENOMINATOR: u64 = 10000;
+
+/// On-chain source of a reference price of a token.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ReferencePriceSource {
+    /// Chainlink token/ETH aggregator
+    ChainlinkEth { aggregator: Address },
+    /// Chainlink token/USD aggregator, converted to ETH with the ETH/USD aggregator
+    ChainlinkUsd { aggregator: Address, eth_usd_aggregator: Address },
+    /// Time weighted average price of a Uniswap V3 pool of the token and the wrapped native token over `period` seconds
+    UniswapV3Twap { pool: Address, period: u32 },
+}
+
+/// What to do with a token which spot price diverges from the reference price.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DivergenceAction {
+    /// Keep the spot price and report the divergence
+    #[default]
+    Flag,
+    /// Unset the price, so swap paths starting with the token are not calculated
+    Refuse,
+}
+
+/// Reference price sources by token address
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct ReferencePriceConfig {
+    pub sources: HashMap<Address, ReferencePriceSource>,
+    pub max_divergence_bps: u64,
+    /// Chainlink answers older than this in seconds are ignored
+    pub max_staleness: u64,
+    pub action: DivergenceAction,
+}
+
+impl Default for ReferencePriceConfig {
+    fn default() -> Self {
+        Self { sources: HashMap::new(), max_divergence_bps: 500, max_staleness: 25 * 3600, action: DivergenceAction::Flag }
+    }
+}
+
+impl ReferencePriceConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_source(mut self, token: Address, source: ReferencePriceSource) -> Self {
+        self.sources.insert(token, source);
+        self
+    }
+
+    pub fn with_max_divergence_bps(self, max_divergence_bps: u64) -> Self {
+        Self { max_divergence_bps, ..self }
+    }
+
+    pub fn with_max_staleness(self, max_staleness: u64) -> Self {
+        Self { max_staleness, ..self }
+    }
+
+    pub fn with_action(self, action: DivergenceAction) -> Self {
+        Self { action, ..self }
+    }
+}
+
+pub struct ReferencePrice {}
+
+impl ReferencePrice {
+    /// Reads the reference price of a token in token units per 1 ETH from the state.
+    /// `weth` is the wrapped native token of the chain, the quote token of TWAP pools.
+    pub fn fetch<DB: DatabaseRef>(
+        db: &DB,
+        env: Env,
+        source: &ReferencePriceSource,
+        token: Address,
+        token_decimals: u8,
+        max_staleness: u64,
+        weth: Address,
+    ) -> Result<U256> {
+        match source {
+            ReferencePriceSource::ChainlinkEth { aggregator } => {
+                let (answer, feed_decimals) = Self::chainlink_answer(db, env, *aggregator, max_staleness)?;
+                Self::price_from_eth_quote(answer, feed_decimals, token_decimals)
+            }
+            ReferencePriceSource::ChainlinkUsd { aggregator, eth_usd_aggregator } => {
+                let (answer, feed_decimals) = Self::chainlink_answer(db, env.clone(), *aggregator, max_staleness)?;
+                let (eth_answer, eth_feed_decimals) = Self::chainlink_answer(db, env, *eth_usd_aggregator, max_staleness)?;
+                Self::price_from_usd_quotes(answer, feed_decimals, eth_answer, eth_feed_decimals, token_decimals)
+            }
+            ReferencePriceSource::UniswapV3Twap { pool, period } => Self::uniswap_v3_twap(db, env, *pool, *period, token, weth),
+        }
+    }
+
+    /// Divergence of the spot price from the reference price in basis points
+    pub fn divergence_bps(spot_price: U256, reference_price: U256) -> u64 {
+        if reference_price.is_zero() {
+            return u64::MAX;
+        }
+        let divergence = spot_price.abs_diff(reference_price) * U256::from(BPS_DENOMINATOR) / reference_price;
+        divergence.saturating_to::<u64>()
+    }
+
+    fn chainlink_answer<DB: DatabaseRef>(db: &DB, env: Env, aggregator: Address, max_staleness: u64) -> Result<(U256, u8)> {
+        let timestamp = env.block.timestamp.saturating_to::<u64>();
+        let decimals = ChainlinkStateReader::decimals(db, env.clone(), aggregator)?;
+        let (answer, updated_at) = ChainlinkStateReader::latest_answer(db, env, aggregator)?;
+        // block timestamp is not known before the first block header
+        if timestamp != 0 && timestamp.saturating_sub(updated_at) > max_staleness {
+            return Err(eyre!("CHAINLINK_ANSWER_STALE"));
+        }
+        Ok((answer, decimals))
+    }
+
+    /// `answer` is the price of one token in ETH with `feed_decimals`
+    fn price_from_eth_quote(answer: U256, feed_decimals: u8, token_decimals: u8) -> Result<U256> {
+        Ok(mul_div(U256::from(10).pow(U256::from(token_decimals)), U256::from(10).pow(U256::from(feed_decimals)), answer)?)
+    }
+
+    /// `answer` is the price of one token and `eth_answer` of one ETH in USD
+    fn price_from_usd_quotes(answer: U256, feed_decimals: u8, eth_answer: U256, eth_feed_decimals: u8, token_decimals: u8) -> Result<U256> {
+        let numerator = eth_answer * U256::from(10).pow(U256::from(feed_decimals));
+        let denominator = answer * U256::from(10).pow(U256::from(eth_feed_decimals));
+        Ok(mul_div(numerator, U256::from(10).pow(U256::from(token_decimals)), denominator)?)
+    }
+
+    fn uniswap_v3_twap<DB: DatabaseRef>(db: &DB, env: Env, pool: Address, period: u32, token: Address, weth: Address) -> Result<U256> {
+        if period == 0 {
+            return Err(eyre!("TWAP_PERIOD_IS_ZERO"));
+        }
+        let token0 = UniswapV3StateReader::token0(db, env.clone(), pool)?;
+        let token1 = UniswapV3StateReader::token1(db, env.clone(), pool)?;
+        let weth_is_token0 = if token0 == weth && token1 == token {
+            true
+        } else if token1 == weth && token0 == token {
+            false
+        } else {
+            return Err(eyre!("TWAP_POOL_NOT_TOKEN_WETH_PAIR"));
+        };
+
+        let tick_cumulatives = UniswapV3StateReader::observe(db, env, pool, vec![period, 0])?;
+        if tick_cumulatives.len() != 2 {
+            return Err(eyre!("BAD_OBSERVE_RESULT"));
+        }
+        let tick = Self::mean_tick(tick_cumulatives[0], tick_cumulatives[1], period)?;
+
+        Self::quote_at_tick(tick, ONE_ETHER, weth_is_token0)
+    }
+
+    /// Mean tick rounded to negative infinity like in the Uniswap oracle library
+    fn mean_tick(tick_cumulative_start: i64, tick_cumulative_end: i64, period: u32) -> Result<i32> {
+        let delta = tick_cumulative_end - tick_cumulative_start;
+        let period = period as i64;
+        let mut tick = delta / period;
+        if delta < 0 && delta % period != 0 {
+            tick -= 1;
+        }
+        Ok(i32::try_from(tick)?)
+    }
+
+    /// Amount of the other token for `base_amount` of the base token at `tick`
+    fn quote_at_tick(tick: i32, base_amount: U256, base_is_token0: bool) -> Result<U256> {
+        let sqrt_ratio = get_sqrt_ratio_at_tick(tick)?;
+        let quote = if sqrt_ratio <= U256::from(u128::MAX) {
+            let ratio_x192 = sqrt_ratio * sqrt_ratio;
+            if base_is_token0 {
+                mul_div(ratio_x192, base_amount, U256::from(1) << 192)?
+            } else {
+                mul_div(U256::from(1) << 192, base_amount, ratio_x192)?
+            }
+        } else {
+            let ratio_x128 = mul_div(sqrt_ratio, sqrt_ratio, U256::from(1) << 64)?;
+            if base_is_token0 {
+                mul_div(ratio_x128, base_amount, U256::from(1) << 128)?
+            } else {
+                mul_div(U256::from(1) << 128, base_amount, ratio_x128)?
+            }
+        };
+        Ok(quote)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_divergence_bps() {
+        assert_eq!(ReferencePrice::divergence_bps(U256::from(1050), U256::from(1000)), 500);
+        assert_eq!(ReferencePrice::divergence_bps(U256::from(900), U256::from(1000)), 1000);
+        assert_eq!(ReferencePrice::divergence_bps(U256::from(900), U256::ZERO), u64::MAX);
+    }
+
+    #[test]
+    fn test_chainlink_prices() {
+        // 1 USDC = 0.0005 ETH, 2000 USDC per ETH
+        let price = ReferencePrice::price_from_eth_quote(U256::from(500_000_000_000_000u64), 18, 6).unwrap();
+        assert_eq!(price, U256::from(2_000_000_000u64));
+
+        // USDC at 1 USD and ETH at 2000 USD with 8 decimals feeds
+        let price = ReferencePrice::price_from_usd_quotes(U256::from(100_000_000u64), 8, U256::from(200_000_000_000u64), 8, 6).unwrap();
+        assert_eq!(price, U256::from(2_000_000_000u64));
+    }
+
+    #[test]
+    fn test_twap() {
+        assert_eq!(ReferencePrice::mean_tick(0, 600, 60).unwrap(), 10);
+        assert_eq!(ReferencePrice::mean_tick(0, -601, 60).unwrap(), -11);
+
+        assert_eq!(ReferencePrice::quote_at_tick(0, ONE_ETHER, true).unwrap(), ONE_ETHER);
+        let quote = ReferencePrice::quote_at_tick(6932, ONE_ETHER, true).unwrap();
+        assert!(quote > ONE_ETHER * U256::from(19) / U256::from(10) && quote < ONE_ETHER * U256::from(21) / U256::from(10));
+    }
+}
@@ -10,7 +10,10 @@ use alloy::{
 use eyre::eyre;
 use lazy_static::lazy_static;
 use loom_types_blockchain::GethStateUpdate;
-use revm::primitives::{Account, Env, ExecutionResult, HaltReason, Output, ResultAndState, TransactTo, CANCUN};
+use revm::primitives::{
+    Account, AccountInfo, Env, EvmState, EvmStorageSlot, ExecutionResult, HaltReason, Output, ResultAndState, TransactTo, CANCUN,
+    KECCAK_EMPTY,
+};
 use revm::{Database, DatabaseCommit, DatabaseRef, Evm};
 use std::collections::BTreeMap;
 use std::fmt::Debug;
@@ -169,6 +172,64 @@ pub fn convert_evm_result_to_rpc(
     Ok((logs, state_update))
 }
 
+fn account_code<DB: DatabaseRef>(state_db: &DB, info: &AccountInfo) -> Option<Bytes> {
+    let code = match &info.code {
+        Some(code) => code.original_bytes(),
+        None if info.code_hash != KECCAK_EMPTY => state_db.code_by_hash_ref(info.code_hash).ok()?.original_bytes(),
+        None => return None,
+    };
+    (!code.is_empty()).then_some(code)
+}
+
+/// Pre and post state of the accounts changed by a transaction in the format of the geth prestate tracer in diff mode.
+/// `state_db` must be the state the transaction was executed on.
+pub fn evm_state_diff<DB: DatabaseRef>(state_db: &DB, state: &EvmState) -> eyre::Result<(GethStateUpdate, GethStateUpdate)> {
+    let mut pre = GethStateUpdate::default();
+    let mut post = GethStateUpdate::default();
+
+    for (address, account) in state.iter() {
+        if !account.is_touched() {
+            continue;
+        }
+        let pre_info = state_db.basic_ref(*address).map_err(|_| eyre!("ACCOUNT_NOT_LOADED"))?;
+        let changed_storage: Vec<(&U256, &EvmStorageSlot)> = account.storage.iter().filter(|(_, slot)| slot.is_changed()).collect();
+
+        let pre_code = pre_info.as_ref().and_then(|info| account_code(state_db, info));
+        let post_code = account_code(state_db, &account.info);
+        let balance_changed = pre_info.as_ref().is_none_or(|info| info.balance != account.info.balance);
+        let nonce_changed = pre_info.as_ref().is_none_or(|info| info.nonce != account.info.nonce);
+        let code_changed = pre_code != post_code;
+
+        if !balance_changed && !nonce_changed && !code_changed && changed_storage.is_empty() && !account.is_selfdestructed() {
+            continue;
+        }
+
+        if let Some(pre_info) = pre_info {
+            let storage = changed_storage.iter().map(|(slot, value)| ((**slot).into(), value.original_value.into())).collect();
+            pre.insert(*address, AccountState { balance: Some(pre_info.balance), code: pre_code, nonce: Some(pre_info.nonce), storage });
+        }
+
+        if !account.is_selfdestructed() {
+            let storage = changed_storage
+                .iter()
+                .filter(|(_, value)| !value.present_value.is_zero())
+                .map(|(slot, value)| ((**slot).into(), value.present_value.into()))
+                .collect();
+            post.insert(
+                *address,
+                AccountState {
+                    balance: balance_changed.then_some(account.info.balance),
+                    code: if code_changed { post_code } else { None },
+                    nonce: nonce_changed.then_some(account.info.nonce),
+                    storage,
+                },
+            );
+        }
+    }
+
+    Ok((pre, post))
+}
+
 pub fn revert_bytes_to_string(bytes: &Bytes) -> String {
     if bytes.len() < 4 {
         return format!("{:?}", bytes);
@@ -2,7 +2,7 @@ use alloy::consensus::Transaction as TransactionTrait;
 use alloy::primitives::{Address, U256};
 use alloy::rpc::types::{Header, Transaction};
 use lazy_static::lazy_static;
-use revm::primitives::{BlobExcessGasAndPrice, BlockEnv, Env, TransactTo, TxEnv};
+use revm::primitives::{AuthorizationList, BlobExcessGasAndPrice, BlockEnv, Env, TransactTo, TxEnv};
 
 lazy_static! {
     static ref COINBASE: Address = "0x1f9090aaE28b8a3dCeaDf281B0F12828e676c326".parse().unwrap();
@@ -48,9 +48,9 @@ pub fn evm_env_from_tx<T: Into<Transaction>>(tx: T, block_header: &Header) -> En
             chain_id: tx.chain_id(),
             access_list: Vec::new(),
             gas_priority_fee: tx.max_priority_fee_per_gas().map(|x| U256::from(x)),
-            blob_hashes: Vec::new(),
-            max_fee_per_blob_gas: None,
-            authorization_list: None,
+            blob_hashes: tx.blob_versioned_hashes().map(|hashes| hashes.to_vec()).unwrap_or_default(),
+            max_fee_per_blob_gas: tx.max_fee_per_blob_gas().map(U256::from),
+            authorization_list: tx.authorization_list().map(|list| AuthorizationList::Signed(list.to_vec())),
         },
     }
 }
@@ -4,7 +4,7 @@ use alloy::providers::ext::AnvilApi;
 use alloy::{network::Network, primitives::Address, providers::Provider, sol_types::private::U256};
 use eyre::{eyre, Result};
 use loom_defi_abi::IERC20::IERC20Instance;
-use loom_defi_address_book::TokenAddressEth;
+use loom_defi_address_book::{TokenAddressBase, TokenAddressEth};
 use loom_evm_db::LoomDBType;
 use loom_node_debug_provider::DebugProviderExt;
 use tracing::error;
@@ -18,6 +18,8 @@ impl BalanceCheater {
             TokenAddressEth::WETH => Ok(calc_hashmap_cell(U256::from(3u32), U256::from_be_slice(owner.as_slice()))),
             TokenAddressEth::USDT => Ok(calc_hashmap_cell(U256::from(2u32), U256::from_be_slice(owner.as_slice()))),
             TokenAddressEth::USDC => Ok(calc_hashmap_cell(U256::from(3u32), U256::from_be_slice(owner.as_slice()))),
+            // WETH9 predeploy of the OP stack chains
+            TokenAddressBase::WETH => Ok(calc_hashmap_cell(U256::from(3u32), U256::from_be_slice(owner.as_slice()))),
             TokenAddressEth::WSTETH => Ok(calc_hashmap_cell(U256::from(0u32), U256::from_be_slice(owner.as_slice()))),
             TokenAddressEth::STETH => Ok(calc_hashmap_cell(U256::from(3u32), U256::from_be_slice(owner.as_slice()))),
             _ => Err(eyre!("ADDRESS_CELL_UNKNOWN")),
@@ -8,15 +8,17 @@ use alloy_rpc_types::{TransactionInput, TransactionRequest};
 use eyre::{eyre, Result};
 use influxdb::{Timestamp, WriteQuery};
 use std::marker::PhantomData;
-use tokio::sync::broadcast::error::RecvError;
+use tokio_util::sync::CancellationToken;
 use tracing::{debug, error, info, trace};
 
 use loom_core_blockchain::{Blockchain, Strategy};
 use loom_evm_utils::NWETH;
+use loom_types_blockchain::Mempool;
+use loom_types_entities::tips::bid_tips_pct;
 use loom_types_entities::{EstimationError, Swap, SwapEncoder};
 
-use loom_core_actors::{subscribe, Actor, ActorResult, Broadcaster, Consumer, Producer, WorkerResult};
-use loom_core_actors_macros::{Consumer, Producer};
+use loom_core_actors::{subscribe, Accessor, Actor, ActorResult, Broadcaster, Mpsc, MpscConsumer, Producer, SharedState, WorkerResult};
+use loom_core_actors_macros::{Accessor, Consumer, Producer};
 use loom_evm_db::{AlloyDB, DatabaseLoomExt};
 use loom_evm_utils::evm::evm_access_list;
 use loom_evm_utils::evm_env::env_for_block;
@@ -51,10 +53,12 @@ where
     let (to, call_value, call_data, _) = swap_encoder.encode(
         estimate_request.swap.clone(),
         estimate_request.tips_pct,
+        estimate_request.max_tips_pct,
         Some(estimate_request.tx_compose.next_block_number),
         None,
         Some(tx_signer.address()),
         Some(estimate_request.tx_compose.eth_balance),
+        estimate_request.funding,
     )?;
 
     let tx_request = TransactionRequest {
@@ -157,10 +161,12 @@ where
     let (to, call_value, call_data, tips_vec) = match swap_encoder.encode(
         estimate_request.swap.clone(),
         estimate_request.tips_pct,
+        estimate_request.max_tips_pct,
         Some(estimate_request.tx_compose.next_block_number),
         Some(gas_cost),
         Some(tx_signer.address()),
         Some(estimate_request.tx_compose.eth_balance),
+        estimate_request.funding,
     ) {
         Ok((to, call_value, call_data, tips_vec)) => (to, call_value, call_data, tips_vec),
         Err(error) => {
@@ -206,7 +212,11 @@ where
     };
 
     let sign_request = MessageSwapCompose::ready(SwapComposeData {
-        tx_compose: TxComposeData { tx_bundle: Some(tx_with_state), ..estimate_request.tx_compose },
+        tx_compose: TxComposeData {
+            tx_bundle: Some(tx_with_state),
+            tips_pct: Some(bid_tips_pct(total_tips, profit_eth, gas_cost)),
+            ..estimate_request.tx_compose
+        },
         poststate: Some(db),
         tips: Some(total_tips + gas_cost),
         ..estimate_request
@@ -238,24 +248,36 @@ where
 async fn estimator_worker<N, DB>(
     client: Option<impl Provider<N> + Clone + 'static>,
     encoder: impl SwapEncoder + Send + Sync + Clone + 'static,
-    compose_channel_rx: Broadcaster<MessageSwapCompose<DB>>,
+    mempool: Option<SharedState<Mempool>>,
+    estimate_channel_rx: Mpsc<MessageSwapCompose<DB>>,
     compose_channel_tx: Broadcaster<MessageSwapCompose<DB>>,
     health_monitor_channel_tx: Option<Broadcaster<MessageHealthEvent>>,
     influxdb_write_channel_tx: Option<Broadcaster<WriteQuery>>,
+    shutdown_token: CancellationToken,
 ) -> WorkerResult
 where
     N: Network,
     DB: DatabaseRef + DatabaseLoomExt + Send + Sync + Clone + 'static,
 {
-    subscribe!(compose_channel_rx);
+    subscribe!(estimate_channel_rx);
 
     loop {
         tokio::select! {
-            msg = compose_channel_rx.recv() => {
-                let compose_request_msg : Result<MessageSwapCompose<DB>, RecvError> = msg;
+            _ = shutdown_token.cancelled() => {
+                break Ok("EVM estimator stopped".to_string());
+            }
+            msg = estimate_channel_rx.recv() => {
+                let compose_request_msg : Option<MessageSwapCompose<DB>> = msg;
                 match compose_request_msg {
-                    Ok(compose_request) =>{
+                    Some(compose_request) =>{
                         if let SwapComposeMessage::Estimate(estimate_request) = compose_request.inner {
+                            // the stuffing tx may have been replaced while the request was routed
+                            if let Some(mempool) = &mempool {
+                                if mempool.read().await.is_any_replaced(&estimate_request.tx_compose.stuffing_txs_hashes) {
+                                    debug!(stuffing = ?estimate_request.tx_compose.stuffing_txs_hashes, "Stuffing tx replaced, estimate request dropped");
+                                    continue;
+                                }
+                            }
                             let compose_channel_tx_cloned = compose_channel_tx.clone();
                             let encoder_cloned = encoder.clone();
                             let client_cloned = client.clone();
@@ -277,19 +299,21 @@ where
                             );
                         }
                     }
-                    Err(e)=>{error!("{e}")}
+                    None => break Err(eyre!("ESTIMATE_CHANNEL_CLOSED")),
                 }
             }
         }
     }
 }
 
-#[derive(Consumer, Producer)]
+#[derive(Accessor, Consumer, Producer)]
 pub struct EvmEstimatorActor<P, N, E, DB: Clone + Send + Sync + 'static> {
     encoder: E,
     client: Option<P>,
+    #[accessor]
+    mempool: Option<SharedState<Mempool>>,
     #[consumer]
-    compose_channel_rx: Option<Broadcaster<MessageSwapCompose<DB>>>,
+    estimate_channel_rx: Option<Mpsc<MessageSwapCompose<DB>>>,
     #[producer]
     compose_channel_tx: Option<Broadcaster<MessageSwapCompose<DB>>>,
     #[producer]
@@ -310,8 +334,9 @@ where
         Self {
             encoder,
             client: None,
+            mempool: None,
             compose_channel_tx: None,
-            compose_channel_rx: None,
+            estimate_channel_rx: None,
             health_monitor_channel_tx: None,
             influxdb_write_channel_tx: None,
             _n: PhantomData::<N>,
@@ -322,8 +347,9 @@ where
         Self {
             encoder,
             client,
+            mempool: None,
             compose_channel_tx: None,
-            compose_channel_rx: None,
+            estimate_channel_rx: None,
             health_monitor_channel_tx: None,
             influxdb_write_channel_tx: None,
             _n: PhantomData::<N>,
@@ -332,8 +358,9 @@ where
 
     pub fn on_bc(self, bc: &Blockchain, strategy: &Strategy<DB>) -> Self {
         Self {
+            mempool: Some(bc.mempool()),
             compose_channel_tx: Some(strategy.swap_compose_channel()),
-            compose_channel_rx: Some(strategy.swap_compose_channel()),
+            estimate_channel_rx: Some(strategy.estimate_channel()),
             health_monitor_channel_tx: Some(bc.health_monitor_channel()),
             influxdb_write_channel_tx: Some(bc.influxdb_write_channel()),
             ..self
@@ -349,13 +376,19 @@ where
     DB: DatabaseRef + DatabaseLoomExt + Send + Sync + Clone,
 {
     fn start(&self) -> ActorResult {
+        self.start_until(CancellationToken::new())
+    }
+
+    fn start_until(&self, shutdown_token: CancellationToken) -> ActorResult {
         let task = tokio::task::spawn(estimator_worker(
             self.client.clone(),
             self.encoder.clone(),
-            self.compose_channel_rx.clone().unwrap(),
+            self.mempool.clone(),
+            self.estimate_channel_rx.clone().unwrap(),
             self.compose_channel_tx.clone().unwrap(),
             self.health_monitor_channel_tx.clone(),
             self.influxdb_write_channel_tx.clone(),
+            shutdown_token,
         ));
         Ok(vec![task])
     }
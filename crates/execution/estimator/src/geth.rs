@@ -8,15 +8,16 @@ use alloy_primitives::{Bytes, TxKind, U256};
 use alloy_provider::Provider;
 use alloy_rpc_types::{TransactionInput, TransactionRequest};
 use eyre::{eyre, Result};
-use tokio::sync::broadcast::error::RecvError;
+use tokio_util::sync::CancellationToken;
 use tracing::{debug, error, info};
 
 use loom_core_blockchain::Strategy;
 use loom_evm_utils::NWETH;
+use loom_types_entities::tips::bid_tips_pct;
 use loom_types_entities::{Swap, SwapEncoder};
 
 use loom_broadcast_flashbots::Flashbots;
-use loom_core_actors::{subscribe, Actor, ActorResult, Broadcaster, Consumer, Producer, WorkerResult};
+use loom_core_actors::{subscribe, Actor, ActorResult, Broadcaster, Mpsc, MpscConsumer, Producer, WorkerResult};
 use loom_core_actors_macros::{Consumer, Producer};
 use loom_types_blockchain::LoomTx;
 use loom_types_events::{MessageSwapCompose, SwapComposeData, SwapComposeMessage, TxComposeData, TxState};
@@ -44,10 +45,12 @@ async fn estimator_task<P: Provider<Ethereum> + Send + Sync + Clone + 'static, D
     let (to, _, call_data, _) = swap_encoder.encode(
         estimate_request.swap.clone(),
         estimate_request.tips_pct,
+        estimate_request.max_tips_pct,
         Some(estimate_request.tx_compose.next_block_number),
         Some(gas_cost),
         Some(tx_signer.address()),
         Some(estimate_request.tx_compose.eth_balance),
+        estimate_request.funding,
     )?;
 
     let mut tx_request = TransactionRequest {
@@ -123,10 +126,12 @@ async fn estimator_task<P: Provider<Ethereum> + Send + Sync + Clone + 'static, D
                             _ => swap_encoder.encode(
                                 estimate_request.swap.clone(),
                                 estimate_request.tips_pct,
+                                estimate_request.max_tips_pct,
                                 Some(estimate_request.tx_compose.next_block_number),
                                 Some(gas_cost),
                                 Some(tx_signer.address()),
                                 Some(estimate_request.tx_compose.eth_balance),
+                                estimate_request.funding,
                             )?,
                         };
 
@@ -153,7 +158,11 @@ async fn estimator_task<P: Provider<Ethereum> + Send + Sync + Clone + 'static, D
                         let total_tips = tips_vec.into_iter().map(|v| v.tips).sum();
 
                         let sign_request = MessageSwapCompose::ready(SwapComposeData {
-                            tx_compose: TxComposeData { gas, ..estimate_request.tx_compose },
+                            tx_compose: TxComposeData {
+                                gas,
+                                tips_pct: Some(bid_tips_pct(total_tips, profit_eth, gas_cost)),
+                                ..estimate_request.tx_compose
+                            },
                             tips: Some(total_tips + gas_cost),
                             ..estimate_request
                         });
@@ -200,17 +209,21 @@ async fn estimator_task<P: Provider<Ethereum> + Send + Sync + Clone + 'static, D
 async fn estimator_worker<P: Provider<Ethereum> + Send + Sync + Clone + 'static, DB: DatabaseRef + Send + Sync + Clone>(
     client: Arc<Flashbots<P>>,
     encoder: impl SwapEncoder + Send + Sync + Clone + 'static,
-    compose_channel_rx: Broadcaster<MessageSwapCompose<DB>>,
+    estimate_channel_rx: Mpsc<MessageSwapCompose<DB>>,
     compose_channel_tx: Broadcaster<MessageSwapCompose<DB>>,
+    shutdown_token: CancellationToken,
 ) -> WorkerResult {
-    subscribe!(compose_channel_rx);
+    subscribe!(estimate_channel_rx);
 
     loop {
         tokio::select! {
-            msg = compose_channel_rx.recv() => {
-                let compose_request_msg : Result<MessageSwapCompose<DB>, RecvError> = msg;
+            _ = shutdown_token.cancelled() => {
+                break Ok("Geth estimator stopped".to_string());
+            }
+            msg = estimate_channel_rx.recv() => {
+                let compose_request_msg : Option<MessageSwapCompose<DB>> = msg;
                 match compose_request_msg {
-                    Ok(compose_request) =>{
+                    Some(compose_request) =>{
                         if let SwapComposeMessage::Estimate(estimate_request) = compose_request.inner {
                             let compose_channel_tx_cloned = compose_channel_tx.clone();
                             let client_cloned = client.clone();
@@ -228,7 +241,7 @@ async fn estimator_worker<P: Provider<Ethereum> + Send + Sync + Clone + 'static,
                             );
                         }
                     }
-                    Err(e)=>{error!("{e}")}
+                    None => break Err(eyre!("ESTIMATE_CHANNEL_CLOSED")),
                 }
             }
         }
@@ -240,7 +253,7 @@ pub struct GethEstimatorActor<P, E, DB: Clone + Send + Sync + 'static> {
     client: Arc<Flashbots<P>>,
     encoder: E,
     #[consumer]
-    compose_channel_rx: Option<Broadcaster<MessageSwapCompose<DB>>>,
+    estimate_channel_rx: Option<Mpsc<MessageSwapCompose<DB>>>,
     #[producer]
     compose_channel_tx: Option<Broadcaster<MessageSwapCompose<DB>>>,
 }
@@ -252,15 +265,11 @@ where
     DB: DatabaseRef + Send + Sync + Clone,
 {
     pub fn new(client: Arc<Flashbots<P>>, encoder: E) -> Self {
-        Self { client, encoder, compose_channel_tx: None, compose_channel_rx: None }
+        Self { client, encoder, compose_channel_tx: None, estimate_channel_rx: None }
     }
 
     pub fn on_bc(self, strategy: &Strategy<DB>) -> Self {
-        Self {
-            compose_channel_tx: Some(strategy.swap_compose_channel()),
-            compose_channel_rx: Some(strategy.swap_compose_channel()),
-            ..self
-        }
+        Self { compose_channel_tx: Some(strategy.swap_compose_channel()), estimate_channel_rx: Some(strategy.estimate_channel()), ..self }
     }
 }
 
@@ -271,11 +280,16 @@ where
     DB: DatabaseRef + Send + Sync + Clone,
 {
     fn start(&self) -> ActorResult {
+        self.start_until(CancellationToken::new())
+    }
+
+    fn start_until(&self, shutdown_token: CancellationToken) -> ActorResult {
         let task = tokio::task::spawn(estimator_worker(
             self.client.clone(),
             self.encoder.clone(),
-            self.compose_channel_rx.clone().unwrap(),
+            self.estimate_channel_rx.clone().unwrap(),
             self.compose_channel_tx.clone().unwrap(),
+            shutdown_token,
         ));
         Ok(vec![task])
     }
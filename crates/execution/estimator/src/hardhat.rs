@@ -5,10 +5,10 @@ use alloy_provider::Provider;
 use alloy_rpc_types::{TransactionInput, TransactionRequest};
 use eyre::{eyre, Result};
 use revm::DatabaseRef;
-use tokio::sync::broadcast::error::RecvError;
+use tokio_util::sync::CancellationToken;
 use tracing::{error, info};
 
-use loom_core_actors::{subscribe, Actor, ActorResult, Broadcaster, Consumer, Producer, WorkerResult};
+use loom_core_actors::{subscribe, Actor, ActorResult, Broadcaster, Mpsc, MpscConsumer, Producer, WorkerResult};
 use loom_core_actors_macros::{Consumer, Producer};
 use loom_node_debug_provider::DebugProviderExt;
 use loom_types_entities::SwapEncoder;
@@ -16,17 +16,21 @@ use loom_types_events::{MessageSwapCompose, SwapComposeData, SwapComposeMessage,
 
 async fn estimator_worker<DB: DatabaseRef + Send + Sync + Clone>(
     swap_encoder: impl SwapEncoder,
-    compose_channel_rx: Broadcaster<MessageSwapCompose<DB>>,
+    estimate_channel_rx: Mpsc<MessageSwapCompose<DB>>,
     compose_channel_tx: Broadcaster<MessageSwapCompose<DB>>,
+    shutdown_token: CancellationToken,
 ) -> WorkerResult {
-    subscribe!(compose_channel_rx);
+    subscribe!(estimate_channel_rx);
 
     loop {
         tokio::select! {
-                    msg = compose_channel_rx.recv() => {
-                        let compose_request_msg : Result<MessageSwapCompose<DB>, RecvError> = msg;
+            _ = shutdown_token.cancelled() => {
+                break Ok("Hardhat estimator stopped".to_string());
+            }
+                    msg = estimate_channel_rx.recv() => {
+                        let compose_request_msg : Option<MessageSwapCompose<DB>> = msg;
                         match compose_request_msg {
-                            Ok(compose_request) =>{
+                            Some(compose_request) =>{
                                 if let SwapComposeMessage::Estimate(estimate_request) = compose_request.inner {
                                     info!("Hardhat estimation");
                                     let token_in = estimate_request.swap.get_first_token().cloned().ok_or(eyre!("NO_TOKEN"))?;
@@ -45,10 +49,12 @@ async fn estimator_worker<DB: DatabaseRef + Send + Sync + Clone>(
                                     let (to, _call_value, call_data, _) = swap_encoder.encode(
                                         estimate_request.swap.clone(),
                                         estimate_request.tips_pct,
+                                        estimate_request.max_tips_pct,
                                         Some(estimate_request.tx_compose.next_block_number),
                                         Some(gas_cost),
                                         Some(tx_signer.address()),
                                         Some(estimate_request.tx_compose.eth_balance),
+                                        estimate_request.funding,
                                     )?;
 
                                     let tx_request = TransactionRequest {
@@ -94,7 +100,7 @@ async fn estimator_worker<DB: DatabaseRef + Send + Sync + Clone>(
                                     }
                                 }
                             }
-                    Err(e)=>{error!("{e}")}
+                    None => break Err(eyre!("ESTIMATE_CHANNEL_CLOSED")),
                 }
             }
         }
@@ -107,7 +113,7 @@ pub struct HardhatEstimatorActor<P, E, DB: Send + Sync + Clone + 'static> {
     client: P,
     encoder: E,
     #[consumer]
-    compose_channel_rx: Option<Broadcaster<MessageSwapCompose<DB>>>,
+    estimate_channel_rx: Option<Mpsc<MessageSwapCompose<DB>>>,
     #[producer]
     compose_channel_tx: Option<Broadcaster<MessageSwapCompose<DB>>>,
 }
@@ -119,7 +125,7 @@ where
     DB: DatabaseRef + Send + Sync + Clone,
 {
     pub fn new(client: P, encoder: E) -> Self {
-        Self { client, encoder, compose_channel_tx: None, compose_channel_rx: None }
+        Self { client, encoder, compose_channel_tx: None, estimate_channel_rx: None }
     }
 }
 
@@ -130,10 +136,15 @@ where
     DB: DatabaseRef + Send + Sync + Clone,
 {
     fn start(&self) -> ActorResult {
+        self.start_until(CancellationToken::new())
+    }
+
+    fn start_until(&self, shutdown_token: CancellationToken) -> ActorResult {
         let task = tokio::task::spawn(estimator_worker(
             self.encoder.clone(),
-            self.compose_channel_rx.clone().unwrap(),
+            self.estimate_channel_rx.clone().unwrap(),
             self.compose_channel_tx.clone().unwrap(),
+            shutdown_token,
         ));
         Ok(vec![task])
     }
@@ -7,6 +7,7 @@ use crate::pool_abi_encoder::ProtocolABIEncoderV2;
 use crate::pool_opcodes_encoder::ProtocolSwapOpcodesEncoderV2;
 use crate::{SwapLineEncoder, SwapStepEncoder, DEFAULT_VIRTUAL_ADDRESS};
 use loom_types_blockchain::MulticallerCalls;
+use loom_types_entities::bidding::{SlopesBidder, TipsBidder};
 use loom_types_entities::Swap;
 
 pub trait MulticallerEncoder {
@@ -19,11 +20,17 @@ pub trait MulticallerEncoder {
 pub struct MulticallerSwapEncoder {
     pub multicaller_address: Address,
     pub swap_step_encoder: SwapStepEncoder,
+    /// Bids the tips of swaps without a tips share
+    pub tips_bidder: Arc<dyn TipsBidder>,
 }
 
 impl MulticallerSwapEncoder {
     pub fn new(multicaller_address: Address, swap_step_encoder: SwapStepEncoder) -> Self {
-        Self { multicaller_address, swap_step_encoder }
+        Self { multicaller_address, swap_step_encoder, tips_bidder: Arc::new(SlopesBidder) }
+    }
+
+    pub fn with_tips_bidder(self, tips_bidder: Arc<dyn TipsBidder>) -> Self {
+        Self { tips_bidder, ..self }
     }
 
     pub fn default_with_address(multicaller_address: Address) -> Self {
@@ -34,7 +41,7 @@ impl MulticallerSwapEncoder {
 
         let swap_step_encoder = SwapStepEncoder::new(multicaller_address, swap_line_encoder);
 
-        Self { multicaller_address, swap_step_encoder }
+        Self::new(multicaller_address, swap_step_encoder)
     }
 
     pub fn get_contract_address(&self) -> Address {
@@ -4,30 +4,13 @@ use eyre::{eyre, OptionExt, Result};
 use loom_types_blockchain::LoomDataTypesEthereum;
 use loom_types_blockchain::MulticallerCalls;
 use loom_types_entities::tips::{tips_and_value_for_swap_type, Tips};
-use loom_types_entities::{Swap, SwapEncoder, SwapStep};
+use loom_types_entities::{Swap, SwapEncoder, SwapFunding, SwapStep};
 use tracing::{debug, error, trace};
 
-impl SwapEncoder for MulticallerSwapEncoder {
-    fn set_address(&mut self, multicaller_address: Address) {
-        self.multicaller_address = multicaller_address;
-        self.swap_step_encoder.multicaller_address = multicaller_address;
-        self.swap_step_encoder.swap_line_encoder.multicaller_address = multicaller_address;
-    }
-
-    fn address(&self) -> Address {
-        self.multicaller_address
-    }
-
-    fn encode(
-        &self,
-        swap: Swap,
-        tips_pct: Option<u32>,
-        _next_block_number: Option<BlockNumber>,
-        gas_cost: Option<U256>,
-        sender_address: Option<Address>,
-        sender_eth_balance: Option<U256>,
-    ) -> Result<(Address, Option<U256>, Bytes, Vec<Tips>)> {
-        let swap_vec = match &swap {
+impl MulticallerSwapEncoder {
+    /// Swap funded with flash swaps through the swap pools or a Balancer flash loan
+    fn encode_flash_swap(&self, swap: &Swap) -> Result<MulticallerCalls> {
+        let swap_vec = match swap {
             Swap::BackrunSwapLine(_) | Swap::BackrunSwapSteps(_) => {
                 vec![swap.to_swap_steps(self.swap_step_encoder.get_contract_address()).ok_or_eyre("SWAP_TYPE_NOTE_COVERED")?]
             }
@@ -50,8 +33,8 @@ impl SwapEncoder for MulticallerSwapEncoder {
         }
         debug!("Swaps:\n{}", swap_steps);
 
-        let mut swap_opcodes = if swap_vec.is_empty() {
-            match &swap {
+        let swap_opcodes = if swap_vec.is_empty() {
+            match swap {
                 Swap::ExchangeSwapLine(swap_line) => {
                     trace!("START: exchange swap line");
                     match self.swap_step_encoder.swap_line_encoder.encode_swap_line_in_amount(swap_line, None) {
@@ -77,24 +60,58 @@ impl SwapEncoder for MulticallerSwapEncoder {
             }
             ret
         };
+        Ok(swap_opcodes)
+    }
+}
+
+impl SwapEncoder for MulticallerSwapEncoder {
+    fn set_address(&mut self, multicaller_address: Address) {
+        self.multicaller_address = multicaller_address;
+        self.swap_step_encoder.multicaller_address = multicaller_address;
+        self.swap_step_encoder.swap_line_encoder.multicaller_address = multicaller_address;
+    }
+
+    fn address(&self) -> Address {
+        self.multicaller_address
+    }
+
+    fn encode(
+        &self,
+        swap: Swap,
+        tips_pct: Option<u32>,
+        max_tips_pct: Option<u32>,
+        _next_block_number: Option<BlockNumber>,
+        gas_cost: Option<U256>,
+        sender_address: Option<Address>,
+        sender_eth_balance: Option<U256>,
+        funding: SwapFunding,
+    ) -> Result<(Address, Option<U256>, Bytes, Vec<Tips>)> {
+        let mut swap_opcodes = match (&swap, funding) {
+            (Swap::BackrunSwapLine(swap_line), SwapFunding::Inventory { .. }) => {
+                trace!("START: inventory swap line");
+                self.swap_step_encoder.swap_line_encoder.encode_inventory_swap_line(swap_line)?
+            }
+            _ => self.encode_flash_swap(&swap)?,
+        };
         trace!("END: swap_opcodes");
 
-        let tips_vec =
-            if let (Some(tips_pct), Some(sender_address), Some(sender_eth_balance)) = (tips_pct, sender_address, sender_eth_balance) {
-                let (tips_vec, _call_value) = tips_and_value_for_swap_type(&swap, Some(tips_pct), gas_cost, sender_eth_balance)?;
-                for tips in &tips_vec {
-                    swap_opcodes = self.swap_step_encoder.encode_tips(
-                        swap_opcodes,
-                        tips.token_in.get_address(),
-                        tips.min_change,
-                        tips.tips,
-                        sender_address,
-                    )?;
-                }
-                tips_vec
-            } else {
-                vec![]
-            };
+        // exchange swaps pay no tips, backrun swaps without a tips share are bid by the tips bidder
+        let pays_tips = tips_pct.is_some() || matches!(swap, Swap::BackrunSwapLine(_) | Swap::BackrunSwapSteps(_) | Swap::Multiple(_));
+        let tips_vec = if let (true, Some(sender_address), Some(sender_eth_balance)) = (pays_tips, sender_address, sender_eth_balance) {
+            let (tips_vec, _call_value) =
+                tips_and_value_for_swap_type(&swap, tips_pct, max_tips_pct, gas_cost, sender_eth_balance, self.tips_bidder.as_ref())?;
+            for tips in &tips_vec {
+                // the inventory balance is kept, only the change above it pays the tips
+                let min_balance = match funding {
+                    SwapFunding::Inventory { balance } => tips.min_change + balance,
+                    SwapFunding::Flash => tips.min_change,
+                };
+                swap_opcodes = self.swap_step_encoder.encode_tips(swap_opcodes, &tips.token_in, min_balance, tips.tips, sender_address)?;
+            }
+            tips_vec
+        } else {
+            vec![]
+        };
 
         let (to, call_data) = self.swap_step_encoder.to_call_data(&swap_opcodes)?;
 
@@ -1,18 +1,17 @@
 use std::sync::Arc;
 
 use alloy_primitives::{Address, U256};
-use eyre::{eyre, Result};
+use eyre::{eyre, OptionExt, Result};
 use tracing::trace;
 
 use crate::pool_abi_encoder::ProtocolAbiSwapEncoderTrait;
 use crate::pool_opcodes_encoder::{MulticallerOpcodesPayload, ProtocolSwapOpcodesEncoderV2, SwapOpcodesEncoderTrait};
 use crate::ProtocolABIEncoderV2;
 use loom_defi_abi::AbiEncoderHelper;
-use loom_defi_address_book::TokenAddressEth;
 use loom_types_blockchain::LoomDataTypesEthereum;
 use loom_types_blockchain::{MulticallerCall, MulticallerCalls};
 use loom_types_entities::SwapAmountType::RelativeStack;
-use loom_types_entities::{PoolWrapper, SwapAmountType, SwapLine, Token};
+use loom_types_entities::{PoolWrapper, PreswapRequirement, SwapAmountType, SwapLine, Token};
 
 #[derive(Clone)]
 pub struct SwapLineEncoder {
@@ -58,7 +57,11 @@ impl SwapLineEncoder {
             let token_from_address = reverse_tokens[pool_idx + 1].get_address();
             let token_to_address = reverse_tokens[pool_idx].get_address();
 
-            let amount_in = if pool_idx == swap_path.pools().len() - 1 { swap_path.amount_in } else { SwapAmountType::RelativeStack(0) };
+            let amount_in = if pool_idx == swap_path.pools().len() - 1 {
+                swap_path.amount_in
+            } else {
+                Self::transfer_fee_amount_in(swap_path, swap_path.pools().len() - 1 - pool_idx).unwrap_or(SwapAmountType::RelativeStack(0))
+            };
 
             self.opcodes_encoder.encode_flash_swap_in_amount_provided(
                 &mut flash_swap_opcodes,
@@ -100,7 +103,11 @@ impl SwapLineEncoder {
 
             let next_pool = if pool_idx < pools.len() - 1 { Some(&pools[pool_idx + 1]) } else { None };
 
-            let amount_out = if pool_idx == pools.len() - 1 { swap_path.amount_out } else { SwapAmountType::RelativeStack(0) };
+            let amount_out = if pool_idx == pools.len() - 1 {
+                swap_path.amount_out
+            } else {
+                Self::transfer_fee_amount_out(swap_path, pool_idx).unwrap_or(SwapAmountType::RelativeStack(0))
+            };
 
             self.opcodes_encoder.encode_flash_swap_out_amount_provided(
                 &mut flash_swap_opcodes,
@@ -386,29 +393,251 @@ impl SwapLineEncoder {
                 self.multicaller_address,
             )?;
 
-            amount_in = RelativeStack(0);
+            amount_in = Self::transfer_fee_amount_in(swap_path, i + 1).unwrap_or(RelativeStack(0));
+        }
+        Ok(swap_opcodes)
+    }
+
+    /// Swap line paid with the tokens held by the multicaller, the amount in is transferred first to pools expecting the funds
+    /// before the swap
+    pub fn encode_inventory_swap_line(&self, swap_path: &SwapLine<LoomDataTypesEthereum>) -> Result<MulticallerCalls> {
+        let SwapAmountType::Set(amount_in) = swap_path.amount_in else {
+            return Err(eyre!("INVENTORY_AMOUNT_IN_NOT_SET"));
+        };
+        let first_token = swap_path.get_first_token().ok_or_eyre("NO_FIRST_TOKEN")?;
+        let first_pool = swap_path.get_first_pool().ok_or_eyre("NO_FIRST_POOL")?;
+
+        let mut swap_opcodes = MulticallerCalls::new();
+        if let PreswapRequirement::Transfer(funds_to) = first_pool.preswap_requirement() {
+            trace!("inventory transfer token={:?}, to={:?}, amount={}", first_token.get_address(), funds_to, amount_in);
+            swap_opcodes
+                .add(MulticallerCall::new_call(first_token.get_address(), &AbiEncoderHelper::encode_erc20_transfer(funds_to, amount_in)));
         }
+        swap_opcodes.merge(self.encode_swap_line_in_amount(swap_path, None)?);
         Ok(swap_opcodes)
     }
 
+    /// For fee-on-transfer tokens the amount received by the pool is lower than the out amount of the previous swap left in stack,
+    /// so we use the calculated amount the pool receives instead.
+    fn transfer_fee_amount_in(swap_path: &SwapLine<LoomDataTypesEthereum>, pool_idx: usize) -> Option<SwapAmountType> {
+        let token_from = swap_path.tokens().get(pool_idx)?;
+        if !token_from.has_transfer_fee() || swap_path.calculation_results.len() != swap_path.pools().len() {
+            return None;
+        }
+        let calculation_result = swap_path.calculation_results.get(pool_idx)?;
+        trace!("fee-on-transfer token={:?} pool_idx={} amount_in={}", token_from.get_address(), pool_idx, calculation_result.amount_in);
+        Some(SwapAmountType::Set(calculation_result.amount_in))
+    }
+
+    /// For fee-on-transfer tokens the pool has to send more than the next pool requests, so we use the calculated gross amount
+    /// the pool sends instead of the amount left in stack.
+    fn transfer_fee_amount_out(swap_path: &SwapLine<LoomDataTypesEthereum>, pool_idx: usize) -> Option<SwapAmountType> {
+        let token_to = swap_path.tokens().get(pool_idx + 1)?;
+        if !token_to.has_transfer_fee() || swap_path.calculation_results.len() != swap_path.pools().len() {
+            return None;
+        }
+        let calculation_result = swap_path.calculation_results.get(pool_idx)?;
+        trace!("fee-on-transfer token={:?} pool_idx={} amount_out={}", token_to.get_address(), pool_idx, calculation_result.amount_out);
+        Some(SwapAmountType::Set(calculation_result.amount_out))
+    }
+
     pub fn encode_tips(
         &self,
         swap_opcodes: MulticallerCalls,
-        token_address: Address,
+        token: &Token,
         min_balance: U256,
         tips: U256,
         to: Address,
     ) -> Result<MulticallerCalls> {
         let mut tips_opcodes = swap_opcodes.clone();
 
-        let call_data = if token_address == TokenAddressEth::WETH {
+        let call_data = if token.is_weth() {
             trace!("encode_multicaller_transfer_tips_weth");
             AbiEncoderHelper::encode_multicaller_transfer_tips_weth(min_balance, tips, to)
         } else {
             trace!("encode_multicaller_transfer_tips");
-            AbiEncoderHelper::encode_multicaller_transfer_tips(token_address, min_balance, tips, to)
+            AbiEncoderHelper::encode_multicaller_transfer_tips(token.get_address(), min_balance, tips, to)
         };
         tips_opcodes.add(MulticallerCall::new_internal_call(&call_data));
         Ok(tips_opcodes)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use loom_defi_address_book::TokenAddressEth;
+    use loom_types_entities::{CalculationResult, MockPool, Pool, SwapPath, TokenBehaviour};
+    use std::sync::Mutex;
+
+    /// Records the amounts passed for each pool instead of encoding calls
+    #[derive(Default)]
+    struct RecordingOpcodesEncoder {
+        amounts: Mutex<Vec<(Address, SwapAmountType)>>,
+    }
+
+    impl RecordingOpcodesEncoder {
+        fn record(&self, pool: &dyn Pool, amount: SwapAmountType) -> Result<()> {
+            self.amounts.lock().unwrap().push((pool.get_address(), amount));
+            Ok(())
+        }
+    }
+
+    impl SwapOpcodesEncoderTrait for RecordingOpcodesEncoder {
+        fn encode_swap_in_amount_provided(
+            &self,
+            _swap_opcodes: &mut MulticallerCalls,
+            _abi_encoder: &dyn ProtocolAbiSwapEncoderTrait,
+            _token_from_address: Address,
+            _token_to_address: Address,
+            amount_in: SwapAmountType,
+            cur_pool: &dyn Pool,
+            _next_pool: Option<&dyn Pool>,
+            _payload: MulticallerOpcodesPayload,
+            _multicaller_address: Address,
+        ) -> Result<()> {
+            self.record(cur_pool, amount_in)
+        }
+
+        fn encode_swap_out_amount_provided(
+            &self,
+            _swap_opcodes: &mut MulticallerCalls,
+            _abi_encoder: &dyn ProtocolAbiSwapEncoderTrait,
+            _token_from_address: Address,
+            _token_to_address: Address,
+            amount_out: SwapAmountType,
+            cur_pool: &dyn Pool,
+            _next_pool: Option<&dyn Pool>,
+            _payload: MulticallerOpcodesPayload,
+            _multicaller_address: Address,
+        ) -> Result<()> {
+            self.record(cur_pool, amount_out)
+        }
+
+        fn encode_flash_swap_in_amount_provided(
+            &self,
+            _swap_opcodes: &mut MulticallerCalls,
+            _abi_encoder: &dyn ProtocolAbiSwapEncoderTrait,
+            _token_from_address: Address,
+            _token_to_address: Address,
+            amount_in: SwapAmountType,
+            flash_pool: &dyn Pool,
+            _prev_pool: Option<&dyn Pool>,
+            _payload: MulticallerOpcodesPayload,
+            _multicaller_address: Address,
+        ) -> Result<()> {
+            self.record(flash_pool, amount_in)
+        }
+
+        fn encode_flash_swap_out_amount_provided(
+            &self,
+            _swap_opcodes: &mut MulticallerCalls,
+            _abi_encoder: &dyn ProtocolAbiSwapEncoderTrait,
+            _token_from_address: Address,
+            _token_to_address: Address,
+            amount_out: SwapAmountType,
+            flash_pool: &dyn Pool,
+            _next_pool: Option<&dyn Pool>,
+            _payload: MulticallerOpcodesPayload,
+            _multicaller_address: Address,
+        ) -> Result<()> {
+            self.record(flash_pool, amount_out)
+        }
+    }
+
+    /// WETH -> FEE -> WETH with a 5% transfer fee on FEE
+    fn fee_token_swap_line() -> (Address, Address, SwapLine<LoomDataTypesEthereum>) {
+        let weth = Token::new(TokenAddressEth::WETH);
+        let fee_token = Token::new(Address::repeat_byte(0xfe));
+        fee_token.set_behaviour(Some(TokenBehaviour::new(500, 500, false, false)));
+
+        let pool_0 = Address::repeat_byte(0x01);
+        let pool_1 = Address::repeat_byte(0x02);
+        let swap_line = SwapLine {
+            path: SwapPath::new(
+                vec![weth.clone(), fee_token.clone(), weth],
+                vec![
+                    MockPool::new(TokenAddressEth::WETH, fee_token.get_address(), pool_0),
+                    MockPool::new(TokenAddressEth::WETH, fee_token.get_address(), pool_1),
+                ],
+            ),
+            amount_in: SwapAmountType::Set(U256::from(1000)),
+            amount_out: SwapAmountType::Set(U256::from(1100)),
+            // pool 0 sends 2000, pool 1 receives 1900 after the transfer fee
+            calculation_results: vec![
+                CalculationResult::new(U256::from(1000), U256::from(2000)),
+                CalculationResult::new(U256::from(1900), U256::from(1100)),
+            ],
+            swap_to: None,
+            gas_used: None,
+        };
+        (pool_0, pool_1, swap_line)
+    }
+
+    #[test]
+    fn test_transfer_fee_amount_out() {
+        let (pool_0, pool_1, swap_line) = fee_token_swap_line();
+        let opcodes_encoder = Arc::new(RecordingOpcodesEncoder::default());
+        let encoder = SwapLineEncoder::new(Address::ZERO, Arc::new(ProtocolABIEncoderV2::default()), opcodes_encoder.clone());
+
+        encoder.encode_flash_swap_line_out_amount(&swap_line, MulticallerCalls::new()).unwrap();
+
+        let amounts = opcodes_encoder.amounts.lock().unwrap();
+        assert_eq!(amounts.len(), 2);
+        // pool 0 sends the gross amount, not the amount pool 1 requests
+        assert_eq!(amounts[0].0, pool_0);
+        assert!(matches!(amounts[0].1, SwapAmountType::Set(amount) if amount == U256::from(2000)));
+        assert_eq!(amounts[1].0, pool_1);
+        assert!(matches!(amounts[1].1, SwapAmountType::Set(amount) if amount == U256::from(1100)));
+    }
+
+    #[test]
+    fn test_transfer_fee_amount_in() {
+        let (pool_0, pool_1, swap_line) = fee_token_swap_line();
+        let opcodes_encoder = Arc::new(RecordingOpcodesEncoder::default());
+        let encoder = SwapLineEncoder::new(Address::ZERO, Arc::new(ProtocolABIEncoderV2::default()), opcodes_encoder.clone());
+
+        encoder.encode_swap_line_in_amount(&swap_line, None).unwrap();
+
+        let amounts = opcodes_encoder.amounts.lock().unwrap();
+        assert_eq!(amounts.len(), 2);
+        assert_eq!(amounts[0].0, pool_0);
+        assert!(matches!(amounts[0].1, SwapAmountType::Set(amount) if amount == U256::from(1000)));
+        // pool 1 swaps the amount received after the transfer fee
+        assert_eq!(amounts[1].0, pool_1);
+        assert!(matches!(amounts[1].1, SwapAmountType::Set(amount) if amount == U256::from(1900)));
+    }
+
+    /// WETH -> USDT -> WETH, the first pool expects the funds as set by `preswap_requirement`
+    fn inventory_swap_line(preswap_requirement: PreswapRequirement) -> SwapLine<LoomDataTypesEthereum> {
+        let weth = Token::new(TokenAddressEth::WETH);
+        let usdt = Token::new(TokenAddressEth::USDT);
+        let pool_0 = MockPool::new(TokenAddressEth::WETH, TokenAddressEth::USDT, Address::repeat_byte(0x01))
+            .with_preswap_requirement(preswap_requirement);
+        let pool_1 = MockPool::new(TokenAddressEth::WETH, TokenAddressEth::USDT, Address::repeat_byte(0x02));
+        SwapLine {
+            path: SwapPath::new(vec![weth.clone(), usdt, weth], vec![pool_0, pool_1]),
+            amount_in: SwapAmountType::Set(U256::from(1000)),
+            ..SwapLine::default()
+        }
+    }
+
+    #[test]
+    fn test_inventory_swap_line() {
+        let pool_0 = Address::repeat_byte(0x01);
+        let opcodes_encoder = Arc::new(RecordingOpcodesEncoder::default());
+        let encoder = SwapLineEncoder::new(Address::ZERO, Arc::new(ProtocolABIEncoderV2::default()), opcodes_encoder.clone());
+
+        // the amount in is transferred to the pool before the swap
+        let calls = encoder.encode_inventory_swap_line(&inventory_swap_line(PreswapRequirement::Transfer(pool_0))).unwrap();
+        assert_eq!(calls.len(), 1);
+        let transfer = calls.get(0).unwrap();
+        assert_eq!(transfer.to, TokenAddressEth::WETH);
+        assert_eq!(transfer.call_data, AbiEncoderHelper::encode_erc20_transfer(pool_0, U256::from(1000)));
+
+        // callback pools pull the funds during the swap
+        let calls = encoder.encode_inventory_swap_line(&inventory_swap_line(PreswapRequirement::Callback)).unwrap();
+        assert!(calls.is_empty());
+        assert_eq!(opcodes_encoder.amounts.lock().unwrap().len(), 4);
+    }
+}
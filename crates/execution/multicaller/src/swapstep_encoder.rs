@@ -8,7 +8,7 @@ use crate::SwapLineEncoder;
 use loom_defi_abi::AbiEncoderHelper;
 use loom_types_blockchain::LoomDataTypesEthereum;
 use loom_types_blockchain::{MulticallerCall, MulticallerCalls};
-use loom_types_entities::{SwapAmountType, SwapStep};
+use loom_types_entities::{SwapAmountType, SwapStep, Token};
 
 lazy_static! {
     static ref BALANCER_VAULT_ADDRESS: Address = "0xBA12222222228d8Ba445958a75a0704d566BF2C8".parse().unwrap();
@@ -44,12 +44,12 @@ impl SwapStepEncoder {
     pub fn encode_tips(
         &self,
         swap_opcodes: MulticallerCalls,
-        token_address: Address,
+        token: &Token,
         min_balance: U256,
         tips: U256,
         funds_to: Address,
     ) -> Result<MulticallerCalls> {
-        self.swap_line_encoder.encode_tips(swap_opcodes, token_address, min_balance, tips, funds_to)
+        self.swap_line_encoder.encode_tips(swap_opcodes, token, min_balance, tips, funds_to)
     }
 
     pub fn encode_balancer_flash_loan(&self, steps: Vec<SwapStep<LoomDataTypesEthereum>>) -> Result<MulticallerCalls> {
@@ -1,19 +1,43 @@
 use async_trait::async_trait;
 use eyre::eyre;
 use influxdb::{Client, ReadQuery, WriteQuery};
-use loom_core_actors::{Actor, ActorResult, Broadcaster, Consumer, WorkerResult};
+use loom_core_actors::{Actor, ActorResult, Broadcaster, Consumer, ShutdownStage, WorkerResult};
 use loom_core_actors_macros::Consumer;
 use loom_core_blockchain::Blockchain;
 use std::collections::HashMap;
 use std::time::Duration;
+use tokio::sync::broadcast::error::{RecvError, TryRecvError};
+use tokio::task::JoinSet;
 use tokio::time::timeout;
+use tokio_util::sync::CancellationToken;
 use tracing::{error, info, warn};
 
+async fn write_query(client: Client, query: WriteQuery) {
+    match timeout(Duration::from_millis(2000), client.query(query)).await {
+        Ok(inner_result) => {
+            if let Err(e) = inner_result {
+                error!("InfluxDB Write failed: {:?}", e);
+            }
+        }
+        Err(elapsed) => {
+            error!("InfluxDB Query timed out: {}", elapsed);
+        }
+    }
+}
+
+fn add_tags(mut event: WriteQuery, tags: &HashMap<String, String>) -> WriteQuery {
+    for (key, value) in tags.iter() {
+        event = event.add_tag(key, value.clone());
+    }
+    event
+}
+
 pub async fn start_influxdb_worker(
     url: String,
     database: String,
     tags: HashMap<String, String>,
     event_receiver: Broadcaster<WriteQuery>,
+    shutdown_token: CancellationToken,
 ) -> WorkerResult {
     let client = Client::new(url, database.clone());
     let create_db_stmt = format!("CREATE DATABASE {}", database);
@@ -23,39 +47,47 @@ pub async fn start_influxdb_worker(
         Err(e) => info!("Database creation failed or already exists: {:?}", e),
     }
     let mut event_receiver = event_receiver.subscribe();
+    let mut writes: JoinSet<()> = JoinSet::new();
     loop {
-        let event_result = event_receiver.recv().await;
-        match event_result {
-            Ok(mut event) => {
-                for (key, value) in tags.iter() {
-                    event = event.add_tag(key, value.clone());
-                }
-                let client_clone = client.clone();
-                tokio::task::spawn(async move {
-                    match timeout(Duration::from_millis(2000), client_clone.query(event)).await {
-                        Ok(inner_result) => {
-                            if let Err(e) = inner_result {
-                                error!("InfluxDB Write failed: {:?}", e);
-                            }
+        tokio::select! {
+            event_result = event_receiver.recv() => {
+                match event_result {
+                    Ok(event) => {
+                        writes.spawn(write_query(client.clone(), add_tags(event, &tags)));
+                    }
+                    Err(e) => match e {
+                        RecvError::Closed => {
+                            error!("InfluxDB channel closed");
+                            return Err(eyre!("INFLUXDB_CHANNEL_CLOSED"));
                         }
-                        Err(elapsed) => {
-                            error!("InfluxDB Query timed out: {}", elapsed);
+                        RecvError::Lagged(lagged) => {
+                            warn!("InfluxDB lagged: {:?}", lagged);
+                            continue;
                         }
-                    }
-                });
-            }
-            Err(e) => match e {
-                tokio::sync::broadcast::error::RecvError::Closed => {
-                    error!("InfluxDB channel closed");
-                    return Err(eyre!("INFLUXDB_CHANNEL_CLOSED"));
+                    },
                 }
-                tokio::sync::broadcast::error::RecvError::Lagged(lagged) => {
-                    warn!("InfluxDB lagged: {:?}", lagged);
-                    continue;
-                }
-            },
+            }
+            Some(_) = writes.join_next() => {}
+            _ = shutdown_token.cancelled() => {
+                break;
+            }
+        }
+    }
+
+    // flush buffered events and wait for in-flight writes
+    let mut flushed = 0;
+    loop {
+        match event_receiver.try_recv() {
+            Ok(event) => {
+                writes.spawn(write_query(client.clone(), add_tags(event, &tags)));
+                flushed += 1;
+            }
+            Err(TryRecvError::Lagged(lagged)) => warn!("InfluxDB lagged on shutdown: {:?}", lagged),
+            Err(_) => break,
         }
     }
+    while writes.join_next().await.is_some() {}
+    Ok(format!("InfluxDB writer stopped, {flushed} buffered events flushed"))
 }
 
 #[derive(Consumer)]
@@ -80,6 +112,10 @@ impl InfluxDbWriterActor {
 #[async_trait]
 impl Actor for InfluxDbWriterActor {
     fn start(&self) -> ActorResult {
+        self.start_until(CancellationToken::new())
+    }
+
+    fn start_until(&self, shutdown_token: CancellationToken) -> ActorResult {
         let influxdb_write_channel_rx = match &self.influxdb_write_channel_rx {
             Some(rx) => rx.clone(),
             None => {
@@ -92,10 +128,15 @@ impl Actor for InfluxDbWriterActor {
             self.database.clone(),
             self.tags.clone(),
             influxdb_write_channel_rx.clone(),
+            shutdown_token,
         ));
         Ok(vec![task])
     }
 
+    fn shutdown_stage(&self) -> ShutdownStage {
+        ShutdownStage::Writer
+    }
+
     fn name(&self) -> &'static str {
         "InfluxDbWriterActor"
     }
@@ -20,7 +20,7 @@ use std::path::Path;
 use std::sync::Arc;
 use tracing::{debug, error, info, trace};
 
-use loom_core_actors::{Actor, ActorResult, Broadcaster, Producer, WorkerResult};
+use loom_core_actors::{Actor, ActorResult, Broadcaster, Producer, ShutdownStage, WorkerResult};
 use loom_core_actors_macros::Producer;
 use loom_core_blockchain::Blockchain;
 use loom_evm_utils::reth_types::append_all_matching_block_logs;
@@ -325,6 +325,10 @@ where
             self.block_state_update_channel.clone(),
         )
     }
+    fn shutdown_stage(&self) -> ShutdownStage {
+        ShutdownStage::Producer
+    }
+
     fn name(&self) -> &'static str {
         self.name()
     }
@@ -350,10 +354,10 @@ mod test {
         let _ = env_logger::builder().format_timestamp_millis().try_init();
 
         info!("Creating channels");
-        let new_block_headers_channel: Broadcaster<MessageBlockHeader> = Broadcaster::new(10);
-        let new_block_with_tx_channel: Broadcaster<MessageBlock> = Broadcaster::new(10);
-        let new_block_state_update_channel: Broadcaster<MessageBlockStateUpdate> = Broadcaster::new(10);
-        let new_block_logs_channel: Broadcaster<MessageBlockLogs> = Broadcaster::new(10);
+        let new_block_headers_channel: Broadcaster<MessageBlockHeader> = Broadcaster::named("new_block_headers", 10);
+        let new_block_with_tx_channel: Broadcaster<MessageBlock> = Broadcaster::named("new_block_with_tx", 10);
+        let new_block_state_update_channel: Broadcaster<MessageBlockStateUpdate> = Broadcaster::named("new_block_state_update", 10);
+        let new_block_logs_channel: Broadcaster<MessageBlockLogs> = Broadcaster::named("new_block_logs", 10);
 
         let node_url = std::env::var("MAINNET_WS")?;
 
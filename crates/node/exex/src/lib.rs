@@ -1,3 +1,3 @@
-pub use reth_exex_worker::{loom_exex, mempool_worker};
+pub use reth_exex_worker::{loom_exex, ExExMempoolSource};
 
 mod reth_exex_worker;
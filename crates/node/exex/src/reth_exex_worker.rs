@@ -3,15 +3,17 @@ use alloy_network::primitives::{BlockTransactions, BlockTransactionsKind};
 use alloy_primitives::map::HashMap;
 use alloy_primitives::{Address, U256};
 use alloy_rpc_types::{Block, TransactionInfo};
-use futures::TryStreamExt;
+use async_trait::async_trait;
+use futures::{future, StreamExt, TryStreamExt};
 use loom_core_actors::Broadcaster;
 use loom_core_blockchain::Blockchain;
 use loom_evm_utils::reth_types::append_all_matching_block_logs_sealed;
 use loom_node_actor_config::NodeBlockActorConfig;
+use loom_node_json_rpc::{MempoolSource, PendingTxStream};
 use loom_types_blockchain::{GethStateUpdate, MempoolTx};
 use loom_types_events::{
     BlockHeader, BlockLogs, BlockStateUpdate, BlockUpdate, Message, MessageBlock, MessageBlockHeader, MessageBlockLogs,
-    MessageBlockStateUpdate, MessageMempoolDataUpdate, NodeMempoolDataUpdate,
+    MessageBlockStateUpdate,
 };
 use reth_exex::{ExExContext, ExExEvent, ExExNotification};
 use reth_node_api::{FullNodeComponents, NodeTypes};
@@ -23,7 +25,6 @@ use reth_transaction_pool::{EthPooledTransaction, TransactionPool};
 use revm::db::states::StorageSlot;
 use revm::db::{BundleAccount, StorageWithOriginalValues};
 use std::sync::Arc;
-use tokio::select;
 use tracing::{debug, error, info};
 
 async fn process_chain(
@@ -196,34 +197,49 @@ where
     Ok(())
 }
 
-pub async fn mempool_worker<Pool>(mempool: Pool, bc: Blockchain) -> eyre::Result<()>
+/// Pending txes of the reth transaction pool as [`MempoolSource`], blob txes get their sidecar from the blob store of the pool
+pub struct ExExMempoolSource<Pool> {
+    pool: Pool,
+}
+
+impl<Pool> ExExMempoolSource<Pool> {
+    pub fn new(pool: Pool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl<Pool> MempoolSource for ExExMempoolSource<Pool>
 where
     Pool: TransactionPool<Transaction = EthPooledTransaction> + Clone + 'static,
 {
-    info!("Mempool worker started");
-    let mut tx_listener = mempool.new_transactions_listener();
-
-    let mempool_tx = bc.new_mempool_tx_channel();
-
-    let eth_tx_builder = EthTxBuilder::default();
-
-    loop {
-        select! {
-            tx_notification = tx_listener.recv() => {
-                if let Some(tx_notification) = tx_notification {
-                    let tx_hash = *tx_notification.transaction.hash();
-                    let recovered_tx  = tx_notification.transaction.to_consensus();
-
-                    if let Ok(tx) = eth_tx_builder.fill(recovered_tx, TransactionInfo::default()) {
-                        let update_msg: MessageMempoolDataUpdate = MessageMempoolDataUpdate::new_with_source(NodeMempoolDataUpdate { tx_hash, mempool_tx: MempoolTx { tx: Some(tx), ..MempoolTx::default() } }, "exex".to_string());
-                        if let Err(e) =  mempool_tx.send(update_msg) {
-                            error!(error=?e.to_string(), "mempool_tx.send");
-                        }else{
-                            debug!(hash = ?tx_notification.transaction.hash(), "Received pool tx");
-                        }
+    async fn subscribe(&self) -> eyre::Result<PendingTxStream> {
+        info!("Mempool source subscribed to transaction pool");
+        let pool = self.pool.clone();
+        let tx_listener = self.pool.new_transactions_listener();
+        let eth_tx_builder = EthTxBuilder::default();
+
+        let stream = futures::stream::unfold(tx_listener, |mut tx_listener| async move {
+            tx_listener.recv().await.map(|tx_notification| (tx_notification, tx_listener))
+        })
+        .filter_map(move |tx_notification| {
+            let tx_hash = *tx_notification.transaction.hash();
+            let recovered_tx = tx_notification.transaction.to_consensus();
+
+            let tx = eth_tx_builder.fill(recovered_tx, TransactionInfo::default()).ok().and_then(|tx| {
+                let mut mempool_entry = MempoolTx { tx: Some(tx), ..MempoolTx::default() };
+                // the consensus tx has no sidecar, it is kept in the blob store of the pool
+                if mempool_entry.is_blob_tx() {
+                    match pool.get_blob(tx_hash) {
+                        Ok(Some(sidecar)) => mempool_entry = mempool_entry.with_blob_sidecar(sidecar.as_ref().clone()),
+                        Ok(None) => debug!(%tx_hash, "Blob sidecar not found"),
+                        Err(error) => error!(%tx_hash, %error, "get_blob"),
                     }
                 }
-            }
-        }
+                mempool_entry.tx
+            });
+            future::ready(tx)
+        });
+        Ok(stream.boxed())
     }
 }
@@ -1,5 +1,5 @@
 use crate::node_exex_worker::node_exex_grpc_worker;
-use loom_core_actors::{Actor, ActorResult, Broadcaster, Producer};
+use loom_core_actors::{Actor, ActorResult, Broadcaster, Producer, ShutdownStage};
 use loom_core_actors_macros::Producer;
 use loom_core_blockchain::Blockchain;
 use loom_types_events::{MessageBlock, MessageBlockHeader, MessageBlockLogs, MessageBlockStateUpdate, MessageMempoolDataUpdate};
@@ -57,6 +57,10 @@ impl Actor for NodeExExGrpcActor {
         Ok(vec![handler])
     }
 
+    fn shutdown_stage(&self) -> ShutdownStage {
+        ShutdownStage::Producer
+    }
+
     fn name(&self) -> &'static str {
         type_name::<Self>().rsplit("::").next().unwrap_or(type_name::<Self>())
     }
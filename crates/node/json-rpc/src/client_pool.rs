@@ -0,0 +1,564 @@
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::{Arc, Mutex, Weak};
+use std::time::{Duration, Instant};
+
+use alloy_json_rpc::PubSubItem;
+use alloy_network::Ethereum;
+use alloy_provider::{Provider, ProviderBuilder, RootProvider};
+use alloy_pubsub::{ConnectionHandle, ConnectionInterface, PubSubConnect, Subscription};
+use alloy_rpc_client::ClientBuilder;
+use alloy_transport::{TransportError, TransportErrorKind, TransportResult};
+use futures::future::join_all;
+use futures::StreamExt;
+use serde::Deserialize;
+use serde_json::value::RawValue;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tracing::{debug, error, info, warn};
+
+/// Subscription items remembered per subscription to drop duplicates received from several clients
+const SEEN_ITEMS_CAPACITY: usize = 10_000;
+
+#[derive(Clone, Debug)]
+pub struct ClientPoolConfig {
+    /// Blocks a client may fall behind the best head of the pool before requests avoid it
+    pub max_head_lag: u64,
+    pub probe_interval: Duration,
+    /// Consecutive failed requests or probes after which a client is avoided until a probe succeeds
+    pub max_failures: u32,
+    /// Time a client has to answer a request before the next client is tried, a timeout counts as a failure
+    pub request_timeout: Duration,
+}
+
+impl Default for ClientPoolConfig {
+    fn default() -> Self {
+        Self { max_head_lag: 2, probe_interval: Duration::from_secs(1), max_failures: 3, request_timeout: Duration::from_secs(5) }
+    }
+}
+
+/// Health of a pool client as seen by the last probes and requests
+#[derive(Clone, Debug, Default)]
+pub struct ClientHealth {
+    pub name: String,
+    pub head: u64,
+    pub head_lag: u64,
+    pub latency: Option<Duration>,
+    pub failures: u32,
+    pub healthy: bool,
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+struct MemberState {
+    head: u64,
+    latency: Option<Duration>,
+    failures: u32,
+    healthy: bool,
+}
+
+impl MemberState {
+    fn is_healthy(&self, best_head: u64, config: &ClientPoolConfig) -> bool {
+        self.latency.is_some() && self.failures < config.max_failures && self.head + config.max_head_lag >= best_head
+    }
+
+    fn on_success(&mut self, latency: Duration) {
+        // moving average to keep a single slow response from reordering the pool
+        self.latency = Some(match self.latency {
+            Some(average) => (average * 7 + latency) / 8,
+            None => latency,
+        });
+        self.failures = 0;
+    }
+}
+
+struct PoolMember {
+    name: String,
+    provider: RootProvider<Ethereum>,
+    state: Mutex<MemberState>,
+}
+
+impl PoolMember {
+    fn state(&self) -> MemberState {
+        self.state.lock().map(|state| *state).unwrap_or_default()
+    }
+
+    fn update(&self, f: impl FnOnce(&mut MemberState)) {
+        if let Ok(mut state) = self.state.lock() {
+            f(&mut state)
+        }
+    }
+}
+
+struct ClientPoolInner {
+    name: String,
+    config: ClientPoolConfig,
+    members: Vec<PoolMember>,
+}
+
+/// Several node clients behind one name.
+///
+/// Request/response calls go to the healthy client with the lowest latency and fail over to the next one on transport errors.
+/// Subscriptions are opened on every client and merged, items already received from another client are dropped.
+/// Clients are probed for their head and latency, a client lagging more than `max_head_lag` blocks is used only as a last resort.
+/// The pool is a [`PubSubConnect`], [`ClientPool::connect_provider`] connects a [`RootProvider`] usable anywhere a provider is.
+#[derive(Clone)]
+pub struct ClientPool {
+    inner: Arc<ClientPoolInner>,
+}
+
+impl ClientPool {
+    pub fn new(name: &str, clients: Vec<(String, RootProvider<Ethereum>)>, config: ClientPoolConfig) -> Self {
+        let members =
+            clients.into_iter().map(|(name, provider)| PoolMember { name, provider, state: Mutex::new(MemberState::default()) }).collect();
+        Self { inner: Arc::new(ClientPoolInner { name: name.to_string(), config, members }) }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.inner.name
+    }
+
+    pub fn health(&self) -> Vec<ClientHealth> {
+        let states: Vec<MemberState> = self.inner.members.iter().map(|member| member.state()).collect();
+        let best_head = states.iter().map(|state| state.head).max().unwrap_or_default();
+        self.inner
+            .members
+            .iter()
+            .zip(states.iter())
+            .map(|(member, state)| ClientHealth {
+                name: member.name.clone(),
+                head: state.head,
+                head_lag: best_head.saturating_sub(state.head),
+                latency: state.latency,
+                failures: state.failures,
+                healthy: state.is_healthy(best_head, &self.inner.config),
+            })
+            .collect()
+    }
+
+    /// Probes the clients once, starts periodic probing and connects a provider to the pool.
+    /// Probing runs as long as the pool or a provider connected to it is alive, [`ClientPool::health`] stays usable.
+    pub async fn connect_provider(&self) -> TransportResult<RootProvider<Ethereum>> {
+        self.probe().await;
+        tokio::task::spawn(probe_worker(Arc::downgrade(&self.inner)));
+        let client = ClientBuilder::default().pubsub(self.clone()).await?;
+        Ok(ProviderBuilder::<_, _, Ethereum>::new().disable_recommended_fillers().on_client(client))
+    }
+
+    /// Sends the request to clients in routing order until one of them responds.
+    /// JSON-RPC errors are responses of the node and are returned without trying other clients.
+    pub async fn request(&self, method: &str, params: &RawValue) -> TransportResult<Box<RawValue>> {
+        let mut last_error = None;
+        for member_idx in self.route() {
+            let member = &self.inner.members[member_idx];
+            let started_at = Instant::now();
+            let response = tokio::time::timeout(
+                self.inner.config.request_timeout,
+                member.provider.raw_request_dyn(Cow::Owned(method.to_string()), params),
+            )
+            .await
+            .unwrap_or_else(|_| Err(TransportErrorKind::custom_str("REQUEST_TIMEOUT")));
+            match response {
+                Ok(result) => {
+                    member.update(|state| state.on_success(started_at.elapsed()));
+                    return Ok(result);
+                }
+                Err(TransportError::ErrorResp(payload)) => {
+                    member.update(|state| state.on_success(started_at.elapsed()));
+                    return Err(TransportError::ErrorResp(payload));
+                }
+                Err(e) => {
+                    warn!(pool = self.name(), client = member.name, method, "Request failed, trying next client : {e}");
+                    member.update(|state| state.failures += 1);
+                    last_error = Some(e);
+                }
+            }
+        }
+        Err(last_error.unwrap_or_else(|| TransportErrorKind::custom_str("NO_CLIENTS_IN_POOL")))
+    }
+
+    /// Member indexes, healthy clients first, each group ordered by latency
+    fn route(&self) -> Vec<usize> {
+        let states: Vec<MemberState> = self.inner.members.iter().map(|member| member.state()).collect();
+        route_order(&states, &self.inner.config)
+    }
+
+    async fn probe(&self) {
+        let config = &self.inner.config;
+        let probes = self.inner.members.iter().map(|member| async move {
+            let started_at = Instant::now();
+            match tokio::time::timeout(config.probe_interval.max(Duration::from_secs(1)), member.provider.get_block_number()).await {
+                Ok(Ok(head)) => member.update(|state| {
+                    state.head = head;
+                    state.on_success(started_at.elapsed());
+                }),
+                Ok(Err(e)) => {
+                    debug!(pool = self.name(), client = member.name, "Probe failed : {e}");
+                    member.update(|state| state.failures += 1);
+                }
+                Err(_) => {
+                    debug!(pool = self.name(), client = member.name, "Probe timed out");
+                    member.update(|state| state.failures += 1);
+                }
+            }
+        });
+        join_all(probes).await;
+
+        let best_head = self.inner.members.iter().map(|member| member.state().head).max().unwrap_or_default();
+        for member in self.inner.members.iter() {
+            let state = member.state();
+            let healthy = state.is_healthy(best_head, config);
+            if healthy != state.healthy {
+                if healthy {
+                    info!(pool = self.name(), client = member.name, head = state.head, "Client is healthy");
+                } else {
+                    warn!(
+                        pool = self.name(),
+                        client = member.name,
+                        head = state.head,
+                        best_head,
+                        failures = state.failures,
+                        "Client is unhealthy"
+                    );
+                }
+                member.update(|state| state.healthy = healthy);
+            }
+        }
+    }
+
+    /// Subscribes on all clients, responds to the frontend and forwards deduplicated items until the task is aborted
+    async fn run_subscription(
+        self,
+        id: serde_json::Value,
+        params: Box<RawValue>,
+        subscription_id: String,
+        items: mpsc::UnboundedSender<PubSubItem>,
+    ) {
+        let new_heads = is_new_heads(&params);
+        let subscriptions = join_all(self.inner.members.iter().map(|member| {
+            let params = params.clone();
+            async move { member.provider.subscribe::<_, Box<RawValue>>(params).await }
+        }))
+        .await;
+
+        if subscriptions.iter().all(|subscription| subscription.is_err()) {
+            let error = subscriptions.into_iter().find_map(|subscription| subscription.err());
+            let _ = items.send_response(&id, Err(error.unwrap_or_else(|| TransportErrorKind::custom_str("NO_CLIENTS_IN_POOL"))));
+            return;
+        }
+        let result = RawValue::from_string(format!("\"{subscription_id}\"")).map_err(TransportError::SerError);
+        if items.send_response(&id, result).is_err() {
+            return;
+        }
+
+        let seen = Mutex::new(SeenItems::new(SEEN_ITEMS_CAPACITY, new_heads));
+        let forwarders = subscriptions.into_iter().enumerate().map(|(member_idx, subscription)| {
+            self.forward_subscription(member_idx, subscription.ok(), &params, &subscription_id, &seen, &items)
+        });
+        join_all(forwarders).await;
+    }
+
+    async fn forward_subscription(
+        &self,
+        member_idx: usize,
+        mut subscription: Option<Subscription<Box<RawValue>>>,
+        params: &RawValue,
+        subscription_id: &str,
+        seen: &Mutex<SeenItems>,
+        items: &mpsc::UnboundedSender<PubSubItem>,
+    ) {
+        let member = &self.inner.members[member_idx];
+        loop {
+            if let Some(subscription) = subscription.take() {
+                let mut stream = subscription.into_stream();
+                while let Some(item) = stream.next().await {
+                    let is_new = seen.lock().map(|mut seen| seen.accept(&item)).unwrap_or(true);
+                    if is_new && items.send_notification(subscription_id, &item).is_err() {
+                        return;
+                    }
+                }
+                warn!(pool = self.name(), client = member.name, "Subscription ended, resubscribing");
+            }
+
+            tokio::time::sleep(self.inner.config.probe_interval).await;
+            if items.is_closed() {
+                return;
+            }
+            match member.provider.subscribe::<_, Box<RawValue>>(params.to_owned()).await {
+                Ok(new_subscription) => subscription = Some(new_subscription),
+                Err(e) => debug!(pool = self.name(), client = member.name, "Resubscribe failed : {e}"),
+            }
+        }
+    }
+}
+
+impl PubSubConnect for ClientPool {
+    fn is_local(&self) -> bool {
+        self.inner.members.iter().all(|member| member.provider.client().is_local())
+    }
+
+    async fn connect(&self) -> TransportResult<ConnectionHandle> {
+        let (handle, interface) = ConnectionHandle::new();
+        tokio::task::spawn(PoolBackend::new(self.clone(), interface).run());
+        Ok(handle)
+    }
+}
+
+async fn probe_worker(pool: Weak<ClientPoolInner>) {
+    loop {
+        let Some(inner) = pool.upgrade() else {
+            break;
+        };
+        let probe_interval = inner.config.probe_interval;
+        ClientPool { inner }.probe().await;
+        tokio::time::sleep(probe_interval).await;
+    }
+}
+
+fn route_order(states: &[MemberState], config: &ClientPoolConfig) -> Vec<usize> {
+    let best_head = states.iter().map(|state| state.head).max().unwrap_or_default();
+    let mut order: Vec<usize> = (0..states.len()).collect();
+    order.sort_by_key(|idx| {
+        let state = &states[*idx];
+        (!state.is_healthy(best_head, config), state.latency.unwrap_or(Duration::MAX))
+    });
+    order
+}
+
+/// Identity of a subscription item, the same block, transaction or log received from different nodes has the same key
+fn item_key(item: &RawValue) -> String {
+    match serde_json::from_str::<serde_json::Value>(item.get()) {
+        Ok(serde_json::Value::String(hash)) => hash.to_lowercase(),
+        Ok(serde_json::Value::Object(object)) if object.contains_key("hash") => object["hash"].as_str().unwrap_or_default().to_lowercase(),
+        Ok(serde_json::Value::Object(object)) if object.contains_key("logIndex") => {
+            format!("{}:{}:{}", object["blockHash"], object["logIndex"], object.get("removed").unwrap_or(&serde_json::Value::Bool(false)))
+                .to_lowercase()
+        }
+        _ => item.get().to_string(),
+    }
+}
+
+fn is_new_heads(params: &RawValue) -> bool {
+    serde_json::from_str::<Vec<serde_json::Value>>(params.get())
+        .ok()
+        .and_then(|params| params.first().and_then(|kind| kind.as_str().map(|kind| kind == "newHeads")))
+        .unwrap_or_default()
+}
+
+fn head_number(item: &RawValue) -> Option<u64> {
+    let header = serde_json::from_str::<serde_json::Value>(item.get()).ok()?;
+    let number = header.get("number")?.as_str()?;
+    u64::from_str_radix(number.trim_start_matches("0x"), 16).ok()
+}
+
+struct SeenItems {
+    capacity: usize,
+    keys: HashSet<String>,
+    order: VecDeque<String>,
+    /// Highest head forwarded, set for newHeads subscriptions only
+    best_head: Option<u64>,
+}
+
+impl SeenItems {
+    fn new(capacity: usize, new_heads: bool) -> Self {
+        Self { capacity, keys: HashSet::new(), order: VecDeque::new(), best_head: new_heads.then_some(0) }
+    }
+
+    /// Returns false if the item was seen already or is a head not above the highest head, as sent by a lagging client
+    fn accept(&mut self, item: &RawValue) -> bool {
+        if let (Some(best_head), Some(number)) = (self.best_head, head_number(item)) {
+            if number <= best_head {
+                return false;
+            }
+            self.best_head = Some(number);
+        }
+        self.insert(item_key(item))
+    }
+
+    /// Returns false if the key was seen already
+    fn insert(&mut self, key: String) -> bool {
+        if !self.keys.insert(key.clone()) {
+            return false;
+        }
+        self.order.push_back(key);
+        if self.order.len() > self.capacity {
+            if let Some(oldest) = self.order.pop_front() {
+                self.keys.remove(&oldest);
+            }
+        }
+        true
+    }
+}
+
+/// Sends JSON-RPC messages to the pubsub frontend
+trait FrontendSender {
+    fn send_item(&self, message: String) -> Result<(), ()>;
+
+    fn send_response(&self, id: &serde_json::Value, result: TransportResult<Box<RawValue>>) -> Result<(), ()> {
+        let message = match result {
+            Ok(result) => format!(r#"{{"jsonrpc":"2.0","id":{id},"result":{}}}"#, result.get()),
+            Err(TransportError::ErrorResp(payload)) => {
+                format!(r#"{{"jsonrpc":"2.0","id":{id},"error":{}}}"#, serde_json::to_string(&payload).map_err(|_| ())?)
+            }
+            Err(e) => serde_json::json!({"jsonrpc": "2.0", "id": id, "error": {"code": -32603, "message": e.to_string()}}).to_string(),
+        };
+        self.send_item(message)
+    }
+
+    fn send_notification(&self, subscription_id: &str, item: &RawValue) -> Result<(), ()> {
+        self.send_item(format!(
+            r#"{{"jsonrpc":"2.0","method":"eth_subscription","params":{{"subscription":"{subscription_id}","result":{}}}}}"#,
+            item.get()
+        ))
+    }
+}
+
+impl FrontendSender for mpsc::UnboundedSender<PubSubItem> {
+    fn send_item(&self, message: String) -> Result<(), ()> {
+        match serde_json::from_str::<PubSubItem>(&message) {
+            Ok(item) => self.send(item).map_err(|_| ()),
+            Err(e) => {
+                error!("Cannot build pubsub item : {e}");
+                Ok(())
+            }
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct FrontendRequest {
+    id: serde_json::Value,
+    method: String,
+    params: Option<Box<RawValue>>,
+}
+
+/// Backend of the pubsub connection, handles requests of the frontend with the pool
+struct PoolBackend {
+    pool: ClientPool,
+    interface: ConnectionInterface,
+    items_tx: mpsc::UnboundedSender<PubSubItem>,
+    items_rx: mpsc::UnboundedReceiver<PubSubItem>,
+    subscriptions: HashMap<String, JoinHandle<()>>,
+    next_subscription_id: u64,
+}
+
+impl PoolBackend {
+    fn new(pool: ClientPool, interface: ConnectionInterface) -> Self {
+        let (items_tx, items_rx) = mpsc::unbounded_channel();
+        Self { pool, interface, items_tx, items_rx, subscriptions: HashMap::new(), next_subscription_id: 1 }
+    }
+
+    async fn run(mut self) {
+        loop {
+            tokio::select! {
+                request = self.interface.recv_from_frontend() => match request {
+                    Some(request) => self.on_request(request),
+                    None => break,
+                },
+                Some(item) = self.items_rx.recv() => {
+                    if self.interface.send_to_frontend(item).is_err() {
+                        break;
+                    }
+                }
+            }
+        }
+        for (_, subscription) in self.subscriptions.drain() {
+            subscription.abort();
+        }
+        debug!(pool = self.pool.name(), "Pool connection closed");
+    }
+
+    fn on_request(&mut self, request: Box<RawValue>) {
+        let request: FrontendRequest = match serde_json::from_str(request.get()) {
+            Ok(request) => request,
+            Err(e) => {
+                error!(pool = self.pool.name(), "Cannot parse request {} : {e}", request.get());
+                return;
+            }
+        };
+        let params = match request.params {
+            Some(params) => params,
+            None => RawValue::from_string("[]".to_string()).unwrap(),
+        };
+
+        match request.method.as_str() {
+            "eth_subscribe" => {
+                let subscription_id = format!("{:#066x}", self.next_subscription_id);
+                self.next_subscription_id += 1;
+                let task = tokio::task::spawn(self.pool.clone().run_subscription(
+                    request.id,
+                    params,
+                    subscription_id.clone(),
+                    self.items_tx.clone(),
+                ));
+                self.subscriptions.insert(subscription_id, task);
+            }
+            "eth_unsubscribe" => {
+                let subscription_ids: Vec<String> = serde_json::from_str(params.get()).unwrap_or_default();
+                let mut found = false;
+                for subscription_id in subscription_ids {
+                    if let Some(subscription) = self.subscriptions.remove(&subscription_id) {
+                        subscription.abort();
+                        found = true;
+                    }
+                }
+                let _ =
+                    self.items_tx.send_response(&request.id, RawValue::from_string(found.to_string()).map_err(TransportError::SerError));
+            }
+            _ => {
+                let pool = self.pool.clone();
+                let items = self.items_tx.clone();
+                tokio::task::spawn(async move {
+                    let result = pool.request(&request.method, &params).await;
+                    let _ = items.send_response(&request.id, result);
+                });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn raw(json: &str) -> Box<RawValue> {
+        RawValue::from_string(json.to_string()).unwrap()
+    }
+
+    #[test]
+    fn test_route_order() {
+        let config = ClientPoolConfig::default();
+        let fast_lagging = MemberState { head: 97, latency: Some(Duration::from_millis(1)), ..MemberState::default() };
+        let slow = MemberState { head: 100, latency: Some(Duration::from_millis(50)), ..MemberState::default() };
+        let fast = MemberState { head: 99, latency: Some(Duration::from_millis(5)), ..MemberState::default() };
+        let failing = MemberState { head: 100, latency: Some(Duration::from_millis(2)), failures: 3, ..MemberState::default() };
+
+        assert_eq!(route_order(&[fast_lagging, slow, fast, failing], &config), vec![2, 1, 0, 3]);
+    }
+
+    #[test]
+    fn test_item_key_dedup() {
+        let mut seen = SeenItems::new(2, false);
+        assert!(seen.insert(item_key(&raw(r#"{"hash":"0xAB","number":"0x1"}"#))));
+        assert!(!seen.insert(item_key(&raw(r#"{"number":"0x1","hash":"0xab","extra":1}"#))));
+
+        assert!(seen.insert(item_key(&raw(r#"{"blockHash":"0x1","logIndex":"0x0"}"#))));
+        assert!(!seen.insert(item_key(&raw(r#"{"blockHash":"0x1","logIndex":"0x0","removed":false}"#))));
+        assert!(seen.insert(item_key(&raw(r#"{"blockHash":"0x1","logIndex":"0x0","removed":true}"#))));
+
+        // oldest keys are forgotten beyond capacity
+        assert!(seen.insert(item_key(&raw(r#"{"hash":"0xab"}"#))));
+    }
+
+    #[test]
+    fn test_new_heads_order() {
+        assert!(is_new_heads(&raw(r#"["newHeads"]"#)));
+        assert!(!is_new_heads(&raw(r#"["logs",{}]"#)));
+
+        let mut seen = SeenItems::new(10, true);
+        assert!(seen.accept(&raw(r#"{"hash":"0x2","number":"0x2"}"#)));
+        // a lagging client delivers an older head after a newer one
+        assert!(!seen.accept(&raw(r#"{"hash":"0x1","number":"0x1"}"#)));
+        assert!(!seen.accept(&raw(r#"{"hash":"0x2b","number":"0x2"}"#)));
+        assert!(seen.accept(&raw(r#"{"hash":"0x3","number":"0x3"}"#)));
+    }
+}
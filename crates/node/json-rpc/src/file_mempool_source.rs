@@ -0,0 +1,68 @@
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+use eyre::Result;
+use futures::StreamExt;
+use serde_json::Value;
+use tracing::warn;
+
+use crate::mempool_source_actor::{transaction_from_json, MempoolSource, PendingTxStream};
+
+/// Replays pending txes from a file, one rpc transaction object or raw tx hex string per line
+pub struct FileMempoolSource {
+    path: PathBuf,
+}
+
+impl FileMempoolSource {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+}
+
+#[async_trait]
+impl MempoolSource for FileMempoolSource {
+    async fn subscribe(&self) -> Result<PendingTxStream> {
+        let content = tokio::fs::read_to_string(&self.path).await?;
+        let mut txs = Vec::new();
+        for (idx, line) in content.lines().enumerate().filter(|(_, line)| !line.trim().is_empty()) {
+            let line = line.trim();
+            // raw txes may be written without quotes
+            let value = serde_json::from_str::<Value>(line).unwrap_or_else(|_| Value::String(line.to_string()));
+            match transaction_from_json(&value) {
+                Some(tx) => txs.push(tx),
+                None => warn!(path = %self.path.display(), line = idx + 1, "Cannot parse mempool tx"),
+            }
+        }
+        Ok(futures::stream::iter(txs).boxed())
+    }
+
+    fn reconnect(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloy_network::TransactionResponse;
+    use alloy_primitives::TxHash;
+    use std::str::FromStr;
+
+    #[tokio::test]
+    async fn test_file_source() -> Result<()> {
+        // eip-155 example tx from the spec
+        let raw_tx = "0xf86c098504a817c800825208943535353535353535353535353535353535353535880de0b6b3a76400008025a028ef61340bd939bc2195fe537567866003e1a15d3c71ff63e1590620aa636276a067cbe9d8997f761aecb703304b3800ccf555c9f3dc64214b297fb1966a3b6d83";
+        let nanos = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH)?.as_nanos();
+        let path = std::env::temp_dir().join(format!("loom_file_mempool_source_{}_{nanos}.txt", std::process::id()));
+        tokio::fs::write(&path, format!("{raw_tx}\n\nnot a tx\n\"{raw_tx}\"\n")).await?;
+
+        let source = FileMempoolSource::new(path.clone());
+        let txs: Vec<_> = source.subscribe().await?.collect().await;
+        tokio::fs::remove_file(&path).await?;
+
+        assert_eq!(txs.len(), 2);
+        assert_eq!(txs[0].tx_hash(), TxHash::from_str("0x33469b22e9f636356c4160a87eb19df52b7412e8eac32a4a55ffe88ea8350788")?);
+        assert!(!source.reconnect());
+        Ok(())
+    }
+}
@@ -0,0 +1,206 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use alloy_network::Ethereum;
+use alloy_primitives::BlockNumber;
+use alloy_provider::Provider;
+use tokio::time::{timeout, MissedTickBehavior};
+use tracing::{debug, error, info, warn};
+
+use loom_core_actors::{Accessor, Actor, ActorResult, Broadcaster, Producer, SharedState, ShutdownStage, WorkerResult};
+use loom_core_actors_macros::{Accessor, Producer};
+use loom_core_blockchain::Blockchain;
+use loom_types_blockchain::ChainParameters;
+use loom_types_entities::{HeadHealth, HeadStatus, LatestBlock};
+use loom_types_events::{HealthEvent, MessageHealthEvent};
+
+/// Lowest max block age, block timestamps have a second resolution and fast chains skip blocks without transactions
+const MIN_MAX_BLOCK_AGE: Duration = Duration::from_secs(5);
+
+#[derive(Clone, Debug)]
+pub struct HeadWatchdogConfig {
+    /// Blocks the local head may be behind the node head
+    pub max_head_lag: u64,
+    /// Age of the local head after which block delivery is considered stalled
+    pub max_block_age: Duration,
+    pub check_interval: Duration,
+}
+
+impl HeadWatchdogConfig {
+    /// About a second of lag but at least one block, three missed blocks but at least [`MIN_MAX_BLOCK_AGE`] and four checks per block
+    pub fn from_chain_parameters(chain_parameters: &ChainParameters) -> Self {
+        let block_time = chain_parameters.block_time.max(Duration::from_millis(1));
+        let max_head_lag = Duration::from_secs(1).as_millis().div_ceil(block_time.as_millis()).max(1) as u64;
+        Self {
+            max_head_lag,
+            max_block_age: (block_time * 3).max(MIN_MAX_BLOCK_AGE),
+            check_interval: (block_time / 4).max(Duration::from_millis(250)),
+        }
+    }
+}
+
+fn head_status(
+    config: &HeadWatchdogConfig,
+    local_head: BlockNumber,
+    node_head: Option<BlockNumber>,
+    block_age: Option<Duration>,
+) -> HeadStatus {
+    if node_head.is_some_and(|node_head| node_head > local_head + config.max_head_lag) {
+        HeadStatus::Lagging
+    } else if block_age.is_none_or(|block_age| block_age > config.max_block_age) {
+        HeadStatus::Stalled
+    } else {
+        HeadStatus::Fresh
+    }
+}
+
+/// Compares the local head with the node head and the wall clock, updates [`HeadHealth`] and reports status changes.
+async fn head_watchdog_worker<P>(
+    client: Option<P>,
+    config: HeadWatchdogConfig,
+    latest_block: SharedState<LatestBlock>,
+    head_health: SharedState<HeadHealth>,
+    health_monitor_channel_tx: Option<Broadcaster<MessageHealthEvent>>,
+) -> WorkerResult
+where
+    P: Provider<Ethereum> + Send + Sync + Clone + 'static,
+{
+    info!(?config, "Head watchdog started");
+    let mut interval = tokio::time::interval(config.check_interval);
+    interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+
+    loop {
+        interval.tick().await;
+
+        // an unreachable node is not a reason to pause, stalled blocks are detected by their age
+        let node_head = match &client {
+            Some(client) => match timeout(config.check_interval, client.get_block_number()).await {
+                Ok(Ok(node_head)) => Some(node_head),
+                Ok(Err(e)) => {
+                    debug!("Cannot get node head : {e}");
+                    None
+                }
+                Err(_) => {
+                    debug!("Node head request timed out");
+                    None
+                }
+            },
+            None => None,
+        };
+
+        let (local_head, timestamp) = {
+            let latest_block = latest_block.read().await;
+            (latest_block.block_number, latest_block.block_header.as_ref().map(|header| header.timestamp))
+        };
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+        let block_age = timestamp.map(|timestamp| Duration::from_secs(now.saturating_sub(timestamp)));
+
+        let status = head_status(&config, local_head, node_head, block_age);
+        let health = HeadHealth { status, local_head, node_head, block_age };
+
+        let previous_status = {
+            let mut head_health = head_health.write().await;
+            std::mem::replace(&mut *head_health, health.clone()).status
+        };
+
+        if status != previous_status {
+            match status {
+                HeadStatus::Fresh => info!(local_head, ?node_head, ?block_age, "Head is fresh, trading resumed"),
+                _ => warn!(?status, local_head, ?node_head, ?block_age, "Head is not fresh, trading paused"),
+            }
+            if let Some(health_monitor_channel_tx) = &health_monitor_channel_tx {
+                if let Err(e) = health_monitor_channel_tx.send(MessageHealthEvent::new(HealthEvent::HeadStatusChanged(health))) {
+                    error!("health_monitor_channel_tx.send error : {e}");
+                }
+            }
+        }
+    }
+}
+
+/// Continuous counterpart of [`crate::WaitForNodeSyncOneShotBlockingActor`]. Router and broadcaster skip swaps while the head is not fresh.
+#[derive(Accessor, Producer)]
+pub struct HeadWatchdogActor<P> {
+    client: Option<P>,
+    config: Option<HeadWatchdogConfig>,
+    #[accessor]
+    latest_block: Option<SharedState<LatestBlock>>,
+    #[accessor]
+    head_health: Option<SharedState<HeadHealth>>,
+    #[producer]
+    health_monitor_channel_tx: Option<Broadcaster<MessageHealthEvent>>,
+}
+
+impl<P> HeadWatchdogActor<P>
+where
+    P: Provider<Ethereum> + Send + Sync + Clone + 'static,
+{
+    pub fn new(client: P) -> HeadWatchdogActor<P> {
+        Self::new_with_provider(Some(client))
+    }
+
+    /// Without a client only the age of the local head is checked, as for blocks received from an ExEx
+    pub fn new_with_provider(client: Option<P>) -> HeadWatchdogActor<P> {
+        HeadWatchdogActor { client, config: None, latest_block: None, head_health: None, health_monitor_channel_tx: None }
+    }
+
+    pub fn with_config(self, config: HeadWatchdogConfig) -> Self {
+        Self { config: Some(config), ..self }
+    }
+
+    /// Thresholds default to the block time of the chain
+    pub fn on_bc(self, bc: &Blockchain) -> Self {
+        Self {
+            config: Some(self.config.unwrap_or_else(|| HeadWatchdogConfig::from_chain_parameters(&bc.chain_parameters()))),
+            latest_block: Some(bc.latest_block()),
+            head_health: Some(bc.head_health()),
+            health_monitor_channel_tx: Some(bc.health_monitor_channel()),
+            ..self
+        }
+    }
+}
+
+impl<P> Actor for HeadWatchdogActor<P>
+where
+    P: Provider<Ethereum> + Send + Sync + Clone + 'static,
+{
+    fn start(&self) -> ActorResult {
+        let task = tokio::task::spawn(head_watchdog_worker(
+            self.client.clone(),
+            self.config.clone().unwrap_or_else(|| HeadWatchdogConfig::from_chain_parameters(&ChainParameters::ethereum())),
+            self.latest_block.clone().unwrap(),
+            self.head_health.clone().unwrap(),
+            self.health_monitor_channel_tx.clone(),
+        ));
+        Ok(vec![task])
+    }
+
+    fn shutdown_stage(&self) -> ShutdownStage {
+        ShutdownStage::Producer
+    }
+
+    fn name(&self) -> &'static str {
+        "HeadWatchdogActor"
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_head_status() {
+        let config = HeadWatchdogConfig::from_chain_parameters(&ChainParameters::ethereum());
+        let age = |secs| Some(Duration::from_secs(secs));
+
+        assert_eq!(head_status(&config, 100, Some(101), age(5)), HeadStatus::Fresh);
+        assert_eq!(head_status(&config, 100, None, age(5)), HeadStatus::Fresh);
+        assert_eq!(head_status(&config, 100, Some(102), age(5)), HeadStatus::Lagging);
+        assert_eq!(head_status(&config, 100, Some(100), age(37)), HeadStatus::Stalled);
+        assert_eq!(head_status(&config, 0, None, None), HeadStatus::Stalled);
+
+        let config = HeadWatchdogConfig::from_chain_parameters(&ChainParameters::arbitrum());
+        assert_eq!(config.max_head_lag, 4);
+        assert_eq!(config.max_block_age, MIN_MAX_BLOCK_AGE);
+        assert_eq!(head_status(&config, 100, Some(104), age(2)), HeadStatus::Fresh);
+        assert_eq!(head_status(&config, 100, Some(105), age(2)), HeadStatus::Lagging);
+    }
+}
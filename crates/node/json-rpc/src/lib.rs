@@ -1,12 +1,22 @@
+pub use client_pool::{ClientHealth, ClientPool, ClientPoolConfig};
+pub use file_mempool_source::FileMempoolSource;
+pub use head_watchdog_actor::{HeadWatchdogActor, HeadWatchdogConfig};
+pub use mempool_source_actor::{MempoolSource, MempoolSourceActor, PendingTxStream};
 pub use node_block_actor::NodeBlockActor;
-pub use node_mempool_actor::NodeMempoolActor;
+pub use node_mempool_source::NodeMempoolSource;
 pub use wait_for_node_sync_actor::WaitForNodeSyncOneShotBlockingActor;
+pub use ws_mempool_source::{BloxrouteMempoolSource, WsMempoolSource};
 
+mod client_pool;
+mod file_mempool_source;
+mod head_watchdog_actor;
+mod mempool_source_actor;
 mod node_block_actor;
 mod node_block_hash_worker;
 mod node_block_logs_worker;
 mod node_block_state_worker;
 mod node_block_with_tx_worker;
-mod node_mempool_actor;
+mod node_mempool_source;
 
 mod wait_for_node_sync_actor;
+mod ws_mempool_source;
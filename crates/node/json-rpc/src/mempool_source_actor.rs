@@ -0,0 +1,119 @@
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use alloy_network::TransactionResponse;
+use alloy_primitives::Bytes;
+use alloy_rpc_types::Transaction;
+use async_trait::async_trait;
+use eyre::Result;
+use futures::stream::BoxStream;
+use futures::StreamExt;
+use serde_json::Value;
+use tracing::{error, info, warn};
+
+use loom_core_actors::{Actor, ActorResult, Broadcaster, Producer, ShutdownStage, WorkerResult};
+use loom_core_actors_macros::*;
+use loom_core_blockchain::Blockchain;
+use loom_evm_utils::reth_types::decode_into_transaction;
+use loom_types_blockchain::{LoomDataTypesEthereum, MempoolTx};
+use loom_types_events::{MessageMempoolDataUpdate, NodeMempoolDataUpdate};
+
+const RECONNECT_DELAY: Duration = Duration::from_secs(1);
+
+pub type PendingTxStream = BoxStream<'static, Transaction>;
+
+/// Feed of pending transactions. The stream ends when the feed disconnects.
+#[async_trait]
+pub trait MempoolSource: Send + Sync + 'static {
+    async fn subscribe(&self) -> Result<PendingTxStream>;
+
+    /// Live feeds are resubscribed after they end, finite sources like files are not
+    fn reconnect(&self) -> bool {
+        true
+    }
+}
+
+/// Parses a pending tx given either as rpc transaction object or as hex encoded raw tx
+pub(crate) fn transaction_from_json(value: &Value) -> Option<Transaction> {
+    match value {
+        Value::String(raw_tx) => {
+            let raw_tx = Bytes::from_str(raw_tx).ok()?;
+            decode_into_transaction(&raw_tx).ok()
+        }
+        Value::Object(_) => serde_json::from_value(value.clone()).ok(),
+        _ => None,
+    }
+}
+
+/// Worker forwards txes of the source as [`MessageMempoolDataUpdate`] tagged with the source name.
+pub async fn mempool_source_worker<S: MempoolSource>(
+    source: Arc<S>,
+    name: String,
+    mempool_tx: Broadcaster<MessageMempoolDataUpdate>,
+) -> WorkerResult {
+    loop {
+        match source.subscribe().await {
+            Ok(mut stream) => {
+                info!(%name, "Mempool source subscribed");
+                while let Some(tx) = stream.next().await {
+                    let tx_hash = tx.tx_hash();
+                    let update_msg: MessageMempoolDataUpdate = MessageMempoolDataUpdate::new_with_source(
+                        NodeMempoolDataUpdate { tx_hash, mempool_tx: MempoolTx { tx: Some(tx), ..MempoolTx::default() } },
+                        name.clone(),
+                    );
+                    if let Err(e) = mempool_tx.send(update_msg) {
+                        error!("mempool_tx.send error : {}", e);
+                        return Ok(name);
+                    }
+                }
+                warn!(%name, "Mempool source stream ended");
+            }
+            Err(e) => {
+                error!(%name, "Mempool source subscription failed : {}", e);
+            }
+        }
+        if !source.reconnect() {
+            break Ok(name);
+        }
+        tokio::time::sleep(RECONNECT_DELAY).await;
+    }
+}
+
+#[derive(Producer)]
+pub struct MempoolSourceActor<S> {
+    /// Source name of the mempool updates, the config key of the actor
+    name: String,
+    source: Arc<S>,
+    #[producer]
+    mempool_tx: Option<Broadcaster<MessageMempoolDataUpdate>>,
+}
+
+impl<S: MempoolSource> MempoolSourceActor<S> {
+    pub fn new(source: S) -> MempoolSourceActor<S> {
+        MempoolSourceActor { name: "MempoolSourceActor".to_string(), source: Arc::new(source), mempool_tx: None }
+    }
+
+    pub fn with_name(self, name: String) -> Self {
+        Self { name, ..self }
+    }
+
+    pub fn on_bc(self, bc: &Blockchain<LoomDataTypesEthereum>) -> Self {
+        Self { mempool_tx: Some(bc.new_mempool_tx_channel()), ..self }
+    }
+}
+
+impl<S: MempoolSource> Actor for MempoolSourceActor<S> {
+    fn start(&self) -> ActorResult {
+        let task = tokio::task::spawn(mempool_source_worker(self.source.clone(), self.name.clone(), self.mempool_tx.clone().unwrap()));
+        Ok(vec![task])
+    }
+
+    fn shutdown_stage(&self) -> ShutdownStage {
+        ShutdownStage::Producer
+    }
+
+    fn name(&self) -> &'static str {
+        "MempoolSourceActor"
+    }
+}
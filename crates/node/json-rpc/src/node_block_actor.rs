@@ -6,7 +6,7 @@ use crate::node_block_hash_worker::new_node_block_header_worker;
 use crate::node_block_logs_worker::new_node_block_logs_worker;
 use crate::node_block_state_worker::new_node_block_state_worker;
 use crate::node_block_with_tx_worker::new_block_with_tx_worker;
-use loom_core_actors::{Actor, ActorResult, Broadcaster, Producer, WorkerResult};
+use loom_core_actors::{Actor, ActorResult, Broadcaster, Producer, ShutdownStage, WorkerResult};
 use loom_core_actors_macros::Producer;
 use loom_core_blockchain::Blockchain;
 use loom_node_actor_config::NodeBlockActorConfig;
@@ -103,6 +103,10 @@ where
             self.block_state_update_channel.clone(),
         )
     }
+    fn shutdown_stage(&self) -> ShutdownStage {
+        ShutdownStage::Producer
+    }
+
     fn name(&self) -> &'static str {
         self.name()
     }
@@ -0,0 +1,28 @@
+use alloy_network::Ethereum;
+use alloy_provider::Provider;
+use async_trait::async_trait;
+use futures::StreamExt;
+
+use crate::mempool_source_actor::{MempoolSource, PendingTxStream};
+
+/// Node `eth_subscribe` feed as [`MempoolSource`]
+pub struct NodeMempoolSource<P> {
+    client: P,
+}
+
+impl<P> NodeMempoolSource<P> {
+    pub fn new(client: P) -> Self {
+        Self { client }
+    }
+}
+
+#[async_trait]
+impl<P> MempoolSource for NodeMempoolSource<P>
+where
+    P: Provider<Ethereum> + Send + Sync + 'static,
+{
+    async fn subscribe(&self) -> eyre::Result<PendingTxStream> {
+        let mempool_subscription = self.client.subscribe_full_pending_transactions().await?;
+        Ok(mempool_subscription.into_stream().boxed())
+    }
+}
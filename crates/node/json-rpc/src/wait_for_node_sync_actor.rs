@@ -1,7 +1,6 @@
 use alloy_network::Ethereum;
 use alloy_provider::Provider;
 use alloy_rpc_types::SyncStatus;
-use eyre::eyre;
 use loom_core_actors::{Actor, ActorResult, WorkerResult};
 use loom_node_debug_provider::DebugProviderExt;
 use std::time::Duration;
@@ -64,19 +63,9 @@ impl<P> Actor for WaitForNodeSyncOneShotBlockingActor<P>
 where
     P: Provider<Ethereum> + DebugProviderExt<Ethereum> + Send + Sync + Clone + 'static,
 {
-    fn start_and_wait(&self) -> eyre::Result<()> {
-        let rt = tokio::runtime::Runtime::new()?; // we need a different runtime to wait for the result
-        let client_cloned = self.client.clone();
-        let handle = rt.spawn(async { wait_for_node_sync_one_shot_worker(client_cloned).await });
-
-        self.wait(Ok(vec![handle]))?;
-        rt.shutdown_background();
-
-        Ok(())
-    }
-
     fn start(&self) -> ActorResult {
-        Err(eyre!("NEED_TO_BE_WAITED"))
+        let task = tokio::task::spawn(wait_for_node_sync_one_shot_worker(self.client.clone()));
+        Ok(vec![task])
     }
 
     fn name(&self) -> &'static str {
@@ -0,0 +1,87 @@
+use alloy_rpc_types::Transaction;
+use async_trait::async_trait;
+use eyre::Result;
+use futures::{future, SinkExt, StreamExt};
+use serde_json::{json, Value};
+use tokio_tungstenite::connect_async;
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::http::header::AUTHORIZATION;
+use tokio_tungstenite::tungstenite::http::HeaderValue;
+use tokio_tungstenite::tungstenite::Message;
+
+use crate::mempool_source_actor::{transaction_from_json, MempoolSource, PendingTxStream};
+
+/// Connects to a WebSocket feed, sends the subscription request and maps every notification with `parse`
+async fn ws_pending_tx_stream(
+    url: &str,
+    auth_header: Option<&str>,
+    subscribe_request: Value,
+    parse: fn(&Value) -> Option<Transaction>,
+) -> Result<PendingTxStream> {
+    let mut request = url.into_client_request()?;
+    if let Some(auth_header) = auth_header {
+        request.headers_mut().insert(AUTHORIZATION, HeaderValue::from_str(auth_header)?);
+    }
+    let (mut ws_stream, _) = connect_async(request).await?;
+    ws_stream.send(Message::text(subscribe_request.to_string())).await?;
+
+    let stream = ws_stream.take_while(|msg| future::ready(msg.is_ok())).filter_map(move |msg| {
+        let tx = match msg {
+            Ok(Message::Text(text)) => serde_json::from_str::<Value>(text.as_str()).ok().and_then(|value| parse(&value)),
+            _ => None,
+        };
+        future::ready(tx)
+    });
+    Ok(stream.boxed())
+}
+
+/// Node style `eth_subscribe` feed of full pending txes over a raw WebSocket, e.g. a devp2p listener.
+/// Notifications may carry rpc transaction objects or raw tx hex.
+pub struct WsMempoolSource {
+    url: String,
+    auth_header: Option<String>,
+}
+
+impl WsMempoolSource {
+    pub fn new(url: String) -> Self {
+        Self { url, auth_header: None }
+    }
+
+    pub fn with_auth_header(self, auth_header: Option<String>) -> Self {
+        Self { auth_header, ..self }
+    }
+}
+
+#[async_trait]
+impl MempoolSource for WsMempoolSource {
+    async fn subscribe(&self) -> Result<PendingTxStream> {
+        let subscribe_request = json!({"jsonrpc": "2.0", "id": 1, "method": "eth_subscribe", "params": ["newPendingTransactions", true]});
+        ws_pending_tx_stream(&self.url, self.auth_header.as_deref(), subscribe_request, |value| {
+            transaction_from_json(&value["params"]["result"])
+        })
+        .await
+    }
+}
+
+/// bloXroute style `newTxs` stream, txes are delivered as raw tx hex
+pub struct BloxrouteMempoolSource {
+    url: String,
+    auth_header: String,
+}
+
+impl BloxrouteMempoolSource {
+    pub fn new(url: String, auth_header: String) -> Self {
+        Self { url, auth_header }
+    }
+}
+
+#[async_trait]
+impl MempoolSource for BloxrouteMempoolSource {
+    async fn subscribe(&self) -> Result<PendingTxStream> {
+        let subscribe_request = json!({"jsonrpc": "2.0", "id": 1, "method": "subscribe", "params": ["newTxs", {"include": ["raw_tx"]}]});
+        ws_pending_tx_stream(&self.url, Some(&self.auth_header), subscribe_request, |value| {
+            transaction_from_json(&value["params"]["result"]["rawTx"])
+        })
+        .await
+    }
+}
@@ -1,6 +1,12 @@
 pub use actor::NodeBlockPlayerActor;
+pub use log_player::MempoolLogPlayerActor;
+pub use recorder::MempoolRecorderActor;
+pub use recording::{PlayerMessage, RecordEntry, RecordedEvent};
 
 mod actor;
 mod compose;
+mod log_player;
 mod mempool;
+mod recorder;
+mod recording;
 mod worker;
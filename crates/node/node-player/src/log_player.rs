@@ -0,0 +1,172 @@
+use std::path::PathBuf;
+use std::time::Duration;
+
+use tokio::fs::File;
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::time::Instant;
+use tokio_util::sync::CancellationToken;
+use tracing::{info, warn};
+
+use crate::recording::{PlayerMessage, RecordEntry};
+use loom_core_actors::{run_sync, Actor, ActorResult, Broadcaster, Producer, ShutdownStage, WorkerResult};
+use loom_core_actors_macros::Producer;
+use loom_core_blockchain::Blockchain;
+use loom_types_events::{MessageBlock, MessageBlockHeader, MessageBlockLogs, MessageBlockStateUpdate, MessageMempoolDataUpdate};
+
+/// Longest wait for the consumers of a half full channel, a subscriber that never receives must not stop the replay
+const MAX_BACKPRESSURE_WAIT: Duration = Duration::from_secs(1);
+
+/// Waits until the consumers received half of the channel and sends the message
+async fn send<T: Clone + Send + Sync + 'static>(channel: &Option<Broadcaster<T>>, msg: T) {
+    if let Some(channel) = channel {
+        let started_at = Instant::now();
+        while channel.depth() * 2 >= channel.capacity() && started_at.elapsed() < MAX_BACKPRESSURE_WAIT {
+            tokio::time::sleep(Duration::from_millis(1)).await;
+        }
+        run_sync!(channel.send(msg));
+    }
+}
+
+fn lagged<T: Clone + Send + Sync + 'static>(channel: &Option<Broadcaster<T>>) -> u64 {
+    channel.as_ref().map(|channel| channel.snapshot().lagged).unwrap_or_default()
+}
+
+/// Worker replays a recording into the channels. Delays between messages are divided by `speed`, with zero speed messages are sent
+/// as fast as the consumers receive them. Messages lost by lagging consumers are reported on every block header.
+#[allow(clippy::too_many_arguments)]
+pub async fn mempool_log_player_worker(
+    path: PathBuf,
+    speed: f64,
+    mempool_tx: Option<Broadcaster<MessageMempoolDataUpdate>>,
+    block_header_tx: Option<Broadcaster<MessageBlockHeader>>,
+    block_with_tx_tx: Option<Broadcaster<MessageBlock>>,
+    block_logs_tx: Option<Broadcaster<MessageBlockLogs>>,
+    block_state_update_tx: Option<Broadcaster<MessageBlockStateUpdate>>,
+    shutdown_token: CancellationToken,
+) -> WorkerResult {
+    let file = File::open(&path).await?;
+    let mut lines = BufReader::new(file).lines();
+    info!(path = %path.display(), speed, "Mempool log player started");
+
+    let mut start: Option<(i64, Instant)> = None;
+    let mut entries = 0usize;
+    let mut total_lagged = 0u64;
+
+    while let Some(line) = lines.next_line().await? {
+        if shutdown_token.is_cancelled() {
+            return Ok(format!("Mempool log player stopped, {entries} messages replayed"));
+        }
+        if line.trim().is_empty() {
+            continue;
+        }
+        let entry: RecordEntry = serde_json::from_str(&line)?;
+
+        if speed > 0.0 {
+            let (start_time_us, start_instant) = *start.get_or_insert((entry.time_us, Instant::now()));
+            let offset_us = (entry.time_us - start_time_us).max(0) as f64 / speed;
+            tokio::time::sleep_until(start_instant + Duration::from_micros(offset_us as u64)).await;
+        }
+
+        match entry.event.into_message()? {
+            PlayerMessage::Mempool(msg) => send(&mempool_tx, msg).await,
+            PlayerMessage::BlockHeader(msg) => {
+                let channels_lagged = lagged(&mempool_tx)
+                    + lagged(&block_header_tx)
+                    + lagged(&block_with_tx_tx)
+                    + lagged(&block_logs_tx)
+                    + lagged(&block_state_update_tx);
+                if channels_lagged > total_lagged {
+                    warn!(lost = channels_lagged - total_lagged, "Consumers lagged, replayed messages were lost");
+                    total_lagged = channels_lagged;
+                }
+                send(&block_header_tx, msg).await
+            }
+            PlayerMessage::Block(msg) => send(&block_with_tx_tx, msg).await,
+            PlayerMessage::BlockLogs(msg) => send(&block_logs_tx, msg).await,
+            PlayerMessage::BlockStateUpdate(msg) => send(&block_state_update_tx, msg).await,
+        }
+        entries += 1;
+        if speed <= 0.0 {
+            tokio::task::yield_now().await;
+        }
+    }
+
+    Ok(format!("Mempool log player finished, {entries} messages replayed"))
+}
+
+/// Replays a recording of [`crate::MempoolRecorderActor`]. Only channel messages are recorded, pool states and simulations are read
+/// from the client at replay time, so a replay reproduces the recorded run only against a node at the recorded blocks.
+#[derive(Producer)]
+pub struct MempoolLogPlayerActor {
+    path: PathBuf,
+    speed: f64,
+    #[producer]
+    mempool_tx: Option<Broadcaster<MessageMempoolDataUpdate>>,
+    #[producer]
+    block_header_tx: Option<Broadcaster<MessageBlockHeader>>,
+    #[producer]
+    block_with_tx_tx: Option<Broadcaster<MessageBlock>>,
+    #[producer]
+    block_logs_tx: Option<Broadcaster<MessageBlockLogs>>,
+    #[producer]
+    block_state_update_tx: Option<Broadcaster<MessageBlockStateUpdate>>,
+}
+
+impl MempoolLogPlayerActor {
+    /// Player at the original speed
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            speed: 1.0,
+            mempool_tx: None,
+            block_header_tx: None,
+            block_with_tx_tx: None,
+            block_logs_tx: None,
+            block_state_update_tx: None,
+        }
+    }
+
+    /// Speed multiplier, e.g. 10.0 replays ten times faster and 0.0 as fast as possible
+    pub fn with_speed(self, speed: f64) -> Self {
+        Self { speed, ..self }
+    }
+
+    pub fn on_bc(self, bc: &Blockchain) -> Self {
+        Self {
+            mempool_tx: Some(bc.new_mempool_tx_channel()),
+            block_header_tx: Some(bc.new_block_headers_channel()),
+            block_with_tx_tx: Some(bc.new_block_with_tx_channel()),
+            block_logs_tx: Some(bc.new_block_logs_channel()),
+            block_state_update_tx: Some(bc.new_block_state_update_channel()),
+            ..self
+        }
+    }
+}
+
+impl Actor for MempoolLogPlayerActor {
+    fn start(&self) -> ActorResult {
+        self.start_until(CancellationToken::new())
+    }
+
+    fn start_until(&self, shutdown_token: CancellationToken) -> ActorResult {
+        let task = tokio::task::spawn(mempool_log_player_worker(
+            self.path.clone(),
+            self.speed,
+            self.mempool_tx.clone(),
+            self.block_header_tx.clone(),
+            self.block_with_tx_tx.clone(),
+            self.block_logs_tx.clone(),
+            self.block_state_update_tx.clone(),
+            shutdown_token,
+        ));
+        Ok(vec![task])
+    }
+
+    fn shutdown_stage(&self) -> ShutdownStage {
+        ShutdownStage::Producer
+    }
+
+    fn name(&self) -> &'static str {
+        "MempoolLogPlayerActor"
+    }
+}
@@ -0,0 +1,146 @@
+use std::path::PathBuf;
+
+use eyre::{eyre, Result};
+use tokio::fs::OpenOptions;
+use tokio::io::{AsyncWriteExt, BufWriter};
+use tokio::sync::broadcast::error::RecvError;
+use tokio_util::sync::CancellationToken;
+use tracing::{info, warn};
+
+use crate::recording::{RecordEntry, RecordedEvent};
+use loom_core_actors::{subscribe, Actor, ActorResult, Broadcaster, Consumer, ShutdownStage, WorkerResult};
+use loom_core_actors_macros::Consumer;
+use loom_core_blockchain::Blockchain;
+use loom_types_events::{MessageBlock, MessageBlockHeader, MessageBlockLogs, MessageBlockStateUpdate, MessageMempoolDataUpdate};
+
+/// Skips lagged messages, a closed channel stops the recorder
+fn received<T>(msg: Result<T, RecvError>, channel: &str) -> Result<Option<T>> {
+    match msg {
+        Ok(msg) => Ok(Some(msg)),
+        Err(RecvError::Lagged(lag)) => {
+            warn!(channel, lag, "Recorder lagged, messages are missing in the recording");
+            Ok(None)
+        }
+        Err(RecvError::Closed) => Err(eyre!("RECORDER_CHANNEL_CLOSED")),
+    }
+}
+
+/// Worker appends mempool and block messages to `path`, one [`RecordEntry`] json per line. The file is flushed on every block header
+/// and when the worker stops.
+pub async fn mempool_recorder_worker(
+    path: PathBuf,
+    mempool_update_rx: Broadcaster<MessageMempoolDataUpdate>,
+    block_header_rx: Broadcaster<MessageBlockHeader>,
+    block_with_tx_rx: Broadcaster<MessageBlock>,
+    block_logs_rx: Broadcaster<MessageBlockLogs>,
+    block_state_update_rx: Broadcaster<MessageBlockStateUpdate>,
+    shutdown_token: CancellationToken,
+) -> WorkerResult {
+    subscribe!(mempool_update_rx);
+    subscribe!(block_header_rx);
+    subscribe!(block_with_tx_rx);
+    subscribe!(block_logs_rx);
+    subscribe!(block_state_update_rx);
+
+    let file = OpenOptions::new().create(true).append(true).open(&path).await?;
+    let mut writer = BufWriter::new(file);
+    info!(path = %path.display(), "Mempool recorder started");
+
+    let mut entries = 0usize;
+    let result: WorkerResult = async {
+        loop {
+            let entry = tokio::select! {
+                msg = mempool_update_rx.recv() => received(msg, "mempool")?.map(|msg| RecordEntry::from_mempool_update(&msg)),
+                msg = block_header_rx.recv() => received(msg, "block_header")?.map(|msg| RecordEntry::from_block_header(&msg)),
+                msg = block_with_tx_rx.recv() => received(msg, "block_with_tx")?.map(|msg| RecordEntry::from_block(&msg)),
+                msg = block_logs_rx.recv() => received(msg, "block_logs")?.map(|msg| RecordEntry::from_block_logs(&msg)),
+                msg = block_state_update_rx.recv() => {
+                    received(msg, "block_state_update")?.map(|msg| RecordEntry::from_block_state_update(&msg))
+                }
+                _ = shutdown_token.cancelled() => break Ok(format!("Mempool recorder stopped, {entries} entries written")),
+            };
+            let Some(entry) = entry else {
+                continue;
+            };
+
+            let mut line = serde_json::to_vec(&entry)?;
+            line.push(b'\n');
+            writer.write_all(&line).await?;
+            entries += 1;
+            if matches!(entry.event, RecordedEvent::BlockHeader { .. }) {
+                writer.flush().await?;
+            }
+        }
+    }
+    .await;
+
+    // entries since the last block header are still buffered
+    writer.flush().await?;
+    result
+}
+
+#[derive(Consumer)]
+pub struct MempoolRecorderActor {
+    path: PathBuf,
+    #[consumer]
+    mempool_update_rx: Option<Broadcaster<MessageMempoolDataUpdate>>,
+    #[consumer]
+    block_header_rx: Option<Broadcaster<MessageBlockHeader>>,
+    #[consumer]
+    block_with_tx_rx: Option<Broadcaster<MessageBlock>>,
+    #[consumer]
+    block_logs_rx: Option<Broadcaster<MessageBlockLogs>>,
+    #[consumer]
+    block_state_update_rx: Option<Broadcaster<MessageBlockStateUpdate>>,
+}
+
+impl MempoolRecorderActor {
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            mempool_update_rx: None,
+            block_header_rx: None,
+            block_with_tx_rx: None,
+            block_logs_rx: None,
+            block_state_update_rx: None,
+        }
+    }
+
+    pub fn on_bc(self, bc: &Blockchain) -> Self {
+        Self {
+            mempool_update_rx: Some(bc.new_mempool_tx_channel()),
+            block_header_rx: Some(bc.new_block_headers_channel()),
+            block_with_tx_rx: Some(bc.new_block_with_tx_channel()),
+            block_logs_rx: Some(bc.new_block_logs_channel()),
+            block_state_update_rx: Some(bc.new_block_state_update_channel()),
+            ..self
+        }
+    }
+}
+
+impl Actor for MempoolRecorderActor {
+    fn start(&self) -> ActorResult {
+        self.start_until(CancellationToken::new())
+    }
+
+    fn start_until(&self, shutdown_token: CancellationToken) -> ActorResult {
+        let task = tokio::task::spawn(mempool_recorder_worker(
+            self.path.clone(),
+            self.mempool_update_rx.clone().unwrap(),
+            self.block_header_rx.clone().unwrap(),
+            self.block_with_tx_rx.clone().unwrap(),
+            self.block_logs_rx.clone().unwrap(),
+            self.block_state_update_rx.clone().unwrap(),
+            shutdown_token,
+        ));
+        Ok(vec![task])
+    }
+
+    fn shutdown_stage(&self) -> ShutdownStage {
+        ShutdownStage::Writer
+    }
+
+    fn name(&self) -> &'static str {
+        "MempoolRecorderActor"
+    }
+}
@@ -0,0 +1,235 @@
+use alloy_consensus::{Transaction as _, TxEnvelope};
+use alloy_eips::eip2718::{Decodable2718, Encodable2718};
+use alloy_eips::eip4895::Withdrawals;
+use alloy_primitives::{Address, Bytes, TxHash, B256};
+use alloy_rpc_types::{Block, BlockTransactions, Header, Log, Transaction};
+use chrono::Utc;
+use eyre::Result;
+use loom_types_blockchain::{GethStateUpdate, GethStateUpdateVec, MempoolTx};
+use loom_types_events::{
+    BlockHeader, BlockLogs, BlockStateUpdate, BlockUpdate, Message, MessageBlock, MessageBlockHeader, MessageBlockLogs,
+    MessageBlockStateUpdate, MessageMempoolDataUpdate, NodeMempoolDataUpdate,
+};
+use serde::{Deserialize, Serialize};
+
+/// One line of a recording
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RecordEntry {
+    /// Unix time in microseconds the message was received
+    #[serde(rename = "t")]
+    pub time_us: i64,
+    #[serde(rename = "e")]
+    pub event: RecordedEvent,
+}
+
+/// Raw EIP-2718 tx with its sender
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RecordedTx {
+    pub raw: Bytes,
+    pub from: Address,
+}
+
+impl RecordedTx {
+    fn new(tx: &Transaction) -> Self {
+        Self { raw: Bytes::from(tx.inner.encoded_2718()), from: tx.from }
+    }
+
+    fn into_block_transaction(self, header: &Header, index: usize) -> Result<Transaction> {
+        let inner = TxEnvelope::decode_2718(&mut self.raw.as_ref())?;
+        let effective_gas_price = Some(inner.effective_gas_price(header.base_fee_per_gas));
+        Ok(Transaction {
+            inner,
+            block_hash: Some(header.hash),
+            block_number: Some(header.number),
+            transaction_index: Some(index as u64),
+            effective_gas_price,
+            from: self.from,
+        })
+    }
+}
+
+/// Recorded channel message. Mempool and block txes are stored as raw EIP-2718 bytes with the sender to keep the log compact.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "k", rename_all = "snake_case")]
+pub enum RecordedEvent {
+    MempoolTx {
+        source: String,
+        tx_hash: TxHash,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        raw_tx: Option<Bytes>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        from: Option<Address>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        logs: Option<Vec<Log>>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        state_update: Option<GethStateUpdate>,
+    },
+    BlockHeader {
+        header: Header,
+    },
+    /// Txes of a block received with tx hashes only are not recorded
+    Block {
+        header: Header,
+        txs: Vec<RecordedTx>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        uncles: Vec<B256>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        withdrawals: Option<Withdrawals>,
+    },
+    BlockLogs {
+        header: Header,
+        logs: Vec<Log>,
+    },
+    BlockStateUpdate {
+        header: Header,
+        state_update: GethStateUpdateVec,
+    },
+}
+
+fn time_us<T>(msg: &Message<T>) -> i64 {
+    msg.time.unwrap_or_else(Utc::now).timestamp_micros()
+}
+
+impl RecordEntry {
+    pub fn from_mempool_update(msg: &MessageMempoolDataUpdate) -> Self {
+        let tx = msg.mempool_tx.tx.as_ref();
+        let event = RecordedEvent::MempoolTx {
+            source: msg.source(),
+            tx_hash: msg.tx_hash,
+            raw_tx: tx.map(|tx| Bytes::from(tx.inner.encoded_2718())),
+            from: tx.map(|tx| tx.from),
+            logs: msg.mempool_tx.logs.clone(),
+            state_update: msg.mempool_tx.state_update.clone(),
+        };
+        Self { time_us: time_us(msg), event }
+    }
+
+    pub fn from_block_header(msg: &MessageBlockHeader) -> Self {
+        Self { time_us: time_us(msg), event: RecordedEvent::BlockHeader { header: msg.header.clone() } }
+    }
+
+    pub fn from_block(msg: &MessageBlock) -> Self {
+        let block = &msg.block;
+        let event = RecordedEvent::Block {
+            header: block.header.clone(),
+            txs: block.transactions.as_transactions().unwrap_or_default().iter().map(RecordedTx::new).collect(),
+            uncles: block.uncles.clone(),
+            withdrawals: block.withdrawals.clone(),
+        };
+        Self { time_us: time_us(msg), event }
+    }
+
+    pub fn from_block_logs(msg: &MessageBlockLogs) -> Self {
+        Self { time_us: time_us(msg), event: RecordedEvent::BlockLogs { header: msg.block_header.clone(), logs: msg.logs.clone() } }
+    }
+
+    pub fn from_block_state_update(msg: &MessageBlockStateUpdate) -> Self {
+        let event = RecordedEvent::BlockStateUpdate { header: msg.block_header.clone(), state_update: msg.state_update.clone() };
+        Self { time_us: time_us(msg), event }
+    }
+}
+
+/// Channel message rebuilt from a recording
+pub enum PlayerMessage {
+    Mempool(MessageMempoolDataUpdate),
+    BlockHeader(MessageBlockHeader),
+    Block(MessageBlock),
+    BlockLogs(MessageBlockLogs),
+    BlockStateUpdate(MessageBlockStateUpdate),
+}
+
+impl RecordedEvent {
+    pub fn into_message(self) -> Result<PlayerMessage> {
+        let msg = match self {
+            RecordedEvent::MempoolTx { source, tx_hash, raw_tx, from, logs, state_update } => {
+                let tx = match (raw_tx, from) {
+                    (Some(raw_tx), Some(from)) => {
+                        let inner = TxEnvelope::decode_2718(&mut raw_tx.as_ref())?;
+                        Some(Transaction {
+                            inner,
+                            block_hash: None,
+                            block_number: None,
+                            transaction_index: None,
+                            effective_gas_price: None,
+                            from,
+                        })
+                    }
+                    _ => None,
+                };
+                let mempool_tx = MempoolTx { tx_hash, tx, logs, state_update, ..MempoolTx::default() };
+                PlayerMessage::Mempool(Message::new_with_source(NodeMempoolDataUpdate { tx_hash, mempool_tx }, source))
+            }
+            RecordedEvent::BlockHeader { header } => PlayerMessage::BlockHeader(Message::new_with_time(BlockHeader::new(header))),
+            RecordedEvent::Block { header, txs, uncles, withdrawals } => {
+                let txs =
+                    txs.into_iter().enumerate().map(|(index, tx)| tx.into_block_transaction(&header, index)).collect::<Result<_>>()?;
+                let block = Block { header, uncles, transactions: BlockTransactions::Full(txs), withdrawals };
+                PlayerMessage::Block(Message::new_with_time(BlockUpdate { block }))
+            }
+            RecordedEvent::BlockLogs { header, logs } => {
+                PlayerMessage::BlockLogs(Message::new_with_time(BlockLogs { block_header: header, logs }))
+            }
+            RecordedEvent::BlockStateUpdate { header, state_update } => {
+                PlayerMessage::BlockStateUpdate(Message::new_with_time(BlockStateUpdate { block_header: header, state_update }))
+            }
+        };
+        Ok(msg)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloy_network::TransactionResponse;
+    use alloy_primitives::hex;
+    use std::str::FromStr;
+
+    fn example_tx() -> Result<Transaction> {
+        // eip-155 example tx from the spec
+        let raw_tx = hex::decode("f86c098504a817c800825208943535353535353535353535353535353535353535880de0b6b3a76400008025a028ef61340bd939bc2195fe537567866003e1a15d3c71ff63e1590620aa636276a067cbe9d8997f761aecb703304b3800ccf555c9f3dc64214b297fb1966a3b6d83")?;
+        let inner = TxEnvelope::decode_2718(&mut raw_tx.as_slice())?;
+        let from = Address::from_str("0x9d8a62f656a8d1615c1294fd71e9cfb3e4855a4f")?;
+        Ok(Transaction { inner, block_hash: None, block_number: None, transaction_index: None, effective_gas_price: None, from })
+    }
+
+    #[test]
+    fn test_mempool_tx_roundtrip() -> Result<()> {
+        let tx = example_tx()?;
+        let from = tx.from;
+        let tx_hash = tx.tx_hash();
+
+        let msg: MessageMempoolDataUpdate = Message::new_with_source(
+            NodeMempoolDataUpdate { tx_hash, mempool_tx: MempoolTx { tx: Some(tx), ..MempoolTx::default() } },
+            "exex".to_string(),
+        );
+        let line = serde_json::to_string(&RecordEntry::from_mempool_update(&msg))?;
+        let entry: RecordEntry = serde_json::from_str(&line)?;
+        assert_eq!(entry.time_us, msg.time.unwrap().timestamp_micros());
+
+        let PlayerMessage::Mempool(replayed) = entry.event.into_message()? else { panic!("NOT_MEMPOOL_MESSAGE") };
+        assert_eq!(replayed.source(), "exex");
+        assert_eq!(replayed.tx_hash, tx_hash);
+        assert_eq!(replayed.mempool_tx.tx.unwrap().from, from);
+        Ok(())
+    }
+
+    #[test]
+    fn test_block_roundtrip() -> Result<()> {
+        let tx = example_tx()?;
+        let mut header = Header::default();
+        header.hash = B256::repeat_byte(1);
+        header.inner.number = 100;
+        let block = Block { header, uncles: vec![], transactions: BlockTransactions::Full(vec![tx.clone()]), withdrawals: None };
+
+        let line = serde_json::to_string(&RecordEntry::from_block(&Message::new_with_time(BlockUpdate { block })))?;
+        assert!(!line.contains("\"uncles\""));
+        let entry: RecordEntry = serde_json::from_str(&line)?;
+
+        let PlayerMessage::Block(replayed) = entry.event.into_message()? else { panic!("NOT_BLOCK_MESSAGE") };
+        let txs = replayed.block.transactions.as_transactions().unwrap();
+        assert_eq!(txs[0].tx_hash(), tx.tx_hash());
+        assert_eq!(txs[0].block_number, Some(100));
+        assert_eq!(txs[0].transaction_index, Some(0));
+        Ok(())
+    }
+}
@@ -0,0 +1,27 @@
+use serde::Serialize;
+use utoipa::ToSchema;
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ActorsResponse {
+    pub channels: Vec<ChannelStats>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ChannelStats {
+    pub name: String,
+    pub capacity: usize,
+    pub depth: usize,
+    pub receivers: usize,
+    pub sent: u64,
+    pub send_errors: u64,
+    pub lagged: u64,
+    pub subscribers: Vec<SubscriberStats>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SubscriberStats {
+    pub id: u64,
+    pub received: u64,
+    pub lagged: u64,
+    pub lag_events: u64,
+}
@@ -1,3 +1,4 @@
+use crate::dto::control::ControlFlagsResponse;
 use serde::Serialize;
 use utoipa::ToSchema;
 
@@ -5,6 +6,7 @@ use utoipa::ToSchema;
 #[serde(tag = "type", rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum WebSocketMessage {
     BlockHeader(BlockHeader),
+    ControlFlags(ControlFlagsResponse),
 }
 
 #[derive(Debug, Serialize, ToSchema)]
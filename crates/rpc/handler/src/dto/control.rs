@@ -0,0 +1,40 @@
+use alloy_primitives::Address;
+use loom_types_entities::ControlFlags;
+use serde::{Deserialize, Serialize};
+use utoipa::PartialSchema;
+use utoipa::ToSchema;
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ControlFlagsResponse {
+    /// Overrides `allow_broadcast` of broadcasters, `null` keeps the configured value
+    pub broadcast: Option<bool>,
+    pub disabled_strategies: Vec<String>,
+    pub max_tips_pct: Option<u32>,
+    #[schema(schema_with = String::schema)]
+    pub token_blacklist: Vec<Address>,
+    #[schema(schema_with = String::schema)]
+    pub disabled_pools: Vec<Address>,
+}
+
+impl From<&ControlFlags> for ControlFlagsResponse {
+    fn from(flags: &ControlFlags) -> Self {
+        Self {
+            broadcast: flags.broadcast,
+            disabled_strategies: flags.disabled_strategies.iter().cloned().collect(),
+            max_tips_pct: flags.max_tips_pct,
+            token_blacklist: flags.token_blacklist.iter().cloned().collect(),
+            disabled_pools: flags.disabled_pools.iter().cloned().collect(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct EnableRequest {
+    pub enabled: bool,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct MaxTipsPctRequest {
+    /// Upper limit of the tips share of the profit in 1/10000, `null` removes the limit
+    pub max_tips_pct: Option<u32>,
+}
@@ -1,4 +1,6 @@
+pub mod actors;
 pub mod block;
+pub mod control;
 pub mod flashbots;
 pub mod pagination;
 pub mod pool;
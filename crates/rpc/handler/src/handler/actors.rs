@@ -0,0 +1,39 @@
+use crate::dto::actors::{ActorsResponse, ChannelStats, SubscriberStats};
+use axum::http::StatusCode;
+use axum::Json;
+use loom_core_actors::channel_snapshots;
+
+/// Get actor channels
+///
+/// Get send, depth and lag counters of all channels between actors
+#[utoipa::path(
+    get,
+    path = "/",
+    tag = "actors",
+    tags = [],
+    responses(
+    (status = 200, description = "Channel stats", body = ActorsResponse),
+    )
+)]
+pub async fn actors() -> Result<Json<ActorsResponse>, (StatusCode, String)> {
+    let mut channels: Vec<ChannelStats> = channel_snapshots()
+        .into_iter()
+        .map(|channel| ChannelStats {
+            name: channel.name,
+            capacity: channel.capacity,
+            depth: channel.depth,
+            receivers: channel.receivers,
+            sent: channel.sent,
+            send_errors: channel.send_errors,
+            lagged: channel.lagged,
+            subscribers: channel
+                .subscribers
+                .into_iter()
+                .map(|s| SubscriberStats { id: s.id, received: s.received, lagged: s.lagged, lag_events: s.lag_events })
+                .collect(),
+        })
+        .collect();
+    channels.sort_by(|a, b| a.name.cmp(&b.name));
+
+    Ok(Json(ActorsResponse { channels }))
+}
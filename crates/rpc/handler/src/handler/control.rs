@@ -0,0 +1,224 @@
+use crate::dto::control::{ControlFlagsResponse, EnableRequest, MaxTipsPctRequest};
+use alloy_primitives::Address;
+use axum::extract::{Path, State};
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::Json;
+use loom_rpc_state::AppState;
+use loom_types_entities::{ControlFlags, STRATEGY_ORIGINS};
+use revm::{DatabaseCommit, DatabaseRef};
+use std::str::FromStr;
+use tracing::info;
+
+fn authorize<DB: DatabaseRef + DatabaseCommit + Send + Sync + Clone + 'static>(
+    app_state: &AppState<DB>,
+    headers: &HeaderMap,
+) -> Result<(), (StatusCode, String)> {
+    let Some(control_token) = app_state.control_token.as_ref() else {
+        return Err((StatusCode::FORBIDDEN, "Control token is not configured".to_string()));
+    };
+    let bearer = headers.get(header::AUTHORIZATION).and_then(|value| value.to_str().ok()).and_then(|value| value.strip_prefix("Bearer "));
+    match bearer {
+        Some(token) if constant_time_eq(token.as_bytes(), control_token.as_bytes()) => Ok(()),
+        _ => Err((StatusCode::UNAUTHORIZED, "Invalid control token".to_string())),
+    }
+}
+
+/// Compares all bytes, so the response time doesn't tell how much of the token matched
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |diff, (a, b)| diff | (a ^ b)) == 0
+}
+
+fn parse_address(address: &str) -> Result<Address, (StatusCode, String)> {
+    Address::from_str(address).map_err(|e| (StatusCode::BAD_REQUEST, format!("Invalid address {address}: {e}")))
+}
+
+/// Applies the change to the shared flags and notifies subscribers of the control flags channel
+async fn update_flags<DB: DatabaseRef + DatabaseCommit + Send + Sync + Clone + 'static>(
+    app_state: &AppState<DB>,
+    update: impl FnOnce(&mut ControlFlags),
+) -> Json<ControlFlagsResponse> {
+    let control_flags = app_state.bc.control_flags();
+    let mut flags = control_flags.write().await;
+    update(&mut flags);
+    info!(flags = ?*flags, "Control flags changed");
+    // no subscribers is not an error
+    let _ = app_state.bc.control_flags_channel().send(flags.clone());
+    Json(ControlFlagsResponse::from(&*flags))
+}
+
+/// Get control flags
+///
+/// Get the runtime switches of broadcasting, strategies, tips, tokens and pools
+#[utoipa::path(
+    get,
+    path = "/",
+    tag = "control",
+    tags = [],
+    responses(
+    (status = 200, description = "Control flags", body = ControlFlagsResponse),
+    (status = 401, description = "Invalid control token"),
+    )
+)]
+pub async fn control_flags<DB: DatabaseRef + DatabaseCommit + Send + Sync + Clone + 'static>(
+    State(app_state): State<AppState<DB>>,
+    headers: HeaderMap,
+) -> Result<Json<ControlFlagsResponse>, (StatusCode, String)> {
+    authorize(&app_state, &headers)?;
+    Ok(Json(ControlFlagsResponse::from(&*app_state.bc.control_flags().read().await)))
+}
+
+/// Switch broadcasting
+///
+/// Overrides `allow_broadcast` of all broadcasters
+#[utoipa::path(
+    post,
+    path = "/broadcast",
+    tag = "control",
+    tags = [],
+    request_body = EnableRequest,
+    responses(
+    (status = 200, description = "Control flags", body = ControlFlagsResponse),
+    (status = 401, description = "Invalid control token"),
+    )
+)]
+pub async fn set_broadcast<DB: DatabaseRef + DatabaseCommit + Send + Sync + Clone + 'static>(
+    State(app_state): State<AppState<DB>>,
+    headers: HeaderMap,
+    Json(request): Json<EnableRequest>,
+) -> Result<Json<ControlFlagsResponse>, (StatusCode, String)> {
+    authorize(&app_state, &headers)?;
+    Ok(update_flags(&app_state, |flags| flags.broadcast = Some(request.enabled)).await)
+}
+
+/// Switch strategy
+///
+/// Enables or disables swaps by origin, one of `block_searcher`, `pending_tx_searcher`, `pending_tx_bundle_searcher`,
+/// `poolcode_searcher`, `merger_searcher`, `samepath_merger` or `diffpath_merger`
+#[utoipa::path(
+    post,
+    path = "/strategies/{name}",
+    tag = "control",
+    tags = [],
+    params(("name" = String, Path, description = "Swap origin")),
+    request_body = EnableRequest,
+    responses(
+    (status = 200, description = "Control flags", body = ControlFlagsResponse),
+    (status = 400, description = "Unknown strategy"),
+    (status = 401, description = "Invalid control token"),
+    )
+)]
+pub async fn set_strategy<DB: DatabaseRef + DatabaseCommit + Send + Sync + Clone + 'static>(
+    State(app_state): State<AppState<DB>>,
+    headers: HeaderMap,
+    Path(name): Path<String>,
+    Json(request): Json<EnableRequest>,
+) -> Result<Json<ControlFlagsResponse>, (StatusCode, String)> {
+    authorize(&app_state, &headers)?;
+    // a typo would disable nothing and look like success
+    if !STRATEGY_ORIGINS.contains(&name.as_str()) {
+        return Err((StatusCode::BAD_REQUEST, format!("Unknown strategy {name}, expected one of {}", STRATEGY_ORIGINS.join(", "))));
+    }
+    Ok(update_flags(&app_state, |flags| {
+        if request.enabled {
+            flags.disabled_strategies.remove(&name);
+        } else {
+            flags.disabled_strategies.insert(name);
+        }
+    })
+    .await)
+}
+
+/// Set max tips percentage
+///
+/// Limits the tips share of the profit in 1/10000
+#[utoipa::path(
+    post,
+    path = "/max_tips_pct",
+    tag = "control",
+    tags = [],
+    request_body = MaxTipsPctRequest,
+    responses(
+    (status = 200, description = "Control flags", body = ControlFlagsResponse),
+    (status = 400, description = "Percentage above 10000"),
+    (status = 401, description = "Invalid control token"),
+    )
+)]
+pub async fn set_max_tips_pct<DB: DatabaseRef + DatabaseCommit + Send + Sync + Clone + 'static>(
+    State(app_state): State<AppState<DB>>,
+    headers: HeaderMap,
+    Json(request): Json<MaxTipsPctRequest>,
+) -> Result<Json<ControlFlagsResponse>, (StatusCode, String)> {
+    authorize(&app_state, &headers)?;
+    if request.max_tips_pct.is_some_and(|max_tips_pct| max_tips_pct > 10000) {
+        return Err((StatusCode::BAD_REQUEST, "max_tips_pct must be <= 10000".to_string()));
+    }
+    Ok(update_flags(&app_state, |flags| flags.max_tips_pct = request.max_tips_pct).await)
+}
+
+/// Switch token
+///
+/// Disabled tokens are blacklisted, swaps through them are dropped
+#[utoipa::path(
+    post,
+    path = "/tokens/{address}",
+    tag = "control",
+    tags = [],
+    params(("address" = String, Path, description = "Token address")),
+    request_body = EnableRequest,
+    responses(
+    (status = 200, description = "Control flags", body = ControlFlagsResponse),
+    (status = 400, description = "Invalid address"),
+    (status = 401, description = "Invalid control token"),
+    )
+)]
+pub async fn set_token<DB: DatabaseRef + DatabaseCommit + Send + Sync + Clone + 'static>(
+    State(app_state): State<AppState<DB>>,
+    headers: HeaderMap,
+    Path(address): Path<String>,
+    Json(request): Json<EnableRequest>,
+) -> Result<Json<ControlFlagsResponse>, (StatusCode, String)> {
+    authorize(&app_state, &headers)?;
+    let address = parse_address(&address)?;
+    Ok(update_flags(&app_state, |flags| {
+        if request.enabled {
+            flags.token_blacklist.remove(&address);
+        } else {
+            flags.token_blacklist.insert(address);
+        }
+    })
+    .await)
+}
+
+/// Switch pool
+///
+/// Swaps through disabled pools are dropped
+#[utoipa::path(
+    post,
+    path = "/pools/{address}",
+    tag = "control",
+    tags = [],
+    params(("address" = String, Path, description = "Pool address")),
+    request_body = EnableRequest,
+    responses(
+    (status = 200, description = "Control flags", body = ControlFlagsResponse),
+    (status = 400, description = "Invalid address"),
+    (status = 401, description = "Invalid control token"),
+    )
+)]
+pub async fn set_pool<DB: DatabaseRef + DatabaseCommit + Send + Sync + Clone + 'static>(
+    State(app_state): State<AppState<DB>>,
+    headers: HeaderMap,
+    Path(address): Path<String>,
+    Json(request): Json<EnableRequest>,
+) -> Result<Json<ControlFlagsResponse>, (StatusCode, String)> {
+    authorize(&app_state, &headers)?;
+    let address = parse_address(&address)?;
+    Ok(update_flags(&app_state, |flags| {
+        if request.enabled {
+            flags.disabled_pools.remove(&address);
+        } else {
+            flags.disabled_pools.insert(address);
+        }
+    })
+    .await)
+}
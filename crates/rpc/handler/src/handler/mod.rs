@@ -1,4 +1,6 @@
+pub mod actors;
 pub mod blocks;
+pub mod control;
 pub mod flashbots;
 pub mod pools;
 pub mod ws;
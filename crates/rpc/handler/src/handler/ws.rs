@@ -5,11 +5,13 @@ use axum::{
 };
 
 use crate::dto::block::{BlockHeader, WebSocketMessage};
+use crate::dto::control::ControlFlagsResponse;
 use eyre::ErrReport;
 use loom_rpc_state::AppState;
 use loom_types_blockchain::ChainParameters;
 use revm::{DatabaseCommit, DatabaseRef};
 use std::net::SocketAddr;
+use tokio::sync::broadcast::error::RecvError;
 use tracing::{error, warn};
 
 /// Handle websocket upgrade
@@ -24,28 +26,55 @@ pub async fn ws_handler<DB: DatabaseRef<Error = ErrReport> + DatabaseCommit + Se
     .on_upgrade(move |socket| on_upgrade(socket, addr, app_state))
 }
 
+async fn send_message(socket: &mut WebSocket, ws_msg: &WebSocketMessage) {
+    match serde_json::to_string(ws_msg) {
+        Ok(json) => {
+            let _ = socket.send(Message::Text(json)).await;
+        }
+        Err(e) => {
+            error!("Failed to serialize websocket message: {}", e);
+        }
+    }
+}
+
 /// Actual websocket statemachine (one will be spawned per connection)
 async fn on_upgrade<DB: DatabaseRef + DatabaseCommit + Send + Sync + Clone + 'static>(
     mut socket: WebSocket,
     _who: SocketAddr,
     app_state: AppState<DB>,
 ) {
-    let mut receiver = app_state.bc.new_block_headers_channel().subscribe();
+    let mut header_receiver = app_state.bc.new_block_headers_channel().subscribe();
+    let mut control_flags_receiver = app_state.bc.control_flags_channel().subscribe();
 
-    while let Ok(header) = receiver.recv().await {
-        let ws_msg = WebSocketMessage::BlockHeader(BlockHeader {
-            number: header.inner.header.number,
-            timestamp: header.inner.header.timestamp,
-            base_fee_per_gas: header.inner.header.base_fee_per_gas,
-            next_block_base_fee: ChainParameters::ethereum().calc_next_block_base_fee_from_header(&header.inner.header),
-        });
-        match serde_json::to_string(&ws_msg) {
-            Ok(json) => {
-                let _ = socket.send(Message::Text(json)).await;
-            }
-            Err(e) => {
-                error!("Failed to serialize block header: {}", e);
-            }
-        }
+    // subscribed before reading, so no change made in between is lost
+    let flags = WebSocketMessage::ControlFlags(ControlFlagsResponse::from(&*app_state.bc.control_flags().read().await));
+    send_message(&mut socket, &flags).await;
+
+    loop {
+        let ws_msg = tokio::select! {
+            header = header_receiver.recv() => match header {
+                Ok(header) => WebSocketMessage::BlockHeader(BlockHeader {
+                    number: header.inner.header.number,
+                    timestamp: header.inner.header.timestamp,
+                    base_fee_per_gas: header.inner.header.base_fee_per_gas,
+                    next_block_base_fee: ChainParameters::ethereum().calc_next_block_base_fee_from_header(&header.inner.header),
+                }),
+                // a slow client misses some messages but keeps the connection
+                Err(RecvError::Lagged(lag)) => {
+                    warn!(lag, "Websocket client lagged on block headers");
+                    continue;
+                }
+                Err(RecvError::Closed) => break,
+            },
+            flags = control_flags_receiver.recv() => match flags {
+                Ok(flags) => WebSocketMessage::ControlFlags(ControlFlagsResponse::from(&flags)),
+                Err(RecvError::Lagged(lag)) => {
+                    warn!(lag, "Websocket client lagged on control flags");
+                    continue;
+                }
+                Err(RecvError::Closed) => break,
+            },
+        };
+        send_message(&mut socket, &ws_msg).await;
     }
 }
@@ -1,4 +1,6 @@
+use crate::dto::actors::{ActorsResponse, ChannelStats, SubscriberStats};
 use crate::dto::block::BlockHeader;
+use crate::dto::control::{ControlFlagsResponse, EnableRequest, MaxTipsPctRequest};
 use crate::dto::pool::MarketStats;
 use crate::dto::pool::Pool;
 use crate::dto::pool::PoolClass;
@@ -7,13 +9,27 @@ use crate::dto::pool::PoolProtocol;
 use crate::dto::pool::PoolResponse;
 use crate::dto::quote::QuoteRequest;
 use crate::dto::quote::QuoteResponse;
+use crate::handler::actors::__path_actors;
 use crate::handler::blocks::__path_latest_block;
+use crate::handler::control::{
+    __path_control_flags, __path_set_broadcast, __path_set_max_tips_pct, __path_set_pool, __path_set_strategy, __path_set_token,
+};
 use crate::handler::pools::__path_market_stats;
 use crate::handler::pools::__path_pool;
 use crate::handler::pools::__path_pool_quote;
 use crate::handler::pools::__path_pools;
 use utoipa::OpenApi;
 
+#[derive(OpenApi)]
+#[openapi(
+    paths(actors),
+    tags(
+        (name = "actors", description = "Actors")
+    ),
+    components(schemas(ActorsResponse, ChannelStats, SubscriberStats))
+)]
+pub struct ActorsApi;
+
 #[derive(OpenApi)]
 #[openapi(
     paths(latest_block),
@@ -24,6 +40,16 @@ use utoipa::OpenApi;
 )]
 pub struct BlockApi;
 
+#[derive(OpenApi)]
+#[openapi(
+    paths(control_flags, set_broadcast, set_strategy, set_max_tips_pct, set_token, set_pool),
+    tags(
+        (name = "control", description = "Control")
+    ),
+    components(schemas(ControlFlagsResponse, EnableRequest, MaxTipsPctRequest))
+)]
+pub struct ControlApi;
+
 #[derive(OpenApi)]
 #[openapi(
     paths(pool, pools, pool_quote, market_stats),
@@ -38,7 +64,9 @@ pub struct MarketApi;
 #[derive(OpenApi)]
 #[openapi(
     nest(
+        (path = "/api/v1/actors", api = ActorsApi),
         (path = "/api/v1/block/", api = BlockApi),
+        (path = "/api/v1/control", api = ControlApi),
         (path = "/api/v1/markets", api = MarketApi)
     )
 )]
@@ -1,4 +1,6 @@
+use crate::handler::actors::actors;
 use crate::handler::blocks::latest_block;
+use crate::handler::control::{control_flags, set_broadcast, set_max_tips_pct, set_pool, set_strategy, set_token};
 use crate::handler::flashbots::flashbots;
 use crate::handler::pools::{market_stats, pool, pool_quote, pools};
 use crate::handler::ws::ws_handler;
@@ -16,9 +18,11 @@ pub fn router<DB: DatabaseRef<Error = ErrReport> + DatabaseCommit + Sync + Send
         .nest(
             "/api/v1",
             Router::new()
+                .nest("/actors", Router::new().route("/", get(actors)))
                 .nest("/block", router_block()) // rename to node
                 .nest("/markets", router_market())
-                .nest("/flashbots", Router::new().route("/", post(flashbots))),
+                .nest("/flashbots", Router::new().route("/", post(flashbots)))
+                .nest("/control", router_control()),
         )
         .route("/ws", get(ws_handler))
         //.merge(SwaggerUi::new("/swagger-ui").url("/api-docs/openapi.json", ApiDoc::openapi()))
@@ -29,6 +33,16 @@ pub fn router_block<DB: DatabaseRef + DatabaseCommit + Sync + Send + Clone + 'st
     Router::new().route("/latest_block", get(latest_block))
 }
 
+pub fn router_control<DB: DatabaseRef + DatabaseCommit + Sync + Send + Clone + 'static>() -> Router<AppState<DB>> {
+    Router::new()
+        .route("/", get(control_flags))
+        .route("/broadcast", post(set_broadcast))
+        .route("/strategies/:name", post(set_strategy))
+        .route("/max_tips_pct", post(set_max_tips_pct))
+        .route("/tokens/:address", post(set_token))
+        .route("/pools/:address", post(set_pool))
+}
+
 pub fn router_market<DB: DatabaseRef<Error = ErrReport> + DatabaseCommit + Sync + Send + Clone + 'static>() -> Router<AppState<DB>> {
     Router::new()
         .route("/pools/:address", get(pool))
@@ -1,7 +1,7 @@
 use crate::router::router;
 use axum::Router;
 use eyre::ErrReport;
-use loom_core_actors::{Actor, ActorResult, WorkerResult};
+use loom_core_actors::{Actor, ActorResult, ShutdownStage, WorkerResult};
 use loom_core_actors_macros::Consumer;
 use loom_core_blockchain::{Blockchain, BlockchainState};
 use loom_rpc_state::AppState;
@@ -19,14 +19,16 @@ pub async fn start_web_server_worker<S, DB>(
     bc: Blockchain,
     state: BlockchainState<DB>,
     db_pool: DbPool,
+    control_token: Option<String>,
     shutdown_token: CancellationToken,
+    stage_shutdown_token: CancellationToken,
 ) -> WorkerResult
 where
     DB: DatabaseRef<Error = ErrReport> + DatabaseCommit + Send + Sync + Clone + Default + 'static,
     S: Clone + Send + Sync + 'static,
     Router: From<Router<S>>,
 {
-    let app_state = AppState { db: db_pool, bc, state };
+    let app_state = AppState { db: db_pool, bc, state, control_token };
     let router = router(app_state);
     let router = router.merge(extra_router);
 
@@ -37,7 +39,10 @@ where
     let listener = TcpListener::bind(host).await?;
     axum::serve(listener, router.into_make_service_with_connect_info::<SocketAddr>())
         .with_graceful_shutdown(async move {
-            shutdown_token.cancelled().await;
+            tokio::select! {
+                _ = shutdown_token.cancelled() => {}
+                _ = stage_shutdown_token.cancelled() => {}
+            }
             info!("Shutting down webserver...");
         })
         .await?;
@@ -51,6 +56,7 @@ pub struct WebServerActor<S, DB: Clone + Send + Sync + 'static> {
     extra_router: Router<S>,
     shutdown_token: CancellationToken,
     db_pool: DbPool,
+    control_token: Option<String>,
     bc: Option<Blockchain>,
     state: Option<BlockchainState<DB>>,
 }
@@ -62,7 +68,11 @@ where
     Router: From<Router<S>>,
 {
     pub fn new(host: String, extra_router: Router<S>, db_pool: DbPool, shutdown_token: CancellationToken) -> Self {
-        Self { host, extra_router, shutdown_token, db_pool, bc: None, state: None }
+        Self { host, extra_router, shutdown_token, db_pool, control_token: None, bc: None, state: None }
+    }
+
+    pub fn with_control_token(self, control_token: Option<String>) -> Self {
+        Self { control_token, ..self }
     }
 
     pub fn on_bc(self, bc: &Blockchain, state: &BlockchainState<DB>) -> Self {
@@ -77,17 +87,28 @@ where
     DB: DatabaseRef<Error = ErrReport> + DatabaseCommit + Send + Sync + Clone + Default + 'static,
 {
     fn start(&self) -> ActorResult {
+        self.start_until(CancellationToken::new())
+    }
+
+    fn start_until(&self, shutdown_token: CancellationToken) -> ActorResult {
         let task = tokio::spawn(start_web_server_worker(
             self.host.clone(),
             self.extra_router.clone(),
             self.bc.clone().unwrap(),
             self.state.clone().unwrap(),
             self.db_pool.clone(),
+            self.control_token.clone(),
             self.shutdown_token.clone(),
+            shutdown_token,
         ));
         Ok(vec![task])
     }
 
+    // serves requests writing to the database until writers are stopped
+    fn shutdown_stage(&self) -> ShutdownStage {
+        ShutdownStage::Writer
+    }
+
     fn name(&self) -> &'static str {
         "WebServerActor"
     }
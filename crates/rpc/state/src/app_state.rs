@@ -7,4 +7,6 @@ pub struct AppState<DB: DatabaseRef + DatabaseCommit + Clone + Send + Sync + 'st
     pub db: DbPool,
     pub bc: Blockchain,
     pub state: BlockchainState<DB>,
+    /// Bearer token required by the control endpoints, they are rejected when not set
+    pub control_token: Option<String>,
 }
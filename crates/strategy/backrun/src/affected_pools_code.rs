@@ -12,11 +12,12 @@ use loom_defi_pools::protocols::{UniswapV2Protocol, UniswapV3Protocol};
 use loom_defi_pools::state_readers::UniswapV3StateReader;
 use loom_defi_pools::{MaverickPool, PancakeV3Pool, UniswapV2Pool, UniswapV3Pool};
 use loom_evm_db::{AlloyDB, LoomDB};
-use loom_types_blockchain::GethStateUpdateVec;
+use loom_types_blockchain::{ChainParameters, GethStateUpdateVec};
 use loom_types_entities::{get_protocol_by_factory, Market, MarketState, Pool, PoolId, PoolProtocol, PoolWrapper, SwapDirection};
 
 pub async fn get_affected_pools_from_code<P, N>(
     client: P,
+    chain_parameters: &ChainParameters,
     market: SharedState<Market>,
     state_update: &GethStateUpdateVec,
 ) -> eyre::Result<BTreeMap<PoolWrapper, Vec<SwapDirection>>>
@@ -90,7 +91,7 @@ where
 
                             match UniswapV3StateReader::factory(&state_db, env.clone(), *address) {
                                 Ok(factory_address) => {
-                                    match get_protocol_by_factory(factory_address) {
+                                    match get_protocol_by_factory(chain_parameters, factory_address) {
                                         PoolProtocol::PancakeV3 => {
                                             let pool = PancakeV3Pool::fetch_pool_data_evm(&state_db, env.clone(), *address);
                                             match pool {
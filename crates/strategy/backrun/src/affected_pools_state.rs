@@ -11,7 +11,14 @@ pub async fn get_affected_pools_from_state_update(
     state_update: &GethStateUpdateVec,
 ) -> BTreeMap<PoolWrapper, Vec<SwapDirection>> {
     let market_guard = market.read().await;
+    affected_pools_from_state_update(&market_guard, state_update)
+}
 
+/// Pools with changed state, pool manager cells are mapped to their pools
+pub(crate) fn affected_pools_from_state_update(
+    market_guard: &Market,
+    state_update: &GethStateUpdateVec,
+) -> BTreeMap<PoolWrapper, Vec<SwapDirection>> {
     let mut affected_pools: BTreeMap<PoolWrapper, Vec<SwapDirection>> = BTreeMap::new();
 
     for state_update_record in state_update.iter() {
@@ -6,10 +6,14 @@ use eyre::ErrReport;
 use influxdb::WriteQuery;
 use revm::{Database, DatabaseCommit, DatabaseRef};
 use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
 use tracing::info;
 
-use loom_core_actors::{Accessor, Actor, ActorResult, Broadcaster, Consumer, Producer, SharedState, WorkerResult};
+use loom_core_actors::{
+    Accessor, Actor, ActorResult, Broadcaster, Consumer, Mpsc, MpscConsumer, MpscProducer, Producer, SharedState, WorkerResult,
+};
 use loom_core_actors_macros::{Accessor, Consumer, Producer};
+use loom_evm_db::DatabaseLoomExt;
 use loom_node_debug_provider::DebugProviderExt;
 use loom_types_blockchain::Mempool;
 use loom_types_entities::{BlockHistory, LatestBlock, Market, MarketState};
@@ -80,20 +84,32 @@ impl<P, N, DB> Actor for StateChangeArbActor<P, N, DB>
 where
     N: Network,
     P: Provider<N> + DebugProviderExt<N> + Send + Sync + Clone + 'static,
-    DB: DatabaseRef<Error = ErrReport> + Database<Error = ErrReport> + DatabaseCommit + Send + Sync + Clone + Default + 'static,
+    DB: DatabaseRef<Error = ErrReport>
+        + Database<Error = ErrReport>
+        + DatabaseCommit
+        + DatabaseLoomExt
+        + Send
+        + Sync
+        + Clone
+        + Default
+        + 'static,
 {
     fn start(&self) -> ActorResult {
-        let searcher_pool_update_channel = Broadcaster::new(100);
+        self.start_until(CancellationToken::new())
+    }
+
+    fn start_until(&self, shutdown_token: CancellationToken) -> ActorResult {
+        let searcher_pool_update_channel = Mpsc::named("searcher_state_update", 100);
         let mut tasks: Vec<JoinHandle<WorkerResult>> = Vec::new();
 
         let mut state_update_searcher = StateChangeArbSearcherActor::new(self.backrun_config.clone());
         match state_update_searcher
             .access(self.market.clone().unwrap())
-            .consume(searcher_pool_update_channel.clone())
+            .consume_mpsc(searcher_pool_update_channel.clone())
             .produce(self.compose_channel_tx.clone().unwrap())
             .produce(self.pool_health_monitor_tx.clone().unwrap())
             .produce(self.influxdb_write_channel_tx.clone().unwrap())
-            .start()
+            .start_until(shutdown_token.clone())
         {
             Err(e) => {
                 panic!("{}", e)
@@ -105,7 +121,9 @@ where
         }
 
         if self.mempool_events_tx.is_some() && self.use_mempool {
-            let mut pending_tx_state_processor = PendingTxStateChangeProcessorActor::new(self.client.clone());
+            let mut pending_tx_state_processor = PendingTxStateChangeProcessorActor::new(self.client.clone())
+                .with_simulation(self.backrun_config.pending_tx_simulation())
+                .with_bundling(self.backrun_config.bundle_pending_txs());
             match pending_tx_state_processor
                 .access(self.mempool.clone().unwrap())
                 .access(self.latest_block.clone().unwrap())
@@ -113,8 +131,8 @@ where
                 .access(self.market_state.clone().unwrap())
                 .consume(self.mempool_events_tx.clone().unwrap())
                 .consume(self.market_events_tx.clone().unwrap())
-                .produce(searcher_pool_update_channel.clone())
-                .start()
+                .produce_mpsc(searcher_pool_update_channel.clone())
+                .start_until(shutdown_token.clone())
             {
                 Err(e) => {
                     panic!("{}", e)
@@ -132,8 +150,8 @@ where
                 .access(self.market.clone().unwrap())
                 .access(self.block_history.clone().unwrap())
                 .consume(self.market_events_tx.clone().unwrap())
-                .produce(searcher_pool_update_channel.clone())
-                .start()
+                .produce_mpsc(searcher_pool_update_channel.clone())
+                .start_until(shutdown_token.clone())
             {
                 Err(e) => {
                     panic!("{}", e)
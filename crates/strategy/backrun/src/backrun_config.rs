@@ -1,4 +1,5 @@
 use alloy_primitives::Address;
+use loom_types_entities::bidding::BiddingConfig;
 use loom_types_entities::strategy_config::StrategyConfig;
 use serde::Deserialize;
 
@@ -7,10 +8,33 @@ pub struct BackrunConfigSection {
     pub backrun_strategy: BackrunConfig,
 }
 
+/// How pending transactions are executed to get their state changes
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PendingTxSimulation {
+    /// `debug_traceCall` on the node for every transaction
+    #[default]
+    Rpc,
+    /// revm on top of the market state, accounts and slots missing in it are fetched from the node at the latest block
+    Local,
+    /// Both, the node result is used and differences are logged
+    Verify,
+}
+
 #[derive(Clone, Deserialize, Debug)]
 pub struct BackrunConfig {
     eoa: Option<Address>,
     smart: bool,
+    #[serde(default)]
+    pending_tx_simulation: PendingTxSimulation,
+    /// Simulate pending txes touching the same pools or sent by the same account as one sequence
+    /// The sequence is always simulated locally, whatever `pending_tx_simulation` is
+    #[serde(default)]
+    bundle_pending_txs: bool,
+    /// Tips bidding of the swap lines of this searcher, capped by the tips share of the search origin.
+    /// Merged swaps are bid by the swap encoder, which uses the `[backrun_strategy]` bidding.
+    #[serde(default)]
+    bidding: BiddingConfig,
 }
 
 impl StrategyConfig for BackrunConfig {
@@ -24,13 +48,37 @@ impl BackrunConfig {
         self.smart
     }
 
+    pub fn pending_tx_simulation(&self) -> PendingTxSimulation {
+        self.pending_tx_simulation
+    }
+
+    pub fn bundle_pending_txs(&self) -> bool {
+        self.bundle_pending_txs
+    }
+
+    pub fn bidding(&self) -> &BiddingConfig {
+        &self.bidding
+    }
+
     pub fn new_dumb() -> Self {
-        Self { eoa: None, smart: false }
+        Self {
+            eoa: None,
+            smart: false,
+            pending_tx_simulation: PendingTxSimulation::default(),
+            bundle_pending_txs: false,
+            bidding: BiddingConfig::default(),
+        }
     }
 }
 
 impl Default for BackrunConfig {
     fn default() -> Self {
-        Self { eoa: None, smart: true }
+        Self {
+            eoa: None,
+            smart: true,
+            pending_tx_simulation: PendingTxSimulation::default(),
+            bundle_pending_txs: false,
+            bidding: BiddingConfig::default(),
+        }
     }
 }
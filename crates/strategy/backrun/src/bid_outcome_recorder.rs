@@ -0,0 +1,170 @@
+use std::collections::{BTreeMap, HashSet};
+use std::path::PathBuf;
+
+use alloy_primitives::{keccak256, Address, BlockNumber, Bytes, TxHash};
+use eyre::eyre;
+use tokio::fs::OpenOptions;
+use tokio::io::{AsyncWriteExt, BufWriter};
+use tokio::sync::broadcast::error::RecvError;
+use tracing::{debug, error, warn};
+
+use loom_core_actors::{subscribe, Actor, ActorResult, Broadcaster, Consumer, WorkerResult};
+use loom_core_actors_macros::Consumer;
+use loom_core_blockchain::Blockchain;
+use loom_evm_utils::NWETH;
+use loom_types_entities::bidding::BidOutcome;
+use loom_types_events::{MessageBlock, MessageTxCompose, RlpState, TxComposeData, TxComposeMessageType};
+
+/// Broadcast bundle waiting for its target block
+struct PendingBid {
+    profit_eth: f64,
+    tips_pct: u32,
+    backrun_tx_hashes: Vec<TxHash>,
+}
+
+impl PendingBid {
+    fn from_compose_data(data: &TxComposeData) -> Option<Self> {
+        let profit_eth = data.swap.as_ref()?.abs_profit_eth();
+        if profit_eth.is_zero() {
+            return None;
+        }
+        // the bid share, the tips of the compose data include the gas cost
+        let tips_pct = data.tips_pct?;
+        let backrun_tx_hashes: Vec<TxHash> = data
+            .rlp_bundle
+            .as_ref()?
+            .iter()
+            .filter_map(|rlp| match rlp {
+                RlpState::Backrun(rlp) => Some(keccak256(rlp)),
+                _ => None,
+            })
+            .collect();
+        if backrun_tx_hashes.is_empty() {
+            return None;
+        }
+        Some(Self { profit_eth: NWETH::to_float(profit_eth), tips_pct, backrun_tx_hashes })
+    }
+}
+
+/// Builder name from the block extra data, the coinbase if the extra data is not readable
+fn builder_name(extra_data: &Bytes, coinbase: Address) -> String {
+    let name: String =
+        String::from_utf8_lossy(extra_data).chars().filter(|c| !c.is_control() && *c != char::REPLACEMENT_CHARACTER).collect();
+    match name.trim() {
+        "" => coinbase.to_string(),
+        name => name.to_string(),
+    }
+}
+
+/// Appends a [`BidOutcome`] for every broadcast bundle once its target block arrives, the outcomes feed the historical bidder
+pub async fn bid_outcome_recorder_worker(
+    path: PathBuf,
+    tx_compose_rx: Broadcaster<MessageTxCompose>,
+    block_with_tx_rx: Broadcaster<MessageBlock>,
+) -> WorkerResult {
+    subscribe!(tx_compose_rx);
+    subscribe!(block_with_tx_rx);
+
+    let file = OpenOptions::new().create(true).append(true).open(&path).await?;
+    let mut writer = BufWriter::new(file);
+    let mut pending_bids: BTreeMap<BlockNumber, Vec<PendingBid>> = BTreeMap::new();
+
+    loop {
+        tokio::select! {
+            msg = tx_compose_rx.recv() => match msg {
+                Ok(msg) => {
+                    if let TxComposeMessageType::Broadcast(data) = msg.inner {
+                        if let Some(bid) = PendingBid::from_compose_data(&data) {
+                            pending_bids.entry(data.next_block_number).or_default().push(bid);
+                        }
+                    }
+                }
+                Err(RecvError::Lagged(lag)) => warn!(lag, "Tx compose channel lagged, bids are not recorded"),
+                Err(RecvError::Closed) => {
+                    error!("Tx compose channel closed");
+                    break Err(eyre!("TX_COMPOSE_CHANNEL_CLOSED"));
+                }
+            },
+            msg = block_with_tx_rx.recv() => match msg {
+                Ok(msg) => {
+                    let block = msg.inner.block;
+                    let block_number = block.header.number;
+                    // bids for older blocks missed their block
+                    let bids = pending_bids.remove(&block_number).unwrap_or_default();
+                    pending_bids = pending_bids.split_off(&block_number);
+                    if bids.is_empty() {
+                        continue;
+                    }
+
+                    let block_tx_hashes: HashSet<TxHash> = block.transactions.hashes().collect();
+                    let builder = builder_name(&block.header.extra_data, block.header.beneficiary);
+                    for bid in bids {
+                        let outcome = BidOutcome {
+                            profit_eth: bid.profit_eth,
+                            tips_pct: bid.tips_pct,
+                            builder: builder.clone(),
+                            included: bid.backrun_tx_hashes.iter().any(|tx_hash| block_tx_hashes.contains(tx_hash)),
+                        };
+                        debug!(block_number, ?outcome, "Bid outcome");
+                        let mut line = serde_json::to_vec(&outcome)?;
+                        line.push(b'\n');
+                        writer.write_all(&line).await?;
+                    }
+                    writer.flush().await?;
+                }
+                Err(RecvError::Lagged(lag)) => warn!(lag, "Block channel lagged, bids are not recorded"),
+                Err(RecvError::Closed) => {
+                    error!("Block channel closed");
+                    break Err(eyre!("BLOCK_WITH_TX_CHANNEL_CLOSED"));
+                }
+            },
+        }
+    }
+}
+
+#[derive(Consumer)]
+pub struct BidOutcomeRecorderActor {
+    path: PathBuf,
+    #[consumer]
+    tx_compose_rx: Option<Broadcaster<MessageTxCompose>>,
+    #[consumer]
+    block_with_tx_rx: Option<Broadcaster<MessageBlock>>,
+}
+
+impl BidOutcomeRecorderActor {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path, tx_compose_rx: None, block_with_tx_rx: None }
+    }
+
+    pub fn on_bc(self, bc: &Blockchain) -> Self {
+        Self { tx_compose_rx: Some(bc.tx_compose_channel()), block_with_tx_rx: Some(bc.new_block_with_tx_channel()), ..self }
+    }
+}
+
+impl Actor for BidOutcomeRecorderActor {
+    fn start(&self) -> ActorResult {
+        let task = tokio::task::spawn(bid_outcome_recorder_worker(
+            self.path.clone(),
+            self.tx_compose_rx.clone().unwrap(),
+            self.block_with_tx_rx.clone().unwrap(),
+        ));
+        Ok(vec![task])
+    }
+
+    fn name(&self) -> &'static str {
+        "BidOutcomeRecorderActor"
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_builder_name() {
+        let coinbase = Address::repeat_byte(1);
+        assert_eq!(builder_name(&Bytes::from_static(b"beaverbuild.org"), coinbase), "beaverbuild.org");
+        assert_eq!(builder_name(&Bytes::from_static(b" Titan\x00 "), coinbase), "Titan");
+        assert_eq!(builder_name(&Bytes::from_static(&[0xd8, 0x83, 0x01]), coinbase), coinbase.to_string());
+    }
+}
@@ -1,6 +1,8 @@
 use super::affected_pools_state::get_affected_pools_from_state_update;
 use eyre::eyre;
-use loom_core_actors::{run_sync, subscribe, Accessor, Actor, ActorResult, Broadcaster, Consumer, Producer, SharedState, WorkerResult};
+use loom_core_actors::{
+    run_sync, subscribe, Accessor, Actor, ActorResult, Broadcaster, Consumer, Mpsc, MpscProducer, Producer, SharedState, WorkerResult,
+};
 use loom_core_actors_macros::{Accessor, Consumer, Producer};
 use loom_core_blockchain::{Blockchain, BlockchainState, Strategy};
 use loom_types_blockchain::ChainParameters;
@@ -9,6 +11,7 @@ use loom_types_entities::{BlockHistory, Market};
 use loom_types_events::{MarketEvents, StateUpdateEvent};
 use revm::DatabaseRef;
 use tokio::sync::broadcast::error::RecvError;
+use tokio_util::sync::CancellationToken;
 use tracing::error;
 
 pub async fn block_state_change_worker<DB: DatabaseRef + Send + Sync + Clone + 'static>(
@@ -16,12 +19,17 @@ pub async fn block_state_change_worker<DB: DatabaseRef + Send + Sync + Clone + '
     market: SharedState<Market>,
     block_history: SharedState<BlockHistory<DB>>,
     market_events_rx: Broadcaster<MarketEvents>,
-    state_updates_broadcaster: Broadcaster<StateUpdateEvent<DB, LoomDataTypesEthereum>>,
+    state_updates_tx: Mpsc<StateUpdateEvent<DB, LoomDataTypesEthereum>>,
+    shutdown_token: CancellationToken,
 ) -> WorkerResult {
     subscribe!(market_events_rx);
 
     loop {
-        let market_event = match market_events_rx.recv().await {
+        let market_event = tokio::select! {
+            market_event = market_events_rx.recv() => market_event,
+            _ = shutdown_token.cancelled() => break Ok("Block state change processor stopped".to_string()),
+        };
+        let market_event = match market_event {
             Ok(market_event) => market_event,
             Err(e) => match e {
                 RecvError::Closed => {
@@ -78,7 +86,7 @@ pub async fn block_state_change_worker<DB: DatabaseRef + Send + Sync + Clone + '
             "block_searcher".to_string(),
             90_00,
         );
-        run_sync!(state_updates_broadcaster.send(request));
+        run_sync!(state_updates_tx.send(request).await);
     }
 }
 
@@ -92,7 +100,7 @@ pub struct BlockStateChangeProcessorActor<DB: Clone + Send + Sync + 'static> {
     #[consumer]
     market_events_rx: Option<Broadcaster<MarketEvents>>,
     #[producer]
-    state_updates_tx: Option<Broadcaster<StateUpdateEvent<DB>>>,
+    state_updates_tx: Option<Mpsc<StateUpdateEvent<DB>>>,
 }
 
 impl<DB: DatabaseRef + Send + Sync + Clone + 'static> BlockStateChangeProcessorActor<DB> {
@@ -125,12 +133,17 @@ impl<DB: DatabaseRef + Send + Sync + Clone + 'static> Default for BlockStateChan
 
 impl<DB: DatabaseRef + Send + Sync + Clone + 'static> Actor for BlockStateChangeProcessorActor<DB> {
     fn start(&self) -> ActorResult {
+        self.start_until(CancellationToken::new())
+    }
+
+    fn start_until(&self, shutdown_token: CancellationToken) -> ActorResult {
         let task = tokio::task::spawn(block_state_change_worker(
             self.chain_parameters.clone(),
             self.market.clone().unwrap(),
             self.block_history.clone().unwrap(),
             self.market_events_rx.clone().unwrap(),
             self.state_updates_tx.clone().unwrap(),
+            shutdown_token,
         ));
         Ok(vec![task])
     }
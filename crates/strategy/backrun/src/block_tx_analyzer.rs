@@ -0,0 +1,473 @@
+use std::collections::HashSet;
+use std::marker::PhantomData;
+use std::path::PathBuf;
+
+use alloy_consensus::Transaction as _;
+use alloy_eips::BlockId;
+use alloy_network::{Network, TransactionResponse};
+use alloy_primitives::{Address, TxHash, U256};
+use alloy_provider::Provider;
+use alloy_rpc_types::{Header, Transaction};
+use eyre::{eyre, ErrReport};
+use rayon::prelude::*;
+use revm::db::CacheDB;
+use revm::primitives::Env;
+use revm::DatabaseRef;
+use serde::Serialize;
+use tokio::fs::OpenOptions;
+use tokio::io::{AsyncWriteExt, BufWriter};
+use tokio::sync::broadcast::error::RecvError;
+use tokio_util::sync::CancellationToken;
+use tracing::{error, info, warn};
+
+use loom_core_actors::{subscribe, Accessor, Actor, ActorResult, Broadcaster, Consumer, SharedState, WorkerResult};
+use loom_core_actors_macros::{Accessor, Consumer};
+use loom_core_blockchain::{Blockchain, BlockchainState};
+use loom_evm_db::{AlloyDB, DatabaseLoomExt};
+use loom_evm_utils::evm_env::env_for_block;
+use loom_evm_utils::NWETH;
+use loom_types_blockchain::{coinbase_balance_diff, ChainParameters};
+use loom_types_entities::{BlockHistory, Market, SwapLine, SwapPath};
+use loom_types_events::MarketEvents;
+
+use crate::SwapCalculator;
+
+use super::affected_pools_state::affected_pools_from_state_update;
+use super::pending_tx_simulator::{transact_and_commit, SimulationBlock};
+use super::state_change_arb_searcher::swap_paths_for_directions;
+
+/// Best backrun opportunity after a tx of a mined block
+#[derive(Clone, Debug, Serialize)]
+pub struct BlockOpportunity {
+    pub block_number: u64,
+    /// Index of the tx that created the opportunity
+    pub tx_index: usize,
+    pub tx_hash: TxHash,
+    pub swap: String,
+    pub pools: Vec<Address>,
+    pub profit_eth: f64,
+    pub captured_by: Option<OpportunityCapture>,
+    /// Not captured when the replay stopped at a failed tx, a capture by a later tx is missing
+    pub partial: bool,
+}
+
+/// Later tx of the same block after which the opportunity was gone
+#[derive(Clone, Debug, Serialize)]
+pub struct OpportunityCapture {
+    pub tx_index: usize,
+    pub tx_hash: TxHash,
+    pub from: Address,
+    pub to: Option<Address>,
+    /// Sent to the coinbase by the tx, priority fee included
+    pub coinbase_diff_eth: f64,
+    pub remaining_profit_eth: f64,
+}
+
+/// Opportunities of a block, the replay stops at the first tx failing to simulate as the later state would be wrong
+#[derive(Clone, Debug)]
+pub struct BlockAnalysis {
+    pub opportunities: Vec<BlockOpportunity>,
+    /// Index of the tx the replay stopped at
+    pub failed_tx_index: Option<usize>,
+}
+
+/// Opportunity not captured yet by a later tx
+struct OpenOpportunity {
+    idx: usize,
+    swap_line: SwapLine,
+    pools: Vec<Address>,
+}
+
+/// Profit of the best swap line on the path, zero if the path is not profitable anymore
+fn best_profit_eth<DB: DatabaseRef<Error = ErrReport>>(path: SwapPath, db: &DB, env: &Env) -> U256 {
+    let mut swap_line = SwapLine { path, ..Default::default() };
+    match SwapCalculator::calculate(&mut swap_line, db, env.clone()) {
+        Ok(_) => swap_line.abs_profit_eth(),
+        Err(_) => U256::ZERO,
+    }
+}
+
+/// Replays the txs of a block one by one on the parent state, searches backruns after each tx
+/// and assigns opportunities to the tx after which they were not profitable anymore.
+pub fn analyze_block<DB: DatabaseRef<Error = ErrReport> + Send + Sync>(
+    market: &SharedState<Market>,
+    chain_id: u64,
+    header: &Header,
+    txs: &[Transaction],
+    parent_state: DB,
+) -> BlockAnalysis {
+    let block = SimulationBlock {
+        chain_id,
+        coinbase: header.beneficiary,
+        number: header.number,
+        timestamp: header.timestamp,
+        base_fee: header.base_fee_per_gas.unwrap_or_default(),
+        excess_blob_gas: header.excess_blob_gas,
+    };
+    let env = env_for_block(header.number, header.timestamp);
+    // same threshold as the state change searcher
+    let min_profit = U256::from(block.base_fee * 100_000);
+
+    let mut db = CacheDB::new(parent_state);
+    let mut opportunities: Vec<BlockOpportunity> = Vec::new();
+    let mut open: Vec<OpenOpportunity> = Vec::new();
+    let mut failed_tx_index = None;
+
+    for (tx_index, tx) in txs.iter().enumerate() {
+        let tx_hash = tx.tx_hash();
+        let (pre, post) = match transact_and_commit(&mut db, &tx.clone().into_request(), &block) {
+            Ok(diff) => diff,
+            Err(error) => {
+                warn!(%error, %tx_hash, tx_index, "Block tx simulation failed, later txs are not analyzed");
+                failed_tx_index = Some(tx_index);
+                break;
+            }
+        };
+
+        let touched: HashSet<Address> = post.keys().copied().collect();
+        open.retain(|open_opportunity| {
+            if !open_opportunity.pools.iter().any(|pool| touched.contains(pool)) {
+                return true;
+            }
+            let remaining_profit = best_profit_eth(open_opportunity.swap_line.path.clone(), &db, &env);
+            if remaining_profit > min_profit {
+                return true;
+            }
+            opportunities[open_opportunity.idx].captured_by = Some(OpportunityCapture {
+                tx_index,
+                tx_hash,
+                from: tx.from,
+                to: tx.to(),
+                coinbase_diff_eth: NWETH::to_float(coinbase_balance_diff(&pre, &post, &block.coinbase).unwrap_or_default()),
+                remaining_profit_eth: NWETH::to_float(remaining_profit),
+            });
+            false
+        });
+
+        let swap_paths: Vec<SwapPath> = {
+            let market_guard = market.inner().blocking_read();
+            let affected_pools = affected_pools_from_state_update(&market_guard, &vec![post]);
+            if affected_pools.is_empty() {
+                continue;
+            }
+            swap_paths_for_directions(&market_guard, &affected_pools)
+        };
+
+        let best_swap_line = swap_paths
+            .into_par_iter()
+            .filter(|path| !open.iter().any(|open_opportunity| &open_opportunity.swap_line.path == path))
+            .filter_map(|path| {
+                let mut swap_line = SwapLine { path, ..Default::default() };
+                SwapCalculator::calculate(&mut swap_line, &db, env.clone()).ok()?;
+                (swap_line.abs_profit_eth() > min_profit).then_some(swap_line)
+            })
+            .max_by_key(|swap_line| swap_line.abs_profit_eth());
+
+        if let Some(swap_line) = best_swap_line {
+            let pools: Vec<Address> = swap_line.pools().iter().map(|pool| pool.get_address()).collect();
+            open.push(OpenOpportunity { idx: opportunities.len(), swap_line: swap_line.clone(), pools: pools.clone() });
+            opportunities.push(BlockOpportunity {
+                block_number: header.number,
+                tx_index,
+                tx_hash,
+                swap: swap_line.to_string(),
+                pools,
+                profit_eth: NWETH::to_float(swap_line.abs_profit_eth()),
+                captured_by: None,
+                partial: false,
+            });
+        }
+    }
+
+    if failed_tx_index.is_some() {
+        for open_opportunity in open {
+            opportunities[open_opportunity.idx].partial = true;
+        }
+    }
+    BlockAnalysis { opportunities, failed_tx_index }
+}
+
+/// Worker analyzes every new block and appends its opportunities to `path`, one [`BlockOpportunity`] json per line.
+/// Block history only holds the market state, accounts and slots missing in it are fetched from the node at the parent block.
+pub async fn block_tx_analyzer_worker<P, N, DB>(
+    client: P,
+    chain_parameters: ChainParameters,
+    path: PathBuf,
+    market: SharedState<Market>,
+    block_history: SharedState<BlockHistory<DB>>,
+    market_events_rx: Broadcaster<MarketEvents>,
+    shutdown_token: CancellationToken,
+) -> WorkerResult
+where
+    N: Network,
+    P: Provider<N> + Send + Sync + Clone + 'static,
+    DB: DatabaseRef<Error = ErrReport> + DatabaseLoomExt + Send + Sync + Clone + 'static,
+{
+    subscribe!(market_events_rx);
+
+    let file = OpenOptions::new().create(true).append(true).open(&path).await?;
+    let mut writer = BufWriter::new(file);
+    info!(path = %path.display(), "Block tx analyzer started");
+
+    loop {
+        let market_event = tokio::select! {
+            market_event = market_events_rx.recv() => market_event,
+            _ = shutdown_token.cancelled() => break Ok("Block tx analyzer stopped".to_string()),
+        };
+        let block_hash = match market_event {
+            Ok(MarketEvents::BlockTxUpdate { block_hash, .. }) => block_hash,
+            Ok(_) => continue,
+            Err(RecvError::Lagged(lag)) => {
+                warn!(lag, "Market events channel lagged, blocks are not analyzed");
+                continue;
+            }
+            Err(RecvError::Closed) => {
+                error!("Market events channel closed");
+                break Err(eyre!("MARKET_EVENTS_RX_CLOSED"));
+            }
+        };
+
+        let (header, txs, mut parent_state) = {
+            let block_history_guard = block_history.read().await;
+            let Some(entry) = block_history_guard.get_block_history_entry(&block_hash) else {
+                error!(%block_hash, "Block history entry not found");
+                continue;
+            };
+            let Some(txs) = entry.block.as_ref().and_then(|block| block.transactions.as_transactions()).map(|txs| txs.to_vec()) else {
+                error!(%block_hash, "Block has no txs");
+                continue;
+            };
+            let Some(parent_state) = block_history_guard.get_block_state(&entry.header.parent_hash).cloned() else {
+                error!(%block_hash, parent_hash = %entry.header.parent_hash, "Parent block state not found");
+                continue;
+            };
+            (entry.header.clone(), txs, parent_state)
+        };
+        match AlloyDB::new(client.clone(), BlockId::hash(header.parent_hash)) {
+            Some(ext_db) => parent_state.with_ext_db(ext_db),
+            None => {
+                error!("AlloyDB is None");
+                continue;
+            }
+        }
+
+        let market_clone = market.clone();
+        let chain_id = chain_parameters.chain_id;
+        let start_time = std::time::Instant::now();
+        let BlockAnalysis { opportunities, failed_tx_index } =
+            tokio::task::spawn_blocking(move || analyze_block(&market_clone, chain_id, &header, &txs, parent_state)).await?;
+
+        let captured = opportunities.iter().filter(|opportunity| opportunity.captured_by.is_some()).count();
+        info!(
+            %block_hash,
+            opportunities = opportunities.len(),
+            captured,
+            ?failed_tx_index,
+            elapsed = start_time.elapsed().as_millis(),
+            "Block analyzed"
+        );
+
+        for opportunity in opportunities.iter() {
+            let mut line = serde_json::to_vec(opportunity)?;
+            line.push(b'\n');
+            writer.write_all(&line).await?;
+        }
+        writer.flush().await?;
+    }
+}
+
+/// Competitor analysis of mined blocks, records backrun opportunities after each tx and the txs that captured them
+#[derive(Accessor, Consumer)]
+pub struct BlockTxAnalyzerActor<P, N, DB: Clone + Send + Sync + 'static> {
+    client: P,
+    path: PathBuf,
+    chain_parameters: ChainParameters,
+    #[accessor]
+    market: Option<SharedState<Market>>,
+    #[accessor]
+    block_history: Option<SharedState<BlockHistory<DB>>>,
+    #[consumer]
+    market_events_rx: Option<Broadcaster<MarketEvents>>,
+    _n: PhantomData<N>,
+}
+
+impl<P, N, DB> BlockTxAnalyzerActor<P, N, DB>
+where
+    N: Network,
+    P: Provider<N> + Send + Sync + Clone + 'static,
+    DB: DatabaseRef<Error = ErrReport> + DatabaseLoomExt + Send + Sync + Clone + 'static,
+{
+    pub fn new(client: P, path: PathBuf) -> Self {
+        Self {
+            client,
+            path,
+            chain_parameters: ChainParameters::ethereum(),
+            market: None,
+            block_history: None,
+            market_events_rx: None,
+            _n: PhantomData,
+        }
+    }
+
+    pub fn on_bc(self, bc: &Blockchain, state: &BlockchainState<DB>) -> Self {
+        Self {
+            chain_parameters: bc.chain_parameters(),
+            market: Some(bc.market()),
+            block_history: Some(state.block_history()),
+            market_events_rx: Some(bc.market_events_channel()),
+            ..self
+        }
+    }
+}
+
+impl<P, N, DB> Actor for BlockTxAnalyzerActor<P, N, DB>
+where
+    N: Network,
+    P: Provider<N> + Send + Sync + Clone + 'static,
+    DB: DatabaseRef<Error = ErrReport> + DatabaseLoomExt + Send + Sync + Clone + 'static,
+{
+    fn start(&self) -> ActorResult {
+        self.start_until(CancellationToken::new())
+    }
+
+    fn start_until(&self, shutdown_token: CancellationToken) -> ActorResult {
+        let task = tokio::task::spawn(block_tx_analyzer_worker(
+            self.client.clone(),
+            self.chain_parameters.clone(),
+            self.path.clone(),
+            self.market.clone().unwrap(),
+            self.block_history.clone().unwrap(),
+            self.market_events_rx.clone().unwrap(),
+            shutdown_token,
+        ));
+        Ok(vec![task])
+    }
+
+    fn name(&self) -> &'static str {
+        "BlockTxAnalyzerActor"
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloy_consensus::{Signed, TxEnvelope, TxLegacy};
+    use alloy_primitives::{Bytes, PrimitiveSignature, TxKind, B256};
+    use loom_defi_address_book::TokenAddressEth;
+    use loom_defi_pools::UniswapV2Pool;
+    use loom_types_entities::Token;
+    use revm::db::EmptyDBTyped;
+    use revm::primitives::{AccountInfo, Bytecode};
+
+    const FUNDED: Address = Address::repeat_byte(1);
+    const EMPTY: Address = Address::repeat_byte(2);
+    const COINBASE: Address = Address::repeat_byte(4);
+    const TOKEN: Address = Address::repeat_byte(0x11);
+    const POOL_A: Address = Address::repeat_byte(0xaa);
+    const POOL_B: Address = Address::repeat_byte(0xbb);
+    /// Stores the calldata word in the reserves cell of uniswap v2 pairs
+    const SET_RESERVES_CODE: [u8; 7] = [0x60, 0x00, 0x35, 0x60, 0x08, 0x55, 0x00];
+
+    fn legacy_tx(id: u8, from: Address, nonce: u64, to: Address, gas_price: u128, input: Bytes) -> Transaction {
+        let tx = TxLegacy { nonce, gas_price, gas_limit: 100_000, to: TxKind::Call(to), input, ..TxLegacy::default() };
+        let signature = PrimitiveSignature::new(U256::from(1), U256::from(1), false);
+        Transaction {
+            inner: TxEnvelope::Legacy(Signed::new_unchecked(tx, signature, TxHash::repeat_byte(id))),
+            block_hash: None,
+            block_number: None,
+            transaction_index: None,
+            effective_gas_price: None,
+            from,
+        }
+    }
+
+    fn transfer_tx(id: u8, from: Address, nonce: u64) -> Transaction {
+        legacy_tx(id, from, nonce, Address::repeat_byte(3), 1, Bytes::new())
+    }
+
+    /// Packed reserves of a TOKEN / WETH pair in ether
+    fn reserves(token: u64, weth: u64) -> Bytes {
+        let ether = U256::from(10).pow(U256::from(18));
+        let packed = U256::from(token) * ether | (U256::from(weth) * ether) << 112;
+        Bytes::from(packed.to_be_bytes::<32>().to_vec())
+    }
+
+    fn header() -> Header {
+        Header {
+            hash: B256::ZERO,
+            total_difficulty: None,
+            size: None,
+            inner: alloy_consensus::Header { number: 1, beneficiary: COINBASE, base_fee_per_gas: Some(1), ..Default::default() },
+        }
+    }
+
+    fn parent_state() -> CacheDB<EmptyDBTyped<ErrReport>> {
+        let mut parent_state = CacheDB::new(EmptyDBTyped::<ErrReport>::new());
+        parent_state.insert_account_info(FUNDED, AccountInfo { balance: U256::from(10).pow(U256::from(18)), ..AccountInfo::default() });
+        parent_state.insert_account_info(COINBASE, AccountInfo { balance: U256::from(1), ..AccountInfo::default() });
+        parent_state
+    }
+
+    #[test]
+    fn test_analyze_block_stops_at_failed_tx() {
+        let header = header();
+        let parent_state = parent_state();
+        let market = SharedState::new(Market::default());
+
+        let txs = vec![transfer_tx(1, FUNDED, 0), transfer_tx(2, FUNDED, 1)];
+        let analysis = analyze_block(&market, 1, &header, &txs, parent_state.clone());
+        assert_eq!(analysis.failed_tx_index, None);
+
+        // the sender can't pay for gas, the tx after it is not replayed on a wrong state
+        let txs = vec![transfer_tx(1, FUNDED, 0), transfer_tx(2, EMPTY, 0), transfer_tx(3, FUNDED, 1)];
+        let analysis = analyze_block(&market, 1, &header, &txs, parent_state);
+        assert_eq!(analysis.failed_tx_index, Some(1));
+        assert!(analysis.opportunities.is_empty());
+    }
+
+    #[test]
+    fn test_analyze_block_captured_opportunity() {
+        let weth = Token::new_with_data(TokenAddressEth::WETH, Some("WETH".to_string()), None, Some(18), true, false);
+        weth.set_eth_price(Some(U256::from(10).pow(U256::from(18))));
+        let mut market = Market::default();
+        market.add_token(weth);
+        market.add_token(Token::new_with_data(TOKEN, Some("TOKEN".to_string()), None, Some(18), false, false));
+
+        let mut parent_state = parent_state();
+        for pool in [POOL_A, POOL_B] {
+            market
+                .add_pool(
+                    UniswapV2Pool::new_with_data(pool, TOKEN, TokenAddressEth::WETH, Address::ZERO, U256::ZERO, U256::ZERO)
+                        .with_reserves_cell(U256::from(8)),
+                )
+                .unwrap();
+            parent_state.insert_account_info(pool, AccountInfo::from_bytecode(Bytecode::new_raw(Bytes::from_static(&SET_RESERVES_CODE))));
+            parent_state.insert_account_storage(pool, U256::from(8), U256::from_be_slice(&reserves(1000, 1000))).unwrap();
+        }
+        let market = SharedState::new(market);
+
+        // the first tx makes TOKEN more expensive in pool A, the second one restores the price and pays the builder
+        let txs = vec![
+            legacy_tx(1, FUNDED, 0, POOL_A, 1, reserves(1000, 1100)),
+            legacy_tx(2, FUNDED, 1, POOL_A, 1_000_000_001, reserves(1000, 1000)),
+        ];
+        let analysis = analyze_block(&market, 1, &header(), &txs, parent_state);
+        assert_eq!(analysis.failed_tx_index, None);
+        assert_eq!(analysis.opportunities.len(), 1);
+
+        let opportunity = &analysis.opportunities[0];
+        assert_eq!(opportunity.tx_index, 0);
+        assert_eq!(opportunity.tx_hash, TxHash::repeat_byte(1));
+        assert!(opportunity.pools.contains(&POOL_A) && opportunity.pools.contains(&POOL_B));
+        assert!(opportunity.profit_eth > 0.0);
+        assert!(!opportunity.partial);
+
+        let capture = opportunity.captured_by.as_ref().unwrap();
+        assert_eq!(capture.tx_index, 1);
+        assert_eq!(capture.tx_hash, TxHash::repeat_byte(2));
+        assert_eq!(capture.from, FUNDED);
+        assert_eq!(capture.to, Some(POOL_A));
+        // priority fee of 1 gwei for every gas unit used
+        assert!(capture.coinbase_diff_eth > 21_000e-9 && capture.coinbase_diff_eth < 100_000e-9);
+    }
+}
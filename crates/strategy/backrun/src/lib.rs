@@ -1,11 +1,17 @@
 pub use arb_actor::StateChangeArbActor;
-pub use backrun_config::{BackrunConfig, BackrunConfigSection};
+pub use backrun_config::{BackrunConfig, BackrunConfigSection, PendingTxSimulation};
+pub use bid_outcome_recorder::BidOutcomeRecorderActor;
 pub use block_state_change_processor::BlockStateChangeProcessorActor;
+pub use block_tx_analyzer::{analyze_block, BlockAnalysis, BlockOpportunity, BlockTxAnalyzerActor, OpportunityCapture};
 pub use pending_tx_state_change_processor::PendingTxStateChangeProcessorActor;
 pub use state_change_arb_searcher::StateChangeArbSearcherActor;
 pub use swap_calculator::SwapCalculator;
 
+mod bid_outcome_recorder;
 mod block_state_change_processor;
+mod block_tx_analyzer;
+mod pending_tx_bundler;
+mod pending_tx_simulator;
 mod pending_tx_state_change_processor;
 mod state_change_arb_searcher;
 
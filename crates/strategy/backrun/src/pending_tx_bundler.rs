@@ -0,0 +1,243 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use alloy_primitives::{Address, TxHash};
+
+/// Upper limit of pending txes simulated in one sequence
+pub const MAX_BUNDLE_TXS: usize = 8;
+
+#[derive(Clone, Debug)]
+pub struct BundleTx<T> {
+    pub tx_hash: TxHash,
+    pub sender: Address,
+    pub nonce: u64,
+    /// Effective gas price for the next block, used for ordering
+    pub gas_price: u128,
+    pub pools: Vec<Address>,
+    pub payload: T,
+}
+
+/// Pending txes of the next block that affect pools. Txes sharing a pool or a sender are grouped and ordered for a combined simulation.
+/// Txes are indexed by pool and sender, a tx without pools is only kept when its sender already has txes in the bundler.
+#[derive(Clone, Debug)]
+pub struct PendingTxBundler<T> {
+    txs: HashMap<TxHash, BundleTx<T>>,
+    by_pool: HashMap<Address, HashSet<TxHash>>,
+    by_sender: HashMap<Address, HashSet<TxHash>>,
+}
+
+impl<T> Default for PendingTxBundler<T> {
+    fn default() -> Self {
+        Self { txs: HashMap::new(), by_pool: HashMap::new(), by_sender: HashMap::new() }
+    }
+}
+
+impl<T: Clone> PendingTxBundler<T> {
+    /// Drops all txes, called when a new block arrives
+    pub fn clear(&mut self) {
+        self.txs.clear();
+        self.by_pool.clear();
+        self.by_sender.clear();
+    }
+
+    /// Drops a tx that is not going to be mined anymore, e.g. after a replacement
+    pub fn remove(&mut self, tx_hash: &TxHash) {
+        let Some(tx) = self.txs.remove(tx_hash) else {
+            return;
+        };
+        Self::unindex(&mut self.by_sender, &tx.sender, tx_hash);
+        for pool in tx.pools.iter() {
+            Self::unindex(&mut self.by_pool, pool, tx_hash);
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.txs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.txs.is_empty()
+    }
+
+    /// True if the sender has txes in the bundler, txes without pools of other senders are not kept
+    pub fn has_sender(&self, sender: &Address) -> bool {
+        self.by_sender.contains_key(sender)
+    }
+
+    /// Adds the tx and returns the ordered sequence of txes connected to it, empty if the tx has no related txes
+    pub fn add(&mut self, tx: BundleTx<T>) -> Vec<BundleTx<T>> {
+        if self.txs.contains_key(&tx.tx_hash) || (tx.pools.is_empty() && !self.has_sender(&tx.sender)) {
+            return Vec::new();
+        }
+        let tx_hash = tx.tx_hash;
+        self.by_sender.entry(tx.sender).or_default().insert(tx_hash);
+        for pool in tx.pools.iter() {
+            self.by_pool.entry(*pool).or_default().insert(tx_hash);
+        }
+        self.txs.insert(tx_hash, tx);
+
+        let group = self.connected(tx_hash);
+        if group.len() < 2 {
+            return Vec::new();
+        }
+        Self::order(group, tx_hash)
+    }
+
+    fn unindex(index: &mut HashMap<Address, HashSet<TxHash>>, key: &Address, tx_hash: &TxHash) {
+        if let Some(tx_hashes) = index.get_mut(key) {
+            tx_hashes.remove(tx_hash);
+            if tx_hashes.is_empty() {
+                index.remove(key);
+            }
+        }
+    }
+
+    /// Txes reachable from `tx_hash` through shared pools or senders, every pool and sender is looked up once
+    fn connected(&self, tx_hash: TxHash) -> Vec<BundleTx<T>> {
+        let mut pools: HashSet<Address> = HashSet::new();
+        let mut senders: HashSet<Address> = HashSet::new();
+        let mut group: HashSet<TxHash> = HashSet::from([tx_hash]);
+        let mut queue: Vec<TxHash> = vec![tx_hash];
+
+        while let Some(tx_hash) = queue.pop() {
+            let Some(tx) = self.txs.get(&tx_hash) else {
+                continue;
+            };
+            let mut linked: Vec<&TxHash> = Vec::new();
+            if senders.insert(tx.sender) {
+                linked.extend(self.by_sender.get(&tx.sender).into_iter().flatten());
+            }
+            for pool in tx.pools.iter() {
+                if pools.insert(*pool) {
+                    linked.extend(self.by_pool.get(pool).into_iter().flatten());
+                }
+            }
+            for linked_hash in linked {
+                if group.insert(*linked_hash) {
+                    queue.push(*linked_hash);
+                }
+            }
+        }
+
+        group.iter().filter_map(|tx_hash| self.txs.get(tx_hash).cloned()).collect()
+    }
+
+    /// Orders by gas price like a block builder, txes of one sender keep the nonce order in the slots they take.
+    /// Over the limit the triggering tx and the lower nonces of its sender are always kept, the other slots go in order.
+    fn order(mut txs: Vec<BundleTx<T>>, tx_hash: TxHash) -> Vec<BundleTx<T>> {
+        txs.sort_by(|a, b| b.gas_price.cmp(&a.gas_price).then(a.tx_hash.cmp(&b.tx_hash)));
+
+        let mut by_sender: BTreeMap<Address, Vec<usize>> = BTreeMap::new();
+        for (idx, tx) in txs.iter().enumerate() {
+            by_sender.entry(tx.sender).or_default().push(idx);
+        }
+        let mut ordered: Vec<Option<BundleTx<T>>> = vec![None; txs.len()];
+        for slots in by_sender.values() {
+            let mut sender_txs: Vec<&BundleTx<T>> = slots.iter().map(|idx| &txs[*idx]).collect();
+            sender_txs.sort_by_key(|tx| tx.nonce);
+            for (slot, tx) in slots.iter().zip(sender_txs) {
+                ordered[*slot] = Some(tx.clone());
+            }
+        }
+
+        let ordered: Vec<BundleTx<T>> = ordered.into_iter().flatten().collect();
+        if ordered.len() <= MAX_BUNDLE_TXS {
+            return ordered;
+        }
+
+        let Some((sender, nonce)) = ordered.iter().find(|tx| tx.tx_hash == tx_hash).map(|tx| (tx.sender, tx.nonce)) else {
+            return ordered.into_iter().take(MAX_BUNDLE_TXS).collect();
+        };
+        let is_required = |tx: &BundleTx<T>| tx.sender == sender && tx.nonce <= nonce;
+        let mut free_slots = MAX_BUNDLE_TXS.saturating_sub(ordered.iter().filter(|&tx| is_required(tx)).count());
+        ordered
+            .into_iter()
+            .filter(|tx| {
+                if is_required(tx) {
+                    true
+                } else if free_slots > 0 {
+                    free_slots -= 1;
+                    true
+                } else {
+                    false
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn bundle_tx(id: u8, sender: u8, nonce: u64, gas_price: u128, pools: &[u8]) -> BundleTx<()> {
+        BundleTx {
+            tx_hash: TxHash::repeat_byte(id),
+            sender: Address::repeat_byte(sender),
+            nonce,
+            gas_price,
+            pools: pools.iter().map(|pool| Address::repeat_byte(*pool)).collect(),
+            payload: (),
+        }
+    }
+
+    #[test]
+    fn test_bundler() {
+        let mut bundler: PendingTxBundler<()> = PendingTxBundler::default();
+        assert!(bundler.add(bundle_tx(1, 1, 0, 10, &[100])).is_empty());
+        assert!(bundler.add(bundle_tx(2, 2, 0, 20, &[200])).is_empty());
+
+        // shares pool 100 with tx 1
+        let bundle = bundler.add(bundle_tx(3, 3, 0, 30, &[100]));
+        let hashes: Vec<TxHash> = bundle.iter().map(|tx| tx.tx_hash).collect();
+        assert_eq!(hashes, vec![TxHash::repeat_byte(3), TxHash::repeat_byte(1)]);
+
+        // same sender as tx 3 with a higher nonce and the lowest gas price, goes last
+        let bundle = bundler.add(bundle_tx(4, 3, 5, 5, &[]));
+        let hashes: Vec<TxHash> = bundle.iter().map(|tx| tx.tx_hash).collect();
+        assert_eq!(hashes, vec![TxHash::repeat_byte(3), TxHash::repeat_byte(1), TxHash::repeat_byte(4)]);
+        assert_eq!(bundle[0].nonce, 0);
+
+        let bundle = bundler.add(bundle_tx(5, 3, 1, 1, &[]));
+        let nonces: Vec<u64> = bundle.iter().filter(|tx| tx.sender == Address::repeat_byte(3)).map(|tx| tx.nonce).collect();
+        assert_eq!(nonces, vec![0, 1, 5]);
+
+        assert!(bundler.add(bundle_tx(5, 3, 1, 1, &[])).is_empty());
+        bundler.remove(&TxHash::repeat_byte(5));
+        assert_eq!(bundler.len(), 4);
+        bundler.clear();
+        assert!(bundler.is_empty());
+    }
+
+    #[test]
+    fn test_bundler_keeps_triggering_tx() {
+        let mut bundler: PendingTxBundler<()> = PendingTxBundler::default();
+        for id in 1..=MAX_BUNDLE_TXS as u8 {
+            bundler.add(bundle_tx(id, id, 0, 10 * id as u128, &[100]));
+        }
+
+        // lowest gas price of the group, the cheapest other tx is dropped instead
+        let trigger = MAX_BUNDLE_TXS as u8 + 1;
+        let bundle = bundler.add(bundle_tx(trigger, trigger, 0, 1, &[100]));
+        let hashes: Vec<TxHash> = bundle.iter().map(|tx| tx.tx_hash).collect();
+        assert_eq!(hashes.len(), MAX_BUNDLE_TXS);
+        assert_eq!(hashes.last(), Some(&TxHash::repeat_byte(trigger)));
+        assert!(!hashes.contains(&TxHash::repeat_byte(1)));
+    }
+
+    #[test]
+    fn test_bundler_skips_untracked_txs() {
+        let mut bundler: PendingTxBundler<()> = PendingTxBundler::default();
+        // no pools and an unknown sender
+        assert!(bundler.add(bundle_tx(1, 1, 0, 10, &[])).is_empty());
+        assert!(bundler.is_empty());
+
+        assert!(bundler.add(bundle_tx(2, 1, 1, 10, &[100])).is_empty());
+        assert!(bundler.has_sender(&Address::repeat_byte(1)));
+        assert_eq!(bundler.add(bundle_tx(1, 1, 0, 10, &[])).len(), 2);
+
+        bundler.remove(&TxHash::repeat_byte(2));
+        bundler.remove(&TxHash::repeat_byte(1));
+        assert!(!bundler.has_sender(&Address::repeat_byte(1)));
+        assert!(bundler.by_pool.is_empty());
+    }
+}
@@ -0,0 +1,267 @@
+use alloy_primitives::{Address, Log, U256};
+use alloy_rpc_types::TransactionRequest;
+use eyre::{eyre, Result};
+use loom_evm_utils::evm::evm_state_diff;
+use loom_types_blockchain::{GethStateUpdate, GethStateUpdateVec, CHAIN_ID_BASE, CHAIN_ID_ETHEREUM, CHAIN_ID_OPTIMISM};
+use revm::db::CacheDB;
+use revm::primitives::{AuthorizationList, BlobExcessGasAndPrice, BlockEnv, CfgEnv, Env, ResultAndState, SpecId, TxEnv};
+use revm::{DatabaseCommit, DatabaseRef, Evm};
+use tracing::warn;
+
+/// Block the pending txes are simulated in, same overrides as used for `debug_traceCall`
+#[derive(Clone, Debug)]
+pub struct SimulationBlock {
+    pub chain_id: u64,
+    pub coinbase: Address,
+    pub number: u64,
+    pub timestamp: u64,
+    pub base_fee: u64,
+    /// Excess blob gas of the simulated block, sets the blob base fee for type 3 txes
+    pub excess_blob_gas: Option<u64>,
+}
+
+/// Fork timestamps of the supported chains, the OP stack forks are mapped to the matching Ethereum spec
+const CANCUN_TIMESTAMP_ETHEREUM: u64 = 1710338135;
+const PRAGUE_TIMESTAMP_ETHEREUM: u64 = 1746612311;
+const ECOTONE_TIMESTAMP_OP_STACK: u64 = 1710374401;
+const ISTHMUS_TIMESTAMP_OP_STACK: u64 = 1746806401;
+
+/// Blob gas target per block, the excess above it raises the blob base fee of the next block
+const TARGET_BLOB_GAS_PER_BLOCK_CANCUN: u64 = 393216;
+const TARGET_BLOB_GAS_PER_BLOCK_PRAGUE: u64 = 786432;
+
+/// Spec of a block on the chain, unknown chains get the latest spec
+pub fn chain_spec_id(chain_id: u64, timestamp: u64) -> SpecId {
+    let (cancun_timestamp, prague_timestamp) = match chain_id {
+        CHAIN_ID_ETHEREUM => (CANCUN_TIMESTAMP_ETHEREUM, PRAGUE_TIMESTAMP_ETHEREUM),
+        CHAIN_ID_OPTIMISM | CHAIN_ID_BASE => (ECOTONE_TIMESTAMP_OP_STACK, ISTHMUS_TIMESTAMP_OP_STACK),
+        _ => return SpecId::PRAGUE,
+    };
+    if timestamp >= prague_timestamp {
+        SpecId::PRAGUE
+    } else if timestamp >= cancun_timestamp {
+        SpecId::CANCUN
+    } else {
+        SpecId::SHANGHAI
+    }
+}
+
+/// Excess blob gas of the block following a parent with `parent_excess_blob_gas` and `parent_blob_gas_used`
+pub fn calc_next_excess_blob_gas(parent_excess_blob_gas: u64, parent_blob_gas_used: u64, is_prague: bool) -> u64 {
+    let target = if is_prague { TARGET_BLOB_GAS_PER_BLOCK_PRAGUE } else { TARGET_BLOB_GAS_PER_BLOCK_CANCUN };
+    (parent_excess_blob_gas + parent_blob_gas_used).saturating_sub(target)
+}
+
+impl SimulationBlock {
+    pub fn spec_id(&self) -> SpecId {
+        chain_spec_id(self.chain_id, self.timestamp)
+    }
+
+    /// Blob base fee of the block, None before Cancun
+    pub fn blob_gas_price(&self) -> Option<u128> {
+        self.blob_excess_gas_and_price().map(|blob| blob.blob_gasprice)
+    }
+
+    fn blob_excess_gas_and_price(&self) -> Option<BlobExcessGasAndPrice> {
+        let spec_id = self.spec_id();
+        if !spec_id.is_enabled_in(SpecId::CANCUN) {
+            return None;
+        }
+        self.excess_blob_gas.map(|excess_blob_gas| BlobExcessGasAndPrice::new(excess_blob_gas, spec_id.is_enabled_in(SpecId::PRAGUE)))
+    }
+}
+
+fn pending_tx_env(block: &SimulationBlock, transaction_request: &TransactionRequest) -> Env {
+    let mut cfg = CfgEnv::default();
+    cfg.chain_id = block.chain_id;
+
+    Env {
+        cfg,
+        block: BlockEnv {
+            number: U256::from(block.number),
+            timestamp: U256::from(block.timestamp),
+            coinbase: block.coinbase,
+            basefee: U256::from(block.base_fee),
+            blob_excess_gas_and_price: block.blob_excess_gas_and_price(),
+            ..BlockEnv::default()
+        },
+        tx: TxEnv {
+            caller: transaction_request.from.unwrap_or_default(),
+            transact_to: transaction_request.to.unwrap_or_default(),
+            // like debug_traceCall nonces are not checked, the tx may be queued behind other pending txes of the sender
+            nonce: None,
+            data: transaction_request.input.input().cloned().unwrap_or_default(),
+            value: transaction_request.value.unwrap_or_default(),
+            gas_price: U256::from(transaction_request.max_fee_per_gas.or(transaction_request.gas_price).unwrap_or_default()),
+            gas_priority_fee: transaction_request.max_priority_fee_per_gas.map(U256::from),
+            gas_limit: transaction_request.gas.unwrap_or_default(),
+            chain_id: transaction_request.chain_id,
+            access_list: transaction_request.access_list.clone().map(|access_list| access_list.0).unwrap_or_default(),
+            blob_hashes: transaction_request.blob_versioned_hashes.clone().unwrap_or_default(),
+            max_fee_per_blob_gas: transaction_request.max_fee_per_blob_gas.map(U256::from),
+            authorization_list: transaction_request.authorization_list.clone().map(AuthorizationList::Signed),
+        },
+    }
+}
+
+fn transact_pending_tx<DB: DatabaseRef>(
+    state_db: &DB,
+    block: &SimulationBlock,
+    transaction_request: &TransactionRequest,
+) -> Result<ResultAndState> {
+    let env = pending_tx_env(block, transaction_request);
+    let mut evm = Evm::builder().with_spec_id(block.spec_id()).with_ref_db(state_db).with_env(Box::new(env)).build();
+    evm.transact().map_err(|_| eyre!("TRANSACT_ERROR"))
+}
+
+/// Executes a pending tx on top of `state_db`.
+/// Returns pre and post state like the prestate tracer in diff mode and the emitted logs.
+pub fn simulate_pending_tx<DB: DatabaseRef>(
+    state_db: DB,
+    transaction_request: &TransactionRequest,
+    block: &SimulationBlock,
+) -> Result<(GethStateUpdate, GethStateUpdate, Vec<Log>)> {
+    let result = transact_pending_tx(&state_db, block, transaction_request)?;
+    let (pre, post) = evm_state_diff(&state_db, &result.state)?;
+    Ok((pre, post, result.result.into_logs()))
+}
+
+/// Executes pending txes one after another, each tx sees the changes of the previous ones.
+/// Returns pre and post state of every tx.
+pub fn simulate_pending_tx_sequence<DB: DatabaseRef>(
+    state_db: DB,
+    transaction_requests: &[TransactionRequest],
+    block: &SimulationBlock,
+) -> Result<(GethStateUpdateVec, GethStateUpdateVec)> {
+    let mut db = CacheDB::new(state_db);
+    let mut pre_vec = GethStateUpdateVec::new();
+    let mut post_vec = GethStateUpdateVec::new();
+
+    for transaction_request in transaction_requests {
+        let (pre, post) = transact_and_commit(&mut db, transaction_request, block)?;
+        pre_vec.push(pre);
+        post_vec.push(post);
+    }
+    Ok((pre_vec, post_vec))
+}
+
+/// Executes a tx on top of `db` and commits its changes. Returns pre and post state of the tx.
+pub fn transact_and_commit<DB: DatabaseRef>(
+    db: &mut CacheDB<DB>,
+    transaction_request: &TransactionRequest,
+    block: &SimulationBlock,
+) -> Result<(GethStateUpdate, GethStateUpdate)> {
+    let result = transact_pending_tx(&*db, block, transaction_request)?;
+    let (pre, post) = evm_state_diff(&*db, &result.state)?;
+    db.commit(result.state);
+    Ok((pre, post))
+}
+
+/// Logs the accounts and slots where the local simulation differs from the node trace, returns true if both are equal
+pub fn compare_state_diff(tx_hash: &impl std::fmt::Display, local: &GethStateUpdate, rpc: &GethStateUpdate) -> bool {
+    let mut equal = true;
+    for (address, rpc_state) in rpc.iter() {
+        match local.get(address) {
+            None => {
+                warn!(%tx_hash, %address, "Local simulation is missing account");
+                equal = false;
+            }
+            // balances are not compared, gas used by the node and revm may differ slightly
+            Some(local_state) => {
+                for (slot, value) in rpc_state.storage.iter() {
+                    if local_state.storage.get(slot) != Some(value) {
+                        warn!(%tx_hash, %address, %slot, rpc = %value, local = ?local_state.storage.get(slot), "Local simulation storage differs");
+                        equal = false;
+                    }
+                }
+            }
+        }
+    }
+    for address in local.keys().filter(|address| !rpc.contains_key(*address)) {
+        warn!(%tx_hash, %address, "Local simulation has extra account");
+        equal = false;
+    }
+    equal
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloy_eips::eip7702::Authorization;
+    use alloy_primitives::{PrimitiveSignature, TxKind, B256};
+    use revm::db::EmptyDB;
+    use revm::primitives::AccountInfo;
+
+    const CALLER: Address = Address::repeat_byte(1);
+    const TARGET: Address = Address::repeat_byte(2);
+
+    fn block(timestamp: u64, excess_blob_gas: Option<u64>) -> SimulationBlock {
+        SimulationBlock { chain_id: CHAIN_ID_ETHEREUM, coinbase: Address::ZERO, number: 1, timestamp, base_fee: 1, excess_blob_gas }
+    }
+
+    fn state_db() -> CacheDB<EmptyDB> {
+        let mut db = CacheDB::new(EmptyDB::default());
+        db.insert_account_info(CALLER, AccountInfo { balance: U256::from(10).pow(U256::from(18)), ..AccountInfo::default() });
+        db
+    }
+
+    fn transaction_request() -> TransactionRequest {
+        TransactionRequest {
+            from: Some(CALLER),
+            to: Some(TxKind::Call(TARGET)),
+            value: Some(U256::from(1)),
+            gas: Some(100_000),
+            max_fee_per_gas: Some(10),
+            max_priority_fee_per_gas: Some(1),
+            chain_id: Some(CHAIN_ID_ETHEREUM),
+            ..TransactionRequest::default()
+        }
+    }
+
+    #[test]
+    fn test_chain_spec_id() {
+        assert_eq!(chain_spec_id(CHAIN_ID_ETHEREUM, CANCUN_TIMESTAMP_ETHEREUM - 1), SpecId::SHANGHAI);
+        assert_eq!(chain_spec_id(CHAIN_ID_ETHEREUM, CANCUN_TIMESTAMP_ETHEREUM), SpecId::CANCUN);
+        assert_eq!(chain_spec_id(CHAIN_ID_ETHEREUM, PRAGUE_TIMESTAMP_ETHEREUM), SpecId::PRAGUE);
+        assert_eq!(chain_spec_id(CHAIN_ID_BASE, PRAGUE_TIMESTAMP_ETHEREUM), SpecId::CANCUN);
+        assert_eq!(chain_spec_id(CHAIN_ID_BASE, ISTHMUS_TIMESTAMP_OP_STACK), SpecId::PRAGUE);
+        assert_eq!(chain_spec_id(31337, 0), SpecId::PRAGUE);
+
+        assert_eq!(calc_next_excess_blob_gas(0, TARGET_BLOB_GAS_PER_BLOCK_PRAGUE, false), TARGET_BLOB_GAS_PER_BLOCK_PRAGUE / 2);
+        assert_eq!(calc_next_excess_blob_gas(0, TARGET_BLOB_GAS_PER_BLOCK_PRAGUE, true), 0);
+    }
+
+    #[test]
+    fn test_blob_tx() {
+        let mut blob_hash = B256::repeat_byte(3);
+        blob_hash.0[0] = 0x01;
+        let transaction_request =
+            TransactionRequest { blob_versioned_hashes: Some(vec![blob_hash]), max_fee_per_blob_gas: Some(1000), ..transaction_request() };
+        // blob base fee is above 1000 with the Cancun update fraction and below it with the Prague one
+        let excess_blob_gas = Some(33_384_770);
+
+        let cancun_block = block(CANCUN_TIMESTAMP_ETHEREUM, excess_blob_gas);
+        assert!(cancun_block.blob_gas_price().unwrap() > 1000);
+        assert!(simulate_pending_tx(state_db(), &transaction_request, &cancun_block).is_err());
+
+        let prague_block = block(PRAGUE_TIMESTAMP_ETHEREUM, excess_blob_gas);
+        assert!(prague_block.blob_gas_price().unwrap() < 1000);
+        let (_, post, _) = simulate_pending_tx(state_db(), &transaction_request, &prague_block).unwrap();
+        assert_eq!(post[&TARGET].balance, Some(U256::from(1)));
+
+        assert_eq!(block(CANCUN_TIMESTAMP_ETHEREUM - 1, excess_blob_gas).blob_gas_price(), None);
+    }
+
+    #[test]
+    fn test_set_code_tx() {
+        let authorization = Authorization { chain_id: U256::from(CHAIN_ID_ETHEREUM), address: TARGET, nonce: 0 }
+            .into_signed(PrimitiveSignature::new(U256::from(1), U256::from(1), false));
+        let transaction_request = TransactionRequest { authorization_list: Some(vec![authorization]), ..transaction_request() };
+
+        // authorization lists are rejected before Prague
+        assert!(simulate_pending_tx(state_db(), &transaction_request, &block(CANCUN_TIMESTAMP_ETHEREUM, Some(0))).is_err());
+
+        let (_, post, _) = simulate_pending_tx(state_db(), &transaction_request, &block(PRAGUE_TIMESTAMP_ETHEREUM, Some(0))).unwrap();
+        assert_eq!(post[&TARGET].balance, Some(U256::from(1)));
+    }
+}
@@ -1,42 +1,85 @@
-use alloy_consensus::constants::{EIP1559_TX_TYPE_ID, EIP2930_TX_TYPE_ID, EIP4844_TX_TYPE_ID, LEGACY_TX_TYPE_ID};
+use alloy_consensus::constants::{EIP1559_TX_TYPE_ID, EIP2930_TX_TYPE_ID, EIP4844_TX_TYPE_ID, EIP7702_TX_TYPE_ID, LEGACY_TX_TYPE_ID};
+use alloy_consensus::Transaction as _;
 use alloy_eips::BlockNumberOrTag;
 use alloy_network::{Network, TransactionBuilder, TransactionResponse};
-use alloy_primitives::{Address, BlockNumber, TxHash, U256};
+use alloy_primitives::{Address, BlockNumber, Log, TxHash, U256};
 use alloy_provider::Provider;
 use alloy_rpc_types::state::StateOverride;
-use alloy_rpc_types::{BlockOverrides, TransactionRequest};
+use alloy_rpc_types::{BlockOverrides, Transaction, TransactionRequest};
 use alloy_rpc_types_trace::geth::GethDebugTracingCallOptions;
 use eyre::{eyre, Result};
 use lazy_static::lazy_static;
 use revm::primitives::bitvec::macros::internal::funty::Fundamental;
+use revm::primitives::SpecId;
 use revm::{Database, DatabaseCommit, DatabaseRef};
 use std::collections::hash_map::Entry;
 use std::collections::HashMap;
 use std::marker::PhantomData;
 use std::sync::Arc;
 use tokio::sync::RwLock;
+use tokio::task::AbortHandle;
+use tokio_util::sync::CancellationToken;
 use tracing::{debug, error, warn};
 
-use loom_core_actors::{subscribe, Accessor, Actor, ActorResult, Broadcaster, Consumer, Producer, SharedState, WorkerResult};
+use loom_core_actors::{
+    subscribe, Accessor, Actor, ActorResult, Broadcaster, Consumer, Mpsc, MpscProducer, Producer, SharedState, WorkerResult,
+};
 use loom_core_actors_macros::{Accessor, Consumer, Producer};
 use loom_core_blockchain::{Blockchain, BlockchainState, Strategy};
+use loom_evm_db::{AlloyDB, DatabaseLoomExt};
 use loom_node_debug_provider::DebugProviderExt;
-use loom_types_blockchain::{debug_trace_call_diff, GethStateUpdateVec, Mempool, TRACING_CALL_OPTS};
+use loom_types_blockchain::{debug_trace_call_diff, ChainParameters, GethStateUpdate, GethStateUpdateVec, Mempool, TRACING_CALL_OPTS};
 use loom_types_entities::required_state::{accounts_vec_len, storage_vec_len};
 use loom_types_entities::{LatestBlock, Market, MarketState};
 use loom_types_events::{MarketEvents, MempoolEvents, StateUpdateEvent};
 
+use crate::PendingTxSimulation;
+
 use super::affected_pools_code::{get_affected_pools_from_code, is_pool_code};
 use super::affected_pools_state::get_affected_pools_from_state_update;
+use super::pending_tx_bundler::{BundleTx, PendingTxBundler};
+use super::pending_tx_simulator::{
+    calc_next_excess_blob_gas, chain_spec_id, compare_state_diff, simulate_pending_tx, simulate_pending_tx_sequence, SimulationBlock,
+};
+
+/// Pending tx and the request with adjusted fees it was simulated with
+type BundlePayload = (Transaction, TransactionRequest);
 
 lazy_static! {
     static ref COINBASE: Address = "0x1f9090aaE28b8a3dCeaDf281B0F12828e676c326".parse().unwrap();
 }
 
+/// Market state with the accounts and slots missing in it fetched from the node at the latest block
+fn provider_backed_state_db<P, N, DB>(client: P, mut state_db: DB) -> DB
+where
+    N: Network,
+    P: Provider<N> + Send + Sync + 'static,
+    DB: DatabaseLoomExt,
+{
+    match AlloyDB::new(client, BlockNumberOrTag::Latest.into()) {
+        Some(ext_db) => state_db.with_ext_db(ext_db),
+        None => error!("AlloyDB is None"),
+    }
+    state_db
+}
+
+/// Simulates the pending tx on the blocking pool, revm and the node requests for missing state are synchronous
+async fn simulate_pending_tx_blocking<DB>(
+    state_db: DB,
+    transaction_request: TransactionRequest,
+    simulation_block: SimulationBlock,
+) -> Result<(GethStateUpdate, GethStateUpdate, Vec<Log>)>
+where
+    DB: DatabaseRef + Send + 'static,
+{
+    tokio::task::spawn_blocking(move || simulate_pending_tx(state_db, &transaction_request, &simulation_block)).await?
+}
+
 /// Process a pending tx from the mempool
 #[allow(clippy::too_many_arguments)]
 pub async fn pending_tx_state_change_task<P, N, DB>(
     client: P,
+    chain_parameters: ChainParameters,
     tx_hash: TxHash,
     market: SharedState<Market>,
     mempool: SharedState<Mempool>,
@@ -47,12 +90,14 @@ pub async fn pending_tx_state_change_task<P, N, DB>(
     cur_block_time: u64,
     cur_next_base_fee: u64,
     cur_state_override: StateOverride,
-    state_updates_broadcaster: Broadcaster<StateUpdateEvent<DB>>,
+    simulation: PendingTxSimulation,
+    bundler: Option<Arc<RwLock<PendingTxBundler<BundlePayload>>>>,
+    state_updates_tx: Mpsc<StateUpdateEvent<DB>>,
 ) -> Result<()>
 where
     N: Network,
     P: Provider<N> + DebugProviderExt<N> + Send + Sync + Clone + 'static,
-    DB: DatabaseRef + Database + DatabaseCommit + Clone + Send + Sync + 'static,
+    DB: DatabaseRef + Database + DatabaseCommit + DatabaseLoomExt + Clone + Send + Sync + 'static,
 {
     let mut state_update_vec: GethStateUpdateVec = Vec::new();
     let mut state_required_vec: GethStateUpdateVec = Vec::new();
@@ -71,7 +116,30 @@ where
 
     let source = mempool_tx.source.clone();
 
+    // blob txes are backrun by sending them in a bundle, this is only possible with their sidecar
+    if mempool_tx.is_blob_tx() && !mempool_tx.has_blob_sidecar() {
+        return Err(eyre!("NO_BLOB_SIDECAR"));
+    }
+
     let mut transaction_request: TransactionRequest = tx.clone().into_request();
+    // excess blob gas of the next block, it sets the blob base fee for type 3 txes
+    let is_prague = chain_spec_id(chain_parameters.chain_id, cur_block_time).is_enabled_in(SpecId::PRAGUE);
+    let next_excess_blob_gas = latest_block
+        .read()
+        .await
+        .block_header
+        .as_ref()
+        .and_then(|header| header.excess_blob_gas.zip(header.blob_gas_used))
+        .map(|(excess_blob_gas, blob_gas_used)| calc_next_excess_blob_gas(excess_blob_gas, blob_gas_used, is_prague));
+
+    let simulation_block = SimulationBlock {
+        chain_id: chain_parameters.chain_id,
+        coinbase: *COINBASE,
+        number: cur_block_number,
+        timestamp: cur_block_time,
+        base_fee: cur_next_base_fee,
+        excess_blob_gas: next_excess_blob_gas,
+    };
 
     let transaction_type = transaction_request.transaction_type.unwrap_or_default();
     if transaction_type == LEGACY_TX_TYPE_ID || transaction_type == EIP2930_TX_TYPE_ID {
@@ -92,7 +160,7 @@ where
                 return Err(eyre!("NO_GAS_PRICE"));
             }
         }
-    } else if transaction_type == EIP1559_TX_TYPE_ID {
+    } else if transaction_type == EIP1559_TX_TYPE_ID || transaction_type == EIP4844_TX_TYPE_ID || transaction_type == EIP7702_TX_TYPE_ID {
         match transaction_request.max_fee_per_gas {
             Some(g) => {
                 if g < cur_next_base_fee as u128 {
@@ -110,10 +178,13 @@ where
                 return Err(eyre!("NO_BASE_FEE"));
             }
         }
-    } else if transaction_type == EIP4844_TX_TYPE_ID {
-        // ignore blob tx
-        debug!("Ignore EIP4844 transaction: hash={:?}", mempool_tx.tx_hash);
-        return Ok(());
+        if let (Some(max_fee_per_blob_gas), Some(next_blob_fee)) =
+            (transaction_request.max_fee_per_blob_gas, simulation_block.blob_gas_price())
+        {
+            if max_fee_per_blob_gas < next_blob_fee {
+                transaction_request.max_fee_per_blob_gas = Some(next_blob_fee);
+            }
+        }
     } else {
         warn!("Unknown transaction type: type={}, hash={:?}", transaction_type, mempool_tx.tx_hash);
         return Err(eyre!("UNKNOWN_TX_TYPE"));
@@ -132,11 +203,58 @@ where
     };
 
     if !(*affecting_tx.read().await.get(&tx_hash).unwrap_or(&true)) {
+        // a non affecting tx can still be a lower nonce of a sender with affecting txes, other txes are not bundled
+        if let Some(bundler) = &bundler {
+            if bundler.read().await.has_sender(&tx.from) && !mempool.read().await.is_any_replaced(&[tx_hash]) {
+                bundle_pending_tx(
+                    client,
+                    bundler,
+                    &tx,
+                    transaction_request,
+                    Vec::new(),
+                    market,
+                    market_state,
+                    &simulation_block,
+                    state_updates_tx,
+                )
+                .await;
+            }
+        }
         return Err(eyre!("NON_AFFECTING_TX"));
     }
 
-    let diff_trace_result =
-        debug_trace_call_diff(client.clone(), transaction_request, BlockNumberOrTag::Latest.into(), Some(call_opts)).await;
+    let bundle_request = bundler.as_ref().map(|_| transaction_request.clone());
+
+    let diff_trace_result = match simulation {
+        PendingTxSimulation::Rpc => {
+            debug_trace_call_diff(client.clone(), transaction_request, BlockNumberOrTag::Latest.into(), Some(call_opts)).await
+        }
+        PendingTxSimulation::Local => {
+            let state_db = provider_backed_state_db(client.clone(), market_state.read().await.state_db.clone());
+            simulate_pending_tx_blocking(state_db, transaction_request, simulation_block.clone()).await.map(|(pre, post, logs)| {
+                debug!(%tx_hash, logs = logs.len(), "Pending tx simulated locally");
+                (pre, post)
+            })
+        }
+        PendingTxSimulation::Verify => {
+            let state_db = provider_backed_state_db(client.clone(), market_state.read().await.state_db.clone());
+            let local_result = simulate_pending_tx_blocking(state_db, transaction_request.clone(), simulation_block.clone()).await;
+            let rpc_result =
+                debug_trace_call_diff(client.clone(), transaction_request, BlockNumberOrTag::Latest.into(), Some(call_opts)).await;
+            match (&local_result, &rpc_result) {
+                (Ok((_, local_post, _)), Ok((_, rpc_post))) => {
+                    if compare_state_diff(&tx_hash, local_post, rpc_post) {
+                        debug!(%tx_hash, "Local simulation matches debug_trace_call");
+                    }
+                }
+                (Err(error), Ok(_)) => warn!(%tx_hash, %error, "Local simulation failed, debug_trace_call succeeded"),
+                (Ok(_), Err(error)) => warn!(%tx_hash, %error, "debug_trace_call failed, local simulation succeeded"),
+                (Err(_), Err(_)) => {}
+            }
+            rpc_result
+        }
+    };
+    let simulated = diff_trace_result.is_ok();
     match diff_trace_result {
         Ok((pre, post)) => {
             state_required_vec.push(pre.clone());
@@ -160,11 +278,12 @@ where
     debug!(%tx_hash, %source, pools = affected_pools.len(), accounts = accounts_len, storage = storage_len, "Mempool affected pools");
 
     affecting_tx.write().await.insert(tx_hash, !affected_pools.is_empty());
+    let pools: Vec<Address> = affected_pools.keys().map(|pool| pool.pool.get_address()).collect();
 
     //TODO : Fix Latest header is empty
     if let Some(latest_header) = latest_block.read().await.block_header.clone() {
         let next_block_number = latest_header.number.as_u64() + 1;
-        let next_block_timestamp = latest_header.timestamp.as_u64() + 12;
+        let next_block_timestamp = latest_header.timestamp.as_u64() + chain_parameters.block_time.as_secs();
 
         if !affected_pools.is_empty() {
             let cur_state_db = market_state.read().await.state_db.clone();
@@ -181,16 +300,31 @@ where
                 "pending_tx_searcher".to_string(),
                 9000,
             );
-            if let Err(e) = state_updates_broadcaster.send(request) {
-                error!("state_updates_broadcaster : {}", e)
+            if let Err(e) = state_updates_tx.send(request).await {
+                error!("state_updates_tx : {}", e)
             }
         }
     } else {
         error!("Latest header is empty")
     }
 
+    if let (true, Some(bundler), Some(bundle_request)) = (simulated, &bundler, bundle_request) {
+        bundle_pending_tx(
+            client.clone(),
+            bundler,
+            &tx,
+            bundle_request,
+            pools,
+            market.clone(),
+            market_state.clone(),
+            &simulation_block,
+            state_updates_tx.clone(),
+        )
+        .await;
+    }
+
     if is_pool_code(&merged_state_update_vec) {
-        match get_affected_pools_from_code(client, market.clone(), &merged_state_update_vec).await {
+        match get_affected_pools_from_code(client, &chain_parameters, market.clone(), &merged_state_update_vec).await {
             Ok(affected_pools) => {
                 match affecting_tx.write().await.entry(tx_hash) {
                     Entry::Occupied(mut v) => {
@@ -207,7 +341,7 @@ where
 
                 if let Some(latest_header) = latest_block.read().await.block_header.clone() {
                     let block_number = latest_header.number.as_u64() + 1;
-                    let block_timestamp = latest_header.timestamp.as_u64() + 12;
+                    let block_timestamp = latest_header.timestamp.as_u64() + chain_parameters.block_time.as_secs();
 
                     if !affected_pools.is_empty() {
                         let cur_state_db = market_state.read().await.state_db.clone();
@@ -225,8 +359,8 @@ where
                             "poolcode_searcher".to_string(),
                             3000,
                         );
-                        if let Err(e) = state_updates_broadcaster.send(request) {
-                            error!("state_updates_broadcaster : {}", e)
+                        if let Err(e) = state_updates_tx.send(request).await {
+                            error!("state_updates_tx : {}", e)
                         }
                     }
                 } else {
@@ -241,41 +375,135 @@ where
     Ok(())
 }
 
+/// Adds the pending tx to the bundler and simulates the txes connected to it, txes without affected pools connect by a known sender
+#[allow(clippy::too_many_arguments)]
+async fn bundle_pending_tx<P, N, DB>(
+    client: P,
+    bundler: &RwLock<PendingTxBundler<BundlePayload>>,
+    tx: &Transaction,
+    transaction_request: TransactionRequest,
+    pools: Vec<Address>,
+    market: SharedState<Market>,
+    market_state: SharedState<MarketState<DB>>,
+    simulation_block: &SimulationBlock,
+    state_updates_tx: Mpsc<StateUpdateEvent<DB>>,
+) where
+    N: Network,
+    P: Provider<N> + Send + Sync + 'static,
+    DB: DatabaseRef + Database + DatabaseCommit + DatabaseLoomExt + Clone + Send + Sync + 'static,
+{
+    let tx_hash = tx.tx_hash();
+    let bundle_tx = BundleTx {
+        tx_hash,
+        sender: tx.from,
+        nonce: tx.nonce(),
+        gas_price: tx.effective_gas_price(Some(simulation_block.base_fee)),
+        pools,
+        payload: (tx.clone(), transaction_request),
+    };
+    let bundle = bundler.write().await.add(bundle_tx);
+    if bundle.is_empty() {
+        return;
+    }
+    if let Err(error) = bundle_state_change(client, market, market_state, bundle, simulation_block, state_updates_tx).await {
+        debug!(%tx_hash, %error, "Pending tx bundle simulation failed");
+    }
+}
+
+/// Simulates the ordered txes as a sequence and sends the combined state change with all txes as stuffing.
+/// Bundles are always simulated locally on the provider backed market state,
+/// the `pending_tx_simulation` setting applies to single txes only.
+async fn bundle_state_change<P, N, DB>(
+    client: P,
+    market: SharedState<Market>,
+    market_state: SharedState<MarketState<DB>>,
+    bundle: Vec<BundleTx<BundlePayload>>,
+    simulation_block: &SimulationBlock,
+    state_updates_tx: Mpsc<StateUpdateEvent<DB>>,
+) -> Result<()>
+where
+    N: Network,
+    P: Provider<N> + Send + Sync + 'static,
+    DB: DatabaseRef + Database + DatabaseCommit + DatabaseLoomExt + Clone + Send + Sync + 'static,
+{
+    let transaction_requests: Vec<TransactionRequest> = bundle.iter().map(|bundle_tx| bundle_tx.payload.1.clone()).collect();
+    let cur_state_db = market_state.read().await.state_db.clone();
+    let state_db = provider_backed_state_db(client, cur_state_db.clone());
+    let sequence_block = simulation_block.clone();
+    let (state_required_vec, state_update_vec) =
+        tokio::task::spawn_blocking(move || simulate_pending_tx_sequence(state_db, &transaction_requests, &sequence_block)).await??;
+
+    let affected_pools = get_affected_pools_from_state_update(market, &state_update_vec).await;
+    debug!(txs = bundle.len(), pools = affected_pools.len(), "Mempool bundle affected pools");
+    if affected_pools.is_empty() {
+        return Ok(());
+    }
+
+    let request = StateUpdateEvent::new(
+        simulation_block.number,
+        simulation_block.timestamp,
+        simulation_block.base_fee,
+        cur_state_db,
+        state_update_vec,
+        Some(state_required_vec),
+        affected_pools,
+        bundle.iter().map(|bundle_tx| bundle_tx.tx_hash).collect(),
+        bundle.into_iter().map(|bundle_tx| bundle_tx.payload.0).collect(),
+        "pending_tx_bundle_searcher".to_string(),
+        9000,
+    );
+    state_updates_tx.send(request).await.map_err(|e| eyre!("STATE_UPDATES_TX : {e}"))
+}
+
 #[allow(clippy::too_many_arguments)]
 pub async fn pending_tx_state_change_worker<P, N, DB>(
     client: P,
+    chain_parameters: ChainParameters,
     market: SharedState<Market>,
     mempool: SharedState<Mempool>,
     latest_block: SharedState<LatestBlock>,
     market_state: SharedState<MarketState<DB>>,
     mempool_events_rx: Broadcaster<MempoolEvents>,
     market_events_rx: Broadcaster<MarketEvents>,
-    state_updates_broadcaster: Broadcaster<StateUpdateEvent<DB>>,
+    state_updates_tx: Mpsc<StateUpdateEvent<DB>>,
+    simulation: PendingTxSimulation,
+    bundle_pending_txs: bool,
+    shutdown_token: CancellationToken,
 ) -> WorkerResult
 where
     N: Network,
     P: Provider<N> + DebugProviderExt<N> + Send + Sync + Clone + 'static,
-    DB: DatabaseRef + Database + DatabaseCommit + Clone + Send + Sync + 'static,
+    DB: DatabaseRef + Database + DatabaseCommit + DatabaseLoomExt + Clone + Send + Sync + 'static,
 {
     subscribe!(mempool_events_rx);
     subscribe!(market_events_rx);
 
     let affecting_tx: Arc<RwLock<HashMap<TxHash, bool>>> = Arc::new(RwLock::new(HashMap::new()));
+    let bundler: Option<Arc<RwLock<PendingTxBundler<BundlePayload>>>> =
+        bundle_pending_txs.then(|| Arc::new(RwLock::new(PendingTxBundler::default())));
     let mut cur_next_base_fee = 0;
     let mut cur_block_number: Option<BlockNumber> = None;
     let mut cur_block_time: Option<u64> = None;
     let mut cur_state_override: StateOverride = StateOverride::default();
+    let mut in_flight: HashMap<TxHash, AbortHandle> = HashMap::new();
 
     loop {
         tokio::select! {
+            _ = shutdown_token.cancelled() => {
+                break Ok("Pending tx state change processor stopped".to_string());
+            }
             msg = market_events_rx.recv() => {
                 if let Ok(msg) = msg {
                     let market_event_msg : MarketEvents = msg;
                     if let MarketEvents::BlockHeaderUpdate{ block_number, block_hash, timestamp, base_fee, next_base_fee } = market_event_msg {
                         debug!("Block header update {} {} base_fee {} ", block_number, block_hash, base_fee);
                         cur_block_number = Some( block_number.as_u64() + 1);
-                        cur_block_time = Some(timestamp + 12 );
+                        cur_block_time = Some(timestamp + chain_parameters.block_time.as_secs());
                         cur_next_base_fee = next_base_fee;
+                        if let Some(bundler) = &bundler {
+                            bundler.write().await.clear();
+                        }
+                        in_flight.retain(|_, task| !task.is_finished());
 
                         for _counter in 0..5  {
                             if let Ok(msg) = market_events_rx.recv().await {
@@ -292,15 +520,26 @@ where
             msg = mempool_events_rx.recv() => {
                 if let Ok(msg) = msg {
                     let mempool_event_msg : MempoolEvents = msg;
+                    if let MempoolEvents::TxReplaced{ tx_hash, replaced_by } = mempool_event_msg {
+                        if let Some(task) = in_flight.remove(&tx_hash) {
+                            debug!(%tx_hash, %replaced_by, "Replaced tx, aborting pending tx task");
+                            task.abort();
+                        }
+                        affecting_tx.write().await.insert(tx_hash, false);
+                        if let Some(bundler) = &bundler {
+                            bundler.write().await.remove(&tx_hash);
+                        }
+                    }
                     if let MempoolEvents::MempoolActualTxUpdate{ tx_hash }  = mempool_event_msg {
                         if cur_block_number.is_none() {
                             warn!("Did not received block header update yet!");
                             continue;
                         }
 
-                        tokio::task::spawn(
+                        let task = tokio::task::spawn(
                             pending_tx_state_change_task(
                                 client.clone(),
+                                chain_parameters.clone(),
                                 tx_hash,
                                 market.clone(),
                                 mempool.clone(),
@@ -311,9 +550,12 @@ where
                                 cur_block_time.unwrap_or_default(),
                                 cur_next_base_fee,
                                 cur_state_override.clone(),
-                                state_updates_broadcaster.clone(),
+                                simulation,
+                                bundler.clone(),
+                                state_updates_tx.clone(),
                             )
                         );
+                        in_flight.insert(tx_hash, task.abort_handle());
                     }
                 }
             }
@@ -324,6 +566,9 @@ where
 #[derive(Accessor, Consumer, Producer)]
 pub struct PendingTxStateChangeProcessorActor<P, N, DB: Clone + Send + Sync + 'static> {
     client: P,
+    chain_parameters: ChainParameters,
+    simulation: PendingTxSimulation,
+    bundle_pending_txs: bool,
     #[accessor]
     market: Option<SharedState<Market>>,
     #[accessor]
@@ -337,7 +582,7 @@ pub struct PendingTxStateChangeProcessorActor<P, N, DB: Clone + Send + Sync + 's
     #[consumer]
     mempool_events_rx: Option<Broadcaster<MempoolEvents>>,
     #[producer]
-    state_updates_tx: Option<Broadcaster<StateUpdateEvent<DB>>>,
+    state_updates_tx: Option<Mpsc<StateUpdateEvent<DB>>>,
     _n: PhantomData<N>,
 }
 
@@ -350,6 +595,9 @@ where
     pub fn new(client: P) -> PendingTxStateChangeProcessorActor<P, N, DB> {
         PendingTxStateChangeProcessorActor {
             client,
+            chain_parameters: ChainParameters::ethereum(),
+            simulation: PendingTxSimulation::default(),
+            bundle_pending_txs: false,
             market: None,
             mempool: None,
             market_state: None,
@@ -361,8 +609,17 @@ where
         }
     }
 
+    pub fn with_simulation(self, simulation: PendingTxSimulation) -> Self {
+        Self { simulation, ..self }
+    }
+
+    pub fn with_bundling(self, bundle_pending_txs: bool) -> Self {
+        Self { bundle_pending_txs, ..self }
+    }
+
     pub fn on_bc(self, bc: &Blockchain, state: &BlockchainState<DB>, strategy: &Strategy<DB>) -> Self {
         Self {
+            chain_parameters: bc.chain_parameters(),
             market: Some(bc.market()),
             mempool: Some(bc.mempool()),
             market_state: Some(state.market_state()),
@@ -379,11 +636,16 @@ impl<P, N, DB> Actor for PendingTxStateChangeProcessorActor<P, N, DB>
 where
     N: Network,
     P: Provider<N> + DebugProviderExt<N> + Send + Sync + Clone + 'static,
-    DB: DatabaseRef + Database + DatabaseCommit + Send + Sync + Clone + 'static,
+    DB: DatabaseRef + Database + DatabaseCommit + DatabaseLoomExt + Send + Sync + Clone + 'static,
 {
     fn start(&self) -> ActorResult {
+        self.start_until(CancellationToken::new())
+    }
+
+    fn start_until(&self, shutdown_token: CancellationToken) -> ActorResult {
         let task = tokio::task::spawn(pending_tx_state_change_worker(
             self.client.clone(),
+            self.chain_parameters.clone(),
             self.market.clone().unwrap(),
             self.mempool.clone().unwrap(),
             self.latest_block.clone().unwrap(),
@@ -391,6 +653,9 @@ where
             self.mempool_events_rx.clone().unwrap(),
             self.market_events_rx.clone().unwrap(),
             self.state_updates_tx.clone().unwrap(),
+            self.simulation,
+            self.bundle_pending_txs,
+            shutdown_token,
         ));
         Ok(vec![task])
     }
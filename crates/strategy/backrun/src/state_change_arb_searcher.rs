@@ -9,17 +9,20 @@ use influxdb::{Timestamp, WriteQuery};
 use rayon::prelude::*;
 use rayon::{ThreadPool, ThreadPoolBuilder};
 use revm::{DatabaseCommit, DatabaseRef};
-use tokio::sync::broadcast::error::RecvError;
+use tokio_util::sync::CancellationToken;
 #[cfg(not(debug_assertions))]
 use tracing::warn;
 use tracing::{debug, error, info, trace};
 
 use crate::BackrunConfig;
 use crate::SwapCalculator;
-use loom_core_actors::{subscribe, Accessor, Actor, ActorResult, Broadcaster, Consumer, Producer, SharedState, WorkerResult};
+use loom_core_actors::{
+    subscribe, Accessor, Actor, ActorResult, Broadcaster, Consumer, Mpsc, MpscConsumer, Producer, SharedState, WorkerResult,
+};
 use loom_core_actors_macros::{Accessor, Consumer, Producer};
 use loom_core_blockchain::{Blockchain, Strategy};
 use loom_evm_db::DatabaseHelpers;
+use loom_types_entities::bidding::TipsBidder;
 use loom_types_entities::strategy_config::StrategyConfig;
 use loom_types_entities::{Market, PoolWrapper, Swap, SwapDirection, SwapError, SwapLine, SwapPath};
 use loom_types_events::{
@@ -27,37 +30,19 @@ use loom_types_events::{
     TxComposeData,
 };
 
-async fn state_change_arb_searcher_task<DB: DatabaseRef<Error = ErrReport> + DatabaseCommit + Send + Sync + Clone + Default + 'static>(
-    thread_pool: Arc<ThreadPool>,
-    backrun_config: BackrunConfig,
-    state_update_event: StateUpdateEvent<DB>,
-    market: SharedState<Market>,
-    swap_request_tx: Broadcaster<MessageSwapCompose<DB>>,
-    pool_health_monitor_tx: Broadcaster<MessageHealthEvent>,
-    influxdb_write_channel_tx: Broadcaster<WriteQuery>,
-) -> Result<()> {
-    debug!("Message received {} stuffing : {:?}", state_update_event.origin, state_update_event.stuffing_tx_hash());
-
-    let mut db = state_update_event.market_state().clone();
-    DatabaseHelpers::apply_geth_state_update_vec(&mut db, state_update_event.state_update().clone());
-
-    let start_time_utc = chrono::Utc::now();
-
-    let start_time = std::time::Instant::now();
+/// Swap paths through the affected pools, the best scored paths of known pools or paths built for new pools
+pub(crate) fn swap_paths_for_directions(market: &Market, directions: &BTreeMap<PoolWrapper, Vec<SwapDirection>>) -> Vec<SwapPath> {
     let mut swap_path_set: HashSet<SwapPath> = HashSet::new();
 
-    let market_guard_read = market.read().await;
-    debug!(elapsed = start_time.elapsed().as_micros(), "market_guard market.read acquired");
-
-    for (pool, v) in state_update_event.directions().iter() {
-        let pool_paths: Vec<SwapPath> = match market_guard_read.get_pool_paths(&pool.get_pool_id()) {
+    for (pool, v) in directions.iter() {
+        let pool_paths: Vec<SwapPath> = match market.get_pool_paths(&pool.get_pool_id()) {
             Some(paths) => {
                 let pool_paths = paths
                     .into_iter()
                     .enumerate()
                     .filter(|(idx, swap_path)| {
                         *idx < 100 || swap_path.score.unwrap_or_default() > 0.97
-                        //&& !swap_path.pools.iter().any(|pool| market_guard_read.is_pool_disabled(&pool.get_pool_id()))
+                        //&& !swap_path.pools.iter().any(|pool| market.is_pool_disabled(&pool.get_pool_id()))
                     })
                     .map(|(_, swap_path)| swap_path)
                     .collect::<Vec<_>>();
@@ -75,7 +60,7 @@ async fn state_change_arb_searcher_task<DB: DatabaseRef<Error = ErrReport> + Dat
             None => {
                 let mut pool_direction: BTreeMap<PoolWrapper, Vec<SwapDirection>> = BTreeMap::new();
                 pool_direction.insert(pool.clone(), v.clone());
-                market_guard_read.build_swap_path_vec(&pool_direction).unwrap_or_default()
+                market.build_swap_path_vec(&pool_direction).unwrap_or_default()
             }
         };
 
@@ -83,11 +68,34 @@ async fn state_change_arb_searcher_task<DB: DatabaseRef<Error = ErrReport> + Dat
             swap_path_set.insert(pool_path);
         }
     }
+    swap_path_set.into_iter().collect()
+}
+
+async fn state_change_arb_searcher_task<DB: DatabaseRef<Error = ErrReport> + DatabaseCommit + Send + Sync + Clone + Default + 'static>(
+    thread_pool: Arc<ThreadPool>,
+    backrun_config: BackrunConfig,
+    bidder: Arc<dyn TipsBidder>,
+    state_update_event: StateUpdateEvent<DB>,
+    market: SharedState<Market>,
+    swap_request_tx: Broadcaster<MessageSwapCompose<DB>>,
+    pool_health_monitor_tx: Broadcaster<MessageHealthEvent>,
+    influxdb_write_channel_tx: Broadcaster<WriteQuery>,
+) -> Result<()> {
+    debug!("Message received {} stuffing : {:?}", state_update_event.origin, state_update_event.stuffing_tx_hash());
+
+    let mut db = state_update_event.market_state().clone();
+    DatabaseHelpers::apply_geth_state_update_vec(&mut db, state_update_event.state_update().clone());
+
+    let start_time_utc = chrono::Utc::now();
+
+    let start_time = std::time::Instant::now();
+
+    let market_guard_read = market.read().await;
+    debug!(elapsed = start_time.elapsed().as_micros(), "market_guard market.read acquired");
+    let swap_path_vec = swap_paths_for_directions(&market_guard_read, state_update_event.directions());
     drop(market_guard_read);
     debug!(elapsed = start_time.elapsed().as_micros(), "market_guard market.read released");
 
-    let swap_path_vec: Vec<SwapPath> = swap_path_set.into_iter().collect();
-
     if swap_path_vec.is_empty() {
         debug!(
             request=?state_update_event.stuffing_txs_hashes().first().unwrap_or_default(),
@@ -170,6 +178,7 @@ async fn state_change_arb_searcher_task<DB: DatabaseRef<Error = ErrReport> + Dat
     while let Some(swap_line_result) = swap_line_rx.recv().await {
         match swap_line_result {
             Ok(swap_line) => {
+                let tips_pct = bidder.tips_pct(swap_line.abs_profit_eth()).min(state_update_event.tips_pct);
                 let prepare_request = SwapComposeMessage::Prepare(SwapComposeData {
                     tx_compose: TxComposeData {
                         eoa: backrun_config.eoa(),
@@ -183,7 +192,7 @@ async fn state_change_arb_searcher_task<DB: DatabaseRef<Error = ErrReport> + Dat
                     },
                     swap: Swap::BackrunSwapLine(swap_line),
                     origin: Some(state_update_event.origin.clone()),
-                    tips_pct: Some(state_update_event.tips_pct),
+                    tips_pct: Some(tips_pct),
                     poststate: Some(db.clone()),
                     poststate_update: Some(state_update_event.state_update().clone()),
                     ..SwapComposeData::default()
@@ -237,10 +246,11 @@ pub async fn state_change_arb_searcher_worker<
 >(
     backrun_config: BackrunConfig,
     market: SharedState<Market>,
-    search_request_rx: Broadcaster<StateUpdateEvent<DB>>,
+    search_request_rx: Mpsc<StateUpdateEvent<DB>>,
     swap_request_tx: Broadcaster<MessageSwapCompose<DB>>,
     pool_health_monitor_tx: Broadcaster<MessageHealthEvent>,
     influxdb_write_channel_tx: Broadcaster<WriteQuery>,
+    shutdown_token: CancellationToken,
 ) -> WorkerResult {
     subscribe!(search_request_rx);
 
@@ -248,26 +258,28 @@ pub async fn state_change_arb_searcher_worker<
     let tasks = (cpus * 5) / 10;
     info!("Starting state arb searcher cpus={cpus}, tasks={tasks}");
     let thread_pool = Arc::new(ThreadPoolBuilder::new().num_threads(tasks).build()?);
+    let bidder = backrun_config.bidding().bidder()?;
+    info!(bidder = bidder.name(), "Tips bidder loaded");
 
     loop {
-        tokio::select! {
-                msg = search_request_rx.recv() => {
-                let pool_update_msg : Result<StateUpdateEvent<DB>, RecvError> = msg;
-                if let Ok(msg) = pool_update_msg {
-                    tokio::task::spawn(
-                        state_change_arb_searcher_task(
-                            thread_pool.clone(),
-                            backrun_config.clone(),
-                            msg,
-                            market.clone(),
-                            swap_request_tx.clone(),
-                            pool_health_monitor_tx.clone(),
-                            influxdb_write_channel_tx.clone(),
-                        )
-                    );
-                }
-            }
-        }
+        let msg = tokio::select! {
+            msg = search_request_rx.recv() => msg,
+            _ = shutdown_token.cancelled() => break Ok("State arb searcher stopped".to_string()),
+        };
+        let Some(msg) = msg else {
+            error!("State update channel closed");
+            break Err(eyre!("STATE_UPDATE_CHANNEL_CLOSED"));
+        };
+        tokio::task::spawn(state_change_arb_searcher_task(
+            thread_pool.clone(),
+            backrun_config.clone(),
+            bidder.clone(),
+            msg,
+            market.clone(),
+            swap_request_tx.clone(),
+            pool_health_monitor_tx.clone(),
+            influxdb_write_channel_tx.clone(),
+        ));
     }
 }
 
@@ -277,7 +289,7 @@ pub struct StateChangeArbSearcherActor<DB: Clone + Send + Sync + 'static> {
     #[accessor]
     market: Option<SharedState<Market>>,
     #[consumer]
-    state_update_rx: Option<Broadcaster<StateUpdateEvent<DB>>>,
+    state_update_rx: Option<Mpsc<StateUpdateEvent<DB>>>,
     #[producer]
     compose_tx: Option<Broadcaster<MessageSwapCompose<DB>>>,
     #[producer]
@@ -314,6 +326,10 @@ impl<DB: DatabaseRef<Error = ErrReport> + DatabaseCommit + Send + Sync + Clone +
     for StateChangeArbSearcherActor<DB>
 {
     fn start(&self) -> ActorResult {
+        self.start_until(CancellationToken::new())
+    }
+
+    fn start_until(&self, shutdown_token: CancellationToken) -> ActorResult {
         let task = tokio::task::spawn(state_change_arb_searcher_worker(
             self.backrun_config.clone(),
             self.market.clone().unwrap(),
@@ -321,6 +337,7 @@ impl<DB: DatabaseRef<Error = ErrReport> + DatabaseCommit + Send + Sync + Clone +
             self.compose_tx.clone().unwrap(),
             self.pool_health_monitor_tx.clone().unwrap(),
             self.influxdb_write_channel_tx.clone().unwrap(),
+            shutdown_token,
         ));
         Ok(vec![task])
     }
@@ -5,10 +5,10 @@ use eyre::{OptionExt, Result};
 use lazy_static::lazy_static;
 use revm::{Database, DatabaseCommit, DatabaseRef};
 use tokio::sync::broadcast::error::RecvError;
-use tokio::sync::broadcast::Receiver;
+use tokio_util::sync::CancellationToken;
 use tracing::{debug, error, info};
 
-use loom_core_actors::{Actor, ActorResult, Broadcaster, Consumer, Producer, WorkerResult};
+use loom_core_actors::{Actor, ActorResult, Broadcaster, BroadcasterReceiver, Consumer, Producer, WorkerResult};
 use loom_core_actors_macros::{Accessor, Consumer, Producer};
 use loom_core_blockchain::{Blockchain, Strategy};
 use loom_evm_utils::NWETH;
@@ -38,18 +38,22 @@ async fn diff_path_merger_worker<DB>(
     market_events_rx: Broadcaster<MarketEvents>,
     compose_channel_rx: Broadcaster<MessageSwapCompose<DB>>,
     compose_channel_tx: Broadcaster<MessageSwapCompose<DB>>,
+    shutdown_token: CancellationToken,
 ) -> WorkerResult
 where
     DB: DatabaseRef + Database + DatabaseCommit + Send + Sync + Clone + 'static,
 {
-    let mut market_events_rx: Receiver<MarketEvents> = market_events_rx.subscribe();
+    let mut market_events_rx: BroadcasterReceiver<MarketEvents> = market_events_rx.subscribe();
 
-    let mut compose_channel_rx: Receiver<MessageSwapCompose<DB>> = compose_channel_rx.subscribe();
+    let mut compose_channel_rx: BroadcasterReceiver<MessageSwapCompose<DB>> = compose_channel_rx.subscribe();
 
     let mut swap_paths: Vec<SwapComposeData<DB>> = Vec::new();
 
     loop {
         tokio::select! {
+            _ = shutdown_token.cancelled() => {
+                break Ok("Diff path merger stopped".to_string());
+            }
             msg = market_events_rx.recv() => {
                 if let Ok(msg) = msg {
                     let market_event_msg : MarketEvents = msg;
@@ -179,10 +183,15 @@ where
     DB: DatabaseRef + Database + DatabaseCommit + Send + Sync + Clone + Default + 'static,
 {
     fn start(&self) -> ActorResult {
+        self.start_until(CancellationToken::new())
+    }
+
+    fn start_until(&self, shutdown_token: CancellationToken) -> ActorResult {
         let task = tokio::task::spawn(diff_path_merger_worker(
             self.market_events.clone().unwrap(),
             self.compose_channel_rx.clone().unwrap(),
             self.compose_channel_tx.clone().unwrap(),
+            shutdown_token,
         ));
         Ok(vec![task])
     }
@@ -16,6 +16,7 @@ use revm::primitives::{BlockEnv, Env, CANCUN};
 use revm::{Database, DatabaseCommit, DatabaseRef, Evm};
 use tokio::sync::broadcast::error::RecvError;
 use tokio::sync::RwLock;
+use tokio_util::sync::CancellationToken;
 use tracing::{debug, error, info, trace};
 
 use loom_core_actors::{subscribe, Accessor, Actor, ActorResult, Broadcaster, Consumer, Producer, SharedState, WorkerResult};
@@ -245,6 +246,7 @@ async fn same_path_merger_worker<
     market_events_rx: Broadcaster<MarketEvents>,
     compose_channel_rx: Broadcaster<MessageSwapCompose<DB>>,
     compose_channel_tx: Broadcaster<MessageSwapCompose<DB>>,
+    shutdown_token: CancellationToken,
 ) -> WorkerResult {
     subscribe!(market_events_rx);
     subscribe!(compose_channel_rx);
@@ -262,6 +264,9 @@ async fn same_path_merger_worker<
 
     loop {
         tokio::select! {
+            _ = shutdown_token.cancelled() => {
+                break Ok("Same path merger stopped".to_string());
+            }
             msg = market_events_rx.recv() => {
                 if let Ok(msg) = msg {
                     let market_event_msg : MarketEvents = msg;
@@ -403,6 +408,10 @@ where
     DB: DatabaseRef<Error = ErrReport> + Database<Error = ErrReport> + DatabaseCommit + Send + Sync + Clone + 'static,
 {
     fn start(&self) -> ActorResult {
+        self.start_until(CancellationToken::new())
+    }
+
+    fn start_until(&self, shutdown_token: CancellationToken) -> ActorResult {
         let task = tokio::task::spawn(same_path_merger_worker(
             self.client.clone(),
             self.latest_block.clone().unwrap(),
@@ -410,6 +419,7 @@ where
             self.market_events.clone().unwrap(),
             self.compose_channel_rx.clone().unwrap(),
             self.compose_channel_tx.clone().unwrap(),
+            shutdown_token,
         ));
         Ok(vec![task])
     }
@@ -1,15 +1,209 @@
+use std::time::Duration;
+
 use alloy_eips::eip1559::BaseFeeParams;
+use alloy_primitives::Address;
 use alloy_rpc_types_eth::Header;
+use eyre::{eyre, ErrReport};
+use loom_defi_address_book::{
+    FactoryAddress, FactoryAddressArbitrum, FactoryAddressBase, FactoryAddressOptimism, TokenAddressArbitrum, TokenAddressBase,
+    TokenAddressEth, TokenAddressOptimism,
+};
+
+pub const CHAIN_ID_ETHEREUM: u64 = 1;
+pub const CHAIN_ID_OPTIMISM: u64 = 10;
+pub const CHAIN_ID_BASE: u64 = 8453;
+pub const CHAIN_ID_ARBITRUM: u64 = 42161;
+
+/// Token added to the market on startup
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChainToken {
+    pub address: Address,
+    pub symbol: String,
+    pub decimals: u8,
+    pub basic: bool,
+    pub middle: bool,
+}
+
+impl ChainToken {
+    pub fn new(address: Address, symbol: &str, decimals: u8) -> Self {
+        Self { address, symbol: symbol.to_string(), decimals, basic: true, middle: false }
+    }
+
+    pub fn middle(self) -> Self {
+        Self { basic: false, middle: true, ..self }
+    }
+}
+
+/// Factory addresses of supported protocols deployed on the chain
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ChainFactories {
+    pub uniswap_v2: Option<Address>,
+    pub uniswap_v3: Option<Address>,
+    pub sushiswap_v2: Option<Address>,
+    pub sushiswap_v3: Option<Address>,
+    pub pancake_v3: Option<Address>,
+    pub nomiswap: Option<Address>,
+    pub dooarswap: Option<Address>,
+    pub safeswap: Option<Address>,
+    pub miniswap: Option<Address>,
+    pub shibaswap: Option<Address>,
+    pub antfarm: Option<Address>,
+    pub integral: Option<Address>,
+    pub maverick: Option<Address>,
+    pub uniswap_v4_pool_manager: Option<Address>,
+}
 
 #[derive(Clone, Debug)]
 pub struct ChainParameters {
     pub chain_id: u64,
     pub base_fee_params: BaseFeeParams,
+    pub block_time: Duration,
+    pub weth: Address,
+    pub multicaller: Option<Address>,
+    pub default_tokens: Vec<ChainToken>,
+    pub factories: ChainFactories,
 }
 
 impl ChainParameters {
     pub fn ethereum() -> ChainParameters {
-        ChainParameters { chain_id: 1, base_fee_params: BaseFeeParams::ethereum() }
+        ChainParameters {
+            chain_id: CHAIN_ID_ETHEREUM,
+            base_fee_params: BaseFeeParams::ethereum(),
+            block_time: Duration::from_secs(12),
+            weth: TokenAddressEth::WETH,
+            multicaller: None,
+            default_tokens: vec![
+                ChainToken::new(TokenAddressEth::WETH, "WETH", 18),
+                ChainToken::new(TokenAddressEth::USDC, "USDC", 6),
+                ChainToken::new(TokenAddressEth::USDT, "USDT", 6),
+                ChainToken::new(TokenAddressEth::DAI, "DAI", 18),
+                ChainToken::new(TokenAddressEth::WBTC, "WBTC", 8),
+                ChainToken::new(TokenAddressEth::THREECRV, "3Crv", 18).middle(),
+            ],
+            factories: ChainFactories {
+                uniswap_v2: Some(FactoryAddress::UNISWAP_V2),
+                uniswap_v3: Some(FactoryAddress::UNISWAP_V3),
+                sushiswap_v2: Some(FactoryAddress::SUSHISWAP_V2),
+                sushiswap_v3: Some(FactoryAddress::SUSHISWAP_V3),
+                pancake_v3: Some(FactoryAddress::PANCAKE_V3),
+                nomiswap: Some(FactoryAddress::NOMISWAP),
+                dooarswap: Some(FactoryAddress::DOOARSWAP),
+                safeswap: Some(FactoryAddress::SAFESWAP),
+                miniswap: Some(FactoryAddress::MINISWAP),
+                shibaswap: Some(FactoryAddress::SHIBASWAP),
+                antfarm: Some(FactoryAddress::ANTFARM),
+                integral: Some(FactoryAddress::INTEGRAL),
+                maverick: Some(FactoryAddress::MAVERICK),
+                uniswap_v4_pool_manager: Some(FactoryAddress::UNISWAP_V4_POOL_MANAGER_ADDRESS),
+            },
+        }
+    }
+
+    pub fn optimism() -> ChainParameters {
+        ChainParameters {
+            chain_id: CHAIN_ID_OPTIMISM,
+            // Canyon parameters
+            base_fee_params: BaseFeeParams::new(250, 6),
+            block_time: Duration::from_secs(2),
+            weth: TokenAddressOptimism::WETH,
+            multicaller: None,
+            default_tokens: vec![
+                ChainToken::new(TokenAddressOptimism::WETH, "WETH", 18),
+                ChainToken::new(TokenAddressOptimism::USDC, "USDC", 6),
+                ChainToken::new(TokenAddressOptimism::USDT, "USDT", 6),
+                ChainToken::new(TokenAddressOptimism::DAI, "DAI", 18),
+                ChainToken::new(TokenAddressOptimism::WBTC, "WBTC", 8),
+                ChainToken::new(TokenAddressOptimism::OP, "OP", 18).middle(),
+            ],
+            factories: ChainFactories {
+                uniswap_v2: Some(FactoryAddressOptimism::UNISWAP_V2),
+                uniswap_v3: Some(FactoryAddressOptimism::UNISWAP_V3),
+                sushiswap_v3: Some(FactoryAddressOptimism::SUSHISWAP_V3),
+                ..ChainFactories::default()
+            },
+        }
+    }
+
+    pub fn base() -> ChainParameters {
+        ChainParameters {
+            chain_id: CHAIN_ID_BASE,
+            // Canyon parameters
+            base_fee_params: BaseFeeParams::new(250, 6),
+            block_time: Duration::from_secs(2),
+            weth: TokenAddressBase::WETH,
+            multicaller: None,
+            default_tokens: vec![
+                ChainToken::new(TokenAddressBase::WETH, "WETH", 18),
+                ChainToken::new(TokenAddressBase::USDC, "USDC", 6),
+                ChainToken::new(TokenAddressBase::USDBC, "USDbC", 6),
+                ChainToken::new(TokenAddressBase::DAI, "DAI", 18),
+                ChainToken::new(TokenAddressBase::CBETH, "cbETH", 18).middle(),
+            ],
+            factories: ChainFactories {
+                uniswap_v2: Some(FactoryAddressBase::UNISWAP_V2),
+                uniswap_v3: Some(FactoryAddressBase::UNISWAP_V3),
+                sushiswap_v2: Some(FactoryAddressBase::SUSHISWAP_V2),
+                sushiswap_v3: Some(FactoryAddressBase::SUSHISWAP_V3),
+                pancake_v3: Some(FactoryAddressBase::PANCAKE_V3),
+                ..ChainFactories::default()
+            },
+        }
+    }
+
+    pub fn arbitrum() -> ChainParameters {
+        ChainParameters {
+            chain_id: CHAIN_ID_ARBITRUM,
+            // base fee is set by the sequencer and barely moves between blocks, so it is kept unchanged
+            base_fee_params: BaseFeeParams::new(1 << 64, 1),
+            block_time: Duration::from_millis(250),
+            weth: TokenAddressArbitrum::WETH,
+            multicaller: None,
+            default_tokens: vec![
+                ChainToken::new(TokenAddressArbitrum::WETH, "WETH", 18),
+                ChainToken::new(TokenAddressArbitrum::WBTC, "WBTC", 8),
+                ChainToken::new(TokenAddressArbitrum::USDC, "USDC", 6),
+                ChainToken::new(TokenAddressArbitrum::USDT, "USDT", 6),
+                ChainToken::new(TokenAddressArbitrum::DAI, "DAI", 18),
+            ],
+            factories: ChainFactories {
+                uniswap_v2: Some(FactoryAddressArbitrum::UNISWAP_V2),
+                uniswap_v3: Some(FactoryAddressArbitrum::UNISWAP_V3),
+                sushiswap_v2: Some(FactoryAddressArbitrum::SUSHISWAP_V2),
+                sushiswap_v3: Some(FactoryAddressArbitrum::SUSHISWAP_V3),
+                pancake_v3: Some(FactoryAddressArbitrum::PANCAKE_V3),
+                ..ChainFactories::default()
+            },
+        }
+    }
+
+    /// Parameters for a chain without a preset, only the base fee calculation is set.
+    pub fn custom(chain_id: u64, base_fee_params: BaseFeeParams, block_time: Duration, weth: Address) -> ChainParameters {
+        ChainParameters {
+            chain_id,
+            base_fee_params,
+            block_time,
+            weth,
+            multicaller: None,
+            default_tokens: vec![],
+            factories: ChainFactories::default(),
+        }
+    }
+
+    /// Preset parameters of the chain, unknown chains like local mainnet forks get the Ethereum parameters
+    pub fn from_chain_id(chain_id: u64) -> ChainParameters {
+        ChainParameters::try_from(chain_id).unwrap_or_else(|_| ChainParameters { chain_id, ..ChainParameters::ethereum() })
+    }
+
+    pub fn with_multicaller(self, multicaller: Address) -> Self {
+        Self { multicaller: Some(multicaller), ..self }
+    }
+
+    pub fn with_default_tokens(self, default_tokens: Vec<ChainToken>) -> Self {
+        Self { default_tokens, ..self }
+    }
+
+    pub fn with_factories(self, factories: ChainFactories) -> Self {
+        Self { factories, ..self }
     }
 
     pub fn calc_next_block_base_fee(&self, gas_used: u64, gas_limit: u64, base_fee: u64) -> u64 {
@@ -26,11 +220,44 @@ impl Default for ChainParameters {
         Self::ethereum()
     }
 }
-impl From<u64> for ChainParameters {
-    fn from(chain_id: u64) -> Self {
+
+impl TryFrom<u64> for ChainParameters {
+    type Error = ErrReport;
+
+    fn try_from(chain_id: u64) -> Result<Self, Self::Error> {
         match chain_id {
-            1 => ChainParameters::ethereum(),
-            _ => unimplemented!(),
+            CHAIN_ID_ETHEREUM => Ok(ChainParameters::ethereum()),
+            CHAIN_ID_OPTIMISM => Ok(ChainParameters::optimism()),
+            CHAIN_ID_BASE => Ok(ChainParameters::base()),
+            CHAIN_ID_ARBITRUM => Ok(ChainParameters::arbitrum()),
+            _ => Err(eyre!("CHAIN_NOT_SUPPORTED")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_chain_presets() {
+        for chain_id in [CHAIN_ID_ETHEREUM, CHAIN_ID_OPTIMISM, CHAIN_ID_BASE, CHAIN_ID_ARBITRUM] {
+            let chain_parameters = ChainParameters::try_from(chain_id).unwrap();
+            assert_eq!(chain_parameters.chain_id, chain_id);
+            assert!(chain_parameters.default_tokens.iter().any(|token| token.address == chain_parameters.weth));
         }
+        assert!(ChainParameters::try_from(31337).is_err());
+
+        let chain_parameters = ChainParameters::from_chain_id(31337);
+        assert_eq!(chain_parameters.chain_id, 31337);
+        assert_eq!(chain_parameters.weth, ChainParameters::ethereum().weth);
+        assert_eq!(chain_parameters.factories, ChainParameters::ethereum().factories);
+    }
+
+    #[test]
+    fn test_arbitrum_base_fee_is_unchanged() {
+        let chain_parameters = ChainParameters::arbitrum();
+        assert_eq!(chain_parameters.calc_next_block_base_fee(0, 30_000_000, 10_000_000), 10_000_000);
+        assert_eq!(chain_parameters.calc_next_block_base_fee(30_000_000, 30_000_000, 10_000_000), 10_000_000);
     }
 }
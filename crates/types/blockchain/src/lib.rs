@@ -1,14 +1,17 @@
 pub use accountnoncetx::AccountNonceAndTransactions;
-pub use chain_parameters::ChainParameters;
+pub use chain_parameters::{
+    ChainFactories, ChainParameters, ChainToken, CHAIN_ID_ARBITRUM, CHAIN_ID_BASE, CHAIN_ID_ETHEREUM, CHAIN_ID_OPTIMISM,
+};
 pub use fetchstate::FetchState;
 pub use loom_data_types::{LoomBlock, LoomDataTypes, LoomHeader, LoomTx};
 pub use loom_data_types_ethereum::LoomDataTypesEthereum;
-pub use mempool::Mempool;
+pub use mempool::{Mempool, MempoolSourceStats};
 pub use mempool_tx::MempoolTx;
 pub use opcodes::*;
 pub use state_update::{
-    debug_log_geth_state_update, debug_trace_block, debug_trace_call_diff, debug_trace_call_post_state, debug_trace_call_pre_state,
-    debug_trace_transaction, get_touched_addresses, GethStateUpdate, GethStateUpdateVec, TRACING_CALL_OPTS, TRACING_OPTS,
+    coinbase_balance_diff, debug_log_geth_state_update, debug_trace_block, debug_trace_call_diff, debug_trace_call_post_state,
+    debug_trace_call_pre_state, debug_trace_transaction, get_touched_addresses, GethStateUpdate, GethStateUpdateVec, TRACING_CALL_OPTS,
+    TRACING_OPTS,
 };
 mod accountnoncetx;
 mod chain_parameters;
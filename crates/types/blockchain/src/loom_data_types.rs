@@ -11,6 +11,9 @@ pub trait LoomTx<LDT: LoomDataTypes> {
     fn from(&self) -> LDT::Address;
 
     fn encode(&self) -> Vec<u8>;
+
+    /// Blob tx that carries its sidecar, only these can be rebroadcast in bundles
+    fn has_blob_sidecar(&self) -> bool;
 }
 
 pub trait LoomHeader<LDT: LoomDataTypes> {
@@ -1,5 +1,5 @@
 use crate::{ChainParameters, GethStateUpdate, LoomBlock, LoomDataTypes, LoomHeader, LoomTx};
-use alloy_consensus::{BlockHeader, Transaction as TransactionTrait};
+use alloy_consensus::{BlockHeader, Transaction as TransactionTrait, TxEip4844Variant, TxEnvelope};
 use alloy_eips::eip2718::Encodable2718;
 use alloy_primitives::{hex, Address, BlockHash, TxHash};
 use alloy_provider::network::TransactionResponse;
@@ -55,6 +55,13 @@ impl LoomTx<LoomDataTypesEthereum> for Transaction {
     fn encode(&self) -> Vec<u8> {
         self.inner.encoded_2718()
     }
+
+    fn has_blob_sidecar(&self) -> bool {
+        match &self.inner {
+            TxEnvelope::Eip4844(signed) => matches!(signed.tx(), TxEip4844Variant::TxEip4844WithSidecar(_)),
+            _ => false,
+        }
+    }
 }
 
 impl LoomHeader<LoomDataTypesEthereum> for Header {
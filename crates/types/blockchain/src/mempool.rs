@@ -6,16 +6,57 @@ use alloy_primitives::BlockNumber;
 use chrono::{DateTime, Utc};
 use eyre::{eyre, Result};
 use std::collections::hash_map::Entry;
+use std::collections::HashSet;
+
+/// First-seen statistics of a mempool source
+#[derive(Clone, Debug, Default)]
+pub struct MempoolSourceStats {
+    /// Txes delivered before any other source
+    pub first_seen: u64,
+    /// Txes already delivered by another source
+    pub late: u64,
+    /// Sum of delays of late txes behind the first source
+    pub late_delay_ms: u64,
+    /// First seen txes that were also delivered by another source
+    pub ahead: u64,
+    /// Sum of leads over the second source of those txes
+    pub ahead_ms: u64,
+}
+
+impl MempoolSourceStats {
+    pub fn avg_late_delay_ms(&self) -> u64 {
+        if self.late == 0 {
+            0
+        } else {
+            self.late_delay_ms / self.late
+        }
+    }
+
+    pub fn avg_ahead_ms(&self) -> u64 {
+        if self.ahead == 0 {
+            0
+        } else {
+            self.ahead_ms / self.ahead
+        }
+    }
+}
 
 #[derive(Clone, Debug, Default)]
 pub struct Mempool<LDT: LoomDataTypes = LoomDataTypesEthereum> {
     pub txs: HashMap<LDT::TxHash, MempoolTx<LDT>>,
     accounts: HashMap<LDT::Address, AccountNonceAndTransactions>,
+    sender_nonces: HashMap<(LDT::Address, u64), LDT::TxHash>,
+    source_stats: HashMap<String, MempoolSourceStats>,
 }
 
 impl<LDT: LoomDataTypes> Mempool<LDT> {
     pub fn new() -> Mempool<LoomDataTypesEthereum> {
-        Mempool { txs: HashMap::default(), accounts: HashMap::default() }
+        Mempool {
+            txs: HashMap::default(),
+            accounts: HashMap::default(),
+            sender_nonces: HashMap::default(),
+            source_stats: HashMap::default(),
+        }
     }
 
     pub fn len(&self) -> usize {
@@ -52,7 +93,11 @@ impl<LDT: LoomDataTypes> Mempool<LDT> {
     pub fn filter_by_gas_price(&self, gas_price: u128) -> Vec<&MempoolTx<LDT>> {
         self.txs
             .values()
-            .filter(|&item| item.mined.is_none() && item.tx.clone().map_or_else(|| false, |tx| tx.gas_price() >= gas_price))
+            .filter(|&item| {
+                item.mined.is_none()
+                    && item.replaced_by.is_none()
+                    && item.tx.clone().map_or_else(|| false, |tx| tx.gas_price() >= gas_price)
+            })
             .collect()
     }
 
@@ -61,6 +106,7 @@ impl<LDT: LoomDataTypes> Mempool<LDT> {
             .values()
             .filter(|&item| {
                 item.mined.is_none()
+                    && item.replaced_by.is_none()
                     && !item.failed.unwrap_or(false)
                     && item.tx.clone().map_or_else(|| false, |tx| tx.gas_price() >= gas_price)
             })
@@ -85,9 +131,19 @@ impl<LDT: LoomDataTypes> Mempool<LDT> {
         }
     }
 
+    pub fn is_replaced(&self, tx_hash: &LDT::TxHash) -> bool {
+        self.txs.get(tx_hash).is_some_and(|tx| tx.replaced_by.is_some())
+    }
+
+    /// True if any of the txes was replaced, a backrun of them can't be included anymore
+    pub fn is_any_replaced(&self, tx_hashes: &[LDT::TxHash]) -> bool {
+        tx_hashes.iter().any(|tx_hash| self.is_replaced(tx_hash))
+    }
+
     pub fn clean(&mut self) {
         self.txs = Default::default();
         self.accounts = Default::default();
+        self.sender_nonces = Default::default();
     }
 
     pub fn clean_txs(&mut self, max_block_number: BlockNumber, max_time: DateTime<Utc>) {
@@ -97,6 +153,78 @@ impl<LDT: LoomDataTypes> Mempool<LDT> {
             .into_iter()
             .filter(|(_, v)| v.mined.unwrap_or(max_block_number + 1) > max_block_number && v.time > max_time)
             .collect();
+        self.retain_tracked_senders();
+    }
+
+    /// Drops sender indexes of removed txes and the nonces of senders without txes left
+    fn retain_tracked_senders(&mut self) {
+        self.sender_nonces.retain(|_, tx_hash| self.txs.contains_key(tx_hash));
+        let senders: HashSet<LDT::Address> = self.sender_nonces.keys().map(|(sender, _)| *sender).collect();
+        self.accounts.retain(|account, _| senders.contains(account));
+    }
+
+    /// Drops the oldest txes when the mempool holds more than `max_txs`, leaves 10% headroom to not evict on every tx.
+    /// Returns hashes of evicted txes.
+    pub fn evict(&mut self, max_txs: usize) -> Vec<LDT::TxHash> {
+        if self.txs.len() <= max_txs {
+            return Vec::new();
+        }
+        let target = max_txs - max_txs / 10;
+        let mut by_age: Vec<(DateTime<Utc>, LDT::TxHash)> = self.txs.iter().map(|(tx_hash, tx)| (tx.time, *tx_hash)).collect();
+        by_age.sort_by_key(|(time, _)| *time);
+
+        let evicted: Vec<LDT::TxHash> = by_age.into_iter().take(self.txs.len() - target).map(|(_, tx_hash)| tx_hash).collect();
+        for tx_hash in evicted.iter() {
+            self.txs.remove(tx_hash);
+        }
+        self.retain_tracked_senders();
+        evicted
+    }
+
+    /// Indexes the tx by sender and nonce. If another pending tx had the same sender and nonce, it is marked as replaced and its hash is returned.
+    pub fn track_replacement(&mut self, tx: &LDT::Transaction) -> Option<LDT::TxHash> {
+        let tx_hash = tx.tx_hash();
+        let previous = self.sender_nonces.insert((tx.from(), tx.nonce()), tx_hash)?;
+        if previous == tx_hash {
+            return None;
+        }
+        match self.txs.get_mut(&previous) {
+            Some(previous_tx) if previous_tx.mined.is_none() => {
+                previous_tx.replaced_by = Some(tx_hash);
+                Some(previous)
+            }
+            _ => None,
+        }
+    }
+
+    /// Records that `source` delivered the tx. The first source counts as first seen, others count as late with the delay behind it.
+    /// When the second source arrives, the lead of the first one is recorded for the tx and the first source.
+    pub fn record_source(&mut self, tx_hash: LDT::TxHash, source: &str, time: DateTime<Utc>) {
+        let Some(entry) = self.txs.get_mut(&tx_hash) else {
+            return;
+        };
+        if entry.seen_sources.iter().any(|seen| seen == source) {
+            return;
+        }
+        let delay_ms = (time - entry.time).num_milliseconds().max(0) as u64;
+        let stats = self.source_stats.entry(source.to_string()).or_default();
+        if entry.seen_sources.is_empty() {
+            stats.first_seen += 1;
+        } else {
+            stats.late += 1;
+            stats.late_delay_ms += delay_ms;
+        }
+        if entry.seen_sources.len() == 1 {
+            entry.first_seen_lead_ms = Some(delay_ms);
+            let first_stats = self.source_stats.entry(entry.seen_sources[0].clone()).or_default();
+            first_stats.ahead += 1;
+            first_stats.ahead_ms += delay_ms;
+        }
+        entry.seen_sources.push(source.to_string());
+    }
+
+    pub fn source_stats(&self) -> &HashMap<String, MempoolSourceStats> {
+        &self.source_stats
     }
 
     pub fn set_mined(&mut self, tx_hash: LDT::TxHash, block_number: BlockNumber) -> &mut Self {
@@ -134,3 +262,61 @@ impl<LDT: LoomDataTypes> Mempool<LDT> {
         self.txs.remove(tx_hash)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloy_consensus::{Signed, TxEnvelope, TxLegacy};
+    use alloy_primitives::{Address, PrimitiveSignature, TxHash, U256};
+    use alloy_rpc_types::Transaction;
+    use chrono::Duration;
+
+    fn legacy_tx(id: u8, sender: u8, nonce: u64) -> Transaction {
+        let tx = TxLegacy { nonce, gas_price: 1, ..TxLegacy::default() };
+        let signature = PrimitiveSignature::new(U256::from(1), U256::from(1), false);
+        Transaction {
+            inner: TxEnvelope::Legacy(Signed::new_unchecked(tx, signature, TxHash::repeat_byte(id))),
+            block_hash: None,
+            block_number: None,
+            transaction_index: None,
+            effective_gas_price: None,
+            from: Address::repeat_byte(sender),
+        }
+    }
+
+    #[test]
+    fn test_replacement_and_eviction() {
+        let mut mempool: Mempool = Mempool::<LoomDataTypesEthereum>::new();
+        let now = Utc::now();
+
+        for (id, nonce) in [(1u8, 0u64), (2, 1), (3, 0)] {
+            let tx = legacy_tx(id, 1, nonce);
+            mempool.add_tx(tx.clone());
+            mempool.txs.get_mut(&TxHash::repeat_byte(id)).unwrap().time = now + Duration::seconds(id as i64);
+            let replaced = mempool.track_replacement(&tx);
+            assert_eq!(replaced, if id == 3 { Some(TxHash::repeat_byte(1)) } else { None });
+        }
+        assert!(mempool.is_replaced(&TxHash::repeat_byte(1)));
+        assert!(mempool.is_any_replaced(&[TxHash::repeat_byte(2), TxHash::repeat_byte(1)]));
+        assert!(!mempool.is_any_replaced(&[TxHash::repeat_byte(2)]));
+        assert_eq!(mempool.filter_ok_by_gas_price(0).len(), 2);
+
+        let seen_at = now + Duration::seconds(2);
+        mempool.record_source(TxHash::repeat_byte(2), "exex", seen_at);
+        mempool.record_source(TxHash::repeat_byte(2), "node", seen_at + Duration::milliseconds(40));
+        mempool.record_source(TxHash::repeat_byte(2), "node", seen_at + Duration::milliseconds(90));
+        assert_eq!(mempool.source_stats()["exex"].first_seen, 1);
+        assert_eq!(mempool.source_stats()["node"].late, 1);
+        assert_eq!(mempool.source_stats()["node"].avg_late_delay_ms(), 40);
+        assert_eq!(mempool.source_stats()["exex"].avg_ahead_ms(), 40);
+        assert_eq!(mempool.get_tx_by_hash(&TxHash::repeat_byte(2)).unwrap().first_seen_lead_ms, Some(40));
+
+        mempool.set_nonce(Address::repeat_byte(1), 0);
+        mempool.set_nonce(Address::repeat_byte(9), 5);
+        assert!(mempool.evict(3).is_empty());
+        assert_eq!(mempool.evict(1), vec![TxHash::repeat_byte(1), TxHash::repeat_byte(2)]);
+        assert_eq!(mempool.len(), 1);
+        // only the sender of the remaining tx keeps its nonce
+        assert_eq!(mempool.accounts.keys().collect::<Vec<_>>(), vec![&Address::repeat_byte(1)]);
+    }
+}
@@ -1,7 +1,9 @@
+use alloy_consensus::{Signed, TxEip4844Variant, TxEip4844WithSidecar, TxEnvelope};
+use alloy_eips::eip4844::BlobTransactionSidecar;
 use alloy_primitives::{BlockNumber, TxHash};
 use chrono::{DateTime, Utc};
 
-use crate::FetchState;
+use crate::{FetchState, LoomTx};
 use crate::{LoomDataTypes, LoomDataTypesEthereum};
 
 #[derive(Clone, Debug)]
@@ -15,6 +17,12 @@ pub struct MempoolTx<D: LoomDataTypes> {
     pub failed: Option<bool>,
     pub state_update: Option<D::StateUpdate>,
     pub pre_state: Option<FetchState<D::StateUpdate>>,
+    /// Tx with the same sender and nonce that superseded this one
+    pub replaced_by: Option<D::TxHash>,
+    /// Sources that delivered the tx, in order of arrival
+    pub seen_sources: Vec<String>,
+    /// How far the first source was ahead of the second one
+    pub first_seen_lead_ms: Option<u64>,
 }
 
 impl MempoolTx<LoomDataTypesEthereum> {
@@ -24,6 +32,37 @@ impl MempoolTx<LoomDataTypesEthereum> {
     pub fn new_with_hash(tx_hash: TxHash) -> MempoolTx<LoomDataTypesEthereum> {
         MempoolTx { tx_hash, ..MempoolTx::default() }
     }
+
+    /// Type 3 tx, with or without its sidecar
+    pub fn is_blob_tx(&self) -> bool {
+        matches!(self.tx.as_ref().map(|tx| &tx.inner), Some(TxEnvelope::Eip4844(_)))
+    }
+
+    /// Type 4 tx carrying an authorization list
+    pub fn is_set_code_tx(&self) -> bool {
+        matches!(self.tx.as_ref().map(|tx| &tx.inner), Some(TxEnvelope::Eip7702(_)))
+    }
+
+    /// Blob txes can only be included in a bundle together with their sidecar
+    pub fn has_blob_sidecar(&self) -> bool {
+        self.tx.as_ref().is_some_and(|tx| tx.has_blob_sidecar())
+    }
+
+    /// Attaches the sidecar to a type 3 tx, other txes are not changed
+    pub fn with_blob_sidecar(mut self, sidecar: BlobTransactionSidecar) -> Self {
+        if let Some(tx) = self.tx.as_mut() {
+            if let TxEnvelope::Eip4844(signed) = &tx.inner {
+                let (variant, signature, hash) = signed.clone().into_parts();
+                let tx_eip4844 = match variant {
+                    TxEip4844Variant::TxEip4844(tx_eip4844) => tx_eip4844,
+                    TxEip4844Variant::TxEip4844WithSidecar(with_sidecar) => with_sidecar.tx,
+                };
+                let with_sidecar = TxEip4844WithSidecar::from_tx_and_sidecar(tx_eip4844, sidecar);
+                tx.inner = TxEnvelope::Eip4844(Signed::new_unchecked(with_sidecar.into(), signature, hash));
+            }
+        }
+        self
+    }
 }
 
 impl<LDT: LoomDataTypes> Default for MempoolTx<LDT> {
@@ -38,6 +77,47 @@ impl<LDT: LoomDataTypes> Default for MempoolTx<LDT> {
             mined: None,
             failed: None,
             pre_state: None,
+            replaced_by: None,
+            seen_sources: Vec::new(),
+            first_seen_lead_ms: None,
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloy_consensus::{TxEip4844, TxEip7702};
+    use alloy_eips::eip2718::Encodable2718;
+    use alloy_primitives::{Address, PrimitiveSignature, U256};
+    use alloy_rpc_types::Transaction;
+
+    fn rpc_tx(inner: TxEnvelope) -> Transaction {
+        Transaction { inner, block_hash: None, block_number: None, transaction_index: None, effective_gas_price: None, from: Address::ZERO }
+    }
+
+    #[test]
+    fn test_blob_and_set_code_txes() {
+        let signature = PrimitiveSignature::new(U256::from(1), U256::from(1), false);
+
+        let blob_tx = TxEip4844 { blob_versioned_hashes: vec![TxHash::repeat_byte(1)], ..TxEip4844::default() };
+        let blob_envelope = TxEnvelope::Eip4844(Signed::new_unchecked(blob_tx.into(), signature, TxHash::repeat_byte(3)));
+        let mempool_tx = MempoolTx { tx: Some(rpc_tx(blob_envelope)), ..MempoolTx::new() };
+        assert!(mempool_tx.is_blob_tx());
+        assert!(!mempool_tx.has_blob_sidecar());
+
+        let encoded_len = mempool_tx.tx.as_ref().unwrap().inner.encoded_2718().len();
+        let mempool_tx = mempool_tx.with_blob_sidecar(BlobTransactionSidecar::default());
+        assert!(mempool_tx.has_blob_sidecar());
+        assert_eq!(mempool_tx.tx.as_ref().unwrap().inner.tx_hash(), &TxHash::repeat_byte(3));
+        // network encoding carries the sidecar
+        assert!(mempool_tx.tx.as_ref().unwrap().inner.encoded_2718().len() > encoded_len);
+
+        let set_code_envelope = TxEnvelope::Eip7702(Signed::new_unchecked(TxEip7702::default(), signature, TxHash::repeat_byte(4)));
+        let mempool_tx =
+            MempoolTx { tx: Some(rpc_tx(set_code_envelope)), ..MempoolTx::new() }.with_blob_sidecar(BlobTransactionSidecar::default());
+        assert!(mempool_tx.is_set_code_tx());
+        assert!(!mempool_tx.is_blob_tx());
+        assert!(!mempool_tx.has_blob_sidecar());
+    }
+}
@@ -1,4 +1,4 @@
-use alloy_primitives::{Address, TxHash};
+use alloy_primitives::{Address, TxHash, U256};
 use alloy_provider::ext::DebugApi;
 use alloy_provider::{Network, Provider};
 use alloy_rpc_types::{BlockId, TransactionRequest};
@@ -9,7 +9,7 @@ use alloy_rpc_types_trace::geth::{
     AccountState, GethDebugBuiltInTracerType, GethDebugTracerConfig, GethDebugTracerType, GethDebugTracingCallOptions,
     GethDebugTracingOptions, GethDefaultTracingOptions, GethTrace, PreStateConfig, PreStateFrame,
 };
-use eyre::Result;
+use eyre::{eyre, Result};
 use lazy_static::lazy_static;
 use std::collections::BTreeMap;
 use tracing::{debug, trace};
@@ -43,6 +43,14 @@ pub fn get_touched_addresses(state_update: &GethStateUpdate) -> Vec<Address> {
     ret
 }
 
+/// Balance increase of the coinbase by a tx, the bribe and priority fee paid to the builder
+pub fn coinbase_balance_diff(pre: &GethStateUpdate, post: &GethStateUpdate, coinbase: &Address) -> Result<U256> {
+    let coinbase_pre = pre.get(coinbase).ok_or(eyre!("COINBASE_NOT_FOUND_IN_PRE"))?;
+    let coinbase_post = post.get(coinbase).ok_or(eyre!("COINBASE_NOT_FOUND_IN_POST"))?;
+
+    Ok(coinbase_post.balance.unwrap_or_default().checked_sub(coinbase_pre.balance.unwrap_or_default()).unwrap_or_default())
+}
+
 pub fn debug_log_geth_state_update(state_update: &GethStateUpdate) {
     for (address, state) in state_update {
         debug!("{} nonce {:?} balance {:?} is_code {}", address, state.nonce, state.balance, state.code.is_some())
@@ -179,7 +187,7 @@ pub async fn debug_trace_transaction<N: Network, P: Provider<N> + DebugApi<N>>(
 mod test {
     use super::*;
     use alloy_primitives::map::B256HashMap;
-    use alloy_primitives::{B256, U256};
+    use alloy_primitives::B256;
     use alloy_provider::network::primitives::BlockTransactionsKind;
     use alloy_provider::ProviderBuilder;
     use alloy_rpc_client::{ClientBuilder, WsConnect};
@@ -207,6 +215,18 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn test_coinbase_balance_diff() {
+        let coinbase = Address::repeat_byte(0x95);
+        let account = |balance: u64| AccountState { balance: Some(U256::from(balance)), ..AccountState::default() };
+
+        let pre = GethStateUpdate::from([(coinbase, account(100))]);
+        let post = GethStateUpdate::from([(coinbase, account(150))]);
+        assert_eq!(coinbase_balance_diff(&pre, &post, &coinbase).unwrap(), U256::from(50));
+        assert_eq!(coinbase_balance_diff(&post, &pre, &coinbase).unwrap(), U256::ZERO);
+        assert!(coinbase_balance_diff(&pre, &GethStateUpdate::new(), &coinbase).is_err());
+    }
+
     #[test]
     fn test_encode_override() {
         let mut state_override: StateOverride = StateOverride::default();
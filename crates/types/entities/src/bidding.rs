@@ -0,0 +1,202 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use alloy_primitives::U256;
+use eyre::Result;
+use loom_evm_utils::NWETH;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info};
+
+use crate::tips::tips_pct_advanced;
+
+/// Upper bounds of the profit buckets in ETH, profits above the last bound fall into an extra bucket
+const PROFIT_BUCKETS_ETH: [f64; 5] = [0.001, 0.01, 0.1, 1.0, 10.0];
+
+/// Granularity of the tips percentages in the outcome statistics
+const TIPS_PCT_STEP: u32 = 500;
+
+/// Chooses the share of the swap profit paid as tips
+pub trait TipsBidder: Send + Sync {
+    /// Tips in 1/10000 of the profit
+    fn tips_pct(&self, profit_eth: U256) -> u32;
+
+    fn name(&self) -> &'static str;
+}
+
+/// Piecewise table lowering the tips share for large profits
+#[derive(Clone, Debug, Default)]
+pub struct SlopesBidder;
+
+impl TipsBidder for SlopesBidder {
+    fn tips_pct(&self, profit_eth: U256) -> u32 {
+        tips_pct_advanced(&profit_eth)
+    }
+
+    fn name(&self) -> &'static str {
+        "slopes"
+    }
+}
+
+/// Result of a submitted bundle, one json per line in the outcomes file.
+/// `builder` is the builder of the target block, `included` tells if the bundle landed in it.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct BidOutcome {
+    pub profit_eth: f64,
+    pub tips_pct: u32,
+    pub builder: String,
+    pub included: bool,
+}
+
+fn profit_bucket(profit_eth: f64) -> usize {
+    PROFIT_BUCKETS_ETH.iter().take_while(|bound| **bound <= profit_eth).count()
+}
+
+/// Bids the tips share with the best expected profit observed for the profit bucket.
+/// A bundle carries one tips amount for all builders, so the outcomes of the builders are pooled
+/// and a single share is chosen per bucket, not a share per builder.
+/// Buckets without enough outcomes fall back to the slopes table.
+#[derive(Clone, Debug, Default)]
+pub struct HistoricalBidder {
+    best_tips_pct: BTreeMap<usize, u32>,
+}
+
+impl HistoricalBidder {
+    /// `builders` limits the outcomes to the builders bundles are sent to, a builder needs `min_samples` outcomes
+    /// with a tips share to be counted for it. Every block is won by a single builder, so the inclusion rate is pooled
+    /// over the counted builders.
+    pub fn from_outcomes(outcomes: &[BidOutcome], builders: Option<&[String]>, min_samples: u64) -> Self {
+        // (profit bucket, tips pct) -> builder -> (attempts, inclusions)
+        let mut stats: BTreeMap<(usize, u32), BTreeMap<&str, (u64, u64)>> = BTreeMap::new();
+        for outcome in outcomes.iter() {
+            if builders.is_some_and(|builders| !builders.contains(&outcome.builder)) {
+                continue;
+            }
+            let tips_pct = outcome.tips_pct.min(10000) / TIPS_PCT_STEP * TIPS_PCT_STEP;
+            let entry =
+                stats.entry((profit_bucket(outcome.profit_eth), tips_pct)).or_default().entry(outcome.builder.as_str()).or_default();
+            entry.0 += 1;
+            if outcome.included {
+                entry.1 += 1;
+            }
+        }
+
+        // profit bucket -> (expected share of the profit kept, tips pct)
+        let mut best: BTreeMap<usize, (f64, u32)> = BTreeMap::new();
+        for ((bucket, tips_pct), builder_stats) in stats.iter() {
+            let (attempts, inclusions) = builder_stats
+                .values()
+                .filter(|(attempts, _)| *attempts >= min_samples)
+                .fold((0u64, 0u64), |(attempts, inclusions), stats| (attempts + stats.0, inclusions + stats.1));
+            if attempts == 0 {
+                continue;
+            }
+            let inclusion_rate = inclusions as f64 / attempts as f64;
+            let expected = inclusion_rate * (10000 - tips_pct) as f64 / 10000.0;
+            debug!(bucket, tips_pct, inclusion_rate, expected, "Bid outcome statistics");
+
+            if best.get(bucket).is_none_or(|(best_expected, _)| expected > *best_expected) {
+                best.insert(*bucket, (expected, *tips_pct));
+            }
+        }
+
+        Self { best_tips_pct: best.into_iter().map(|(bucket, (_, tips_pct))| (bucket, tips_pct)).collect() }
+    }
+
+    /// Loads outcomes from a json lines file
+    pub fn load(path: &Path, builders: Option<&[String]>, min_samples: u64) -> Result<Self> {
+        let outcomes = std::fs::read_to_string(path)?
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(serde_json::from_str)
+            .collect::<Result<Vec<BidOutcome>, _>>()?;
+        let bidder = Self::from_outcomes(&outcomes, builders, min_samples);
+        info!(path = %path.display(), outcomes = outcomes.len(), buckets = bidder.best_tips_pct.len(), "Bid outcomes loaded");
+        Ok(bidder)
+    }
+}
+
+impl TipsBidder for HistoricalBidder {
+    fn tips_pct(&self, profit_eth: U256) -> u32 {
+        match self.best_tips_pct.get(&profit_bucket(NWETH::to_float(profit_eth))) {
+            Some(tips_pct) => *tips_pct,
+            None => SlopesBidder.tips_pct(profit_eth),
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        "historical"
+    }
+}
+
+fn default_min_samples() -> u64 {
+    20
+}
+
+/// Bidding strategy of a searcher
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum BiddingConfig {
+    #[default]
+    Slopes,
+    Historical {
+        /// Json lines file with [`BidOutcome`] records
+        path: PathBuf,
+        #[serde(default)]
+        builders: Option<Vec<String>>,
+        #[serde(default = "default_min_samples")]
+        min_samples: u64,
+    },
+}
+
+impl BiddingConfig {
+    pub fn bidder(&self) -> Result<Arc<dyn TipsBidder>> {
+        let bidder: Arc<dyn TipsBidder> = match self {
+            BiddingConfig::Slopes => Arc::new(SlopesBidder),
+            BiddingConfig::Historical { path, builders, min_samples } => {
+                Arc::new(HistoricalBidder::load(path, builders.as_deref(), *min_samples)?)
+            }
+        };
+        Ok(bidder)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn outcomes(profit_eth: f64, tips_pct: u32, builder: &str, attempts: usize, inclusions: usize) -> Vec<BidOutcome> {
+        (0..attempts).map(|idx| BidOutcome { profit_eth, tips_pct, builder: builder.to_string(), included: idx < inclusions }).collect()
+    }
+
+    #[test]
+    fn test_historical_bidder() {
+        let mut history = outcomes(0.05, 9000, "flashbots", 20, 18);
+        // lower tips lose too often to pay off
+        history.extend(outcomes(0.05, 5000, "flashbots", 20, 2));
+        // too few samples
+        history.extend(outcomes(0.05, 2000, "flashbots", 5, 5));
+        history.extend(outcomes(0.05, 2000, "other", 50, 50));
+
+        let bidder = HistoricalBidder::from_outcomes(&history, Some(&["flashbots".to_string()]), 10);
+        assert_eq!(bidder.tips_pct(NWETH::from_float(0.05)), 9000);
+        // no outcomes in the bucket
+        assert_eq!(bidder.tips_pct(NWETH::from_float(20.0)), SlopesBidder.tips_pct(NWETH::from_float(20.0)));
+
+        let bidder = HistoricalBidder::from_outcomes(&history, None, 10);
+        assert_eq!(bidder.tips_pct(NWETH::from_float(0.05)), 2000);
+    }
+
+    #[test]
+    fn test_historical_bidder_pools_builders() {
+        // 0.4 pooled keeps 0.2 of the profit, combined as independent chances it would keep 0.32
+        let mut history = outcomes(2.0, 5000, "flashbots", 20, 8);
+        history.extend(outcomes(2.0, 5000, "beaver", 20, 8));
+        // 0.8 pooled keeps 0.24 of the profit
+        history.extend(outcomes(2.0, 7000, "flashbots", 20, 16));
+        history.extend(outcomes(2.0, 7000, "beaver", 20, 16));
+
+        let bidder = HistoricalBidder::from_outcomes(&history, None, 10);
+        assert_eq!(bidder.tips_pct(NWETH::from_float(2.0)), 7000);
+    }
+}
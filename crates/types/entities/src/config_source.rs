@@ -0,0 +1,230 @@
+use std::path::{Path, PathBuf};
+
+use toml::Value;
+use tracing::debug;
+
+use crate::strategy_config::LoadConfigError;
+
+/// Selects the per-environment override of a config file, `LOOM_ENV=prod` loads `config.prod.toml` over `config.toml`
+pub const CONFIG_ENV_VAR: &str = "LOOM_ENV";
+
+/// Prefix of string values replaced with the content of a file
+pub const SECRET_FILE_PREFIX: &str = "file:";
+
+/// Base config file followed by the override for the environment in [`CONFIG_ENV_VAR`] if it is set and the file exists
+pub fn config_layers(file_path: &Path) -> Vec<PathBuf> {
+    let mut layers = vec![file_path.to_path_buf()];
+    if let Ok(env) = std::env::var(CONFIG_ENV_VAR) {
+        if !env.is_empty() {
+            let stem = file_path.file_stem().and_then(|s| s.to_str()).unwrap_or("config");
+            let file_name = match file_path.extension().and_then(|s| s.to_str()) {
+                Some(extension) => format!("{stem}.{env}.{extension}"),
+                None => format!("{stem}.{env}"),
+            };
+            let overlay = file_path.with_file_name(file_name);
+            if overlay.exists() {
+                layers.push(overlay);
+            } else {
+                debug!("Config override {} not found", overlay.display());
+            }
+        }
+    }
+    layers
+}
+
+/// Reads config files, merges them in order and interpolates environment variables and secret files
+pub fn load_toml_layers(files: &[PathBuf]) -> Result<Value, LoadConfigError> {
+    let contents = files.iter().map(std::fs::read_to_string).collect::<Result<Vec<_>, _>>()?;
+    parse_toml_layers(&contents)
+}
+
+/// Parses contents of config files, merges them in order and interpolates environment variables and secret files
+pub fn parse_toml_layers(contents: &[String]) -> Result<Value, LoadConfigError> {
+    let mut config = Value::Table(Default::default());
+    for contents in contents {
+        merge_toml(&mut config, toml::from_str(contents)?);
+    }
+    interpolate_toml(&mut config, &|name| std::env::var(name).ok())?;
+    Ok(config)
+}
+
+/// Merges `overlay` into `base`. Tables are merged key by key, all other values are replaced.
+pub fn merge_toml(base: &mut Value, overlay: Value) {
+    match (base, overlay) {
+        (Value::Table(base), Value::Table(overlay)) => {
+            for (key, value) in overlay {
+                match base.get_mut(&key) {
+                    Some(base_value) => merge_toml(base_value, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, overlay) => *base = overlay,
+    }
+}
+
+/// Replaces `${VAR}` and `${VAR:-default}` in all string values and loads values starting with `file:` from files.
+/// Only literal `file:` values are loaded, a variable expanding to `file:...` is kept as is.
+/// `$${` is kept as a literal `${`. Secret file paths are relative to the working directory.
+pub fn interpolate_toml(value: &mut Value, lookup: &dyn Fn(&str) -> Option<String>) -> Result<(), LoadConfigError> {
+    interpolate_value(value, "", lookup)
+}
+
+fn interpolate_value(value: &mut Value, path: &str, lookup: &dyn Fn(&str) -> Option<String>) -> Result<(), LoadConfigError> {
+    match value {
+        Value::String(s) => {
+            *s = match s.strip_prefix(SECRET_FILE_PREFIX) {
+                Some(file) => read_secret_file(&interpolate_str(file, path, lookup)?, path)?,
+                None => interpolate_str(s, path, lookup)?,
+            };
+        }
+        Value::Table(table) => {
+            for (key, value) in table.iter_mut() {
+                let path = if path.is_empty() { key.clone() } else { format!("{path}.{key}") };
+                interpolate_value(value, &path, lookup)?;
+            }
+        }
+        Value::Array(array) => {
+            for (idx, value) in array.iter_mut().enumerate() {
+                interpolate_value(value, &format!("{path}[{idx}]"), lookup)?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+fn interpolate_str(s: &str, path: &str, lookup: &dyn Fn(&str) -> Option<String>) -> Result<String, LoadConfigError> {
+    let mut result = String::with_capacity(s.len());
+    let mut rest = s;
+
+    while let Some(start) = rest.find('$') {
+        result.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        if let Some(escaped) = rest.strip_prefix("$${") {
+            result.push_str("${");
+            rest = escaped;
+        } else if let Some(reference) = rest.strip_prefix("${") {
+            let end = reference.find('}').ok_or_else(|| LoadConfigError::Interpolation {
+                path: path.to_string(),
+                message: format!("unclosed variable reference in \"{s}\""),
+            })?;
+            let (name, default) = match reference[..end].split_once(":-") {
+                Some((name, default)) => (name, Some(default)),
+                None => (&reference[..end], None),
+            };
+            match (lookup(name).filter(|value| !value.is_empty()), default) {
+                (Some(value), _) => result.push_str(&value),
+                (None, Some(default)) => result.push_str(default),
+                (None, None) => {
+                    return Err(LoadConfigError::Interpolation {
+                        path: path.to_string(),
+                        message: format!("environment variable {name} is not set"),
+                    })
+                }
+            }
+            rest = &reference[end + 1..];
+        } else {
+            result.push('$');
+            rest = &rest[1..];
+        }
+    }
+    result.push_str(rest);
+    Ok(result)
+}
+
+fn read_secret_file(file: &str, path: &str) -> Result<String, LoadConfigError> {
+    match std::fs::read_to_string(file) {
+        Ok(contents) => Ok(contents.trim_end_matches(['\r', '\n']).to_string()),
+        Err(e) => Err(LoadConfigError::Interpolation { path: path.to_string(), message: format!("cannot read secret file {file} : {e}") }),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn lookup(name: &str) -> Option<String> {
+        match name {
+            "NODE_HOST" => Some("10.0.0.1".to_string()),
+            "EMPTY" => Some(String::new()),
+            "SECRET_DIR" => Some(std::env::temp_dir().display().to_string()),
+            "FILE_REF" => Some("file:/etc/hostname".to_string()),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_interpolation() {
+        let mut config: Value = toml::from_str(
+            r#"
+            url = "ws://${NODE_HOST}:8546"
+            db = "${DB_URL:-postgres://localhost/loom}"
+            empty = "${EMPTY:-default}"
+            escaped = "$${NODE_HOST} costs $5"
+            relays = [{ url = "https://${NODE_HOST}" }]
+            "#,
+        )
+        .unwrap();
+        interpolate_toml(&mut config, &lookup).unwrap();
+
+        assert_eq!(config["url"].as_str(), Some("ws://10.0.0.1:8546"));
+        assert_eq!(config["db"].as_str(), Some("postgres://localhost/loom"));
+        assert_eq!(config["empty"].as_str(), Some("default"));
+        assert_eq!(config["escaped"].as_str(), Some("${NODE_HOST} costs $5"));
+        assert_eq!(config["relays"][0]["url"].as_str(), Some("https://10.0.0.1"));
+
+        let mut config: Value = toml::from_str(r#"clients = { local = { url = "${MISSING}" } }"#).unwrap();
+        match interpolate_toml(&mut config, &lookup) {
+            Err(LoadConfigError::Interpolation { path, .. }) => assert_eq!(path, "clients.local.url"),
+            other => panic!("unexpected result {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_secret_file() {
+        let file_name = format!("loom_secret_{}", std::process::id());
+        std::fs::write(std::env::temp_dir().join(&file_name), "secret\n").unwrap();
+
+        let mut config = Value::String(format!("{SECRET_FILE_PREFIX}${{SECRET_DIR}}/{file_name}"));
+        interpolate_toml(&mut config, &lookup).unwrap();
+        std::fs::remove_file(std::env::temp_dir().join(&file_name)).unwrap();
+
+        assert_eq!(config.as_str(), Some("secret"));
+
+        let mut config = Value::String("${FILE_REF}".to_string());
+        interpolate_toml(&mut config, &lookup).unwrap();
+        assert_eq!(config.as_str(), Some("file:/etc/hostname"));
+    }
+
+    #[test]
+    fn test_merge_layers() {
+        let mut base: Value = toml::from_str(
+            r#"
+            [clients]
+            local = { url = "ws://localhost:8546", transport = "ws" }
+            [webserver]
+            host = "127.0.0.1:3333"
+            "#,
+        )
+        .unwrap();
+        let overlay: Value = toml::from_str(
+            r#"
+            [clients.local]
+            url = "ws://10.0.0.1:8546"
+            [clients.remote]
+            url = "ws://10.0.0.2:8546"
+            "#,
+        )
+        .unwrap();
+        merge_toml(&mut base, overlay);
+
+        assert_eq!(base["clients"]["local"]["url"].as_str(), Some("ws://10.0.0.1:8546"));
+        assert_eq!(base["clients"]["local"]["transport"].as_str(), Some("ws"));
+        assert_eq!(base["clients"]["remote"]["url"].as_str(), Some("ws://10.0.0.2:8546"));
+        assert_eq!(base["webserver"]["host"].as_str(), Some("127.0.0.1:3333"));
+    }
+}
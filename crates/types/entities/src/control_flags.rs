@@ -0,0 +1,73 @@
+use std::collections::BTreeSet;
+
+use alloy_primitives::Address;
+use serde::{Deserialize, Serialize};
+
+use crate::Swap;
+
+/// Origins of swaps set by the searchers and mergers, the strategies that can be disabled
+pub const STRATEGY_ORIGINS: [&str; 7] = [
+    "block_searcher",
+    "pending_tx_searcher",
+    "pending_tx_bundle_searcher",
+    "poolcode_searcher",
+    "merger_searcher",
+    "samepath_merger",
+    "diffpath_merger",
+];
+
+/// Runtime switches set through the control API. Router and broadcaster check them for every swap.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ControlFlags {
+    /// Overrides `allow_broadcast` of broadcasters when set
+    pub broadcast: Option<bool>,
+    /// Origins of swaps that are dropped by the router, e.g. `pending_tx_searcher` for the mempool backrunner
+    pub disabled_strategies: BTreeSet<String>,
+    /// Upper limit of the tips share of the profit in 1/10000, applied by the encoder after the tips bidder
+    pub max_tips_pct: Option<u32>,
+    pub token_blacklist: BTreeSet<Address>,
+    pub disabled_pools: BTreeSet<Address>,
+}
+
+impl ControlFlags {
+    pub fn is_broadcast_allowed(&self, allow_broadcast: bool) -> bool {
+        self.broadcast.unwrap_or(allow_broadcast)
+    }
+
+    /// Swaps without an origin belong to no strategy and are never disabled
+    pub fn is_strategy_enabled(&self, origin: Option<&str>) -> bool {
+        origin.is_none_or(|origin| !self.disabled_strategies.contains(origin))
+    }
+
+    /// Reason the swap must not be executed, `None` if it is allowed
+    pub fn swap_rejection(&self, swap: &Swap) -> Option<&'static str> {
+        if swap.get_token_address_vec().iter().any(|token| self.token_blacklist.contains(token)) {
+            Some("TOKEN_BLACKLISTED")
+        } else if swap.get_pool_address_vec().iter().any(|pool| self.disabled_pools.contains(pool)) {
+            Some("POOL_DISABLED")
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_control_flags() {
+        let mut flags = ControlFlags::default();
+        assert!(flags.is_broadcast_allowed(true));
+        assert!(!flags.is_broadcast_allowed(false));
+        assert!(flags.is_strategy_enabled(Some("pending_tx_searcher")));
+
+        flags.broadcast = Some(false);
+        flags.disabled_strategies.insert("pending_tx_searcher".to_string());
+        assert!(!flags.is_strategy_enabled(Some("pending_tx_searcher")));
+        assert!(flags.is_strategy_enabled(Some("block_searcher")));
+        assert!(flags.is_strategy_enabled(None));
+        assert!(!flags.is_broadcast_allowed(true));
+        assert_eq!(flags.swap_rejection(&Swap::None), None);
+    }
+}
@@ -0,0 +1,31 @@
+use std::time::Duration;
+
+use alloy_primitives::BlockNumber;
+
+/// Freshness of the local head compared to the node head and the chain block time
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum HeadStatus {
+    /// Local state follows the node head
+    #[default]
+    Fresh,
+    /// Local head is behind the node head by more blocks than allowed
+    Lagging,
+    /// No new block was received for longer than the block time allows
+    Stalled,
+}
+
+/// Last head check of the watchdog. Swaps are not routed or broadcast while the head is not fresh.
+#[derive(Clone, Debug, Default)]
+pub struct HeadHealth {
+    pub status: HeadStatus,
+    pub local_head: BlockNumber,
+    pub node_head: Option<BlockNumber>,
+    /// Time since the timestamp of the local head
+    pub block_age: Option<Duration>,
+}
+
+impl HeadHealth {
+    pub fn is_fresh(&self) -> bool {
+        self.status == HeadStatus::Fresh
+    }
+}
@@ -0,0 +1,238 @@
+use std::collections::HashMap;
+
+use alloy_primitives::{Address, BlockNumber, U256};
+use serde::Deserialize;
+
+use crate::{Swap, SwapAmountType, SwapLine};
+
+/// How the input tokens of a swap are provided
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SwapFunding {
+    /// Flash swaps through the swap pools or a Balancer flash loan
+    #[default]
+    Flash,
+    /// Transferred from the tokens held by the multicaller, `balance` is its balance of the input token before the swap
+    Inventory { balance: U256 },
+}
+
+/// When swaps are funded from the multicaller inventory
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecutionMode {
+    #[default]
+    Flash,
+    /// Inventory for swap lines that would need a Balancer flash loan
+    InventoryFallback,
+    /// Inventory whenever it covers the amount in
+    Inventory,
+}
+
+/// Balance of an inventory token compared to its rebalancing bounds
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InventoryLevel {
+    Low,
+    Normal,
+    High,
+}
+
+fn to_token_amount(value: f64, decimals: u8) -> U256 {
+    U256::from((value * 10f64.powi(decimals as i32)) as u128)
+}
+
+/// Limits of a token held by the multicaller, amounts are in token units
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct InventoryTokenConfig {
+    /// Max amount used by one swap
+    pub max_amount_in: Option<f64>,
+    /// Rebalancing alert below this balance
+    pub min_balance: Option<f64>,
+    /// Rebalancing alert above this balance
+    pub max_balance: Option<f64>,
+}
+
+impl InventoryTokenConfig {
+    pub fn max_amount_in(&self, decimals: u8) -> Option<U256> {
+        self.max_amount_in.map(|value| to_token_amount(value, decimals))
+    }
+
+    pub fn level(&self, balance: U256, decimals: u8) -> InventoryLevel {
+        if self.min_balance.is_some_and(|min_balance| balance < to_token_amount(min_balance, decimals)) {
+            InventoryLevel::Low
+        } else if self.max_balance.is_some_and(|max_balance| balance > to_token_amount(max_balance, decimals)) {
+            InventoryLevel::High
+        } else {
+            InventoryLevel::Normal
+        }
+    }
+}
+
+/// Inventory amounts taken by swaps routed for the next block. The multicaller balance is only updated after the block,
+/// without reservations swaps in flight would be funded from the same tokens.
+#[derive(Clone, Debug, Default)]
+pub struct InventoryReservations {
+    block_number: BlockNumber,
+    reserved: HashMap<Address, U256>,
+}
+
+impl InventoryReservations {
+    /// Reserved amount of the token, reservations of earlier blocks are released
+    pub fn reserved(&mut self, token: Address, block_number: BlockNumber) -> U256 {
+        self.release_before(block_number);
+        self.reserved.get(&token).copied().unwrap_or_default()
+    }
+
+    pub fn reserve(&mut self, token: Address, block_number: BlockNumber, amount: U256) {
+        self.release_before(block_number);
+        let reserved = self.reserved.entry(token).or_default();
+        *reserved = reserved.saturating_add(amount);
+    }
+
+    fn release_before(&mut self, block_number: BlockNumber) {
+        if block_number > self.block_number {
+            self.block_number = block_number;
+            self.reserved.clear();
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct InventoryConfig {
+    #[serde(default)]
+    pub mode: ExecutionMode,
+    /// Tokens swaps can be funded with
+    #[serde(default)]
+    pub tokens: HashMap<Address, InventoryTokenConfig>,
+}
+
+/// Neither part of the swap line can flash swap, the encoder falls back to a Balancer flash loan
+fn needs_flash_loan(swap_line: &SwapLine, multicaller: Address) -> bool {
+    match swap_line.to_swap_steps(multicaller) {
+        Some((swap_step_0, swap_step_1)) => !swap_step_0.can_flash_swap() && !swap_step_1.can_flash_swap(),
+        None => true,
+    }
+}
+
+impl InventoryConfig {
+    /// Inventory funding for single swap lines starting with an inventory token, `balance` is the multicaller balance of this token
+    /// and `reserved` the part of it taken by swaps in flight
+    pub fn funding(&self, swap: &Swap, multicaller: Address, balance: U256, reserved: U256) -> SwapFunding {
+        if self.mode == ExecutionMode::Flash {
+            return SwapFunding::Flash;
+        }
+        let Swap::BackrunSwapLine(swap_line) = swap else {
+            return SwapFunding::Flash;
+        };
+        let (Some(token), SwapAmountType::Set(amount_in)) = (swap_line.get_first_token(), &swap_line.amount_in) else {
+            return SwapFunding::Flash;
+        };
+        let Some(token_config) = self.tokens.get(&token.get_address()) else {
+            return SwapFunding::Flash;
+        };
+
+        let unreserved = balance.saturating_sub(reserved);
+        let available = match token_config.max_amount_in(token.get_decimals()) {
+            Some(max_amount_in) => max_amount_in.min(unreserved),
+            None => unreserved,
+        };
+        if *amount_in > available {
+            return SwapFunding::Flash;
+        }
+        if self.mode == ExecutionMode::InventoryFallback && !needs_flash_loan(swap_line, multicaller) {
+            return SwapFunding::Flash;
+        }
+        SwapFunding::Inventory { balance }
+    }
+
+    /// Funding of a swap for the block, inventory funded swaps reserve their amount in until the next block
+    pub fn reserve_funding(
+        &self,
+        swap: &Swap,
+        multicaller: Address,
+        balance: U256,
+        block_number: BlockNumber,
+        reservations: &mut InventoryReservations,
+    ) -> SwapFunding {
+        let Some(token) = swap.get_first_token().map(|token| token.get_address()) else {
+            return SwapFunding::Flash;
+        };
+        let funding = self.funding(swap, multicaller, balance, reservations.reserved(token, block_number));
+        if let (SwapFunding::Inventory { .. }, Swap::BackrunSwapLine(SwapLine { amount_in: SwapAmountType::Set(amount_in), .. })) =
+            (funding, swap)
+        {
+            reservations.reserve(token, block_number, *amount_in);
+        }
+        funding
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::mock_pool::MockPool;
+    use crate::{SwapPath, Token};
+    use loom_defi_address_book::{TokenAddressEth, UniswapV2PoolAddress};
+    use loom_evm_utils::NWETH;
+    use std::sync::Arc;
+
+    fn weth_swap(amount_in: f64, flash_swap: bool) -> Swap {
+        let weth = Arc::new(Token::new_with_data(TokenAddressEth::WETH, Some("WETH".to_string()), None, Some(18), true, false));
+        let usdt = Arc::new(Token::new_with_data(TokenAddressEth::USDT, Some("USDT".to_string()), None, Some(6), true, false));
+        let pool = MockPool::new(TokenAddressEth::WETH, TokenAddressEth::USDT, UniswapV2PoolAddress::WETH_USDT).with_flash_swap(flash_swap);
+        let path = SwapPath::new(vec![weth.clone(), usdt.clone(), usdt, weth], vec![pool.clone(), pool]);
+        Swap::BackrunSwapLine(SwapLine { path, amount_in: SwapAmountType::Set(NWETH::from_float(amount_in)), ..SwapLine::default() })
+    }
+
+    #[test]
+    fn test_inventory_funding() {
+        let weth_config = InventoryTokenConfig { max_amount_in: Some(5.0), min_balance: Some(1.0), max_balance: Some(20.0) };
+        let mut config =
+            InventoryConfig { mode: ExecutionMode::Inventory, tokens: HashMap::from([(TokenAddressEth::WETH, weth_config.clone())]) };
+        let balance = NWETH::from_float(10.0);
+
+        assert_eq!(config.funding(&weth_swap(2.0, true), Address::ZERO, balance, U256::ZERO), SwapFunding::Inventory { balance });
+        // over the per swap limit
+        assert_eq!(config.funding(&weth_swap(6.0, true), Address::ZERO, balance, U256::ZERO), SwapFunding::Flash);
+        // over the balance
+        assert_eq!(config.funding(&weth_swap(2.0, true), Address::ZERO, NWETH::from_float(1.0), U256::ZERO), SwapFunding::Flash);
+
+        config.mode = ExecutionMode::Flash;
+        assert_eq!(config.funding(&weth_swap(2.0, true), Address::ZERO, balance, U256::ZERO), SwapFunding::Flash);
+
+        assert_eq!(weth_config.level(NWETH::from_float(0.5), 18), InventoryLevel::Low);
+        assert_eq!(weth_config.level(balance, 18), InventoryLevel::Normal);
+        assert_eq!(weth_config.level(NWETH::from_float(25.0), 18), InventoryLevel::High);
+    }
+
+    #[test]
+    fn test_inventory_fallback_funding() {
+        let config = InventoryConfig {
+            mode: ExecutionMode::InventoryFallback,
+            tokens: HashMap::from([(TokenAddressEth::WETH, InventoryTokenConfig::default())]),
+        };
+        let balance = NWETH::from_float(10.0);
+
+        // the pools can flash swap, no flash loan to replace
+        assert_eq!(config.funding(&weth_swap(2.0, true), Address::ZERO, balance, U256::ZERO), SwapFunding::Flash);
+        assert_eq!(config.funding(&weth_swap(2.0, false), Address::ZERO, balance, U256::ZERO), SwapFunding::Inventory { balance });
+        // over the balance the flash loan is kept
+        assert_eq!(config.funding(&weth_swap(20.0, false), Address::ZERO, balance, U256::ZERO), SwapFunding::Flash);
+    }
+
+    #[test]
+    fn test_inventory_reservations() {
+        let config = InventoryConfig {
+            mode: ExecutionMode::Inventory,
+            tokens: HashMap::from([(TokenAddressEth::WETH, InventoryTokenConfig::default())]),
+        };
+        let balance = NWETH::from_float(5.0);
+        let mut reservations = InventoryReservations::default();
+
+        let funding = config.reserve_funding(&weth_swap(3.0, true), Address::ZERO, balance, 100, &mut reservations);
+        assert_eq!(funding, SwapFunding::Inventory { balance });
+        // the first swap of the block is still in flight
+        assert_eq!(config.reserve_funding(&weth_swap(3.0, true), Address::ZERO, balance, 100, &mut reservations), SwapFunding::Flash);
+        assert_eq!(reservations.reserved(TokenAddressEth::WETH, 100), NWETH::from_float(3.0));
+        // released with the next block
+        assert_eq!(config.reserve_funding(&weth_swap(3.0, true), Address::ZERO, balance, 101, &mut reservations), funding);
+    }
+}
@@ -5,7 +5,10 @@ extern crate core;
 pub use account_nonce_balance::{AccountNonceAndBalanceState, AccountNonceAndBalances};
 pub use block_history::{BlockHistory, BlockHistoryEntry, BlockHistoryManager, BlockHistoryState};
 pub use calculation_result::CalculationResult;
+pub use control_flags::{ControlFlags, STRATEGY_ORIGINS};
 pub use datafetcher::{DataFetcher, FetchState};
+pub use head_health::{HeadHealth, HeadStatus};
+pub use inventory::{ExecutionMode, InventoryConfig, InventoryLevel, InventoryReservations, InventoryTokenConfig, SwapFunding};
 pub use keystore::KeyStore;
 pub use latest_block::LatestBlock;
 pub use market::Market;
@@ -23,9 +26,11 @@ pub use swap_line::{SwapAmountType, SwapLine};
 pub use swap_path::{SwapPath, SwapPaths};
 pub use swap_path_builder::build_swap_path_vec;
 pub use swap_step::SwapStep;
-pub use token::{Token, TokenWrapper};
+pub use token::{Token, TokenBehaviour, TokenWrapper, TRANSFER_FEE_DENOMINATOR};
 
 mod block_history;
+mod head_health;
+mod inventory;
 mod latest_block;
 mod market;
 mod market_state;
@@ -35,6 +40,7 @@ mod swap_path;
 mod token;
 
 pub mod account_nonce_balance;
+pub mod bidding;
 pub mod required_state;
 mod swap_path_builder;
 mod swap_step;
@@ -46,6 +52,8 @@ mod keystore;
 pub mod private;
 
 mod calculation_result;
+mod control_flags;
+pub mod config_source;
 mod datafetcher;
 mod mock_pool;
 pub mod strategy_config;
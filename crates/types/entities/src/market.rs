@@ -36,6 +36,8 @@ pub struct Market<LDT: LoomDataTypes = LoomDataTypesEthereum> {
     token_pools: HashMap<LDT::Address, Vec<PoolId<LDT>>>,
     // swap_paths
     swap_paths: SwapPaths<LDT>,
+    // wrapped native token of the chain if it differs from LDT::WETH
+    weth: Option<LDT::Address>,
 }
 
 impl<LDT: LoomDataTypes> Display for Market<LDT> {
@@ -66,15 +68,51 @@ impl<LDT: LoomDataTypes> Display for Market<LDT> {
 impl<LDT: LoomDataTypes> Market<LDT> {
     #[inline]
     pub fn is_weth(&self, &address: &LDT::Address) -> bool {
-        address.eq(&LDT::WETH)
+        address.eq(&self.weth())
     }
+
+    /// Wrapped native token address of the chain.
+    #[inline]
+    pub fn weth(&self) -> LDT::Address {
+        self.weth.unwrap_or(LDT::WETH)
+    }
+
+    /// Set wrapped native token address for chains where it differs from the mainnet WETH.
+    pub fn set_weth(&mut self, weth: LDT::Address) {
+        self.weth = Some(weth);
+        if let Some(token) = self.tokens.get(&weth).cloned() {
+            self.update_token(token);
+        }
+    }
+
+    /// Marks the wrapped native token of the chain, tokens are only known as WETH by the `LDT::WETH` address otherwise
+    fn mark_weth(&self, mut token: Arc<Token<LDT>>) -> Arc<Token<LDT>> {
+        if self.is_weth(&token.get_address()) && !token.is_weth() {
+            Arc::make_mut(&mut token).set_weth();
+        }
+        token
+    }
+
     /// Add a [`Token`] reference to the market.
     pub fn add_token<T: Into<Arc<Token<LDT>>>>(&mut self, token: T) {
-        let arc_token: Arc<Token<LDT>> = token.into();
+        let arc_token = self.mark_weth(token.into());
         self.token_symbols.insert(arc_token.get_symbol(), arc_token.get_address());
         self.tokens.insert(arc_token.get_address(), arc_token);
     }
 
+    /// Replace a [`Token`] in the market and in all swap paths, the token is added if it does not exist.
+    pub fn update_token<T: Into<Arc<Token<LDT>>>>(&mut self, token: T) {
+        let arc_token = self.mark_weth(token.into());
+        if let Some(old_token) = self.tokens.get(&arc_token.get_address()) {
+            let old_symbol = old_token.get_symbol();
+            if self.token_symbols.get(&old_symbol) == Some(&arc_token.get_address()) {
+                self.token_symbols.remove(&old_symbol);
+            }
+        }
+        self.swap_paths.replace_token(&arc_token);
+        self.add_token(arc_token);
+    }
+
     /// Check if the token is a basic token.
     #[inline]
     pub fn is_basic_token(&self, address: &LDT::Address) -> bool {
@@ -287,7 +325,7 @@ mod tests {
     use crate::mock_pool::MockPool;
     use alloy_primitives::Address;
     use eyre::Result;
-    use loom_defi_address_book::TokenAddressEth;
+    use loom_defi_address_book::{TokenAddressArbitrum, TokenAddressEth};
 
     #[test]
     fn test_add_pool() {
@@ -295,7 +333,7 @@ mod tests {
         let pool_address = Address::random();
         let token0 = Address::random();
         let token1 = Address::random();
-        let mock_pool = MockPool { address: pool_address, token0, token1 };
+        let mock_pool = MockPool::new(token0, token1, pool_address);
 
         let result = market.add_pool(mock_pool);
 
@@ -321,6 +359,22 @@ mod tests {
         assert_eq!(market.get_token(&token_address).unwrap().get_address(), token_address);
     }
 
+    #[test]
+    fn test_update_token() {
+        let mut market = Market::<LoomDataTypesEthereum>::default();
+        let token_address = Address::random();
+        market.add_token(Token::new(token_address));
+
+        let token = market.get_token(&token_address).unwrap();
+        let updated_token = token.with_metadata(Some("TST".to_string()), Some("Test".to_string()), Some(6));
+
+        market.update_token(updated_token);
+
+        assert_eq!(market.get_token(&token_address).unwrap().get_decimals(), 6);
+        assert_eq!(market.get_token_by_symbol(&"TST".to_string()).unwrap().get_address(), token_address);
+        assert!(market.get_token_by_symbol(&token_address.to_string()).is_none());
+    }
+
     #[test]
     fn test_get_token_default() {
         let market = Market::<LoomDataTypesEthereum>::default();
@@ -335,7 +389,7 @@ mod tests {
     fn test_get_pool() {
         let mut market = Market::default();
         let pool_address = Address::random();
-        let mock_pool = MockPool { address: pool_address, token0: Address::ZERO, token1: Address::ZERO };
+        let mock_pool = MockPool::new(Address::ZERO, Address::ZERO, pool_address);
         market.add_pool(mock_pool.clone());
 
         let pool = market.get_pool(&PoolId::Address(pool_address));
@@ -347,7 +401,7 @@ mod tests {
     fn test_is_pool() {
         let mut market = Market::default();
         let pool_address = Address::random();
-        let mock_pool = MockPool { address: pool_address, token0: Address::ZERO, token1: Address::ZERO };
+        let mock_pool = MockPool::new(Address::ZERO, Address::ZERO, pool_address);
         market.add_pool(mock_pool.clone());
 
         let is_pool = market.is_pool(&PoolId::Address(pool_address));
@@ -371,7 +425,7 @@ mod tests {
         let pool_address = Address::random();
         let token0 = Address::random();
         let token1 = Address::random();
-        let mock_pool = MockPool { address: pool_address, token0, token1 };
+        let mock_pool = MockPool::new(token0, token1, pool_address);
         market.add_pool(mock_pool.clone());
 
         assert!(!market.is_pool_disabled(&PoolId::Address(pool_address)));
@@ -394,7 +448,7 @@ mod tests {
         let pool_address = Address::random();
         let token0 = Address::random();
         let token1 = Address::random();
-        let mock_pool = MockPool { address: pool_address, token0, token1 };
+        let mock_pool = MockPool::new(token0, token1, pool_address);
         market.add_pool(mock_pool);
 
         let pools = market.get_token_token_pools(&token0, &token1);
@@ -408,7 +462,7 @@ mod tests {
         let pool_address = Address::random();
         let token0 = Address::random();
         let token1 = Address::random();
-        let mock_pool = MockPool { address: pool_address, token0, token1 };
+        let mock_pool = MockPool::new(token0, token1, pool_address);
         market.add_pool(mock_pool);
 
         let tokens = market.get_token_tokens(&token0);
@@ -422,7 +476,7 @@ mod tests {
         let pool_address = Address::random();
         let token0 = Address::random();
         let token1 = Address::random();
-        let mock_pool = MockPool { address: pool_address, token0, token1 };
+        let mock_pool = MockPool::new(token0, token1, pool_address);
         market.add_pool(mock_pool);
 
         let pools = market.get_token_pools(&token0).cloned();
@@ -441,12 +495,12 @@ mod tests {
         // Swap pool: token weth -> token1
         let pool_address1 = Address::random();
         let token1 = Address::random();
-        let mock_pool1 = PoolWrapper::new(Arc::new(MockPool { address: pool_address1, token0: TokenAddressEth::WETH, token1 }));
+        let mock_pool1 = PoolWrapper::new(Arc::new(MockPool::new(TokenAddressEth::WETH, token1, pool_address1)));
         market.add_pool(mock_pool1.clone());
 
         // Swap pool: token weth -> token1
         let pool_address2 = Address::random();
-        let mock_pool2 = PoolWrapper::new(Arc::new(MockPool { address: pool_address2, token0: TokenAddressEth::WETH, token1 }));
+        let mock_pool2 = PoolWrapper::new(Arc::new(MockPool::new(TokenAddressEth::WETH, token1, pool_address2)));
         market.add_pool(mock_pool2.clone());
 
         // Add test swap paths
@@ -505,17 +559,17 @@ mod tests {
 
         // Swap pool: weth -> token1
         let pool_address1 = Address::random();
-        let mock_pool = PoolWrapper::new(Arc::new(MockPool { address: pool_address1, token0: token1, token1: TokenAddressEth::WETH }));
+        let mock_pool = PoolWrapper::new(Arc::new(MockPool::new(token1, TokenAddressEth::WETH, pool_address1)));
         market.add_pool(mock_pool);
 
         // Swap pool: token1 -> token2
         let pool_address2 = Address::random();
-        let mock_pool2 = PoolWrapper::new(Arc::new(MockPool { address: pool_address2, token0: token1, token1: token2 }));
+        let mock_pool2 = PoolWrapper::new(Arc::new(MockPool::new(token1, token2, pool_address2)));
         market.add_pool(mock_pool2);
 
         // Swap pool: token2 -> weth
         let pool_address3 = Address::random();
-        let mock_pool3 = PoolWrapper::new(Arc::new(MockPool { address: pool_address3, token0: token2, token1: TokenAddressEth::WETH }));
+        let mock_pool3 = PoolWrapper::new(Arc::new(MockPool::new(token2, TokenAddressEth::WETH, pool_address3)));
         market.add_pool(mock_pool3.clone());
 
         // under test
@@ -563,4 +617,18 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_chain_weth_is_marked() {
+        let mut market = Market::default();
+        market.add_token(Token::new(TokenAddressArbitrum::WETH));
+        assert!(!market.get_token(&TokenAddressArbitrum::WETH).unwrap().is_weth());
+
+        market.set_weth(TokenAddressArbitrum::WETH);
+        assert!(market.get_token(&TokenAddressArbitrum::WETH).unwrap().is_weth());
+
+        market.update_token(Token::new(TokenAddressArbitrum::WETH));
+        assert!(market.get_token(&TokenAddressArbitrum::WETH).unwrap().is_weth());
+        assert!(!market.get_token_or_default(&TokenAddressEth::USDT).is_weth());
+    }
 }
@@ -13,11 +13,21 @@ pub struct MockPool {
     pub(crate) token0: Address,
     pub(crate) token1: Address,
     pub(crate) address: Address,
+    pub(crate) flash_swap: bool,
+    pub(crate) preswap_requirement: PreswapRequirement,
 }
 
 impl MockPool {
     pub fn new(token0: Address, token1: Address, address: Address) -> Self {
-        Self { token0, token1, address }
+        Self { token0, token1, address, flash_swap: true, preswap_requirement: PreswapRequirement::Base }
+    }
+
+    pub fn with_flash_swap(self, flash_swap: bool) -> Self {
+        Self { flash_swap, ..self }
+    }
+
+    pub fn with_preswap_requirement(self, preswap_requirement: PreswapRequirement) -> Self {
+        Self { preswap_requirement, ..self }
     }
 }
 
@@ -77,7 +87,7 @@ impl Pool for MockPool {
     }
 
     fn can_flash_swap(&self) -> bool {
-        panic!("Not implemented")
+        self.flash_swap
     }
 
     fn can_calculate_in_amount(&self) -> bool {
@@ -101,6 +111,6 @@ impl Pool for MockPool {
     }
 
     fn preswap_requirement(&self) -> PreswapRequirement {
-        PreswapRequirement::Base
+        self.preswap_requirement.clone()
     }
 }
@@ -10,39 +10,42 @@ use crate::swap_direction::SwapDirection;
 use crate::PoolId;
 use alloy_primitives::{Address, Bytes, U256};
 use eyre::{eyre, ErrReport, Result};
-use loom_defi_address_book::FactoryAddress;
-use loom_types_blockchain::{LoomDataTypes, LoomDataTypesEthereum};
+use loom_types_blockchain::{ChainParameters, LoomDataTypes, LoomDataTypesEthereum};
 use revm::primitives::Env;
 use revm::DatabaseRef;
 use serde::{Deserialize, Serialize};
 use strum_macros::{Display, EnumIter, EnumString, VariantNames};
 
-pub fn get_protocol_by_factory(factory_address: Address) -> PoolProtocol {
-    if factory_address == FactoryAddress::UNISWAP_V2 {
+/// Returns the protocol of the factory using factory addresses of the chain.
+pub fn get_protocol_by_factory(chain_parameters: &ChainParameters, factory_address: Address) -> PoolProtocol {
+    let factories = &chain_parameters.factories;
+    let is_factory = |address: Option<Address>| address == Some(factory_address);
+
+    if is_factory(factories.uniswap_v2) {
         PoolProtocol::UniswapV2
-    } else if factory_address == FactoryAddress::UNISWAP_V3 {
+    } else if is_factory(factories.uniswap_v3) {
         PoolProtocol::UniswapV3
-    } else if factory_address == FactoryAddress::PANCAKE_V3 {
+    } else if is_factory(factories.pancake_v3) {
         PoolProtocol::PancakeV3
-    } else if factory_address == FactoryAddress::NOMISWAP {
+    } else if is_factory(factories.nomiswap) {
         PoolProtocol::NomiswapStable
-    } else if factory_address == FactoryAddress::ANTFARM {
+    } else if is_factory(factories.antfarm) {
         PoolProtocol::AntFarm
-    } else if factory_address == FactoryAddress::SUSHISWAP_V2 {
+    } else if is_factory(factories.sushiswap_v2) {
         PoolProtocol::Sushiswap
-    } else if factory_address == FactoryAddress::SUSHISWAP_V3 {
+    } else if is_factory(factories.sushiswap_v3) {
         PoolProtocol::SushiswapV3
-    } else if factory_address == FactoryAddress::DOOARSWAP {
+    } else if is_factory(factories.dooarswap) {
         PoolProtocol::DooarSwap
-    } else if factory_address == FactoryAddress::SAFESWAP {
+    } else if is_factory(factories.safeswap) {
         PoolProtocol::Safeswap
-    } else if factory_address == FactoryAddress::MINISWAP {
+    } else if is_factory(factories.miniswap) {
         PoolProtocol::Miniswap
-    } else if factory_address == FactoryAddress::SHIBASWAP {
+    } else if is_factory(factories.shibaswap) {
         PoolProtocol::Shibaswap
-    } else if factory_address == FactoryAddress::MAVERICK {
+    } else if is_factory(factories.maverick) {
         PoolProtocol::Maverick
-    } else if factory_address == FactoryAddress::INTEGRAL {
+    } else if is_factory(factories.integral) {
         PoolProtocol::Integral
     } else {
         PoolProtocol::Unknown
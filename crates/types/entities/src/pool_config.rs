@@ -1,4 +1,5 @@
 use crate::PoolClass;
+use loom_types_blockchain::ChainParameters;
 use std::collections::HashMap;
 use strum::IntoEnumIterator;
 
@@ -6,6 +7,7 @@ use strum::IntoEnumIterator;
 pub struct PoolsLoadingConfig {
     threads: Option<usize>,
     is_enabled: HashMap<PoolClass, bool>,
+    chain_parameters: ChainParameters,
 }
 
 impl PoolsLoadingConfig {
@@ -15,7 +17,7 @@ impl PoolsLoadingConfig {
             is_enabled.insert(pool_class, true);
         }
 
-        Self { threads: None, is_enabled }
+        Self { threads: None, is_enabled, chain_parameters: ChainParameters::ethereum() }
     }
 
     pub fn disable_all(self) -> Self {
@@ -52,6 +54,14 @@ impl PoolsLoadingConfig {
     pub fn threads(&self) -> Option<usize> {
         self.threads
     }
+
+    pub fn with_chain_parameters(self, chain_parameters: ChainParameters) -> Self {
+        Self { chain_parameters, ..self }
+    }
+
+    pub fn chain_parameters(&self) -> ChainParameters {
+        self.chain_parameters.clone()
+    }
 }
 
 impl Default for PoolsLoadingConfig {
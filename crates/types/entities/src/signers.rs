@@ -1,6 +1,6 @@
-use alloy_consensus::{SignableTransaction, TxEnvelope};
+use alloy_consensus::{SignableTransaction, Signed, TxEnvelope, TypedTransaction};
 use alloy_network::{TransactionBuilder, TxSigner as AlloyTxSigner, TxSignerSync};
-use alloy_primitives::{hex, Address, Bytes, B256};
+use alloy_primitives::{hex, Address, Bytes, PrimitiveSignature as Signature, B256};
 use alloy_rpc_types::Transaction;
 use alloy_signer_local::PrivateKeySigner;
 use eyre::{eyre, OptionExt, Result};
@@ -47,24 +47,15 @@ impl LoomTxSigner<LoomDataTypesEthereum> for TxSignerEth {
         tx_req: <LoomDataTypesEthereum as LoomDataTypes>::TransactionRequest,
     ) -> Pin<Box<dyn Future<Output = Result<<LoomDataTypesEthereum as LoomDataTypes>::Transaction>> + Send + 'a>> {
         let fut = async move {
-            let mut typed_tx = tx_req
-                .build_typed_tx()
-                .map_err(|e| eyre!("TRANSACTION_TYPE_IS_MISSING"))?
-                .eip1559()
-                .ok_or_eyre("TRANSACTION_IS_NOT_EIP1559")?
-                .clone();
-            let signature = self.wallet.sign_transaction(&mut typed_tx).await?;
-            let signed_tx = typed_tx.clone().into_signed(signature);
-            let tx_env: TxEnvelope = signed_tx.into();
-            let tx = Transaction {
-                inner: tx_env,
-                block_hash: None,
-                block_number: None,
-                transaction_index: None,
-                effective_gas_price: None,
-                from: self.address(),
+            let typed_tx = tx_req.build_typed_tx().map_err(|e| eyre!("TRANSACTION_TYPE_IS_MISSING"))?;
+            let tx_env = match typed_tx {
+                TypedTransaction::Legacy(tx) => self.sign_typed(tx).await?,
+                TypedTransaction::Eip2930(tx) => self.sign_typed(tx).await?,
+                TypedTransaction::Eip1559(tx) => self.sign_typed(tx).await?,
+                TypedTransaction::Eip4844(tx) => self.sign_typed(tx).await?,
+                TypedTransaction::Eip7702(tx) => self.sign_typed(tx).await?,
             };
-            eyre::Result::<Transaction>::Ok(tx)
+            eyre::Result::<Transaction>::Ok(self.to_transaction(tx_env))
         };
         Box::pin(fut)
 
@@ -78,27 +69,15 @@ impl LoomTxSigner<LoomDataTypesEthereum> for TxSignerEth {
         &self,
         tx_req: <LoomDataTypesEthereum as LoomDataTypes>::TransactionRequest,
     ) -> Result<<LoomDataTypesEthereum as LoomDataTypes>::Transaction> {
-        let mut typed_tx = tx_req
-            .build_unsigned()
-            .map_err(|e| eyre!(format!("CANNOT_BUILD_UNSIGNED with error: {}", e)))?
-            .eip1559()
-            .ok_or_eyre("TRANSACTION_IS_NOT_EIP1559")?
-            .clone();
-
-        let signature = self.wallet.sign_transaction_sync(&mut typed_tx)?;
-        let signed_tx = typed_tx.clone().into_signed(signature);
-
-        let hash = signed_tx.signature_hash();
-        let tx_env: TxEnvelope = signed_tx.into();
-        let tx = Transaction {
-            inner: tx_env,
-            block_hash: None,
-            block_number: None,
-            transaction_index: None,
-            effective_gas_price: None,
-            from: self.address(),
+        let typed_tx = tx_req.build_unsigned().map_err(|e| eyre!(format!("CANNOT_BUILD_UNSIGNED with error: {}", e)))?;
+        let tx_env = match typed_tx {
+            TypedTransaction::Legacy(tx) => self.sign_typed_sync(tx)?,
+            TypedTransaction::Eip2930(tx) => self.sign_typed_sync(tx)?,
+            TypedTransaction::Eip1559(tx) => self.sign_typed_sync(tx)?,
+            TypedTransaction::Eip4844(tx) => self.sign_typed_sync(tx)?,
+            TypedTransaction::Eip7702(tx) => self.sign_typed_sync(tx)?,
         };
-        Ok(tx)
+        Ok(self.to_transaction(tx_env))
     }
 }
 
@@ -106,6 +85,35 @@ impl TxSignerEth {
     pub fn new(wallet: PrivateKeySigner) -> TxSignerEth {
         TxSignerEth { address: wallet.address(), wallet }
     }
+
+    async fn sign_typed<T>(&self, mut tx: T) -> Result<TxEnvelope>
+    where
+        T: SignableTransaction<Signature> + Send,
+        Signed<T>: Into<TxEnvelope>,
+    {
+        let signature = self.wallet.sign_transaction(&mut tx).await?;
+        Ok(tx.into_signed(signature).into())
+    }
+
+    fn sign_typed_sync<T>(&self, mut tx: T) -> Result<TxEnvelope>
+    where
+        T: SignableTransaction<Signature>,
+        Signed<T>: Into<TxEnvelope>,
+    {
+        let signature = self.wallet.sign_transaction_sync(&mut tx)?;
+        Ok(tx.into_signed(signature).into())
+    }
+
+    fn to_transaction(&self, tx_env: TxEnvelope) -> Transaction {
+        Transaction {
+            inner: tx_env,
+            block_hash: None,
+            block_number: None,
+            transaction_index: None,
+            effective_gas_price: None,
+            from: self.address,
+        }
+    }
 }
 
 #[derive(Clone, Default)]
@@ -224,6 +232,17 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_sign_legacy() -> Result<()> {
+        let wallet = PrivateKeySigner::from_bytes(&B256::repeat_byte(1))?;
+        let signer = TxSignerEth::new(wallet);
+        let tx_req = TransactionRequest::default().with_to(Address::ZERO).with_nonce(1).with_gas_limit(21000).with_gas_price(1);
+        let tx = signer.sign_sync(tx_req)?;
+        assert!(matches!(tx.inner, TxEnvelope::Legacy(_)));
+        assert_eq!(tx.inner.recover_signer()?, signer.address());
+        Ok(())
+    }
+
     // TxSigners tests
 
     #[test]
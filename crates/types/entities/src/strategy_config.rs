@@ -4,12 +4,16 @@ use std::path::PathBuf;
 use thiserror::Error;
 use tokio::fs;
 
+use crate::config_source::{config_layers, parse_toml_layers};
+
 #[derive(Debug, Error)]
 pub enum LoadConfigError {
     #[error("IO error: {0}")]
     IoError(#[from] std::io::Error),
     #[error("TOML error: {0}")]
     TomlError(#[from] toml::de::Error),
+    #[error("Interpolation error at {path}: {message}")]
+    Interpolation { path: String, message: String },
 }
 
 pub trait StrategyConfig {
@@ -17,8 +21,13 @@ pub trait StrategyConfig {
     fn eoa(&self) -> Option<Address>;
 }
 
+/// Loads the config with the environment override and interpolation, see [`crate::config_source`]
 pub async fn load_from_file<C: DeserializeOwned>(file_path: PathBuf) -> Result<C, LoadConfigError> {
-    let contents = fs::read_to_string(file_path).await?;
-    let config: C = toml::from_str(&contents)?;
+    let mut contents = Vec::new();
+    for file in config_layers(&file_path) {
+        contents.push(fs::read_to_string(file).await?);
+    }
+    let value = parse_toml_layers(&contents)?;
+    let config: C = value.try_into()?;
     Ok(config)
 }
@@ -106,6 +106,20 @@ impl<LDT: LoomDataTypes> Swap<LDT> {
         }
     }
 
+    pub fn get_token_address_vec(&self) -> Vec<LDT::Address> {
+        match self {
+            Swap::ExchangeSwapLine(swap_line) => swap_line.tokens().iter().map(|item| item.get_address()).collect(),
+            Swap::BackrunSwapLine(swap_line) => swap_line.tokens().iter().map(|item| item.get_address()).collect(),
+            Swap::BackrunSwapSteps((sp0, sp1)) => {
+                let mut swap_line_vec = sp0.swap_line_vec().clone();
+                swap_line_vec.extend(sp1.swap_line_vec().clone());
+                swap_line_vec.iter().flat_map(|item| item.tokens().iter().map(|t| t.get_address()).collect::<Vec<_>>()).collect()
+            }
+            Swap::Multiple(swap_vec) => swap_vec.iter().flat_map(|x| x.get_token_address_vec()).collect(),
+            Swap::None => Vec::new(),
+        }
+    }
+
     pub fn get_pools_vec(&self) -> Vec<PoolWrapper<LDT>> {
         match self {
             Swap::ExchangeSwapLine(swap_line) => swap_line.pools().clone(),
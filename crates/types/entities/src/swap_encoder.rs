@@ -1,5 +1,5 @@
 use crate::tips::Tips;
-use crate::Swap;
+use crate::{Swap, SwapFunding};
 use alloy_primitives::{Address, BlockNumber, Bytes, U256};
 use eyre::Result;
 use std::ops::Deref;
@@ -8,10 +8,13 @@ use std::sync::Arc;
 pub trait SwapEncoder {
     /// Encodes Swap
     ///
+    /// - tips_pct - share of the profit paid as tips, chosen by the tips bidder when not set
+    /// - max_tips_pct - upper limit of the tips share
     /// - next_block_number - number of the next block
     /// - next_block_gas_price - base_fee + priority fee for transaction
     /// - sender_address - EOA of of the transaction
     /// - sender_eth_balance - balance of EOA
+    /// - funding - flash swaps or tokens held by the contract
     ///
     /// returns (to. value, call_data) for transaction
     #[allow(clippy::too_many_arguments)]
@@ -19,10 +22,12 @@ pub trait SwapEncoder {
         &self,
         swap: Swap,
         tips_pct: Option<u32>,
+        max_tips_pct: Option<u32>,
         next_block_number: Option<BlockNumber>,
         gas_cost: Option<U256>,
         sender_address: Option<Address>,
         sender_eth_balance: Option<U256>,
+        funding: SwapFunding,
     ) -> Result<(Address, Option<U256>, Bytes, Vec<Tips>)>
     where
         Self: Sized;
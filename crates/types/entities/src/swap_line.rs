@@ -57,7 +57,7 @@ pub struct SwapLine<LDT: LoomDataTypes = LoomDataTypesEthereum> {
     pub amount_in: SwapAmountType<LDT>,
     /// Output token amount of the swap
     pub amount_out: SwapAmountType<LDT>,
-    /// The in and out amounts for each swap step
+    /// The in and out amounts for each swap step as seen by the pool, excluding token transfer fees
     pub calculation_results: Vec<CalculationResult>,
     /// Output token of the swap
     pub swap_to: Option<LDT::Address>,
@@ -330,8 +330,11 @@ impl<LDT: LoomDataTypes> SwapLine<LDT> {
         for (i, pool) in self.pools().iter().enumerate() {
             let token_from = &self.tokens()[i];
             let token_to = &self.tokens()[i + 1];
-            match pool.calculate_out_amount(state, env.clone(), &token_from.get_address(), &token_to.get_address(), current_in_amount) {
-                Ok((out_amount_result, gas_result)) => {
+            // fee-on-transfer tokens: the pool only receives the amount after the transfer fee
+            let pool_in_amount = token_from.calc_transfer_in_amount(current_in_amount);
+            match pool.calculate_out_amount(state, env.clone(), &token_from.get_address(), &token_to.get_address(), pool_in_amount) {
+                Ok((pool_out_amount, gas_result)) => {
+                    let out_amount_result = token_to.calc_transfer_out_amount(pool_out_amount);
                     if out_amount_result.is_zero() {
                         return Err(SwapError::<LDT> {
                             msg: "ZERO_OUT_AMOUNT".to_string(),
@@ -353,7 +356,7 @@ impl<LDT: LoomDataTypes> SwapLine<LDT> {
                         });
                     }
 
-                    calculation_results.push(CalculationResult::new(current_in_amount, out_amount_result));
+                    calculation_results.push(CalculationResult::new(pool_in_amount, pool_out_amount));
                     current_in_amount = out_amount_result;
                     final_out_amount = out_amount_result;
                     gas_used += gas_result
@@ -395,8 +398,11 @@ impl<LDT: LoomDataTypes> SwapLine<LDT> {
         for (i, pool) in pool_reverse.iter().enumerate() {
             let token_from = &tokens_reverse[i + 1];
             let token_to = &tokens_reverse[i];
-            match pool.calculate_in_amount(state, env.clone(), &token_from.get_address(), &token_to.get_address(), current_out_amount) {
-                Ok((in_amount_result, gas_result)) => {
+            // fee-on-transfer tokens: the pool has to send more to deliver the requested amount
+            let pool_out_amount = token_to.calc_transfer_out_gross_amount(current_out_amount);
+            match pool.calculate_in_amount(state, env.clone(), &token_from.get_address(), &token_to.get_address(), pool_out_amount) {
+                Ok((pool_in_amount, gas_result)) => {
+                    let in_amount_result = token_from.calc_transfer_in_gross_amount(pool_in_amount);
                     if in_amount_result == U256::MAX || in_amount_result == U256::ZERO {
                         return Err(SwapError::<LDT> {
                             msg: "ZERO_AMOUNT".to_string(),
@@ -407,7 +413,7 @@ impl<LDT: LoomDataTypes> SwapLine<LDT> {
                             amount: current_out_amount,
                         });
                     }
-                    calculation_results.push(CalculationResult::new(current_out_amount, in_amount_result));
+                    calculation_results.push(CalculationResult::new(pool_in_amount, pool_out_amount));
                     current_out_amount = in_amount_result;
                     final_in_amount = in_amount_result;
                     gas_used += gas_result;
@@ -426,6 +432,8 @@ impl<LDT: LoomDataTypes> SwapLine<LDT> {
                 }
             }
         }
+        // results are stored in path order like for the in amount calculation
+        calculation_results.reverse();
         Ok((final_in_amount, gas_used, calculation_results))
     }
 
@@ -542,10 +550,9 @@ mod tests {
     fn default_swap_line() -> (MockPool, MockPool, SwapLine<LoomDataTypesEthereum>) {
         let token0 = Arc::new(Token::new_with_data(TokenAddressEth::WETH, Some("WETH".to_string()), None, Some(18), true, false));
         let token1 = Arc::new(Token::new_with_data(TokenAddressEth::USDT, Some("USDT".to_string()), None, Some(6), true, false));
-        let pool1 =
-            MockPool { token0: TokenAddressEth::WETH, token1: TokenAddressEth::USDT, address: UniswapV3PoolAddress::WETH_USDT_3000 };
+        let pool1 = MockPool::new(TokenAddressEth::WETH, TokenAddressEth::USDT, UniswapV3PoolAddress::WETH_USDT_3000);
         let pool2_address = Address::random();
-        let pool2 = MockPool { token0: TokenAddressEth::WETH, token1: TokenAddressEth::USDT, address: UniswapV2PoolAddress::WETH_USDT };
+        let pool2 = MockPool::new(TokenAddressEth::WETH, TokenAddressEth::USDT, UniswapV2PoolAddress::WETH_USDT);
 
         let swap_path =
             SwapPath::new(vec![token0.clone(), token1.clone(), token1.clone(), token0.clone()], vec![pool1.clone(), pool2.clone()]);
@@ -140,6 +140,7 @@ impl<LDT: LoomDataTypes> SwapPath<LDT> {
 pub struct SwapPaths<LDT: LoomDataTypes = LoomDataTypesEthereum> {
     pub paths: Vec<SwapPath<LDT>>,
     pub pool_paths: HashMap<PoolId<LDT>, Vec<usize>>,
+    pub token_paths: HashMap<LDT::Address, Vec<usize>>,
     pub path_hash_map: HashMap<u64, usize>,
     pub disabled_directions: HashMap<u64, bool>,
 }
@@ -149,6 +150,7 @@ impl<LDT: LoomDataTypes> SwapPaths<LDT> {
         SwapPaths {
             paths: Vec::new(),
             pool_paths: HashMap::default(),
+            token_paths: HashMap::default(),
             path_hash_map: HashMap::default(),
             disabled_directions: HashMap::default(),
         }
@@ -194,6 +196,13 @@ impl<LDT: LoomDataTypes> SwapPaths<LDT> {
                 for pool in &path.pools {
                     self.pool_paths.entry(pool.get_pool_id()).or_default().push(path_idx);
                 }
+                // tokens of cycles appear twice, each path is indexed once per token
+                for token in &path.tokens {
+                    let token_paths = self.token_paths.entry(token.get_address()).or_default();
+                    if token_paths.last() != Some(&path_idx) {
+                        token_paths.push(path_idx);
+                    }
+                }
 
                 self.paths.push(path);
                 Some(path_idx)
@@ -201,6 +210,22 @@ impl<LDT: LoomDataTypes> SwapPaths<LDT> {
         }
     }
 
+    /// Replace all references to a token with the same address, used to refresh token metadata
+    pub fn replace_token(&mut self, token: &Arc<Token<LDT>>) -> usize {
+        let mut replaced = 0;
+        let Some(token_paths) = self.token_paths.get(&token.get_address()) else { return 0 };
+        for path_idx in token_paths.iter() {
+            let Some(path) = self.paths.get_mut(*path_idx) else { continue };
+            for path_token in path.tokens.iter_mut() {
+                if path_token.get_address() == token.get_address() {
+                    *path_token = token.clone();
+                    replaced += 1;
+                }
+            }
+        }
+        replaced
+    }
+
     pub fn disable_path(&mut self, swap_path: &SwapPath<LDT>, disable: bool) -> bool {
         if let Some(swap_path_idx) = self.path_hash_map.get(&swap_path.get_hash()) {
             if let Some(swap_path) = self.paths.get_mut(*swap_path_idx) {
@@ -409,6 +434,30 @@ mod test {
         println!("{paths:?}")
     }
 
+    #[test]
+    fn test_replace_token() {
+        let basic_token = Token::new(Address::repeat_byte(0x11));
+
+        let paths_vec: Vec<SwapPath> = (0..3)
+            .map(|i| {
+                SwapPath::new(
+                    vec![basic_token.clone(), Token::new(Address::repeat_byte(i)), basic_token.clone()],
+                    vec![
+                        PoolWrapper::new(Arc::new(EmptyPool::new(Address::repeat_byte(i + 1)))),
+                        PoolWrapper::new(Arc::new(EmptyPool::new(Address::repeat_byte(i + 2)))),
+                    ],
+                )
+            })
+            .collect();
+        let mut paths = SwapPaths::from(paths_vec);
+        assert_eq!(paths.token_paths.get(&Address::repeat_byte(0x11)), Some(&vec![0, 1, 2]));
+
+        let token = Arc::new(Token::new(Address::repeat_byte(1)).with_metadata(Some("TKN".to_string()), None, Some(6)));
+        assert_eq!(paths.replace_token(&token), 1);
+        assert_eq!(paths.paths[1].tokens[1].get_symbol(), "TKN");
+        assert_eq!(paths.replace_token(&Arc::new(Token::new(Address::repeat_byte(0x22)))), 0);
+    }
+
     #[tokio::test]
     async fn async_test() {
         let basic_token = Token::new(Address::repeat_byte(0x11));
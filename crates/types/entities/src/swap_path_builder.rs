@@ -461,7 +461,7 @@ pub fn build_swap_path_vec<LDT: LoomDataTypes>(
             }
 
             if (!market.is_basic_token(&token_from_address) && !market.is_basic_token(&token_to_address))
-                || ((token_from_address != LDT::WETH) && (token_to_address != LDT::WETH))
+                || (!market.is_weth(&token_from_address) && !market.is_weth(&token_to_address))
             {
                 ret_map.extend(build_swap_path_three_hopes_no_basic(market, pool, token_from_address, token_to_address)?);
             }
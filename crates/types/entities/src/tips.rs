@@ -2,6 +2,7 @@ use std::collections::HashMap;
 use std::fmt::{Display, Formatter};
 use std::sync::Arc;
 
+use crate::bidding::TipsBidder;
 use crate::{Swap, Token};
 use alloy_primitives::utils::format_units;
 use alloy_primitives::{Address, U256};
@@ -56,20 +57,34 @@ pub fn tips_pct_advanced(profit: &U256) -> u32 {
     start_pct.to()
 }
 
+/// Share of the profit after gas paid as tips, the same share the bidders choose
+pub fn bid_tips_pct(tips: U256, profit_eth: U256, gas_cost: U256) -> u32 {
+    let net_profit_eth = profit_eth.saturating_sub(gas_cost);
+    if net_profit_eth.is_zero() {
+        return 0;
+    }
+    (tips * U256::from(10000) / net_profit_eth).saturating_to()
+}
+
 pub fn randomize_tips_pct(tips_pct: u32) -> u32 {
     let rnd: u32 = random::<u32>() % 50;
-    tips_pct - rnd
+    tips_pct.saturating_sub(rnd)
 }
 
+/// Tips of the swap and the value of the tx. Swaps without a tips share, like merged swaps, are bid by `bidder`.
+/// `max_tips_pct` caps the share after bidding.
 pub fn tips_and_value_for_swap_type(
     swap: &Swap,
     tips_pct: Option<u32>,
+    max_tips_pct: Option<u32>,
     gas_cost: Option<U256>,
     eth_balance: U256,
+    bidder: &dyn TipsBidder,
 ) -> Result<(Vec<Tips>, U256)> {
     let total_profit_eth = swap.abs_profit_eth();
     info!("Total profit eth : {}", format_units(total_profit_eth, "ether").unwrap_or_default());
-    let tips_pct = randomize_tips_pct(tips_pct.unwrap_or(tips_pct_advanced(&total_profit_eth)));
+    let tips_pct = tips_pct.unwrap_or_else(|| bidder.tips_pct(total_profit_eth));
+    let tips_pct = randomize_tips_pct(max_tips_pct.map_or(tips_pct, |max_tips_pct| tips_pct.min(max_tips_pct)));
 
     if let Some(gas_cost) = gas_cost {
         if total_profit_eth < gas_cost {
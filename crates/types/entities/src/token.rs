@@ -11,15 +11,52 @@ use loom_types_blockchain::{LoomDataTypes, LoomDataTypesEthereum};
 
 const ONE_ETHER: U256 = Unit::ETHER.wei_const();
 
+/// Denominator for transfer fees, fees are stored in basis points.
+pub const TRANSFER_FEE_DENOMINATOR: u32 = 10000;
+
+/// Token transfer behaviour detected by simulating transfers against a pool.
+/// `transfer_fee_in` is taken when the token is sent to a pool (sell), `transfer_fee_out` when it is sent out of a pool (buy).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TokenBehaviour {
+    pub transfer_fee_in: u32,
+    pub transfer_fee_out: u32,
+    pub rebasing: bool,
+    pub blacklistable: bool,
+}
+
+impl TokenBehaviour {
+    pub fn new(transfer_fee_in: u32, transfer_fee_out: u32, rebasing: bool, blacklistable: bool) -> Self {
+        Self {
+            transfer_fee_in: transfer_fee_in.min(TRANSFER_FEE_DENOMINATOR),
+            transfer_fee_out: transfer_fee_out.min(TRANSFER_FEE_DENOMINATOR),
+            rebasing,
+            blacklistable,
+        }
+    }
+
+    #[inline]
+    pub fn has_transfer_fee(&self) -> bool {
+        self.transfer_fee_in > 0 || self.transfer_fee_out > 0
+    }
+
+    #[inline]
+    pub fn is_standard(&self) -> bool {
+        !self.has_transfer_fee() && !self.rebasing
+    }
+}
+
 #[derive(Clone, Debug, Default)]
 pub struct Token<LDT: LoomDataTypes = LoomDataTypesEthereum> {
     address: LDT::Address,
     basic: bool,
     middle: bool,
+    // wrapped native token of the chain
+    weth: bool,
     decimals: u8,
     name: Option<String>,
     symbol: Option<String>,
     eth_price: Arc<RwLock<Option<U256>>>,
+    behaviour: Arc<RwLock<Option<TokenBehaviour>>>,
 }
 
 pub type TokenWrapper<LDT> = Arc<Token<LDT>>;
@@ -72,7 +109,33 @@ impl<LDT: LoomDataTypes> Token<LDT> {
         basic: bool,
         middle: bool,
     ) -> Token<LDT> {
-        Token { address, symbol, name, decimals: decimals.unwrap_or(18), basic, middle, eth_price: Arc::new(RwLock::new(None)) }
+        Token {
+            address,
+            symbol,
+            name,
+            decimals: decimals.unwrap_or(18),
+            basic,
+            middle,
+            weth: address == LDT::WETH,
+            eth_price: Arc::new(RwLock::new(None)),
+            behaviour: Arc::new(RwLock::new(None)),
+        }
+    }
+
+    /// Copy of the token with new metadata, price and behaviour stay shared with the original token.
+    pub fn with_metadata(&self, symbol: Option<String>, name: Option<String>, decimals: Option<u8>) -> Token<LDT> {
+        Token {
+            symbol: symbol.or(self.symbol.clone()),
+            name: name.or(self.name.clone()),
+            decimals: decimals.unwrap_or(self.decimals),
+            ..self.clone()
+        }
+    }
+
+    /// Returns true if symbol was set, tokens created from an address only have no metadata.
+    #[inline]
+    pub fn has_metadata(&self) -> bool {
+        self.symbol.is_some()
     }
 
     #[inline]
@@ -124,6 +187,12 @@ impl<LDT: LoomDataTypes> Token<LDT> {
         self
     }
 
+    /// Marks the token as the wrapped native token of the chain, the market marks the token of its chain parameters
+    pub fn set_weth(&mut self) -> &mut Self {
+        self.weth = true;
+        self
+    }
+
     pub fn to_float(&self, value: U256) -> f64 {
         if self.decimals == 0 {
             0f64
@@ -158,9 +227,10 @@ impl<LDT: LoomDataTypes> Token<LDT> {
         multiplier.mul(U256::from(10).pow(U256::from(self.decimals))).add(modulus)
     }
 
+    /// Wrapped native token of the chain, the token at `LDT::WETH` or the one marked by the market of the chain
     #[inline]
     pub fn is_weth(&self) -> bool {
-        self.address.eq(&LDT::WETH)
+        self.weth
     }
 
     pub fn get_eth_price(&self) -> Option<U256> {
@@ -188,11 +258,88 @@ impl<LDT: LoomDataTypes> Token<LDT> {
         let x = self.get_eth_price();
         x.map(|x| eth_value.mul(x).div(ONE_ETHER))
     }
+
+    /// Returns true if the behaviour of the token was already detected.
+    pub fn is_behaviour_known(&self) -> bool {
+        self.behaviour.read().is_ok_and(|x| x.is_some())
+    }
+
+    /// Returns detected behaviour of the token, standard ERC20 behaviour is assumed if unknown.
+    pub fn get_behaviour(&self) -> TokenBehaviour {
+        if self.is_weth() {
+            return TokenBehaviour::default();
+        }
+        match self.behaviour.read() {
+            Ok(x) => x.unwrap_or_default(),
+            _ => TokenBehaviour::default(),
+        }
+    }
+
+    pub fn set_behaviour(&self, behaviour: Option<TokenBehaviour>) {
+        if let Ok(mut x) = self.behaviour.write() {
+            *x = behaviour;
+        }
+    }
+
+    #[inline]
+    pub fn has_transfer_fee(&self) -> bool {
+        self.get_behaviour().has_transfer_fee()
+    }
+
+    #[inline]
+    pub fn is_rebasing(&self) -> bool {
+        self.get_behaviour().rebasing
+    }
+
+    #[inline]
+    pub fn is_blacklistable(&self) -> bool {
+        self.get_behaviour().blacklistable
+    }
+
+    fn apply_fee(amount: U256, fee: u32) -> U256 {
+        if fee == 0 {
+            amount
+        } else {
+            amount.saturating_mul(U256::from(TRANSFER_FEE_DENOMINATOR - fee)).div(U256::from(TRANSFER_FEE_DENOMINATOR))
+        }
+    }
+
+    fn reverse_fee(amount: U256, fee: u32) -> U256 {
+        if fee == 0 {
+            amount
+        } else if fee >= TRANSFER_FEE_DENOMINATOR {
+            U256::MAX
+        } else {
+            let denominator = U256::from(TRANSFER_FEE_DENOMINATOR - fee);
+            amount.saturating_mul(U256::from(TRANSFER_FEE_DENOMINATOR)).saturating_add(denominator - U256::from(1)).div(denominator)
+        }
+    }
+
+    /// Amount received by a pool when `amount` is transferred to it.
+    pub fn calc_transfer_in_amount(&self, amount: U256) -> U256 {
+        Self::apply_fee(amount, self.get_behaviour().transfer_fee_in)
+    }
+
+    /// Amount received from a pool when the pool transfers `amount` out.
+    pub fn calc_transfer_out_amount(&self, amount: U256) -> U256 {
+        Self::apply_fee(amount, self.get_behaviour().transfer_fee_out)
+    }
+
+    /// Amount to transfer to a pool for the pool to receive `amount`.
+    pub fn calc_transfer_in_gross_amount(&self, amount: U256) -> U256 {
+        Self::reverse_fee(amount, self.get_behaviour().transfer_fee_in)
+    }
+
+    /// Amount a pool has to transfer out for the recipient to receive `amount`.
+    pub fn calc_transfer_out_gross_amount(&self, amount: U256) -> U256 {
+        Self::reverse_fee(amount, self.get_behaviour().transfer_fee_out)
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use alloy_primitives::Address;
     use loom_defi_address_book::TokenAddressEth;
 
     #[test]
@@ -212,4 +359,30 @@ mod test {
 
         println!("{}", weth_token.to_float(one_ether));
     }
+
+    #[test]
+    fn test_transfer_fee() {
+        let token = Token::<LoomDataTypesEthereum>::new(Address::repeat_byte(1));
+        let amount = U256::from(1_000_000u64);
+
+        assert!(!token.is_behaviour_known());
+        assert_eq!(token.calc_transfer_in_amount(amount), amount);
+
+        token.set_behaviour(Some(TokenBehaviour::new(500, 100, false, false)));
+
+        assert!(token.is_behaviour_known());
+        assert!(token.has_transfer_fee());
+        assert_eq!(token.calc_transfer_in_amount(amount), U256::from(950_000u64));
+        assert_eq!(token.calc_transfer_out_amount(amount), U256::from(990_000u64));
+        assert_eq!(token.calc_transfer_in_gross_amount(U256::from(950_000u64)), amount);
+        assert_eq!(token.calc_transfer_out_gross_amount(U256::from(990_000u64)), amount);
+    }
+
+    #[test]
+    fn test_weth_has_no_transfer_fee() {
+        let weth_token = Token::<LoomDataTypesEthereum>::new(TokenAddressEth::WETH);
+        weth_token.set_behaviour(Some(TokenBehaviour::new(500, 500, true, true)));
+
+        assert!(weth_token.get_behaviour().is_standard());
+    }
 }
@@ -27,4 +27,9 @@ pub enum MempoolEvents<LDT: LoomDataTypes = LoomDataTypesEthereum> {
     MempoolLogUpdate {
         tx_hash: LDT::TxHash,
     },
+    /// A tx with the same sender and nonce superseded the transaction, work on it should be dropped.
+    TxReplaced {
+        tx_hash: LDT::TxHash,
+        replaced_by: LDT::TxHash,
+    },
 }
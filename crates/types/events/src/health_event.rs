@@ -1,12 +1,29 @@
 use crate::Message;
+use alloy_primitives::U256;
 use loom_types_blockchain::{LoomDataTypes, LoomDataTypesEthereum};
-use loom_types_entities::{EstimationError, SwapError};
+use loom_types_entities::{EstimationError, HeadHealth, InventoryLevel, SwapError};
 
 #[derive(Clone, Debug)]
 pub enum HealthEvent<LDT: LoomDataTypes = LoomDataTypesEthereum> {
     PoolSwapError(SwapError<LDT>),
     SwapLineEstimationError(EstimationError<LDT>),
     MonitorTx(LDT::TxHash),
+    /// Spot price of the token diverges from its reference price, prices are in token units per 1 ETH
+    PriceDivergence {
+        token: LDT::Address,
+        spot_price: U256,
+        reference_price: U256,
+        divergence_bps: u64,
+        refused: bool,
+    },
+    /// Head status changed, trading is paused until the head is fresh again
+    HeadStatusChanged(HeadHealth),
+    /// Multicaller balance of an inventory token changed its level, low and high balances need rebalancing
+    InventoryLevelChanged {
+        token: LDT::Address,
+        balance: U256,
+        level: InventoryLevel,
+    },
 }
 
 pub type MessageHealthEvent<LDT = LoomDataTypesEthereum> = Message<HealthEvent<LDT>>;
@@ -4,7 +4,7 @@ use alloy_eips::eip2718::Encodable2718;
 use alloy_primitives::{Bytes, U256};
 use eyre::{eyre, Result};
 use loom_types_blockchain::{LoomDataTypes, LoomDataTypesEthereum};
-use loom_types_entities::{PoolId, Swap};
+use loom_types_entities::{PoolId, Swap, SwapFunding};
 use revm::DatabaseRef;
 use std::ops::Deref;
 
@@ -58,7 +58,10 @@ pub struct SwapComposeData<DB, LDT: LoomDataTypes = LoomDataTypesEthereum> {
     pub poststate_update: Option<Vec<LDT::StateUpdate>>,
     pub origin: Option<String>,
     pub tips_pct: Option<u32>,
+    /// Upper limit of the tips percentage set by the control flags, applied to the share chosen by the strategy or the bidder
+    pub max_tips_pct: Option<u32>,
     pub tips: Option<U256>,
+    pub funding: SwapFunding,
 }
 
 impl<DB: Clone + 'static, LDT: LoomDataTypes> SwapComposeData<DB, LDT> {
@@ -117,7 +120,9 @@ impl<DB: DatabaseRef + Send + Sync + Clone + 'static, LDT: LoomDataTypes> Defaul
             poststate_update: None,
             origin: None,
             tips_pct: None,
+            max_tips_pct: None,
             tips: None,
+            funding: SwapFunding::Flash,
         }
     }
 }
@@ -51,6 +51,8 @@ pub struct TxComposeData<LDT: LoomDataTypes = LoomDataTypesEthereum> {
     pub origin: Option<String>,
     pub swap: Option<Swap>,
     pub tips: Option<U256>,
+    /// Share of the profit after gas paid as tips
+    pub tips_pct: Option<u32>,
 }
 
 impl<LDT: LoomDataTypes> Default for TxComposeData<LDT> {
@@ -73,6 +75,7 @@ impl<LDT: LoomDataTypes> Default for TxComposeData<LDT> {
             origin: None,
             swap: None,
             tips: None,
+            tips_pct: None,
         }
     }
 }